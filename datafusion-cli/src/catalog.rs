@@ -18,6 +18,7 @@
 use async_trait::async_trait;
 use datafusion::catalog::schema::SchemaProvider;
 use datafusion::catalog::{CatalogList, CatalogProvider};
+use datafusion::common::Statistics;
 use datafusion::datasource::listing::{
     ListingTable, ListingTableConfig, ListingTableUrl,
 };
@@ -41,6 +42,7 @@ impl DynamicFileCatalog {
     }
 }
 
+#[async_trait]
 impl CatalogList for DynamicFileCatalog {
     fn as_any(&self) -> &dyn Any {
         self
@@ -64,6 +66,16 @@ impl CatalogList for DynamicFileCatalog {
             .catalog(name)
             .map(|catalog| Arc::new(DynamicFileCatalogProvider::new(catalog, state)) as _)
     }
+
+    async fn resolve_catalog(
+        &self,
+        name: &str,
+    ) -> Result<Option<Arc<dyn CatalogProvider>>> {
+        let state = self.state.clone();
+        Ok(self.inner.resolve_catalog(name).await?.map(|catalog| {
+            Arc::new(DynamicFileCatalogProvider::new(catalog, state)) as _
+        }))
+    }
 }
 
 /// Wraps another catalog provider
@@ -81,6 +93,7 @@ impl DynamicFileCatalogProvider {
     }
 }
 
+#[async_trait]
 impl CatalogProvider for DynamicFileCatalogProvider {
     fn as_any(&self) -> &dyn Any {
         self
@@ -97,6 +110,18 @@ impl CatalogProvider for DynamicFileCatalogProvider {
             .map(|schema| Arc::new(DynamicFileSchemaProvider::new(schema, state)) as _)
     }
 
+    async fn resolve_schema(
+        &self,
+        name: &str,
+    ) -> Result<Option<Arc<dyn SchemaProvider>>> {
+        let state = self.state.clone();
+        Ok(self
+            .inner
+            .resolve_schema(name)
+            .await?
+            .map(|schema| Arc::new(DynamicFileSchemaProvider::new(schema, state)) as _))
+    }
+
     fn register_schema(
         &self,
         name: &str,
@@ -131,6 +156,10 @@ impl SchemaProvider for DynamicFileSchemaProvider {
         self.inner.table_names()
     }
 
+    async fn resolve_table_names(&self) -> Result<Vec<String>> {
+        self.inner.resolve_table_names().await
+    }
+
     fn register_table(
         &self,
         name: String,
@@ -162,4 +191,16 @@ impl SchemaProvider for DynamicFileSchemaProvider {
     fn table_exist(&self, name: &str) -> bool {
         self.inner.table_exist(name)
     }
+
+    fn table_statistics(&self, name: &str) -> Option<Statistics> {
+        self.inner.table_statistics(name)
+    }
+
+    fn register_table_statistics(
+        &self,
+        name: &str,
+        statistics: Statistics,
+    ) -> Result<()> {
+        self.inner.register_table_statistics(name, statistics)
+    }
 }
@@ -16,8 +16,9 @@
 // under the License.
 
 use clap::Parser;
+use datafusion::catalog::persistent::PersistentCatalogList;
 use datafusion::error::{DataFusionError, Result};
-use datafusion::execution::context::SessionConfig;
+use datafusion::execution::context::{SessionConfig, SessionState};
 use datafusion::execution::memory_pool::{FairSpillPool, GreedyMemoryPool};
 use datafusion::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
 use datafusion::prelude::SessionContext;
@@ -122,6 +123,12 @@ struct Args {
         help = "Specify the memory pool type 'greedy' or 'fair', default to 'greedy'"
     )]
     mem_pool_type: Option<PoolType>,
+
+    #[clap(
+        long,
+        help = "Persist the schemas, external tables and views to the given JSON file, and re-create them on startup"
+    )]
+    catalog_path: Option<String>,
 }
 
 #[tokio::main]
@@ -165,11 +172,30 @@ pub async fn main() -> Result<()> {
             rn_config
         };
 
-    let runtime_env = create_runtime_env(rn_config.clone())?;
+    let runtime_env = Arc::new(create_runtime_env(rn_config.clone())?);
 
-    let mut ctx =
-        SessionContext::with_config_rt(session_config.clone(), Arc::new(runtime_env));
+    let catalog_list = match &args.catalog_path {
+        Some(path) => Some(Arc::new(PersistentCatalogList::try_new(path)?)),
+        None => None,
+    };
+    let mut ctx = match &catalog_list {
+        Some(catalog_list) => {
+            SessionContext::with_state(SessionState::with_config_rt_and_catalog_list(
+                session_config.clone(),
+                runtime_env,
+                catalog_list.clone(),
+            ))
+        }
+        None => SessionContext::with_config_rt(session_config.clone(), runtime_env),
+    };
     ctx.refresh_catalogs().await?;
+    if let Some(catalog_list) = &catalog_list {
+        // tables that cannot be created any more, e.g. because their files
+        // were removed, should not prevent the CLI from starting
+        if let Err(e) = catalog_list.load(&ctx).await {
+            eprintln!("Failed to load the persisted catalog: {e}");
+        }
+    }
     // install dynamic catalog provider that knows how to open files
     ctx.register_catalog_list(Arc::new(DynamicFileCatalog::new(
         ctx.state().catalog_list(),
@@ -66,6 +66,8 @@ async fn main() -> Result<()> {
         table_partition_cols: vec![],
         output_ordering: vec![],
         infinite_source: false,
+        column_renames: vec![],
+        nested_projection: vec![],
     };
 
     let result =
@@ -251,6 +251,24 @@ config_namespace! {
         /// and sorted in a single RecordBatch rather than sorted in
         /// batches and merged.
         pub sort_in_place_threshold_bytes: usize, default = 1024 * 1024
+
+        /// Sorts with a `fetch` (e.g. `ORDER BY ... LIMIT n`) of at most this
+        /// many rows only keep the top rows in a bounded heap instead of
+        /// buffering and sorting their entire input. Set to 0 to disable.
+        pub sort_topk_max_fetch: usize, default = 10000
+
+        /// Range partitioning buffers up to this many bytes of each input
+        /// partition to sample the split points from, and samples the rows
+        /// buffered so far once the limit or the memory limit is reached.
+        pub range_partition_sample_bytes: usize, default = 16 * 1024 * 1024
+
+        /// Compression codec for the Arrow IPC files written by spilling
+        /// operators. Valid values are: uncompressed, lz4_frame and zstd
+        pub spill_compression: String, default = "uncompressed".to_string()
+
+        /// Maximum time in milliseconds a query started through a `QueryHandle`
+        /// may run before it is cancelled. A value of 0 disables the timeout.
+        pub statement_timeout: u64, default = 0
     }
 }
 
@@ -353,6 +371,23 @@ config_namespace! {
         /// Sets bloom filter number of distinct values. If NULL, uses
         /// default parquet writer setting
         pub bloom_filter_ndv: Option<u64>, default = None
+
+        /// Controls whether DataFusion will attempt to speed up writing
+        /// a single large parquet file by encoding its row groups in parallel.
+        /// Each row group is encoded into memory on its own task and the
+        /// results are stitched together in order. Files with bloom filters
+        /// enabled are always written serially
+        pub allow_single_file_parallelism: bool, default = false
+
+        /// If allow_single_file_parallelism is true, sets the maximum number
+        /// of row groups which may be encoded in parallel. Higher values use
+        /// more memory as each in-flight row group is buffered in full
+        pub maximum_parallel_row_group_writers: usize, default = 16
+
+        /// If allow_single_file_parallelism is true, sets the maximum number
+        /// of RecordBatches buffered for each input stream before they are
+        /// assigned to a row group
+        pub maximum_buffered_record_batches_per_stream: usize, default = 2
     }
 }
 
@@ -370,6 +405,24 @@ config_namespace! {
         /// By adjusting the `scalar_update_factor`, you can balance the trade-off between
         /// more efficient accumulator updates and the number of groups affected.
         pub scalar_update_factor: usize, default = 10
+
+        /// Number of input rows a partial hash aggregation processes before it
+        /// checks whether the aggregation reduces the number of rows enough, see
+        /// `skip_partial_aggregation_probe_ratio_threshold`
+        pub skip_partial_aggregation_probe_rows_threshold: usize, default = 100_000
+
+        /// When the ratio of the number of groups to the number of input rows of a
+        /// partial hash aggregation is greater than or equal to this threshold after
+        /// `skip_partial_aggregation_probe_rows_threshold` input rows, the partial
+        /// aggregation stops aggregating and passes the state of each input row
+        /// through to the final aggregation. A value greater than 1.0 disables this
+        pub skip_partial_aggregation_probe_ratio_threshold: f64, default = 0.8
+
+        /// When set to true, hash aggregations grouping on several columns of
+        /// primitive, string, binary or dictionary types intern their group keys
+        /// column by column. When set to false, these group keys are converted to
+        /// rows and interned as such
+        pub intern_group_columns: bool, default = true
     }
 }
 
@@ -380,6 +433,35 @@ config_namespace! {
         /// repartitioning to increase parallelism to leverage more CPU cores
         pub enable_round_robin_repartition: bool, default = true
 
+        /// When set to true, the physical plan optimizer will restrict Parquet scans
+        /// to the fields of struct columns that are accessed by the query, e.g. only
+        /// the leaves of field `a` of struct `s` are read for `SELECT s['a'] FROM t`
+        pub enable_nested_projection_pushdown: bool, default = true
+
+        /// When set to true, a hash join that collects its build side into a single
+        /// partition publishes the bounds of its join keys once the build side is
+        /// collected. The bounds are pushed down to the Parquet scans and filters on
+        /// the probe side, which skip the row groups, pages and rows that cannot match
+        pub enable_runtime_join_filters: bool, default = true
+
+        /// When set to true, hash partitioned joins whose probe side join keys have
+        /// heavy hitters in their statistics spread the probe side rows holding them
+        /// over all the partitions, and send the matching build side rows to every
+        /// partition, instead of hash partitioning them to a single partition
+        pub enable_skew_join: bool, default = false
+
+        /// When set to true, hash partitioned aggregations whose grouping keys have
+        /// heavy hitters in their statistics spread the partially aggregated rows
+        /// holding them over all the partitions, and combine them in an additional
+        /// partial aggregation before they are hash partitioned to a single partition
+        pub enable_skew_aggregate: bool, default = false
+
+        /// A value of a join or grouping key is a heavy hitter for `enable_skew_join`
+        /// and `enable_skew_aggregate` when the number of rows holding it is estimated
+        /// to exceed this fraction of the number of rows each partition receives when
+        /// the rows are evenly distributed over `target_partitions` partitions
+        pub skew_heavy_hitter_fraction: f64, default = 0.5
+
         /// When set to true, the optimizer will insert filters before a join between
         /// a nullable and non-nullable column to filter out nulls on the nullable side. This
         /// filter can add additional overhead when the file format does not fully support
@@ -437,6 +519,22 @@ config_namespace! {
         /// ```
         pub repartition_sorts: bool, default = true
 
+        /// When set to true, and `repartition_sorts` is also enabled, sorts that
+        /// produce a single partition range partition their input on the sort
+        /// keys, sort each partition in parallel and concatenate the sorted
+        /// partitions in order, instead of merging them on a single core.
+        /// With this flag enabled, the second plan above would turn into
+        ///
+        /// ```text
+        ///      "OrderedConcatExec: [a@0 ASC]",
+        ///      "  SortExec: [a@0 ASC]",
+        ///      "    RepartitionExec: partitioning=Range([a@0 ASC], 8), input_partitions=8",
+        ///      "      RepartitionExec: partitioning=RoundRobinBatch(8), input_partitions=1",
+        /// ```
+        /// Sorts with a limit are always merged, as each partition only produces
+        /// a few rows.
+        pub repartition_sorts_by_range: bool, default = false
+
         /// When true, DataFusion will opportunistically remove sorts by replacing
         /// `RepartitionExec` with `SortPreservingRepartitionExec`, and
         /// `CoalescePartitionsExec` with `SortPreservingMergeExec`,
@@ -497,6 +497,23 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_writeroptions_parquet_column_specific_unsupported() {
+        let mut option_map: HashMap<String, String> = HashMap::new();
+        option_map.insert("max_row_group_size::col1".to_owned(), "123".to_owned());
+
+        let options = StatementOptions::from(&option_map);
+        let config = ConfigOptions::new();
+
+        let err = ParquetWriterOptions::try_from((&config, &options))
+            .expect_err("max_row_group_size cannot be set per column");
+        assert_eq!(
+            err.strip_backtrace(),
+            "Invalid or Unsupported Configuration: \
+            Option max_row_group_size cannot be set for an individual column!"
+        );
+    }
+
     #[test]
     fn test_writeroptions_csv_from_statement_options() -> Result<()> {
         let mut option_map: HashMap<String, String> = HashMap::new();
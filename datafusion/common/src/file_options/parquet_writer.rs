@@ -111,6 +111,22 @@ pub fn default_builder(options: &ConfigOptions) -> Result<WriterPropertiesBuilde
     Ok(builder)
 }
 
+/// Returns true if the given parquet writer option may be overridden
+/// for individual columns, e.g. `'compression::col1' 'zstd(3)'`
+fn is_column_specific_option(option: &str) -> bool {
+    matches!(
+        option.to_lowercase().as_str(),
+        "bloom_filter_enabled"
+            | "encoding"
+            | "dictionary_enabled"
+            | "compression"
+            | "statistics_enabled"
+            | "max_statistics_size"
+            | "bloom_filter_fpp"
+            | "bloom_filter_ndv"
+    )
+}
+
 impl TryFrom<(&ConfigOptions, &StatementOptions)> for ParquetWriterOptions {
     type Error = DataFusionError;
 
@@ -122,6 +138,11 @@ impl TryFrom<(&ConfigOptions, &StatementOptions)> for ParquetWriterOptions {
         let mut builder = default_builder(configs)?;
         for (option, value) in &statement_options.options {
             let (option, col_path) = split_option_and_column_path(option);
+            if col_path.is_some() && !is_column_specific_option(&option) {
+                return Err(DataFusionError::Configuration(format!(
+                    "Option {option} cannot be set for an individual column!"
+                )));
+            }
             builder = match option.to_lowercase().as_str(){
                 "max_row_group_size" => builder
                     .set_max_row_group_size(value.parse()
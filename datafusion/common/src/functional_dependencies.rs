@@ -25,7 +25,7 @@ use std::fmt::{Display, Formatter};
 
 /// This object defines a constraint on a table.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
-enum Constraint {
+pub enum Constraint {
     /// Columns with the given indices form a composite primary key (they are
     /// jointly unique and not nullable):
     PrimaryKey(Vec<usize>),
@@ -110,6 +110,11 @@ impl Constraints {
     pub fn is_empty(&self) -> bool {
         self.inner.is_empty()
     }
+
+    /// Iterate over the individual constraints
+    pub fn iter(&self) -> impl Iterator<Item = &Constraint> {
+        self.inner.iter()
+    }
 }
 
 impl Display for Constraints {
@@ -17,44 +17,180 @@
 
 //! This module provides data structures to represent statistics
 
-use std::fmt::Display;
+use std::fmt::{self, Debug, Display};
 
 use crate::ScalarValue;
 
+/// Represents a statistical value along with how precisely it is known.
+/// Operators propagate the precision of their input statistics, so that
+/// consumers can tell actual values apart from estimates.
+#[derive(Clone, Copy, PartialEq, Eq, Default)]
+pub enum Precision<T> {
+    /// The exact value is known
+    Exact(T),
+    /// The value is not known exactly, but is likely close to this value
+    Inexact(T),
+    /// Nothing is known about the value
+    #[default]
+    Absent,
+}
+
+impl<T: Clone + PartialEq + PartialOrd> Precision<T> {
+    /// Returns the value if it is known, exactly or not
+    pub fn get_value(&self) -> Option<&T> {
+        match self {
+            Precision::Exact(value) | Precision::Inexact(value) => Some(value),
+            Precision::Absent => None,
+        }
+    }
+
+    /// Transforms the value, keeping its precision
+    pub fn map<U, F>(self, f: F) -> Precision<U>
+    where
+        F: FnOnce(T) -> U,
+    {
+        match self {
+            Precision::Exact(value) => Precision::Exact(f(value)),
+            Precision::Inexact(value) => Precision::Inexact(f(value)),
+            Precision::Absent => Precision::Absent,
+        }
+    }
+
+    /// Returns `Some(true)` if the value is exact, `Some(false)` if it is an
+    /// estimate and `None` if it is absent
+    pub fn is_exact(&self) -> Option<bool> {
+        match self {
+            Precision::Exact(_) => Some(true),
+            Precision::Inexact(_) => Some(false),
+            Precision::Absent => None,
+        }
+    }
+
+    /// Returns the maximum of two values. The result is only exact if both
+    /// values are exact.
+    pub fn max(&self, other: &Precision<T>) -> Precision<T> {
+        self.combine(other, |a, b| if a >= b { a.clone() } else { b.clone() })
+    }
+
+    /// Returns the minimum of two values. The result is only exact if both
+    /// values are exact.
+    pub fn min(&self, other: &Precision<T>) -> Precision<T> {
+        self.combine(other, |a, b| if a <= b { a.clone() } else { b.clone() })
+    }
+
+    /// Demotes an exact value to an estimate
+    pub fn to_inexact(self) -> Self {
+        match self {
+            Precision::Exact(value) => Precision::Inexact(value),
+            other => other,
+        }
+    }
+
+    /// Applies `f` to the values of `self` and `other`. The result is absent if
+    /// either value is absent, and exact only if both values are exact.
+    fn combine<F>(&self, other: &Precision<T>, f: F) -> Precision<T>
+    where
+        F: FnOnce(&T, &T) -> T,
+    {
+        match (self, other) {
+            (Precision::Exact(a), Precision::Exact(b)) => Precision::Exact(f(a, b)),
+            (
+                Precision::Exact(a) | Precision::Inexact(a),
+                Precision::Exact(b) | Precision::Inexact(b),
+            ) => Precision::Inexact(f(a, b)),
+            _ => Precision::Absent,
+        }
+    }
+}
+
+impl Precision<usize> {
+    /// Adds two values. The result is only exact if both values are exact.
+    pub fn add(&self, other: &Precision<usize>) -> Precision<usize> {
+        self.combine(other, |a, b| a + b)
+    }
+
+    /// Subtracts `other` from `self`, saturating at zero. The result is only
+    /// exact if both values are exact.
+    pub fn sub(&self, other: &Precision<usize>) -> Precision<usize> {
+        self.combine(other, |a, b| a.saturating_sub(*b))
+    }
+
+    /// Multiplies two values. The result is only exact if both values are exact.
+    pub fn multiply(&self, other: &Precision<usize>) -> Precision<usize> {
+        self.combine(other, |a, b| a * b)
+    }
+
+    /// Scales the value by the estimated `selectivity` of a filter. The result
+    /// is always inexact.
+    pub fn with_estimated_selectivity(self, selectivity: f64) -> Precision<usize> {
+        self.map(|value| (value as f64 * selectivity).ceil() as usize)
+            .to_inexact()
+    }
+}
+
+impl<T: Debug> Debug for Precision<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Precision::Exact(value) => write!(f, "Exact({value:?})"),
+            Precision::Inexact(value) => write!(f, "Inexact({value:?})"),
+            Precision::Absent => write!(f, "Absent"),
+        }
+    }
+}
+
+impl<T: Debug> Display for Precision<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        Debug::fmt(self, f)
+    }
+}
+
+impl<T> Precision<T> {
+    /// Returns an exact value if `value` is known, and absent otherwise.
+    /// Only use it for values that are known to be exact.
+    pub fn exact_or_absent(value: Option<T>) -> Self {
+        value.map_or(Precision::Absent, Precision::Exact)
+    }
+}
+
 /// Statistics for a relation
-/// Fields are optional and can be inexact because the sources
+/// Each statistic carries its [`Precision`] because the sources
 /// sometimes provide approximate estimates for performance reasons
 /// and the transformations output are not always predictable.
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Statistics {
     /// The number of table rows
-    pub num_rows: Option<usize>,
+    pub num_rows: Precision<usize>,
     /// total bytes of the table rows
-    pub total_byte_size: Option<usize>,
+    pub total_byte_size: Precision<usize>,
     /// Statistics on a column level
     pub column_statistics: Option<Vec<ColumnStatistics>>,
-    /// If true, any field that is `Some(..)` is the actual value in the data provided by the operator (it is not
-    /// an estimate). Any or all other fields might still be None, in which case no information is known.
-    /// if false, any field that is `Some(..)` may contain an inexact estimate and may not be the actual value.
-    pub is_exact: bool,
+}
+
+impl Statistics {
+    /// Demotes all the statistics to estimates, for operators whose output
+    /// can not be derived exactly from the statistics of their input
+    pub fn into_inexact(self) -> Self {
+        Statistics {
+            num_rows: self.num_rows.to_inexact(),
+            total_byte_size: self.total_byte_size.to_inexact(),
+            column_statistics: self.column_statistics.map(|column_statistics| {
+                column_statistics
+                    .into_iter()
+                    .map(ColumnStatistics::into_inexact)
+                    .collect()
+            }),
+        }
+    }
 }
 
 impl Display for Statistics {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        if self.num_rows.is_none() && self.total_byte_size.is_none() && !self.is_exact {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.num_rows == Precision::Absent && self.total_byte_size == Precision::Absent
+        {
             return Ok(());
         }
 
-        let rows = self
-            .num_rows
-            .map_or_else(|| "None".to_string(), |v| v.to_string());
-        let bytes = self
-            .total_byte_size
-            .map_or_else(|| "None".to_string(), |v| v.to_string());
-
-        write!(f, "rows={}, bytes={}, exact={}", rows, bytes, self.is_exact)?;
-
-        Ok(())
+        write!(f, "rows={}, bytes={}", self.num_rows, self.total_byte_size)
     }
 }
 
@@ -62,11 +198,322 @@ impl Display for Statistics {
 #[derive(Clone, Debug, Default, PartialEq, Eq)]
 pub struct ColumnStatistics {
     /// Number of null values on column
-    pub null_count: Option<usize>,
+    pub null_count: Precision<usize>,
     /// Maximum value of column
-    pub max_value: Option<ScalarValue>,
+    pub max_value: Precision<ScalarValue>,
     /// Minimum value of column
-    pub min_value: Option<ScalarValue>,
+    pub min_value: Precision<ScalarValue>,
     /// Number of distinct values
-    pub distinct_count: Option<usize>,
+    pub distinct_count: Precision<usize>,
+    /// Equi-depth histogram of the non-null values of column. Histograms are
+    /// always estimates.
+    pub histogram: Option<Histogram>,
+    /// Most common non-null values of column, in descending order of frequency
+    pub most_common_values: Option<Vec<ValueFrequency>>,
+}
+
+impl ColumnStatistics {
+    /// Demotes all the statistics to estimates
+    pub fn into_inexact(self) -> Self {
+        ColumnStatistics {
+            null_count: self.null_count.to_inexact(),
+            max_value: self.max_value.to_inexact(),
+            min_value: self.min_value.to_inexact(),
+            distinct_count: self.distinct_count.to_inexact(),
+            histogram: self.histogram,
+            most_common_values: self.most_common_values,
+        }
+    }
+}
+
+/// An equi-depth histogram over the non-null values of a column.
+///
+/// Buckets are sorted by their bounds, do not overlap and hold
+/// approximately the same number of rows, which keeps the estimates
+/// accurate for skewed data where min/max based interpolation is not.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Histogram {
+    /// Buckets of the histogram, in ascending order
+    pub buckets: Vec<HistogramBucket>,
+}
+
+/// A bucket of a [`Histogram`], covering the closed range `[lower, upper]`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HistogramBucket {
+    /// Smallest value in the bucket
+    pub lower: ScalarValue,
+    /// Largest value in the bucket
+    pub upper: ScalarValue,
+    /// Number of rows with a value in the bucket
+    pub num_rows: usize,
+    /// Number of distinct values in the bucket
+    pub distinct_count: usize,
+}
+
+/// A value of a column along with its number of occurrences
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ValueFrequency {
+    /// The value
+    pub value: ScalarValue,
+    /// Number of rows holding the value
+    pub count: usize,
+}
+
+impl Histogram {
+    /// Total number of rows covered by the histogram
+    pub fn num_rows(&self) -> usize {
+        self.buckets.iter().map(|bucket| bucket.num_rows).sum()
+    }
+
+    /// Estimates the number of rows with a value within the closed range
+    /// `[lower, upper]`. A null bound leaves that side of the range unbounded.
+    pub fn range_rows(&self, lower: &ScalarValue, upper: &ScalarValue) -> f64 {
+        self.buckets
+            .iter()
+            .map(|bucket| bucket.num_rows as f64 * bucket.overlap_fraction(lower, upper))
+            .sum()
+    }
+
+    /// Estimates the number of rows equal to `value`.
+    ///
+    /// Values listed in `most_common_values` use their recorded frequency. Other
+    /// values are assumed to be evenly spread over the distinct values of their
+    /// bucket, once the most common values in that bucket are set aside.
+    pub fn value_rows(
+        &self,
+        value: &ScalarValue,
+        most_common_values: &[ValueFrequency],
+    ) -> f64 {
+        if let Some(frequency) = most_common_values.iter().find(|f| &f.value == value) {
+            return frequency.count as f64;
+        }
+        let Some(bucket) = self.buckets.iter().find(|bucket| bucket.contains(value))
+        else {
+            return 0.0;
+        };
+        let (num_rows, distinct_count) = most_common_values
+            .iter()
+            .filter(|frequency| bucket.contains(&frequency.value))
+            .fold(
+                (bucket.num_rows, bucket.distinct_count),
+                |(num_rows, distinct_count), frequency| {
+                    (
+                        num_rows.saturating_sub(frequency.count),
+                        distinct_count.saturating_sub(1),
+                    )
+                },
+            );
+        if distinct_count == 0 {
+            0.0
+        } else {
+            num_rows as f64 / distinct_count as f64
+        }
+    }
+}
+
+impl HistogramBucket {
+    /// Returns true if `value` falls within the bounds of this bucket
+    pub fn contains(&self, value: &ScalarValue) -> bool {
+        !value.is_null() && is_le(&self.lower, value) && is_le(value, &self.upper)
+    }
+
+    /// Estimates the fraction of the rows of this bucket whose values fall
+    /// within the closed range `[lower, upper]`. A null bound leaves that side
+    /// of the range unbounded.
+    ///
+    /// Values are assumed to be uniformly distributed within the bucket.
+    pub fn overlap_fraction(&self, lower: &ScalarValue, upper: &ScalarValue) -> f64 {
+        let lower = if lower.is_null() || is_le(lower, &self.lower) {
+            &self.lower
+        } else {
+            lower
+        };
+        let upper = if upper.is_null() || is_le(&self.upper, upper) {
+            &self.upper
+        } else {
+            upper
+        };
+        if !is_le(lower, upper) {
+            return 0.0;
+        }
+        if lower == &self.lower && upper == &self.upper {
+            return 1.0;
+        }
+        if lower == upper {
+            return 1.0 / self.distinct_count.max(1) as f64;
+        }
+        match (
+            scalar_position(lower),
+            scalar_position(upper),
+            scalar_position(&self.lower),
+            scalar_position(&self.upper),
+        ) {
+            (Some(lower), Some(upper), Some(min), Some(max)) => {
+                // Discrete types count both endpoints of a range
+                let step = if self.lower.get_datatype().is_floating() {
+                    0.0
+                } else {
+                    1.0
+                };
+                let width = max - min + step;
+                if width > 0.0 {
+                    ((upper - lower + step) / width).clamp(0.0, 1.0)
+                } else {
+                    1.0
+                }
+            }
+            // Without a notion of distance between values, assume that a
+            // partially overlapping range covers half of the bucket
+            _ => 0.5,
+        }
+    }
+}
+
+/// Returns true if `left` is known to be less than or equal to `right`
+fn is_le(left: &ScalarValue, right: &ScalarValue) -> bool {
+    matches!(
+        left.partial_cmp(right),
+        Some(std::cmp::Ordering::Less | std::cmp::Ordering::Equal)
+    )
+}
+
+/// Maps a numeric or temporal value to its position on the real line, used to
+/// interpolate within histogram buckets
+fn scalar_position(value: &ScalarValue) -> Option<f64> {
+    match value {
+        ScalarValue::Int8(Some(v)) => Some(*v as f64),
+        ScalarValue::Int16(Some(v)) => Some(*v as f64),
+        ScalarValue::Int32(Some(v)) => Some(*v as f64),
+        ScalarValue::Int64(Some(v)) => Some(*v as f64),
+        ScalarValue::UInt8(Some(v)) => Some(*v as f64),
+        ScalarValue::UInt16(Some(v)) => Some(*v as f64),
+        ScalarValue::UInt32(Some(v)) => Some(*v as f64),
+        ScalarValue::UInt64(Some(v)) => Some(*v as f64),
+        ScalarValue::Float32(Some(v)) => Some(*v as f64),
+        ScalarValue::Float64(Some(v)) => Some(*v),
+        ScalarValue::Date32(Some(v)) => Some(*v as f64),
+        ScalarValue::Date64(Some(v)) => Some(*v as f64),
+        ScalarValue::TimestampSecond(Some(v), _)
+        | ScalarValue::TimestampMillisecond(Some(v), _)
+        | ScalarValue::TimestampMicrosecond(Some(v), _)
+        | ScalarValue::TimestampNanosecond(Some(v), _) => Some(*v as f64),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn precision_arithmetic() {
+        let exact = Precision::Exact(10_usize);
+        let inexact = Precision::Inexact(4_usize);
+        let absent = Precision::<usize>::Absent;
+
+        assert_eq!(exact.add(&Precision::Exact(5)), Precision::Exact(15));
+        assert_eq!(exact.add(&inexact), Precision::Inexact(14));
+        assert_eq!(exact.add(&absent), Precision::Absent);
+        assert_eq!(exact.sub(&inexact), Precision::Inexact(6));
+        assert_eq!(inexact.sub(&exact), Precision::Inexact(0));
+        assert_eq!(exact.multiply(&Precision::Exact(3)), Precision::Exact(30));
+        assert_eq!(exact.max(&inexact), Precision::Inexact(10));
+        assert_eq!(exact.min(&Precision::Exact(2)), Precision::Exact(2));
+        assert_eq!(
+            exact.with_estimated_selectivity(0.25),
+            Precision::Inexact(3)
+        );
+        assert_eq!(absent.with_estimated_selectivity(0.25), Precision::Absent);
+        assert_eq!(exact.is_exact(), Some(true));
+        assert_eq!(inexact.is_exact(), Some(false));
+        assert_eq!(absent.is_exact(), None);
+        assert_eq!(
+            Precision::exact_or_absent(Some(3_usize)),
+            Precision::Exact(3)
+        );
+        assert_eq!(Precision::<usize>::exact_or_absent(None), Precision::Absent);
+    }
+
+    #[test]
+    fn statistics_display() {
+        let statistics = Statistics {
+            num_rows: Precision::Exact(8),
+            ..Default::default()
+        };
+        assert_eq!(statistics.to_string(), "rows=Exact(8), bytes=Absent");
+        assert_eq!(
+            statistics.into_inexact().to_string(),
+            "rows=Inexact(8), bytes=Absent"
+        );
+        assert_eq!(Statistics::default().to_string(), "");
+    }
+
+    fn bucket(
+        lower: i64,
+        upper: i64,
+        num_rows: usize,
+        distinct: usize,
+    ) -> HistogramBucket {
+        HistogramBucket {
+            lower: ScalarValue::Int64(Some(lower)),
+            upper: ScalarValue::Int64(Some(upper)),
+            num_rows,
+            distinct_count: distinct,
+        }
+    }
+
+    fn int(value: i64) -> ScalarValue {
+        ScalarValue::Int64(Some(value))
+    }
+
+    #[test]
+    fn histogram_range_rows() {
+        let histogram = Histogram {
+            buckets: vec![
+                bucket(0, 0, 50, 1),
+                bucket(1, 10, 25, 10),
+                bucket(11, 100, 25, 90),
+            ],
+        };
+        assert_eq!(histogram.num_rows(), 100);
+
+        let unbounded = ScalarValue::Null;
+        assert_eq!(histogram.range_rows(&unbounded, &unbounded), 100.0);
+        assert_eq!(histogram.range_rows(&int(0), &int(0)), 50.0);
+        assert_eq!(histogram.range_rows(&unbounded, &int(5)), 50.0 + 12.5);
+        assert_eq!(histogram.range_rows(&int(101), &unbounded), 0.0);
+        assert_eq!(histogram.range_rows(&int(11), &int(100)), 25.0);
+    }
+
+    #[test]
+    fn histogram_value_rows() {
+        let histogram = Histogram {
+            buckets: vec![bucket(1, 10, 60, 10), bucket(11, 20, 40, 10)],
+        };
+        let most_common_values = vec![ValueFrequency {
+            value: int(3),
+            count: 33,
+        }];
+
+        assert_eq!(histogram.value_rows(&int(3), &most_common_values), 33.0);
+        assert_eq!(histogram.value_rows(&int(4), &most_common_values), 3.0);
+        assert_eq!(histogram.value_rows(&int(15), &most_common_values), 4.0);
+        assert_eq!(histogram.value_rows(&int(4), &[]), 6.0);
+        assert_eq!(histogram.value_rows(&int(42), &most_common_values), 0.0);
+    }
+
+    #[test]
+    fn bucket_overlap_fraction_without_distance() {
+        let bucket = HistogramBucket {
+            lower: ScalarValue::from("a"),
+            upper: ScalarValue::from("m"),
+            num_rows: 10,
+            distinct_count: 4,
+        };
+        let unbounded = ScalarValue::Null;
+        assert_eq!(bucket.overlap_fraction(&unbounded, &unbounded), 1.0);
+        assert_eq!(bucket.overlap_fraction(&"c".into(), &"c".into()), 0.25);
+        assert_eq!(bucket.overlap_fraction(&"c".into(), &unbounded), 0.5);
+        assert_eq!(bucket.overlap_fraction(&"x".into(), &unbounded), 0.0);
+    }
 }
@@ -21,7 +21,9 @@
 /// replicating values in the other, non nested rows.
 ///
 /// Conceptually this operation is like joining each row with all the
-/// values in the list column.
+/// values in the list column. When several list columns are unnested
+/// together, their values are zipped, and the shorter lists are padded
+/// with nulls.
 ///
 /// If `preserve_nulls` is false, nulls and empty lists
 /// from the input column are not carried through to the output. This
@@ -64,6 +66,12 @@
 pub struct UnnestOptions {
     /// Should nulls in the input be preserved? Defaults to true
     pub preserve_nulls: bool,
+    /// The name of a column holding the 1 based position of each value
+    /// in its list (`WITH ORDINALITY`), if any. Defaults to `None`
+    pub ordinality: Option<String>,
+    /// Should lists of lists be unnested until the values are no longer
+    /// lists? Defaults to false
+    pub recursive: bool,
 }
 
 impl Default for UnnestOptions {
@@ -71,6 +79,8 @@ impl Default for UnnestOptions {
         Self {
             // default to true to maintain backwards compatible behavior
             preserve_nulls: true,
+            ordinality: None,
+            recursive: false,
         }
     }
 }
@@ -87,4 +97,17 @@ impl UnnestOptions {
         self.preserve_nulls = preserve_nulls;
         self
     }
+
+    /// Add a column named `name` holding the 1 based position of each
+    /// unnested value in its list, like `WITH ORDINALITY`
+    pub fn with_ordinality(mut self, name: impl Into<String>) -> Self {
+        self.ordinality = Some(name.into());
+        self
+    }
+
+    /// Set whether lists of lists are unnested recursively
+    pub fn with_recursive(mut self, recursive: bool) -> Self {
+        self.recursive = recursive;
+        self
+    }
 }
@@ -0,0 +1,146 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Benchmarks for aggregations of inputs sorted on the GROUP BY column
+//!
+//! Vectorized aggregates (`SUM`) run in the hash based stream, which emits
+//! the complete groups early, while `DISTINCT` aggregates run in the sort
+//! based stream, which aggregates one group at a time with row based
+//! accumulators.
+
+#[macro_use]
+extern crate criterion;
+extern crate arrow;
+extern crate datafusion;
+
+use crate::criterion::Criterion;
+use arrow::array::{ArrayRef, Int64Array};
+use arrow::datatypes::{DataType, Field, Schema};
+use arrow::record_batch::RecordBatch;
+use datafusion::physical_plan::aggregates::{
+    AggregateExec, AggregateMode, PhysicalGroupBy,
+};
+use datafusion::physical_plan::expressions::{col, DistinctCount, Sum};
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::{collect, AggregateExpr, ExecutionPlan};
+use datafusion::prelude::SessionContext;
+use datafusion_physical_expr::PhysicalSortExpr;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+const BATCH_SIZE: usize = 8192;
+const NUM_BATCHES: usize = 16;
+
+/// Creates batches sorted on the group column `a`, each group having
+/// `group_size` rows, and a random value column `b`
+fn sorted_batches(group_size: usize) -> Vec<RecordBatch> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int64, false),
+        Field::new("b", DataType::Int64, false),
+    ]));
+    let mut rng = StdRng::seed_from_u64(42);
+    (0..NUM_BATCHES)
+        .map(|batch| {
+            let start = batch * BATCH_SIZE;
+            let a: Int64Array = (start..start + BATCH_SIZE)
+                .map(|row| Some((row / group_size) as i64))
+                .collect();
+            let b: Int64Array = (0..BATCH_SIZE)
+                .map(|_| Some(rng.gen_range(0..100)))
+                .collect();
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(a) as ArrayRef, Arc::new(b) as ArrayRef],
+            )
+            .unwrap()
+        })
+        .collect()
+}
+
+/// Aggregates `batches` grouped on `a`, declaring them sorted on `a`
+fn aggregate_sorted(
+    ctx: &SessionContext,
+    rt: &Runtime,
+    batches: &[RecordBatch],
+    aggregates: &[&str],
+) {
+    let schema = batches[0].schema();
+    let b = col("b", &schema).unwrap();
+    let aggr_expr = aggregates
+        .iter()
+        .map(|name| -> Arc<dyn AggregateExpr> {
+            match *name {
+                "sum" => Arc::new(Sum::new(b.clone(), "SUM(b)", DataType::Int64)),
+                "count_distinct" => Arc::new(DistinctCount::new(
+                    DataType::Int64,
+                    b.clone(),
+                    "COUNT(DISTINCT b)".to_string(),
+                )),
+                _ => unreachable!(),
+            }
+        })
+        .collect::<Vec<_>>();
+    let group_by =
+        PhysicalGroupBy::new_single(vec![(col("a", &schema).unwrap(), "a".into())]);
+    let sort_expr = PhysicalSortExpr {
+        expr: col("a", &schema).unwrap(),
+        options: Default::default(),
+    };
+    let input = Arc::new(
+        MemoryExec::try_new(&[batches.to_vec()], schema.clone(), None)
+            .unwrap()
+            .with_sort_information(vec![vec![sort_expr]]),
+    );
+    let aggregate: Arc<dyn ExecutionPlan> = Arc::new(
+        AggregateExec::try_new(
+            AggregateMode::Single,
+            group_by,
+            aggr_expr,
+            vec![None; aggregates.len()],
+            vec![None; aggregates.len()],
+            input,
+            schema,
+        )
+        .unwrap(),
+    );
+    criterion::black_box(rt.block_on(collect(aggregate, ctx.task_ctx())).unwrap());
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let cases = [
+        ("sum", vec!["sum"]),
+        ("count_distinct", vec!["count_distinct"]),
+        ("sum_count_distinct", vec!["sum", "count_distinct"]),
+    ];
+
+    let ctx = SessionContext::new();
+    let rt = Runtime::new().unwrap();
+    for group_size in [1, 10, 1000] {
+        let batches = sorted_batches(group_size);
+        for (name, aggregates) in &cases {
+            c.bench_function(
+                &format!("aggregate_sorted {name} group_size={group_size}"),
+                |b| b.iter(|| aggregate_sorted(&ctx, &rt, &batches, aggregates)),
+            );
+        }
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
@@ -0,0 +1,174 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Benchmarks for aggregations grouping on multiple columns, comparing
+//! interning their group keys column by column with interning them as rows
+
+#[macro_use]
+extern crate criterion;
+extern crate arrow;
+extern crate datafusion;
+
+use crate::criterion::Criterion;
+use arrow::array::{ArrayRef, DictionaryArray, Int32Array, Int64Array, StringArray};
+use arrow::datatypes::{DataType, Field, Int32Type, Schema};
+use arrow::record_batch::RecordBatch;
+use datafusion::physical_plan::aggregates::{
+    AggregateExec, AggregateMode, PhysicalGroupBy,
+};
+use datafusion::physical_plan::expressions::col;
+use datafusion::physical_plan::memory::MemoryExec;
+use datafusion::physical_plan::{collect, ExecutionPlan};
+use datafusion::prelude::{SessionConfig, SessionContext};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use std::sync::Arc;
+use tokio::runtime::Runtime;
+
+const BATCH_SIZE: usize = 8192;
+const NUM_BATCHES: usize = 16;
+const NUM_GROUPS: i64 = 10_000;
+
+fn seedable_rng() -> StdRng {
+    StdRng::seed_from_u64(42)
+}
+
+/// Creates batches with an i64 and an i32 group column
+fn primitive_batches() -> Vec<RecordBatch> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int64, true),
+        Field::new("b", DataType::Int32, true),
+    ]));
+    let mut rng = seedable_rng();
+    (0..NUM_BATCHES)
+        .map(|_| {
+            let a: Int64Array = (0..BATCH_SIZE)
+                .map(|_| Some(rng.gen_range(0..NUM_GROUPS)))
+                .collect();
+            let b: Int32Array =
+                (0..BATCH_SIZE).map(|_| Some(rng.gen_range(0..4))).collect();
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(a) as ArrayRef, Arc::new(b) as ArrayRef],
+            )
+            .unwrap()
+        })
+        .collect()
+}
+
+/// Creates batches with an i64 and a short string group column
+fn primitive_string_batches() -> Vec<RecordBatch> {
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int64, true),
+        Field::new("b", DataType::Utf8, true),
+    ]));
+    let mut rng = seedable_rng();
+    (0..NUM_BATCHES)
+        .map(|_| {
+            let a: Int64Array = (0..BATCH_SIZE)
+                .map(|_| Some(rng.gen_range(0..NUM_GROUPS)))
+                .collect();
+            let b: StringArray = (0..BATCH_SIZE)
+                .map(|_| Some(format!("value-{}", rng.gen_range(0..4))))
+                .collect();
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(a) as ArrayRef, Arc::new(b) as ArrayRef],
+            )
+            .unwrap()
+        })
+        .collect()
+}
+
+/// Creates batches with a dictionary encoded string and an i32 group column
+fn dictionary_batches() -> Vec<RecordBatch> {
+    let dictionary_type =
+        DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8));
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", dictionary_type, true),
+        Field::new("b", DataType::Int32, true),
+    ]));
+    let hosts: Vec<_> = (0..100).map(|i| format!("host-{i}")).collect();
+    let mut rng = seedable_rng();
+    (0..NUM_BATCHES)
+        .map(|_| {
+            let a: DictionaryArray<Int32Type> = (0..BATCH_SIZE)
+                .map(|_| hosts[rng.gen_range(0..hosts.len())].as_str())
+                .collect();
+            let b: Int32Array = (0..BATCH_SIZE)
+                .map(|_| Some(rng.gen_range(0..100)))
+                .collect();
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(a) as ArrayRef, Arc::new(b) as ArrayRef],
+            )
+            .unwrap()
+        })
+        .collect()
+}
+
+/// Groups `batches` on all of their columns, as in `SELECT DISTINCT a, b`
+fn group_by(ctx: &SessionContext, rt: &Runtime, batches: &[RecordBatch]) {
+    let schema = batches[0].schema();
+    let group_by = PhysicalGroupBy::new_single(
+        schema
+            .fields()
+            .iter()
+            .map(|f| (col(f.name(), &schema).unwrap(), f.name().clone()))
+            .collect(),
+    );
+    let input =
+        Arc::new(MemoryExec::try_new(&[batches.to_vec()], schema.clone(), None).unwrap());
+    let aggregate: Arc<dyn ExecutionPlan> = Arc::new(
+        AggregateExec::try_new(
+            AggregateMode::Single,
+            group_by,
+            vec![],
+            vec![],
+            vec![],
+            input,
+            schema,
+        )
+        .unwrap(),
+    );
+    criterion::black_box(rt.block_on(collect(aggregate, ctx.task_ctx())).unwrap());
+}
+
+fn criterion_benchmark(c: &mut Criterion) {
+    let cases = [
+        ("i64_i32", primitive_batches()),
+        ("i64_utf8", primitive_string_batches()),
+        ("dictionary_i32", dictionary_batches()),
+    ];
+
+    let rt = Runtime::new().unwrap();
+    for (interning, intern_columns) in [("columns", true), ("rows", false)] {
+        let config = SessionConfig::new().set_bool(
+            "datafusion.execution.aggregate.intern_group_columns",
+            intern_columns,
+        );
+        let ctx = SessionContext::with_config(config);
+        for (name, batches) in &cases {
+            c.bench_function(&format!("group_by {name} as {interning}"), |b| {
+                b.iter(|| group_by(&ctx, &rt, batches))
+            });
+        }
+    }
+}
+
+criterion_group!(benches, criterion_benchmark);
+criterion_main!(benches);
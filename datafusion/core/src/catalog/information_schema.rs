@@ -23,13 +23,20 @@ use async_trait::async_trait;
 use std::{any::Any, sync::Arc};
 
 use arrow::{
-    array::{StringBuilder, UInt64Builder},
+    array::{BooleanBuilder, StringBuilder, UInt64Builder},
     datatypes::{DataType, Field, Schema, SchemaRef},
     record_batch::RecordBatch,
 };
+use datafusion_common::Constraint;
+use datafusion_expr::{
+    AggregateFunction, BuiltInWindowFunction, BuiltinScalarFunction, Signature,
+    TypeSignature, Volatility,
+};
+use strum::IntoEnumIterator;
 
 use crate::datasource::streaming::StreamingTable;
 use crate::datasource::TableProvider;
+use crate::error::Result;
 use crate::execution::context::TaskContext;
 use crate::logical_expr::TableType;
 use crate::physical_plan::stream::RecordBatchStreamAdapter;
@@ -46,9 +53,24 @@ pub(crate) const TABLES: &str = "tables";
 pub(crate) const VIEWS: &str = "views";
 pub(crate) const COLUMNS: &str = "columns";
 pub(crate) const DF_SETTINGS: &str = "df_settings";
+pub(crate) const ROUTINES: &str = "routines";
+pub(crate) const PARAMETERS: &str = "parameters";
+pub(crate) const SCHEMATA: &str = "schemata";
+pub(crate) const TABLE_CONSTRAINTS: &str = "table_constraints";
+pub(crate) const KEY_COLUMN_USAGE: &str = "key_column_usage";
 
 /// All information schema tables
-pub const INFORMATION_SCHEMA_TABLES: &[&str] = &[TABLES, VIEWS, COLUMNS, DF_SETTINGS];
+pub const INFORMATION_SCHEMA_TABLES: &[&str] = &[
+    TABLES,
+    VIEWS,
+    COLUMNS,
+    DF_SETTINGS,
+    ROUTINES,
+    PARAMETERS,
+    SCHEMATA,
+    TABLE_CONSTRAINTS,
+    KEY_COLUMN_USAGE,
+];
 
 /// Implements the `information_schema` virtual schema and tables
 ///
@@ -76,17 +98,23 @@ struct InformationSchemaConfig {
 
 impl InformationSchemaConfig {
     /// Construct the `information_schema.tables` virtual table
-    async fn make_tables(&self, builder: &mut InformationSchemaTablesBuilder) {
+    async fn make_tables(
+        &self,
+        builder: &mut InformationSchemaTablesBuilder,
+    ) -> Result<()> {
         // create a mem table with the names of tables
 
         for catalog_name in self.catalog_list.catalog_names() {
-            let catalog = self.catalog_list.catalog(&catalog_name).unwrap();
+            let Some(catalog) = self.catalog_list.resolve_catalog(&catalog_name).await?
+            else {
+                continue;
+            };
 
             for schema_name in catalog.schema_names() {
                 if schema_name != INFORMATION_SCHEMA {
                     // schema name may not exist in the catalog, so we need to check
-                    if let Some(schema) = catalog.schema(&schema_name) {
-                        for table_name in schema.table_names() {
+                    if let Some(schema) = catalog.resolve_schema(&schema_name).await? {
+                        for table_name in schema.resolve_table_names().await? {
                             if let Some(table) = schema.table(&table_name).await {
                                 builder.add_table(
                                     &catalog_name,
@@ -101,32 +129,30 @@ impl InformationSchemaConfig {
             }
 
             // Add a final list for the information schema tables themselves
-            builder.add_table(&catalog_name, INFORMATION_SCHEMA, TABLES, TableType::View);
-            builder.add_table(&catalog_name, INFORMATION_SCHEMA, VIEWS, TableType::View);
-            builder.add_table(
-                &catalog_name,
-                INFORMATION_SCHEMA,
-                COLUMNS,
-                TableType::View,
-            );
-            builder.add_table(
-                &catalog_name,
-                INFORMATION_SCHEMA,
-                DF_SETTINGS,
-                TableType::View,
-            );
+            for table_name in INFORMATION_SCHEMA_TABLES {
+                builder.add_table(
+                    &catalog_name,
+                    INFORMATION_SCHEMA,
+                    table_name,
+                    TableType::View,
+                );
+            }
         }
+        Ok(())
     }
 
-    async fn make_views(&self, builder: &mut InformationSchemaViewBuilder) {
+    async fn make_views(&self, builder: &mut InformationSchemaViewBuilder) -> Result<()> {
         for catalog_name in self.catalog_list.catalog_names() {
-            let catalog = self.catalog_list.catalog(&catalog_name).unwrap();
+            let Some(catalog) = self.catalog_list.resolve_catalog(&catalog_name).await?
+            else {
+                continue;
+            };
 
             for schema_name in catalog.schema_names() {
                 if schema_name != INFORMATION_SCHEMA {
                     // schema name may not exist in the catalog, so we need to check
-                    if let Some(schema) = catalog.schema(&schema_name) {
-                        for table_name in schema.table_names() {
+                    if let Some(schema) = catalog.resolve_schema(&schema_name).await? {
+                        for table_name in schema.resolve_table_names().await? {
                             if let Some(table) = schema.table(&table_name).await {
                                 builder.add_view(
                                     &catalog_name,
@@ -140,18 +166,25 @@ impl InformationSchemaConfig {
                 }
             }
         }
+        Ok(())
     }
 
     /// Construct the `information_schema.columns` virtual table
-    async fn make_columns(&self, builder: &mut InformationSchemaColumnsBuilder) {
+    async fn make_columns(
+        &self,
+        builder: &mut InformationSchemaColumnsBuilder,
+    ) -> Result<()> {
         for catalog_name in self.catalog_list.catalog_names() {
-            let catalog = self.catalog_list.catalog(&catalog_name).unwrap();
+            let Some(catalog) = self.catalog_list.resolve_catalog(&catalog_name).await?
+            else {
+                continue;
+            };
 
             for schema_name in catalog.schema_names() {
                 if schema_name != INFORMATION_SCHEMA {
                     // schema name may not exist in the catalog, so we need to check
-                    if let Some(schema) = catalog.schema(&schema_name) {
-                        for table_name in schema.table_names() {
+                    if let Some(schema) = catalog.resolve_schema(&schema_name).await? {
+                        for table_name in schema.resolve_table_names().await? {
                             if let Some(table) = schema.table(&table_name).await {
                                 for (field_position, field) in
                                     table.schema().fields().iter().enumerate()
@@ -170,6 +203,7 @@ impl InformationSchemaConfig {
                 }
             }
         }
+        Ok(())
     }
 
     /// Construct the `information_schema.df_settings` virtual table
@@ -182,6 +216,355 @@ impl InformationSchemaConfig {
             builder.add_setting(entry);
         }
     }
+
+    /// Construct the `information_schema.schemata` virtual table
+    async fn make_schemata(
+        &self,
+        builder: &mut InformationSchemataBuilder,
+    ) -> Result<()> {
+        for catalog_name in self.catalog_list.catalog_names() {
+            let Some(catalog) = self.catalog_list.resolve_catalog(&catalog_name).await?
+            else {
+                continue;
+            };
+
+            for schema_name in catalog.schema_names() {
+                if schema_name != INFORMATION_SCHEMA {
+                    // schema name may not exist in the catalog, so we need to check
+                    if catalog.resolve_schema(&schema_name).await?.is_some() {
+                        builder.add_schema(&catalog_name, &schema_name);
+                    }
+                }
+            }
+
+            builder.add_schema(&catalog_name, INFORMATION_SCHEMA);
+        }
+        Ok(())
+    }
+
+    /// Construct the `information_schema.table_constraints` virtual table
+    async fn make_table_constraints(
+        &self,
+        builder: &mut InformationSchemaTableConstraintsBuilder,
+    ) -> Result<()> {
+        for catalog_name in self.catalog_list.catalog_names() {
+            let Some(catalog) = self.catalog_list.resolve_catalog(&catalog_name).await?
+            else {
+                continue;
+            };
+
+            for schema_name in catalog.schema_names() {
+                if schema_name != INFORMATION_SCHEMA {
+                    // schema name may not exist in the catalog, so we need to check
+                    if let Some(schema) = catalog.resolve_schema(&schema_name).await? {
+                        for table_name in schema.resolve_table_names().await? {
+                            let Some(table) = schema.table(&table_name).await else {
+                                continue;
+                            };
+                            let Some(constraints) = table.constraints() else {
+                                continue;
+                            };
+                            for constraint in constraints.iter() {
+                                let table_schema = table.schema();
+                                let key =
+                                    TableKey::new(&table_name, &table_schema, constraint);
+                                builder.add_constraint(
+                                    &catalog_name,
+                                    &schema_name,
+                                    &table_name,
+                                    &key,
+                                );
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Construct the `information_schema.key_column_usage` virtual table
+    async fn make_key_column_usage(
+        &self,
+        builder: &mut InformationSchemaKeyColumnUsageBuilder,
+    ) -> Result<()> {
+        for catalog_name in self.catalog_list.catalog_names() {
+            let Some(catalog) = self.catalog_list.resolve_catalog(&catalog_name).await?
+            else {
+                continue;
+            };
+
+            for schema_name in catalog.schema_names() {
+                if schema_name != INFORMATION_SCHEMA {
+                    // schema name may not exist in the catalog, so we need to check
+                    if let Some(schema) = catalog.resolve_schema(&schema_name).await? {
+                        for table_name in schema.resolve_table_names().await? {
+                            let Some(table) = schema.table(&table_name).await else {
+                                continue;
+                            };
+                            let Some(constraints) = table.constraints() else {
+                                continue;
+                            };
+                            for constraint in constraints.iter() {
+                                let table_schema = table.schema();
+                                let key =
+                                    TableKey::new(&table_name, &table_schema, constraint);
+                                for (position, column_name) in
+                                    key.column_names.iter().enumerate()
+                                {
+                                    builder.add_key_column(
+                                        &catalog_name,
+                                        &schema_name,
+                                        &table_name,
+                                        &key.name,
+                                        column_name,
+                                        position,
+                                    );
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Construct the `information_schema.routines` virtual table
+    fn make_routines(
+        &self,
+        ctx: &TaskContext,
+        builder: &mut InformationSchemaRoutinesBuilder,
+    ) {
+        let options = &ctx.session_config().options().catalog;
+        for routine in routines(ctx) {
+            builder.add_routine(
+                &options.default_catalog,
+                &options.default_schema,
+                &routine,
+            );
+        }
+    }
+
+    /// Construct the `information_schema.parameters` virtual table
+    fn make_parameters(
+        &self,
+        ctx: &TaskContext,
+        builder: &mut InformationSchemaParametersBuilder,
+    ) {
+        let options = &ctx.session_config().options().catalog;
+        for routine in routines(ctx) {
+            for (rid, variant) in routine.variants.iter().enumerate() {
+                for (position, arg_type) in variant.arg_types.iter().enumerate() {
+                    let is_variadic =
+                        variant.is_variadic && position + 1 == variant.arg_types.len();
+                    builder.add_parameter(
+                        &options.default_catalog,
+                        &options.default_schema,
+                        &routine.name,
+                        position + 1,
+                        "IN",
+                        arg_type.as_ref(),
+                        is_variadic,
+                        rid,
+                    );
+                }
+                if let Some(return_type) = &variant.return_type {
+                    builder.add_parameter(
+                        &options.default_catalog,
+                        &options.default_schema,
+                        &routine.name,
+                        1,
+                        "OUT",
+                        Some(return_type),
+                        false,
+                        rid,
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// A primary key or unique constraint of a table, as listed in
+/// `information_schema.table_constraints` and `information_schema.key_column_usage`
+struct TableKey {
+    /// The constraint name, following the postgres naming convention
+    name: String,
+    /// `PRIMARY KEY` or `UNIQUE`
+    constraint_type: &'static str,
+    /// The constrained columns, in key order
+    column_names: Vec<String>,
+}
+
+impl TableKey {
+    fn new(table_name: &str, schema: &SchemaRef, constraint: &Constraint) -> Self {
+        let (constraint_type, indices) = match constraint {
+            Constraint::PrimaryKey(indices) => ("PRIMARY KEY", indices),
+            Constraint::Unique(indices) => ("UNIQUE", indices),
+        };
+        let column_names: Vec<String> = indices
+            .iter()
+            .map(|idx| schema.field(*idx).name().clone())
+            .collect();
+        let name = match constraint {
+            Constraint::PrimaryKey(_) => format!("{table_name}_pkey"),
+            Constraint::Unique(_) => {
+                format!("{table_name}_{}_key", column_names.join("_"))
+            }
+        };
+        Self {
+            name,
+            constraint_type,
+            column_names,
+        }
+    }
+}
+
+/// A function listed in `information_schema.routines`
+struct Routine {
+    name: String,
+    /// `SCALAR`, `AGGREGATE` or `WINDOW`
+    function_type: &'static str,
+    volatility: Volatility,
+    /// The argument lists accepted by the function, see [`signature_variants`]
+    variants: Vec<RoutineVariant>,
+}
+
+impl Routine {
+    fn new(
+        name: String,
+        function_type: &'static str,
+        signature: &Signature,
+        return_type: impl Fn(&[DataType]) -> Result<DataType>,
+    ) -> Self {
+        let variants = signature_variants(&signature.type_signature)
+            .into_iter()
+            .map(|(arg_types, is_variadic)| {
+                // The return type can only be resolved for concrete argument types
+                let return_type = arg_types
+                    .iter()
+                    .cloned()
+                    .collect::<Option<Vec<_>>>()
+                    .and_then(|types| return_type(&types).ok());
+                RoutineVariant {
+                    arg_types,
+                    is_variadic,
+                    return_type,
+                }
+            })
+            .collect();
+
+        Self {
+            name,
+            function_type,
+            volatility: signature.volatility,
+            variants,
+        }
+    }
+
+    /// The return type of the function, if it is the same for all variants
+    fn return_type(&self) -> Option<&DataType> {
+        let mut return_types = self.variants.iter().map(|v| v.return_type.as_ref());
+        let first = return_types.next()??;
+        return_types
+            .all(|return_type| return_type == Some(first))
+            .then_some(first)
+    }
+}
+
+/// One argument list accepted by a [`Routine`]
+struct RoutineVariant {
+    /// The argument types, `None` if any type is accepted
+    arg_types: Vec<Option<DataType>>,
+    /// Whether the last argument may be repeated
+    is_variadic: bool,
+    return_type: Option<DataType>,
+}
+
+/// Expands a [`TypeSignature`] into the argument lists it accepts, together with
+/// whether the last argument is variadic
+fn signature_variants(signature: &TypeSignature) -> Vec<(Vec<Option<DataType>>, bool)> {
+    match signature {
+        TypeSignature::Exact(types) => {
+            vec![(types.iter().cloned().map(Some).collect(), false)]
+        }
+        // A function without arguments
+        TypeSignature::Uniform(0, _) => vec![(vec![], false)],
+        TypeSignature::Uniform(n, types) => types
+            .iter()
+            .map(|t| (vec![Some(t.clone()); *n], false))
+            .collect(),
+        TypeSignature::Variadic(types) => types
+            .iter()
+            .map(|t| (vec![Some(t.clone())], true))
+            .collect(),
+        TypeSignature::VariadicEqual | TypeSignature::VariadicAny => {
+            vec![(vec![None], true)]
+        }
+        TypeSignature::Any(n) => vec![(vec![None; *n], false)],
+        TypeSignature::OneOf(signatures) => {
+            signatures.iter().flat_map(signature_variants).collect()
+        }
+    }
+}
+
+/// Returns the built-in and user defined functions available to `ctx`
+fn routines(ctx: &TaskContext) -> Vec<Routine> {
+    let mut routines = vec![];
+
+    for fun in BuiltinScalarFunction::iter() {
+        routines.push(Routine::new(
+            fun.to_string(),
+            "SCALAR",
+            &fun.signature(),
+            |types| fun.return_type(types),
+        ));
+    }
+    for fun in AggregateFunction::iter() {
+        routines.push(Routine::new(
+            fun.to_string().to_lowercase(),
+            "AGGREGATE",
+            &fun.signature(),
+            |types| fun.return_type(types),
+        ));
+    }
+    for fun in BuiltInWindowFunction::iter() {
+        routines.push(Routine::new(
+            fun.to_string().to_lowercase(),
+            "WINDOW",
+            &fun.signature(),
+            |types| fun.return_type(types),
+        ));
+    }
+
+    for udf in ctx.scalar_functions().values() {
+        routines.push(Routine::new(
+            udf.name.clone(),
+            "SCALAR",
+            &udf.signature,
+            |types| (udf.return_type)(types).map(|t| t.as_ref().clone()),
+        ));
+    }
+    for udaf in ctx.aggregate_functions().values() {
+        routines.push(Routine::new(
+            udaf.name.clone(),
+            "AGGREGATE",
+            &udaf.signature,
+            |types| (udaf.return_type)(types).map(|t| t.as_ref().clone()),
+        ));
+    }
+    for udwf in ctx.window_functions().values() {
+        routines.push(Routine::new(
+            udwf.name.clone(),
+            "WINDOW",
+            &udwf.signature,
+            |types| (udwf.return_type)(types).map(|t| t.as_ref().clone()),
+        ));
+    }
+
+    routines.sort_by(|a, b| (a.function_type, &a.name).cmp(&(b.function_type, &b.name)));
+    routines
 }
 
 #[async_trait]
@@ -191,12 +574,10 @@ impl SchemaProvider for InformationSchemaProvider {
     }
 
     fn table_names(&self) -> Vec<String> {
-        vec![
-            TABLES.to_string(),
-            VIEWS.to_string(),
-            COLUMNS.to_string(),
-            DF_SETTINGS.to_string(),
-        ]
+        INFORMATION_SCHEMA_TABLES
+            .iter()
+            .map(|name| name.to_string())
+            .collect()
     }
 
     async fn table(&self, name: &str) -> Option<Arc<dyn TableProvider>> {
@@ -209,6 +590,16 @@ impl SchemaProvider for InformationSchemaProvider {
             Arc::new(InformationSchemaViews::new(config))
         } else if name.eq_ignore_ascii_case("df_settings") {
             Arc::new(InformationSchemaDfSettings::new(config))
+        } else if name.eq_ignore_ascii_case("routines") {
+            Arc::new(InformationSchemaRoutines::new(config))
+        } else if name.eq_ignore_ascii_case("parameters") {
+            Arc::new(InformationSchemaParameters::new(config))
+        } else if name.eq_ignore_ascii_case("schemata") {
+            Arc::new(InformationSchemata::new(config))
+        } else if name.eq_ignore_ascii_case("table_constraints") {
+            Arc::new(InformationSchemaTableConstraints::new(config))
+        } else if name.eq_ignore_ascii_case("key_column_usage") {
+            Arc::new(InformationSchemaKeyColumnUsage::new(config))
         } else {
             return None;
         };
@@ -219,7 +610,18 @@ impl SchemaProvider for InformationSchemaProvider {
     }
 
     fn table_exist(&self, name: &str) -> bool {
-        matches!(name.to_ascii_lowercase().as_str(), TABLES | VIEWS | COLUMNS)
+        matches!(
+            name.to_ascii_lowercase().as_str(),
+            TABLES
+                | VIEWS
+                | COLUMNS
+                | DF_SETTINGS
+                | ROUTINES
+                | PARAMETERS
+                | SCHEMATA
+                | TABLE_CONSTRAINTS
+                | KEY_COLUMN_USAGE
+        )
     }
 }
 
@@ -263,7 +665,7 @@ impl PartitionStream for InformationSchemaTables {
             self.schema.clone(),
             // TODO: Stream this
             futures::stream::once(async move {
-                config.make_tables(&mut builder).await;
+                config.make_tables(&mut builder).await?;
                 Ok(builder.finish())
             }),
         ))
@@ -354,7 +756,7 @@ impl PartitionStream for InformationSchemaViews {
             self.schema.clone(),
             // TODO: Stream this
             futures::stream::once(async move {
-                config.make_views(&mut builder).await;
+                config.make_views(&mut builder).await?;
                 Ok(builder.finish())
             }),
         ))
@@ -468,7 +870,7 @@ impl PartitionStream for InformationSchemaColumns {
             self.schema.clone(),
             // TODO: Stream this
             futures::stream::once(async move {
-                config.make_columns(&mut builder).await;
+                config.make_columns(&mut builder).await?;
                 Ok(builder.finish())
             }),
         ))
@@ -684,3 +1086,557 @@ impl InformationSchemaDfSettingsBuilder {
         .unwrap()
     }
 }
+
+struct InformationSchemata {
+    schema: SchemaRef,
+    config: InformationSchemaConfig,
+}
+
+impl InformationSchemata {
+    fn new(config: InformationSchemaConfig) -> Self {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("catalog_name", DataType::Utf8, false),
+            Field::new("schema_name", DataType::Utf8, false),
+            Field::new("schema_owner", DataType::Utf8, true),
+            Field::new("default_character_set_catalog", DataType::Utf8, true),
+            Field::new("default_character_set_schema", DataType::Utf8, true),
+            Field::new("default_character_set_name", DataType::Utf8, true),
+            Field::new("sql_path", DataType::Utf8, true),
+        ]));
+
+        Self { schema, config }
+    }
+
+    fn builder(&self) -> InformationSchemataBuilder {
+        InformationSchemataBuilder {
+            catalog_names: StringBuilder::new(),
+            schema_names: StringBuilder::new(),
+            schema_owners: StringBuilder::new(),
+            default_character_set_catalogs: StringBuilder::new(),
+            default_character_set_schemas: StringBuilder::new(),
+            default_character_set_names: StringBuilder::new(),
+            sql_paths: StringBuilder::new(),
+            schema: self.schema.clone(),
+        }
+    }
+}
+
+impl PartitionStream for InformationSchemata {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let mut builder = self.builder();
+        let config = self.config.clone();
+        Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            // TODO: Stream this
+            futures::stream::once(async move {
+                config.make_schemata(&mut builder).await?;
+                Ok(builder.finish())
+            }),
+        ))
+    }
+}
+
+/// Builds the `information_schema.SCHEMATA` table row by row
+///
+/// Columns are based on <https://www.postgresql.org/docs/current/infoschema-schemata.html>
+struct InformationSchemataBuilder {
+    schema: SchemaRef,
+    catalog_names: StringBuilder,
+    schema_names: StringBuilder,
+    schema_owners: StringBuilder,
+    default_character_set_catalogs: StringBuilder,
+    default_character_set_schemas: StringBuilder,
+    default_character_set_names: StringBuilder,
+    sql_paths: StringBuilder,
+}
+
+impl InformationSchemataBuilder {
+    fn add_schema(&mut self, catalog_name: &str, schema_name: &str) {
+        self.catalog_names.append_value(catalog_name);
+        self.schema_names.append_value(schema_name);
+
+        // DataFusion has no notion of schema owners, character sets or
+        // schema paths, so these are null
+        self.schema_owners.append_null();
+        self.default_character_set_catalogs.append_null();
+        self.default_character_set_schemas.append_null();
+        self.default_character_set_names.append_null();
+        self.sql_paths.append_null();
+    }
+
+    fn finish(&mut self) -> RecordBatch {
+        RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(self.catalog_names.finish()),
+                Arc::new(self.schema_names.finish()),
+                Arc::new(self.schema_owners.finish()),
+                Arc::new(self.default_character_set_catalogs.finish()),
+                Arc::new(self.default_character_set_schemas.finish()),
+                Arc::new(self.default_character_set_names.finish()),
+                Arc::new(self.sql_paths.finish()),
+            ],
+        )
+        .unwrap()
+    }
+}
+
+struct InformationSchemaTableConstraints {
+    schema: SchemaRef,
+    config: InformationSchemaConfig,
+}
+
+impl InformationSchemaTableConstraints {
+    fn new(config: InformationSchemaConfig) -> Self {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("constraint_catalog", DataType::Utf8, false),
+            Field::new("constraint_schema", DataType::Utf8, false),
+            Field::new("constraint_name", DataType::Utf8, false),
+            Field::new("table_catalog", DataType::Utf8, false),
+            Field::new("table_schema", DataType::Utf8, false),
+            Field::new("table_name", DataType::Utf8, false),
+            Field::new("constraint_type", DataType::Utf8, false),
+            Field::new("is_deferrable", DataType::Utf8, false),
+            Field::new("initially_deferred", DataType::Utf8, false),
+            Field::new("enforced", DataType::Utf8, false),
+        ]));
+
+        Self { schema, config }
+    }
+
+    fn builder(&self) -> InformationSchemaTableConstraintsBuilder {
+        InformationSchemaTableConstraintsBuilder {
+            catalog_names: StringBuilder::new(),
+            schema_names: StringBuilder::new(),
+            constraint_names: StringBuilder::new(),
+            table_names: StringBuilder::new(),
+            constraint_types: StringBuilder::new(),
+            is_deferrables: StringBuilder::new(),
+            initially_deferreds: StringBuilder::new(),
+            enforceds: StringBuilder::new(),
+            schema: self.schema.clone(),
+        }
+    }
+}
+
+impl PartitionStream for InformationSchemaTableConstraints {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let mut builder = self.builder();
+        let config = self.config.clone();
+        Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            // TODO: Stream this
+            futures::stream::once(async move {
+                config.make_table_constraints(&mut builder).await?;
+                Ok(builder.finish())
+            }),
+        ))
+    }
+}
+
+/// Builds the `information_schema.TABLE_CONSTRAINTS` table row by row
+///
+/// Columns are based on <https://www.postgresql.org/docs/current/infoschema-table-constraints.html>
+struct InformationSchemaTableConstraintsBuilder {
+    schema: SchemaRef,
+    catalog_names: StringBuilder,
+    schema_names: StringBuilder,
+    constraint_names: StringBuilder,
+    table_names: StringBuilder,
+    constraint_types: StringBuilder,
+    is_deferrables: StringBuilder,
+    initially_deferreds: StringBuilder,
+    enforceds: StringBuilder,
+}
+
+impl InformationSchemaTableConstraintsBuilder {
+    fn add_constraint(
+        &mut self,
+        catalog_name: &str,
+        schema_name: &str,
+        table_name: &str,
+        key: &TableKey,
+    ) {
+        // Constraints always live in the schema of their table
+        self.catalog_names.append_value(catalog_name);
+        self.schema_names.append_value(schema_name);
+        self.constraint_names.append_value(&key.name);
+        self.table_names.append_value(table_name);
+        self.constraint_types.append_value(key.constraint_type);
+        self.is_deferrables.append_value("NO");
+        self.initially_deferreds.append_value("NO");
+        // DataFusion uses constraints for planning but does not check them
+        self.enforceds.append_value("NO");
+    }
+
+    fn finish(&mut self) -> RecordBatch {
+        let catalog_names = Arc::new(self.catalog_names.finish());
+        let schema_names = Arc::new(self.schema_names.finish());
+        RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                catalog_names.clone(),
+                schema_names.clone(),
+                Arc::new(self.constraint_names.finish()),
+                catalog_names,
+                schema_names,
+                Arc::new(self.table_names.finish()),
+                Arc::new(self.constraint_types.finish()),
+                Arc::new(self.is_deferrables.finish()),
+                Arc::new(self.initially_deferreds.finish()),
+                Arc::new(self.enforceds.finish()),
+            ],
+        )
+        .unwrap()
+    }
+}
+
+struct InformationSchemaKeyColumnUsage {
+    schema: SchemaRef,
+    config: InformationSchemaConfig,
+}
+
+impl InformationSchemaKeyColumnUsage {
+    fn new(config: InformationSchemaConfig) -> Self {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("constraint_catalog", DataType::Utf8, false),
+            Field::new("constraint_schema", DataType::Utf8, false),
+            Field::new("constraint_name", DataType::Utf8, false),
+            Field::new("table_catalog", DataType::Utf8, false),
+            Field::new("table_schema", DataType::Utf8, false),
+            Field::new("table_name", DataType::Utf8, false),
+            Field::new("column_name", DataType::Utf8, false),
+            Field::new("ordinal_position", DataType::UInt64, false),
+            Field::new("position_in_unique_constraint", DataType::UInt64, true),
+        ]));
+
+        Self { schema, config }
+    }
+
+    fn builder(&self) -> InformationSchemaKeyColumnUsageBuilder {
+        InformationSchemaKeyColumnUsageBuilder {
+            catalog_names: StringBuilder::new(),
+            schema_names: StringBuilder::new(),
+            constraint_names: StringBuilder::new(),
+            table_names: StringBuilder::new(),
+            column_names: StringBuilder::new(),
+            ordinal_positions: UInt64Builder::new(),
+            positions_in_unique_constraint: UInt64Builder::new(),
+            schema: self.schema.clone(),
+        }
+    }
+}
+
+impl PartitionStream for InformationSchemaKeyColumnUsage {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, _ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let mut builder = self.builder();
+        let config = self.config.clone();
+        Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            // TODO: Stream this
+            futures::stream::once(async move {
+                config.make_key_column_usage(&mut builder).await?;
+                Ok(builder.finish())
+            }),
+        ))
+    }
+}
+
+/// Builds the `information_schema.KEY_COLUMN_USAGE` table row by row
+///
+/// Columns are based on <https://www.postgresql.org/docs/current/infoschema-key-column-usage.html>
+struct InformationSchemaKeyColumnUsageBuilder {
+    schema: SchemaRef,
+    catalog_names: StringBuilder,
+    schema_names: StringBuilder,
+    constraint_names: StringBuilder,
+    table_names: StringBuilder,
+    column_names: StringBuilder,
+    ordinal_positions: UInt64Builder,
+    positions_in_unique_constraint: UInt64Builder,
+}
+
+impl InformationSchemaKeyColumnUsageBuilder {
+    fn add_key_column(
+        &mut self,
+        catalog_name: &str,
+        schema_name: &str,
+        table_name: &str,
+        constraint_name: &str,
+        column_name: &str,
+        key_position: usize,
+    ) {
+        self.catalog_names.append_value(catalog_name);
+        self.schema_names.append_value(schema_name);
+        self.constraint_names.append_value(constraint_name);
+        self.table_names.append_value(table_name);
+        self.column_names.append_value(column_name);
+        self.ordinal_positions.append_value(key_position as u64 + 1);
+        // Only set for foreign keys, which DataFusion does not support
+        self.positions_in_unique_constraint.append_null();
+    }
+
+    fn finish(&mut self) -> RecordBatch {
+        let catalog_names = Arc::new(self.catalog_names.finish());
+        let schema_names = Arc::new(self.schema_names.finish());
+        RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                catalog_names.clone(),
+                schema_names.clone(),
+                Arc::new(self.constraint_names.finish()),
+                catalog_names,
+                schema_names,
+                Arc::new(self.table_names.finish()),
+                Arc::new(self.column_names.finish()),
+                Arc::new(self.ordinal_positions.finish()),
+                Arc::new(self.positions_in_unique_constraint.finish()),
+            ],
+        )
+        .unwrap()
+    }
+}
+
+struct InformationSchemaRoutines {
+    schema: SchemaRef,
+    config: InformationSchemaConfig,
+}
+
+impl InformationSchemaRoutines {
+    fn new(config: InformationSchemaConfig) -> Self {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("specific_catalog", DataType::Utf8, false),
+            Field::new("specific_schema", DataType::Utf8, false),
+            Field::new("specific_name", DataType::Utf8, false),
+            Field::new("routine_catalog", DataType::Utf8, false),
+            Field::new("routine_schema", DataType::Utf8, false),
+            Field::new("routine_name", DataType::Utf8, false),
+            Field::new("routine_type", DataType::Utf8, false),
+            Field::new("is_deterministic", DataType::Boolean, true),
+            Field::new("data_type", DataType::Utf8, true),
+            Field::new("function_type", DataType::Utf8, false),
+        ]));
+
+        Self { schema, config }
+    }
+
+    fn builder(&self) -> InformationSchemaRoutinesBuilder {
+        InformationSchemaRoutinesBuilder {
+            catalog_names: StringBuilder::new(),
+            schema_names: StringBuilder::new(),
+            routine_names: StringBuilder::new(),
+            routine_types: StringBuilder::new(),
+            is_deterministics: BooleanBuilder::new(),
+            data_types: StringBuilder::new(),
+            function_types: StringBuilder::new(),
+            schema: self.schema.clone(),
+        }
+    }
+}
+
+impl PartitionStream for InformationSchemaRoutines {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let config = self.config.clone();
+        let mut builder = self.builder();
+        Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            // TODO: Stream this
+            futures::stream::once(async move {
+                config.make_routines(&ctx, &mut builder);
+                Ok(builder.finish())
+            }),
+        ))
+    }
+}
+
+/// Builds the `information_schema.ROUTINES` table row by row
+///
+/// Columns are based on <https://www.postgresql.org/docs/current/infoschema-routines.html>
+struct InformationSchemaRoutinesBuilder {
+    schema: SchemaRef,
+    catalog_names: StringBuilder,
+    schema_names: StringBuilder,
+    routine_names: StringBuilder,
+    routine_types: StringBuilder,
+    is_deterministics: BooleanBuilder,
+    data_types: StringBuilder,
+    function_types: StringBuilder,
+}
+
+impl InformationSchemaRoutinesBuilder {
+    fn add_routine(&mut self, catalog_name: &str, schema_name: &str, routine: &Routine) {
+        // Functions are not overloaded by name, so the specific name of a
+        // routine is its name
+        self.catalog_names.append_value(catalog_name);
+        self.schema_names.append_value(schema_name);
+        self.routine_names.append_value(&routine.name);
+        self.routine_types.append_value("FUNCTION");
+        self.is_deterministics
+            .append_value(routine.volatility == Volatility::Immutable);
+        self.data_types
+            .append_option(routine.return_type().map(|t| format!("{t:?}")));
+        self.function_types.append_value(routine.function_type);
+    }
+
+    fn finish(&mut self) -> RecordBatch {
+        let catalog_names = Arc::new(self.catalog_names.finish());
+        let schema_names = Arc::new(self.schema_names.finish());
+        let routine_names = Arc::new(self.routine_names.finish());
+        RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                catalog_names.clone(),
+                schema_names.clone(),
+                routine_names.clone(),
+                catalog_names,
+                schema_names,
+                routine_names,
+                Arc::new(self.routine_types.finish()),
+                Arc::new(self.is_deterministics.finish()),
+                Arc::new(self.data_types.finish()),
+                Arc::new(self.function_types.finish()),
+            ],
+        )
+        .unwrap()
+    }
+}
+
+struct InformationSchemaParameters {
+    schema: SchemaRef,
+    config: InformationSchemaConfig,
+}
+
+impl InformationSchemaParameters {
+    fn new(config: InformationSchemaConfig) -> Self {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("specific_catalog", DataType::Utf8, false),
+            Field::new("specific_schema", DataType::Utf8, false),
+            Field::new("specific_name", DataType::Utf8, false),
+            Field::new("ordinal_position", DataType::UInt64, false),
+            Field::new("parameter_mode", DataType::Utf8, false),
+            Field::new("parameter_name", DataType::Utf8, true),
+            Field::new("data_type", DataType::Utf8, false),
+            Field::new("is_variadic", DataType::Boolean, false),
+            Field::new("rid", DataType::UInt64, false),
+        ]));
+
+        Self { schema, config }
+    }
+
+    fn builder(&self) -> InformationSchemaParametersBuilder {
+        InformationSchemaParametersBuilder {
+            catalog_names: StringBuilder::new(),
+            schema_names: StringBuilder::new(),
+            routine_names: StringBuilder::new(),
+            ordinal_positions: UInt64Builder::new(),
+            parameter_modes: StringBuilder::new(),
+            parameter_names: StringBuilder::new(),
+            data_types: StringBuilder::new(),
+            is_variadics: BooleanBuilder::new(),
+            rids: UInt64Builder::new(),
+            schema: self.schema.clone(),
+        }
+    }
+}
+
+impl PartitionStream for InformationSchemaParameters {
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    fn execute(&self, ctx: Arc<TaskContext>) -> SendableRecordBatchStream {
+        let config = self.config.clone();
+        let mut builder = self.builder();
+        Box::pin(RecordBatchStreamAdapter::new(
+            self.schema.clone(),
+            // TODO: Stream this
+            futures::stream::once(async move {
+                config.make_parameters(&ctx, &mut builder);
+                Ok(builder.finish())
+            }),
+        ))
+    }
+}
+
+/// Builds the `information_schema.PARAMETERS` table row by row
+///
+/// Columns are based on <https://www.postgresql.org/docs/current/infoschema-parameters.html>,
+/// with an additional `rid` column identifying the argument list of the
+/// routine a parameter belongs to
+struct InformationSchemaParametersBuilder {
+    schema: SchemaRef,
+    catalog_names: StringBuilder,
+    schema_names: StringBuilder,
+    routine_names: StringBuilder,
+    ordinal_positions: UInt64Builder,
+    parameter_modes: StringBuilder,
+    parameter_names: StringBuilder,
+    data_types: StringBuilder,
+    is_variadics: BooleanBuilder,
+    rids: UInt64Builder,
+}
+
+impl InformationSchemaParametersBuilder {
+    #[allow(clippy::too_many_arguments)]
+    fn add_parameter(
+        &mut self,
+        catalog_name: &str,
+        schema_name: &str,
+        routine_name: &str,
+        ordinal_position: usize,
+        parameter_mode: &str,
+        data_type: Option<&DataType>,
+        is_variadic: bool,
+        rid: usize,
+    ) {
+        self.catalog_names.append_value(catalog_name);
+        self.schema_names.append_value(schema_name);
+        self.routine_names.append_value(routine_name);
+        self.ordinal_positions.append_value(ordinal_position as u64);
+        self.parameter_modes.append_value(parameter_mode);
+        // Function parameters are not named
+        self.parameter_names.append_null();
+        // "System supplied type" --> Use debug format of the datatype, `ANY`
+        // for parameters accepting any type
+        match data_type {
+            Some(data_type) => self.data_types.append_value(format!("{data_type:?}")),
+            None => self.data_types.append_value("ANY"),
+        }
+        self.is_variadics.append_value(is_variadic);
+        self.rids.append_value(rid as u64);
+    }
+
+    fn finish(&mut self) -> RecordBatch {
+        RecordBatch::try_new(
+            self.schema.clone(),
+            vec![
+                Arc::new(self.catalog_names.finish()),
+                Arc::new(self.schema_names.finish()),
+                Arc::new(self.routine_names.finish()),
+                Arc::new(self.ordinal_positions.finish()),
+                Arc::new(self.parameter_modes.finish()),
+                Arc::new(self.parameter_names.finish()),
+                Arc::new(self.data_types.finish()),
+                Arc::new(self.is_variadics.finish()),
+                Arc::new(self.rids.finish()),
+            ],
+        )
+        .unwrap()
+    }
+}
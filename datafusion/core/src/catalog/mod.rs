@@ -19,17 +19,25 @@
 
 pub mod information_schema;
 pub mod listing_schema;
+pub mod persistent;
 pub mod schema;
 
 pub use datafusion_sql::{ResolvedTableReference, TableReference};
 
 use crate::catalog::schema::SchemaProvider;
+use async_trait::async_trait;
 use dashmap::DashMap;
 use datafusion_common::{exec_err, not_impl_err, DataFusionError, Result};
 use std::any::Any;
 use std::sync::Arc;
 
 /// Represent a list of named catalogs
+///
+/// Catalogs backed by remote services (e.g. a Hive metastore or a REST
+/// catalog) can resolve them lazily by overriding
+/// [`CatalogList::resolve_catalog`], which is used during planning instead
+/// of [`CatalogList::catalog`].
+#[async_trait]
 pub trait CatalogList: Sync + Send {
     /// Returns the catalog list as [`Any`](std::any::Any)
     /// so that it can be downcast to a specific implementation.
@@ -43,11 +51,35 @@ pub trait CatalogList: Sync + Send {
         catalog: Arc<dyn CatalogProvider>,
     ) -> Option<Arc<dyn CatalogProvider>>;
 
+    /// Adds a new catalog to this catalog list, like
+    /// [`CatalogList::register_catalog`], but returns an error if the catalog
+    /// cannot be added, e.g. because it cannot be persisted.
+    ///
+    /// By default calls [`CatalogList::register_catalog`]
+    fn try_register_catalog(
+        &self,
+        name: String,
+        catalog: Arc<dyn CatalogProvider>,
+    ) -> Result<Option<Arc<dyn CatalogProvider>>> {
+        Ok(self.register_catalog(name, catalog))
+    }
+
     /// Retrieves the list of available catalog names
     fn catalog_names(&self) -> Vec<String>;
 
     /// Retrieves a specific catalog by name, provided it exists.
     fn catalog(&self, name: &str) -> Option<Arc<dyn CatalogProvider>>;
+
+    /// Retrieves a specific catalog by name, provided it exists, without
+    /// blocking the calling thread, e.g. while fetching it over the network.
+    ///
+    /// By default returns [`CatalogList::catalog`]
+    async fn resolve_catalog(
+        &self,
+        name: &str,
+    ) -> Result<Option<Arc<dyn CatalogProvider>>> {
+        Ok(self.catalog(name))
+    }
 }
 
 /// Simple in-memory list of catalogs
@@ -100,6 +132,7 @@ impl Default for MemoryCatalogProvider {
 }
 
 /// Represents a catalog, comprising a number of named schemas.
+#[async_trait]
 pub trait CatalogProvider: Sync + Send {
     /// Returns the catalog provider as [`Any`](std::any::Any)
     /// so that it can be downcast to a specific implementation.
@@ -111,6 +144,18 @@ pub trait CatalogProvider: Sync + Send {
     /// Retrieves a specific schema from the catalog by name, provided it exists.
     fn schema(&self, name: &str) -> Option<Arc<dyn SchemaProvider>>;
 
+    /// Retrieves a specific schema from the catalog by name, provided it
+    /// exists, without blocking the calling thread, e.g. while fetching it
+    /// over the network.
+    ///
+    /// By default returns [`CatalogProvider::schema`]
+    async fn resolve_schema(
+        &self,
+        name: &str,
+    ) -> Result<Option<Arc<dyn SchemaProvider>>> {
+        Ok(self.schema(name))
+    }
+
     /// Adds a new schema to this catalog.
     ///
     /// If a schema of the same name existed before, it is replaced in
@@ -209,10 +254,13 @@ impl CatalogProvider for MemoryCatalogProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::assert_batches_eq;
     use crate::catalog::schema::MemorySchemaProvider;
     use crate::datasource::empty::EmptyTable;
     use crate::datasource::TableProvider;
-    use arrow::datatypes::Schema;
+    use crate::execution::context::{SessionConfig, SessionContext, SessionState};
+    use crate::execution::runtime_env::RuntimeEnv;
+    use arrow::datatypes::{DataType, Field, Schema};
 
     #[test]
     fn default_register_schema_not_supported() {
@@ -224,11 +272,11 @@ mod tests {
             }
 
             fn schema_names(&self) -> Vec<String> {
-                unimplemented!()
+                vec![]
             }
 
             fn schema(&self, _name: &str) -> Option<Arc<dyn SchemaProvider>> {
-                unimplemented!()
+                None
             }
         }
 
@@ -274,4 +322,156 @@ mod tests {
         let cat = Arc::new(MemoryCatalogProvider::new()) as Arc<dyn CatalogProvider>;
         assert!(cat.deregister_schema("foo", false).unwrap().is_none());
     }
+
+    /// A catalog list whose catalogs, schemas and tables can only be
+    /// resolved asynchronously, like those of a remote metastore
+    struct RemoteCatalogList {
+        schema: Arc<RemoteSchema>,
+    }
+
+    #[async_trait]
+    impl CatalogList for RemoteCatalogList {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn register_catalog(
+            &self,
+            _name: String,
+            _catalog: Arc<dyn CatalogProvider>,
+        ) -> Option<Arc<dyn CatalogProvider>> {
+            None
+        }
+
+        fn try_register_catalog(
+            &self,
+            _name: String,
+            _catalog: Arc<dyn CatalogProvider>,
+        ) -> Result<Option<Arc<dyn CatalogProvider>>> {
+            not_impl_err!("Registering new catalogs is not supported")
+        }
+
+        fn catalog_names(&self) -> Vec<String> {
+            vec!["remote".to_string()]
+        }
+
+        fn catalog(&self, _name: &str) -> Option<Arc<dyn CatalogProvider>> {
+            None
+        }
+
+        async fn resolve_catalog(
+            &self,
+            name: &str,
+        ) -> Result<Option<Arc<dyn CatalogProvider>>> {
+            tokio::task::yield_now().await;
+            let catalog = RemoteCatalog {
+                schema: self.schema.clone(),
+            };
+            Ok((name == "remote").then(|| Arc::new(catalog) as _))
+        }
+    }
+
+    struct RemoteCatalog {
+        schema: Arc<RemoteSchema>,
+    }
+
+    #[async_trait]
+    impl CatalogProvider for RemoteCatalog {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn schema_names(&self) -> Vec<String> {
+            vec!["db".to_string()]
+        }
+
+        fn schema(&self, _name: &str) -> Option<Arc<dyn SchemaProvider>> {
+            None
+        }
+
+        async fn resolve_schema(
+            &self,
+            name: &str,
+        ) -> Result<Option<Arc<dyn SchemaProvider>>> {
+            tokio::task::yield_now().await;
+            Ok((name == "db").then(|| self.schema.clone() as _))
+        }
+    }
+
+    #[derive(Default)]
+    struct RemoteSchema {
+        fetched: parking_lot::Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl SchemaProvider for RemoteSchema {
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        fn table_names(&self) -> Vec<String> {
+            vec![]
+        }
+
+        async fn resolve_table_names(&self) -> Result<Vec<String>> {
+            tokio::task::yield_now().await;
+            Ok(vec!["t1".to_string(), "t2".to_string()])
+        }
+
+        async fn table(&self, name: &str) -> Option<Arc<dyn TableProvider>> {
+            tokio::task::yield_now().await;
+            self.fetched.lock().push(name.to_string());
+            let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+            Some(Arc::new(EmptyTable::new(Arc::new(schema))))
+        }
+
+        fn table_exist(&self, _name: &str) -> bool {
+            false
+        }
+    }
+
+    #[tokio::test]
+    async fn async_catalog_resolution() -> Result<()> {
+        let schema = Arc::new(RemoteSchema::default());
+        let catalog_list = Arc::new(RemoteCatalogList {
+            schema: schema.clone(),
+        });
+        let config = SessionConfig::new()
+            .with_create_default_catalog_and_schema(false)
+            .with_information_schema(true);
+        let state = SessionState::with_config_rt_and_catalog_list(
+            config,
+            Arc::new(RuntimeEnv::default()),
+            catalog_list,
+        );
+        let ctx = SessionContext::with_state(state);
+
+        // only the referenced table is fetched
+        let batches = ctx
+            .sql("SELECT a FROM remote.db.t2")
+            .await?
+            .collect()
+            .await?;
+        assert!(batches.iter().all(|b| b.num_rows() == 0));
+        assert_eq!(*schema.fetched.lock(), ["t2"]);
+
+        let batches = ctx
+            .sql(
+                "SELECT table_name FROM remote.information_schema.tables \
+                 WHERE table_schema = 'db' ORDER BY table_name",
+            )
+            .await?
+            .collect()
+            .await?;
+        let expected = [
+            "+------------+",
+            "| table_name |",
+            "+------------+",
+            "| t1         |",
+            "| t2         |",
+            "+------------+",
+        ];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
 }
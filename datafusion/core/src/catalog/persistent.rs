@@ -0,0 +1,749 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`PersistentCatalogList`]: a [`CatalogList`] whose catalogs, schemas and
+//! table definitions are persisted to a local JSON file
+
+use crate::catalog::schema::{MemorySchemaProvider, SchemaProvider};
+use crate::catalog::{CatalogList, CatalogProvider, MemoryCatalogProvider};
+use crate::datasource::TableProvider;
+use crate::execution::context::SessionContext;
+use async_trait::async_trait;
+use dashmap::DashMap;
+use datafusion_common::{
+    exec_err, plan_err, DataFusionError, OwnedTableReference, Result, Statistics,
+};
+use datafusion_expr::{
+    CreateExternalTable, CreateView, DdlStatement, LogicalPlan, TableType,
+};
+use log::warn;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::any::Any;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// The version of the format of the metadata file
+const METADATA_VERSION: u32 = 1;
+
+/// A [`CatalogList`] that persists its catalogs, schemas and the
+/// definitions of its tables and views to a local JSON file, so that they
+/// survive the process and can be shared by several sessions.
+///
+/// Every `CREATE DATABASE`, `CREATE SCHEMA`, `CREATE EXTERNAL TABLE` and
+/// `CREATE VIEW` (and the matching `DROP` statements) is written to the
+/// file as it is executed. Only the SQL definition of a table is stored:
+/// it includes its location, file format, options and partition columns,
+/// and the table is created again from it by [`Self::load`]. Tables without
+/// a definition, such as those created with `CREATE TABLE` or registered
+/// programmatically, are only kept in memory.
+///
+/// ```no_run
+/// # use datafusion::catalog::persistent::PersistentCatalogList;
+/// # use datafusion::error::Result;
+/// # use datafusion::execution::context::{SessionConfig, SessionContext, SessionState};
+/// # use datafusion::execution::runtime_env::RuntimeEnv;
+/// # use std::sync::Arc;
+/// # #[tokio::main]
+/// # async fn main() -> Result<()> {
+/// let path = "/var/lib/datafusion/catalog.json";
+/// let catalog_list = Arc::new(PersistentCatalogList::try_new(path)?);
+/// let state = SessionState::with_config_rt_and_catalog_list(
+///     SessionConfig::new(),
+///     Arc::new(RuntimeEnv::default()),
+///     catalog_list.clone(),
+/// );
+/// let ctx = SessionContext::with_state(state);
+/// // re-create the tables and views defined by previous sessions
+/// catalog_list.load(&ctx).await?;
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Relative table locations are resolved against the working directory of
+/// the process loading the definitions, so absolute locations should be
+/// preferred.
+pub struct PersistentCatalogList {
+    catalogs: DashMap<String, Arc<dyn CatalogProvider>>,
+    store: Arc<MetadataStore>,
+}
+
+impl PersistentCatalogList {
+    /// Create a new [`PersistentCatalogList`] persisted to the JSON file at
+    /// `path`, reading the definitions already stored in it, if any.
+    ///
+    /// The definitions are only turned into tables by [`Self::load`].
+    pub fn try_new(path: impl Into<PathBuf>) -> Result<Self> {
+        Ok(Self {
+            catalogs: DashMap::new(),
+            store: Arc::new(MetadataStore::try_new(path.into())?),
+        })
+    }
+
+    /// Returns the path of the file the catalogs are persisted to
+    pub fn path(&self) -> &Path {
+        &self.store.path
+    }
+
+    /// Create the persisted catalogs, schemas, tables and views that do not
+    /// exist yet in `ctx`, which must use this catalog list
+    pub async fn load(&self, ctx: &SessionContext) -> Result<()> {
+        let metadata = self.store.metadata.lock().clone();
+
+        // tables and views are created once all the schemas exist, as views
+        // may reference tables of any schema
+        let mut definitions = vec![];
+        for (catalog_name, catalog_metadata) in metadata.catalogs {
+            let catalog = match self.catalog(&catalog_name) {
+                Some(catalog) => catalog,
+                None => {
+                    let catalog = Arc::new(MemoryCatalogProvider::new());
+                    self.try_register_catalog(catalog_name.clone(), catalog)?;
+                    self.catalog(&catalog_name).ok_or_else(|| {
+                        DataFusionError::Internal(format!(
+                            "Catalog {catalog_name} was not registered"
+                        ))
+                    })?
+                }
+            };
+
+            for (schema_name, schema_metadata) in catalog_metadata.schemas {
+                let schema = match catalog.schema(&schema_name) {
+                    Some(schema) => schema,
+                    None => {
+                        let schema = Arc::new(MemorySchemaProvider::new());
+                        catalog.register_schema(&schema_name, schema)?;
+                        catalog.schema(&schema_name).ok_or_else(|| {
+                            DataFusionError::Internal(format!(
+                                "Schema {schema_name} was not registered"
+                            ))
+                        })?
+                    }
+                };
+
+                for table in schema_metadata.tables {
+                    if schema.table_exist(&table.name) {
+                        continue;
+                    }
+                    let name = OwnedTableReference::full(
+                        catalog_name.clone(),
+                        schema_name.clone(),
+                        table.name,
+                    );
+                    definitions.push((name, table.definition));
+                }
+            }
+        }
+
+        // a view can only be planned once the tables and views it references
+        // exist, so retry the failed definitions for as long as some succeed
+        while !definitions.is_empty() {
+            let mut failed = vec![];
+            let mut last_error = None;
+            let num_definitions = definitions.len();
+            for (name, definition) in definitions {
+                if let Err(e) = Self::create_table(ctx, &name, &definition).await {
+                    last_error = Some(e);
+                    failed.push((name, definition));
+                }
+            }
+
+            if failed.len() == num_definitions {
+                return Err(last_error.unwrap());
+            }
+            definitions = failed;
+        }
+        Ok(())
+    }
+
+    /// Create the table or view `name` from its SQL `definition`
+    async fn create_table(
+        ctx: &SessionContext,
+        name: &OwnedTableReference,
+        definition: &str,
+    ) -> Result<()> {
+        let plan = match ctx.state().create_logical_plan(definition).await? {
+            LogicalPlan::Ddl(DdlStatement::CreateExternalTable(cmd)) => {
+                LogicalPlan::Ddl(DdlStatement::CreateExternalTable(CreateExternalTable {
+                    name: name.clone(),
+                    if_not_exists: true,
+                    ..cmd
+                }))
+            }
+            LogicalPlan::Ddl(DdlStatement::CreateView(cmd)) => {
+                LogicalPlan::Ddl(DdlStatement::CreateView(CreateView {
+                    name: name.clone(),
+                    or_replace: false,
+                    ..cmd
+                }))
+            }
+            _ => return plan_err!("Invalid definition of table {name}: {definition}"),
+        };
+        ctx.execute_logical_plan(plan).await?;
+        Ok(())
+    }
+}
+
+impl CatalogList for PersistentCatalogList {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    /// Registers `catalog` even if it cannot be persisted, in which case it
+    /// is only kept in memory. Use [`CatalogList::try_register_catalog`] to
+    /// get the error instead.
+    fn register_catalog(
+        &self,
+        name: String,
+        catalog: Arc<dyn CatalogProvider>,
+    ) -> Option<Arc<dyn CatalogProvider>> {
+        match self.try_register_catalog(name.clone(), Arc::clone(&catalog)) {
+            Ok(previous) => previous,
+            Err(e) => {
+                warn!("Failed to persist catalog {name}, keeping it in memory: {e}");
+                let catalog = PersistentCatalogProvider::new(&name, catalog, &self.store);
+                self.catalogs.insert(name, Arc::new(catalog))
+            }
+        }
+    }
+
+    fn try_register_catalog(
+        &self,
+        name: String,
+        catalog: Arc<dyn CatalogProvider>,
+    ) -> Result<Option<Arc<dyn CatalogProvider>>> {
+        let catalog = PersistentCatalogProvider::new(&name, catalog, &self.store);
+        let schema_names = catalog.inner.schema_names();
+        self.store.update_and_register(
+            |metadata| {
+                let mut changed = !metadata.catalogs.contains_key(&name);
+                let catalog = metadata.catalogs.entry(name.clone()).or_default();
+                for schema_name in schema_names {
+                    changed |= insert_schema(catalog, &schema_name);
+                }
+                changed
+            },
+            || Ok(self.catalogs.insert(name.clone(), Arc::new(catalog))),
+        )
+    }
+
+    fn catalog_names(&self) -> Vec<String> {
+        self.catalogs.iter().map(|c| c.key().clone()).collect()
+    }
+
+    fn catalog(&self, name: &str) -> Option<Arc<dyn CatalogProvider>> {
+        self.catalogs.get(name).map(|c| c.value().clone())
+    }
+}
+
+/// A [`CatalogProvider`] wrapping another one to persist the schemas that
+/// are registered with it, see [`PersistentCatalogList`]
+pub struct PersistentCatalogProvider {
+    name: String,
+    inner: Arc<dyn CatalogProvider>,
+    store: Arc<MetadataStore>,
+}
+
+impl PersistentCatalogProvider {
+    fn new(
+        name: &str,
+        inner: Arc<dyn CatalogProvider>,
+        store: &Arc<MetadataStore>,
+    ) -> Self {
+        // avoid wrapping catalogs that are already persisted
+        let inner = match inner.as_any().downcast_ref::<Self>() {
+            Some(persistent) => Arc::clone(&persistent.inner),
+            None => inner,
+        };
+        Self {
+            name: name.to_string(),
+            inner,
+            store: Arc::clone(store),
+        }
+    }
+
+    /// Returns the wrapped [`CatalogProvider`]
+    pub fn inner(&self) -> &Arc<dyn CatalogProvider> {
+        &self.inner
+    }
+
+    fn wrap(
+        &self,
+        schema_name: &str,
+        schema: Arc<dyn SchemaProvider>,
+    ) -> Arc<dyn SchemaProvider> {
+        Arc::new(PersistentSchemaProvider {
+            catalog_name: self.name.clone(),
+            schema_name: schema_name.to_string(),
+            inner: schema,
+            store: Arc::clone(&self.store),
+        })
+    }
+}
+
+#[async_trait]
+impl CatalogProvider for PersistentCatalogProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema_names(&self) -> Vec<String> {
+        self.inner.schema_names()
+    }
+
+    fn schema(&self, name: &str) -> Option<Arc<dyn SchemaProvider>> {
+        self.inner
+            .schema(name)
+            .map(|schema| self.wrap(name, schema))
+    }
+
+    async fn resolve_schema(
+        &self,
+        name: &str,
+    ) -> Result<Option<Arc<dyn SchemaProvider>>> {
+        let schema = self.inner.resolve_schema(name).await?;
+        Ok(schema.map(|schema| self.wrap(name, schema)))
+    }
+
+    fn register_schema(
+        &self,
+        name: &str,
+        schema: Arc<dyn SchemaProvider>,
+    ) -> Result<Option<Arc<dyn SchemaProvider>>> {
+        let schema = match schema.as_any().downcast_ref::<PersistentSchemaProvider>() {
+            Some(persistent) => Arc::clone(&persistent.inner),
+            None => schema,
+        };
+
+        let previous = self.store.update_and_register(
+            |metadata| {
+                let catalog = metadata.catalogs.entry(self.name.clone()).or_default();
+                insert_schema(catalog, name)
+            },
+            || self.inner.register_schema(name, schema),
+        )?;
+        Ok(previous.map(|schema| self.wrap(name, schema)))
+    }
+
+    fn deregister_schema(
+        &self,
+        name: &str,
+        cascade: bool,
+    ) -> Result<Option<Arc<dyn SchemaProvider>>> {
+        if self.inner.schema(name).is_none() {
+            return Ok(None);
+        }
+        let removed = self.store.update_and_register(
+            |metadata| {
+                metadata
+                    .catalogs
+                    .get_mut(&self.name)
+                    .map(|catalog| catalog.schemas.remove(name).is_some())
+                    .unwrap_or(false)
+            },
+            || self.inner.deregister_schema(name, cascade),
+        )?;
+        Ok(removed.map(|schema| self.wrap(name, schema)))
+    }
+}
+
+/// A [`SchemaProvider`] wrapping another one to persist the definitions of
+/// the tables that are registered with it, see [`PersistentCatalogList`]
+pub struct PersistentSchemaProvider {
+    catalog_name: String,
+    schema_name: String,
+    inner: Arc<dyn SchemaProvider>,
+    store: Arc<MetadataStore>,
+}
+
+impl PersistentSchemaProvider {
+    /// Returns the wrapped [`SchemaProvider`]
+    pub fn inner(&self) -> &Arc<dyn SchemaProvider> {
+        &self.inner
+    }
+
+    /// Apply `f` to the persisted metadata of this schema, then call
+    /// `register`, see [`MetadataStore::update_and_register`]
+    fn update_and_register<T>(
+        &self,
+        f: impl FnOnce(&mut SchemaMetadata) -> bool,
+        register: impl FnOnce() -> Result<T>,
+    ) -> Result<T> {
+        self.store.update_and_register(
+            |metadata| {
+                let schema = metadata
+                    .catalogs
+                    .entry(self.catalog_name.clone())
+                    .or_default()
+                    .schemas
+                    .entry(self.schema_name.clone())
+                    .or_default();
+                f(schema)
+            },
+            register,
+        )
+    }
+}
+
+#[async_trait]
+impl SchemaProvider for PersistentSchemaProvider {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn table_names(&self) -> Vec<String> {
+        self.inner.table_names()
+    }
+
+    async fn resolve_table_names(&self) -> Result<Vec<String>> {
+        self.inner.resolve_table_names().await
+    }
+
+    async fn table(&self, name: &str) -> Option<Arc<dyn TableProvider>> {
+        self.inner.table(name).await
+    }
+
+    fn register_table(
+        &self,
+        name: String,
+        table: Arc<dyn TableProvider>,
+    ) -> Result<Option<Arc<dyn TableProvider>>> {
+        let definition = persisted_definition(table.as_ref());
+        self.update_and_register(
+            |schema| match definition {
+                Some(definition) => schema.set_table(name.clone(), definition),
+                None => schema.remove_table(&name),
+            },
+            || self.inner.register_table(name.clone(), table),
+        )
+    }
+
+    fn deregister_table(&self, name: &str) -> Result<Option<Arc<dyn TableProvider>>> {
+        if !self.inner.table_exist(name) {
+            return Ok(None);
+        }
+        self.update_and_register(
+            |schema| schema.remove_table(name),
+            || self.inner.deregister_table(name),
+        )
+    }
+
+    fn table_exist(&self, name: &str) -> bool {
+        self.inner.table_exist(name)
+    }
+
+    fn table_statistics(&self, name: &str) -> Option<Statistics> {
+        self.inner.table_statistics(name)
+    }
+
+    fn register_table_statistics(
+        &self,
+        name: &str,
+        statistics: Statistics,
+    ) -> Result<()> {
+        self.inner.register_table_statistics(name, statistics)
+    }
+
+    fn deregister_table_statistics(&self, name: &str) -> Result<Option<Statistics>> {
+        self.inner.deregister_table_statistics(name)
+    }
+}
+
+/// Returns the definition to persist for `table`, if any
+fn persisted_definition(table: &dyn TableProvider) -> Option<String> {
+    match table.table_type() {
+        TableType::Base | TableType::View => {
+            table.get_table_definition().map(str::to_string)
+        }
+        TableType::Temporary => None,
+    }
+}
+
+/// The contents of the metadata file of a [`PersistentCatalogList`]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct Metadata {
+    version: u32,
+    catalogs: BTreeMap<String, CatalogMetadata>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct CatalogMetadata {
+    schemas: BTreeMap<String, SchemaMetadata>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+struct SchemaMetadata {
+    /// The tables and views of the schema, in the order they were created
+    tables: Vec<TableMetadata>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct TableMetadata {
+    name: String,
+    /// The SQL statement creating the table or view
+    definition: String,
+}
+
+impl SchemaMetadata {
+    /// Set the definition of the table `name`, returning true if it changed
+    fn set_table(&mut self, name: String, definition: String) -> bool {
+        if let Some(table) = self.tables.iter().find(|t| t.name == name) {
+            if table.definition == definition {
+                return false;
+            }
+        }
+        // a replaced table moves to the end, after the tables it may reference
+        self.remove_table(&name);
+        self.tables.push(TableMetadata { name, definition });
+        true
+    }
+
+    /// Remove the table `name`, returning true if it existed
+    fn remove_table(&mut self, name: &str) -> bool {
+        let len = self.tables.len();
+        self.tables.retain(|t| t.name != name);
+        self.tables.len() != len
+    }
+}
+
+/// Add the schema `name` to `catalog` if it is not persisted yet, keeping
+/// the tables of an existing schema. Returns true if it was added.
+fn insert_schema(catalog: &mut CatalogMetadata, name: &str) -> bool {
+    if catalog.schemas.contains_key(name) {
+        return false;
+    }
+    catalog
+        .schemas
+        .insert(name.to_string(), SchemaMetadata::default());
+    true
+}
+
+/// The metadata of a [`PersistentCatalogList`] and the file it is
+/// persisted to
+struct MetadataStore {
+    path: PathBuf,
+    metadata: Mutex<Metadata>,
+}
+
+impl MetadataStore {
+    fn try_new(path: PathBuf) -> Result<Self> {
+        let metadata = if path.exists() {
+            let file = std::fs::File::open(&path)?;
+            let metadata: Metadata = serde_json::from_reader(file).map_err(|e| {
+                DataFusionError::Execution(format!(
+                    "Failed to read catalog metadata from {}: {e}",
+                    path.display()
+                ))
+            })?;
+            if metadata.version > METADATA_VERSION {
+                return exec_err!(
+                    "Catalog metadata in {} has version {}, but only versions up to \
+                     {METADATA_VERSION} are supported",
+                    path.display(),
+                    metadata.version
+                );
+            }
+            metadata
+        } else {
+            Metadata {
+                version: METADATA_VERSION,
+                ..Default::default()
+            }
+        };
+
+        Ok(Self {
+            path,
+            metadata: Mutex::new(metadata),
+        })
+    }
+
+    /// Apply `f` to the metadata and write it to the file if `f` returns
+    /// true, then call `register` to make the change in memory.
+    ///
+    /// Nothing is registered if the metadata cannot be written, and the
+    /// previous metadata is written back if `register` fails, so that the
+    /// file only holds what is registered.
+    fn update_and_register<T>(
+        &self,
+        f: impl FnOnce(&mut Metadata) -> bool,
+        register: impl FnOnce() -> Result<T>,
+    ) -> Result<T> {
+        let mut metadata = self.metadata.lock();
+        let mut updated = metadata.clone();
+        let changed = f(&mut updated);
+        if changed {
+            self.write(&updated)?;
+        }
+
+        match register() {
+            Ok(value) => {
+                *metadata = updated;
+                Ok(value)
+            }
+            Err(e) => {
+                if changed {
+                    self.write(&metadata)?;
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Write `metadata` to the file
+    fn write(&self, metadata: &Metadata) -> Result<()> {
+        let json = serde_json::to_vec_pretty(metadata)
+            .map_err(|e| DataFusionError::External(Box::new(e)))?;
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        // write to a temporary file first, so that the metadata is never
+        // left half written
+        let tmp_path = self.path.with_extension("tmp");
+        std::fs::write(&tmp_path, json)?;
+        std::fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_eq;
+    use crate::execution::context::{SessionConfig, SessionState};
+    use crate::execution::runtime_env::RuntimeEnv;
+    use tempfile::TempDir;
+
+    fn persistent_context(
+        path: &Path,
+    ) -> Result<(SessionContext, Arc<PersistentCatalogList>)> {
+        let catalog_list = Arc::new(PersistentCatalogList::try_new(path)?);
+        let state = SessionState::with_config_rt_and_catalog_list(
+            SessionConfig::new(),
+            Arc::new(RuntimeEnv::default()),
+            catalog_list.clone(),
+        );
+        Ok((SessionContext::with_state(state), catalog_list))
+    }
+
+    #[tokio::test]
+    async fn test_persistent_catalog() -> Result<()> {
+        let dir = TempDir::new()?;
+        let data = dir.path().join("data.csv");
+        std::fs::write(&data, "a|b\n1|x\n2|y\n")?;
+        let path = dir.path().join("catalog").join("catalog.json");
+
+        let (ctx, _) = persistent_context(&path)?;
+        ctx.sql("CREATE SCHEMA s").await?;
+        ctx.sql(&format!(
+            "CREATE EXTERNAL TABLE t (a INT, b VARCHAR) STORED AS CSV \
+             WITH HEADER ROW DELIMITER '|' LOCATION '{}'",
+            data.display()
+        ))
+        .await?;
+        ctx.sql("CREATE VIEW s.v AS SELECT b FROM t WHERE a > 1")
+            .await?;
+        ctx.sql("CREATE TABLE m AS VALUES (1)").await?;
+        drop(ctx);
+
+        // a new session re-creates the persisted schemas, tables and views
+        let (ctx, catalog_list) = persistent_context(&path)?;
+        catalog_list.load(&ctx).await?;
+
+        let batches = ctx
+            .sql("SELECT * FROM t ORDER BY a")
+            .await?
+            .collect()
+            .await?;
+        let expected = [
+            "+---+---+",
+            "| a | b |",
+            "+---+---+",
+            "| 1 | x |",
+            "| 2 | y |",
+            "+---+---+",
+        ];
+        assert_batches_eq!(expected, &batches);
+
+        let batches = ctx.sql("SELECT * FROM s.v").await?.collect().await?;
+        let expected = ["+---+", "| b |", "+---+", "| y |", "+---+"];
+        assert_batches_eq!(expected, &batches);
+
+        // memory tables have no definition and are not persisted
+        assert!(ctx.table("m").await.is_err());
+
+        // loading again is a no-op
+        catalog_list.load(&ctx).await?;
+
+        ctx.sql("DROP VIEW s.v").await?;
+        drop(ctx);
+
+        let (ctx, catalog_list) = persistent_context(&path)?;
+        catalog_list.load(&ctx).await?;
+        assert!(ctx.table("s.v").await.is_err());
+        assert!(ctx.table("t").await.is_ok());
+        assert!(ctx.catalog("datafusion").unwrap().schema("s").is_some());
+
+        ctx.sql("DROP SCHEMA s").await?;
+        drop(ctx);
+
+        let (ctx, catalog_list) = persistent_context(&path)?;
+        catalog_list.load(&ctx).await?;
+        assert!(ctx.catalog("datafusion").unwrap().schema("s").is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_persistent_catalog_write_failure() -> Result<()> {
+        let dir = TempDir::new()?;
+        let data = dir.path().join("data.csv");
+        std::fs::write(&data, "a|b\n1|x\n")?;
+        // the parent of the metadata file is a file, so it cannot be written
+        let path = data.join("catalog.json");
+
+        let (ctx, _) = persistent_context(&path)?;
+        assert!(ctx.sql("CREATE DATABASE c").await.is_err());
+        assert!(ctx.catalog("c").is_none());
+
+        assert!(ctx.sql("CREATE SCHEMA s").await.is_err());
+        assert!(ctx.catalog("datafusion").unwrap().schema("s").is_none());
+
+        let sql = format!(
+            "CREATE EXTERNAL TABLE t (a INT, b VARCHAR) STORED AS CSV \
+             WITH HEADER ROW DELIMITER '|' LOCATION '{}'",
+            data.display()
+        );
+        assert!(ctx.sql(&sql).await.is_err());
+        assert!(ctx.table("t").await.is_err());
+        assert!(!path.exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_persistent_catalog_invalid_file() -> Result<()> {
+        let dir = TempDir::new()?;
+        let path = dir.path().join("catalog.json");
+        std::fs::write(&path, "not json")?;
+
+        let err = PersistentCatalogList::try_new(&path).err().unwrap();
+        assert!(
+            err.to_string().contains("Failed to read catalog metadata"),
+            "{err}"
+        );
+        Ok(())
+    }
+}
@@ -26,6 +26,7 @@ use std::sync::Arc;
 
 use crate::datasource::TableProvider;
 use crate::error::{DataFusionError, Result};
+use crate::physical_plan::Statistics;
 
 /// Represents a schema, comprising a number of named tables.
 #[async_trait]
@@ -37,6 +38,14 @@ pub trait SchemaProvider: Sync + Send {
     /// Retrieves the list of available table names in this schema.
     fn table_names(&self) -> Vec<String>;
 
+    /// Retrieves the list of available table names in this schema without
+    /// blocking the calling thread, e.g. while fetching it over the network.
+    ///
+    /// By default returns [`SchemaProvider::table_names`]
+    async fn resolve_table_names(&self) -> Result<Vec<String>> {
+        Ok(self.table_names())
+    }
+
     /// Retrieves a specific table from the schema by name, provided it exists.
     async fn table(&self, name: &str) -> Option<Arc<dyn TableProvider>>;
 
@@ -62,11 +71,37 @@ pub trait SchemaProvider: Sync + Send {
     /// If no matched table in the schema provider, return false.
     /// Otherwise, return true.
     fn table_exist(&self, name: &str) -> bool;
+
+    /// Retrieves the statistics collected for a table by `ANALYZE TABLE`, if any.
+    #[allow(unused_variables)]
+    fn table_statistics(&self, name: &str) -> Option<Statistics> {
+        None
+    }
+
+    /// If supported by the implementation, stores the statistics collected for
+    /// an existing table by `ANALYZE TABLE`, replacing any previous ones.
+    #[allow(unused_variables)]
+    fn register_table_statistics(
+        &self,
+        name: &str,
+        statistics: Statistics,
+    ) -> Result<()> {
+        exec_err!("schema provider does not support registering table statistics")
+    }
+
+    /// If supported by the implementation, removes the statistics collected for
+    /// a table by `ANALYZE TABLE`, e.g. because its data changed, and returns
+    /// them. If the table has no statistics, returns Ok(None).
+    #[allow(unused_variables)]
+    fn deregister_table_statistics(&self, name: &str) -> Result<Option<Statistics>> {
+        Ok(None)
+    }
 }
 
 /// Simple in-memory implementation of a schema.
 pub struct MemorySchemaProvider {
     tables: DashMap<String, Arc<dyn TableProvider>>,
+    statistics: DashMap<String, Statistics>,
 }
 
 impl MemorySchemaProvider {
@@ -74,6 +109,7 @@ impl MemorySchemaProvider {
     pub fn new() -> Self {
         Self {
             tables: DashMap::new(),
+            statistics: DashMap::new(),
         }
     }
 }
@@ -113,12 +149,36 @@ impl SchemaProvider for MemorySchemaProvider {
     }
 
     fn deregister_table(&self, name: &str) -> Result<Option<Arc<dyn TableProvider>>> {
+        self.statistics.remove(name);
         Ok(self.tables.remove(name).map(|(_, table)| table))
     }
 
     fn table_exist(&self, name: &str) -> bool {
         self.tables.contains_key(name)
     }
+
+    fn table_statistics(&self, name: &str) -> Option<Statistics> {
+        self.statistics.get(name).map(|s| s.value().clone())
+    }
+
+    fn register_table_statistics(
+        &self,
+        name: &str,
+        statistics: Statistics,
+    ) -> Result<()> {
+        if !self.table_exist(name) {
+            return exec_err!("The table {name} doesn't exist");
+        }
+        self.statistics.insert(name.to_string(), statistics);
+        Ok(())
+    }
+
+    fn deregister_table_statistics(&self, name: &str) -> Result<Option<Statistics>> {
+        Ok(self
+            .statistics
+            .remove(name)
+            .map(|(_, statistics)| statistics))
+    }
 }
 
 #[cfg(test)]
@@ -51,7 +51,8 @@ use crate::datasource::{provider_as_source, MemTable, TableProvider};
 use crate::error::Result;
 use crate::execution::{
     context::{SessionState, TaskContext},
-    FunctionRegistry,
+    query_handle::{QueryHandle, StatementTimeout},
+    CancellationToken, FunctionRegistry,
 };
 use crate::logical_expr::{
     col, utils::find_window_exprs, Expr, JoinType, LogicalPlan, LogicalPlanBuilder,
@@ -244,6 +245,43 @@ impl DataFrame {
         Ok(DataFrame::new(self.session_state, plan))
     }
 
+    /// Expand each list element of several columns to multiple rows,
+    /// zipping the elements of the lists of each row together.
+    ///
+    /// Struct columns are expanded into one column per field.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.read_csv("tests/data/example.csv", CsvReadOptions::new()).await?;
+    /// let df = df.unnest_columns(&["a", "b"])?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn unnest_columns(self, columns: &[&str]) -> Result<DataFrame> {
+        self.unnest_columns_with_options(columns, UnnestOptions::new())
+    }
+
+    /// Expand each list element of several columns to multiple rows, with
+    /// behavior controlled by [`UnnestOptions`].
+    ///
+    /// Please see the documentation on [`UnnestOptions`] for more
+    /// details about the meaning of unnest.
+    pub fn unnest_columns_with_options(
+        self,
+        columns: &[&str],
+        options: UnnestOptions,
+    ) -> Result<DataFrame> {
+        let columns = columns.iter().map(|c| Column::from(*c)).collect();
+        let plan = LogicalPlanBuilder::from(self.plan)
+            .unnest_columns_with_options(columns, options)?
+            .build()?;
+        Ok(DataFrame::new(self.session_state, plan))
+    }
+
     /// Filter a DataFrame to only include rows that match the specified filter expression.
     ///
     /// ```
@@ -806,6 +844,47 @@ impl DataFrame {
         execute_stream(plan, task_ctx)
     }
 
+    /// Executes this DataFrame and returns a cancellable [`QueryHandle`]
+    /// streaming its results.
+    ///
+    /// If `datafusion.execution.statement_timeout` is set, the query is
+    /// cancelled once it has been running (including planning) for longer
+    /// than the timeout.
+    ///
+    /// ```
+    /// # use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let df = ctx.read_csv("tests/data/example.csv", CsvReadOptions::new()).await?;
+    /// let handle = df.execute_with_handle().await?;
+    /// // can also be cancelled from another task with
+    /// // `handle.cancellation_token().clone()`
+    /// handle.cancel();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn execute_with_handle(self) -> Result<QueryHandle> {
+        let token = CancellationToken::new();
+        let timeout_ms = self
+            .session_state
+            .config_options()
+            .execution
+            .statement_timeout;
+        let timeout = StatementTimeout::start(&token, timeout_ms);
+
+        let task_ctx = Arc::new(self.task_ctx().with_cancellation_token(token.clone()));
+        let plan = self.create_physical_plan().await?;
+        token.check()?;
+
+        let spill_usage = task_ctx.spill_usage().clone();
+        let stream = execute_stream(plan, task_ctx)?;
+        Ok(QueryHandle::new(stream, token)
+            .with_timeout(timeout)
+            .with_spill_usage(spill_usage))
+    }
+
     /// Executes this DataFrame and collects all results into a vector of vector of RecordBatch
     /// maintaining the input partitioning.
     ///
@@ -27,7 +27,9 @@ use arrow::csv::WriterBuilder;
 use arrow::datatypes::{DataType, Field, Fields, Schema};
 use arrow::{self, datatypes::SchemaRef};
 use arrow_array::RecordBatch;
-use datafusion_common::{exec_err, not_impl_err, DataFusionError, FileType};
+use datafusion_common::{
+    exec_err, not_impl_err, DataFusionError, FileType, FileTypeWriterOptions,
+};
 use datafusion_execution::TaskContext;
 use datafusion_physical_expr::PhysicalExpr;
 
@@ -268,8 +270,15 @@ impl FileFormat for CsvFormat {
             return not_impl_err!("Overwrites are not implemented yet for CSV");
         }
 
-        if self.file_compression_type != FileCompressionType::UNCOMPRESSED {
-            return not_impl_err!("Inserting compressed CSV is not implemented yet.");
+        // Files written to a table must be readable by its scans, so the
+        // compression configured on the format wins over the writer default
+        let mut conf = conf;
+        if self.file_compression_type.is_compressed() {
+            let mut csv_writer_options =
+                conf.file_type_writer_options.try_into_csv()?.clone();
+            csv_writer_options.compression = *self.file_compression_type.get_variant();
+            conf.file_type_writer_options =
+                FileTypeWriterOptions::CSV(csv_writer_options);
         }
 
         let sink_schema = conf.output_schema().clone();
@@ -525,6 +534,8 @@ impl DataSink for CsvSink {
                         // Uniquely identify this batch of files with a random string, to prevent collisions overwriting files
                         let write_id =
                             Alphanumeric.sample_string(&mut rand::thread_rng(), 16);
+                        let file_ext =
+                            FileType::CSV.get_ext_with_compression(compression)?;
                         for part_idx in 0..num_partitions {
                             let serializer = CsvSerializer::new()
                                 .with_builder(builder.clone())
@@ -532,7 +543,7 @@ impl DataSink for CsvSink {
                             serializers.push(Box::new(serializer));
                             let file_path = base_path
                                 .prefix()
-                                .child(format!("{}_{}.csv", write_id, part_idx));
+                                .child(format!("{}_{}{}", write_id, part_idx, file_ext));
                             let object_meta = ObjectMeta {
                                 location: file_path,
                                 last_modified: chrono::offset::Utc::now(),
@@ -603,6 +614,7 @@ mod tests {
     use datafusion_common::FileCompressionType;
     use datafusion_common::FileType;
     use datafusion_common::GetExt;
+    use datafusion_common::Precision;
     use datafusion_expr::{col, lit};
     use futures::StreamExt;
     use object_store::local::LocalFileSystem;
@@ -633,8 +645,8 @@ mod tests {
         assert_eq!(tt_batches, 50 /* 100/2 */);
 
         // test metadata
-        assert_eq!(exec.statistics().num_rows, None);
-        assert_eq!(exec.statistics().total_byte_size, None);
+        assert_eq!(exec.statistics().num_rows, Precision::Absent);
+        assert_eq!(exec.statistics().total_byte_size, Precision::Absent);
 
         Ok(())
     }
@@ -23,6 +23,7 @@ use bytes::Bytes;
 use datafusion_common::not_impl_err;
 use datafusion_common::DataFusionError;
 use datafusion_common::FileType;
+use datafusion_common::FileTypeWriterOptions;
 use datafusion_execution::TaskContext;
 use rand::distributions::Alphanumeric;
 use rand::distributions::DistString;
@@ -178,9 +179,17 @@ impl FileFormat for JsonFormat {
             return not_impl_err!("Overwrites are not implemented yet for Json");
         }
 
-        if self.file_compression_type != FileCompressionType::UNCOMPRESSED {
-            return not_impl_err!("Inserting compressed JSON is not implemented yet.");
+        // Files written to a table must be readable by its scans, so the
+        // compression configured on the format wins over the writer default
+        let mut conf = conf;
+        if self.file_compression_type.is_compressed() {
+            let mut json_writer_options =
+                conf.file_type_writer_options.try_into_json()?.clone();
+            json_writer_options.compression = *self.file_compression_type.get_variant();
+            conf.file_type_writer_options =
+                FileTypeWriterOptions::JSON(json_writer_options);
         }
+
         let sink_schema = conf.output_schema().clone();
         let sink = Arc::new(JsonSink::new(conf, self.file_compression_type));
 
@@ -314,12 +323,14 @@ impl DataSink for JsonSink {
                         // Uniquely identify this batch of files with a random string, to prevent collisions overwriting files
                         let write_id =
                             Alphanumeric.sample_string(&mut rand::thread_rng(), 16);
+                        let file_ext =
+                            FileType::JSON.get_ext_with_compression(compression)?;
                         for part_idx in 0..num_partitions {
                             let serializer = JsonSerializer::new();
                             serializers.push(Box::new(serializer));
                             let file_path = base_path
                                 .prefix()
-                                .child(format!("{}_{}.json", write_id, part_idx));
+                                .child(format!("{}_{}{}", write_id, part_idx, file_ext));
                             let object_meta = ObjectMeta {
                                 location: file_path,
                                 last_modified: chrono::offset::Utc::now(),
@@ -373,6 +384,7 @@ impl DataSink for JsonSink {
 mod tests {
     use super::super::test_util::scan_format;
     use datafusion_common::cast::as_int64_array;
+    use datafusion_common::Precision;
     use futures::StreamExt;
     use object_store::local::LocalFileSystem;
 
@@ -403,8 +415,8 @@ mod tests {
         assert_eq!(tt_batches, 6 /* 12/2 */);
 
         // test metadata
-        assert_eq!(exec.statistics().num_rows, None);
-        assert_eq!(exec.statistics().total_byte_size, None);
+        assert_eq!(exec.statistics().num_rows, Precision::Absent);
+        assert_eq!(exec.statistics().total_byte_size, Precision::Absent);
 
         Ok(())
     }
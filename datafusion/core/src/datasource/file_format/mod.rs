@@ -162,6 +162,8 @@ pub(crate) mod test_util {
                     table_partition_cols: vec![],
                     output_ordering: vec![],
                     infinite_source: false,
+                    column_renames: vec![],
+                    nested_projection: vec![],
                 },
                 None,
             )
@@ -21,24 +21,44 @@ use rand::distributions::DistString;
 use std::any::Any;
 use std::fmt;
 use std::fmt::Debug;
+use std::io::Write;
 use std::sync::Arc;
-use tokio::task::JoinSet;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use tokio::sync::mpsc::{self, Receiver};
+use tokio::task::{JoinHandle, JoinSet};
 
 use arrow::datatypes::SchemaRef;
 use arrow::datatypes::{Fields, Schema};
+use arrow::record_batch::RecordBatch;
 use async_trait::async_trait;
-use bytes::{BufMut, BytesMut};
-use datafusion_common::{exec_err, not_impl_err, plan_err, DataFusionError, FileType};
+use bytes::{BufMut, Bytes, BytesMut};
+use datafusion_common::{
+    exec_err, internal_err, not_impl_err, plan_err, DataFusionError, FileType, Precision,
+};
 use datafusion_execution::TaskContext;
 use datafusion_physical_expr::PhysicalExpr;
 use futures::{StreamExt, TryStreamExt};
 use hashbrown::HashMap;
+use log::debug;
+use object_store::path::Path;
 use object_store::{ObjectMeta, ObjectStore};
-use parquet::arrow::{parquet_to_arrow_schema, AsyncArrowWriter};
-use parquet::file::footer::{decode_footer, decode_metadata};
+use parking_lot::Mutex;
+use parquet::arrow::{
+    arrow_to_parquet_schema, parquet_to_arrow_schema, ArrowWriter, AsyncArrowWriter,
+};
+use parquet::column::writer::ColumnCloseResult;
+use parquet::data_type::AsBytes;
+use parquet::file::footer::{decode_footer, decode_metadata, parse_metadata};
 use parquet::file::metadata::ParquetMetaData;
+use parquet::file::page_index::index::{Index, PageIndex};
+use parquet::file::page_index::index_reader::{
+    read_columns_indexes, read_pages_locations,
+};
 use parquet::file::properties::WriterProperties;
 use parquet::file::statistics::Statistics as ParquetStatistics;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::format::{BoundaryOrder, ColumnIndex, OffsetIndex};
+use parquet::schema::types::SchemaDescriptor;
 use rand::distributions::Alphanumeric;
 
 use super::write::FileWriterMode;
@@ -59,6 +79,7 @@ use crate::error::Result;
 use crate::execution::context::SessionState;
 use crate::physical_plan::expressions::{MaxAccumulator, MinAccumulator};
 use crate::physical_plan::insert::{DataSink, FileSinkExec};
+use crate::physical_plan::stream::RecordBatchReceiverStream;
 use crate::physical_plan::{
     Accumulator, DisplayAs, DisplayFormatType, ExecutionPlan, SendableRecordBatchStream,
     Statistics,
@@ -563,10 +584,9 @@ async fn fetch_statistics(
     };
 
     let statistics = Statistics {
-        num_rows: Some(num_rows as usize),
-        total_byte_size: Some(total_byte_size as usize),
+        num_rows: Precision::Exact(num_rows as usize),
+        total_byte_size: Precision::Exact(total_byte_size as usize),
         column_statistics: column_stats,
-        is_exact: true,
     };
 
     Ok(statistics)
@@ -659,6 +679,27 @@ impl DataSink for ParquetSink {
             .runtime_env()
             .object_store(&self.config.object_store_url)?;
 
+        let parquet_opts = &context.session_config().options().execution.parquet;
+        let parquet_schema = arrow_to_parquet_schema(&self.config.output_schema)?;
+        if self.config.single_file_output
+            && parquet_opts.allow_single_file_parallelism
+            && matches!(self.config.writer_mode, FileWriterMode::PutMultipart)
+            && !has_bloom_filters(&parquet_schema, parquet_props)
+        {
+            let file_path = self.config.table_paths[0].prefix();
+            let row_count = output_single_parquet_file_parallelized(
+                object_store,
+                file_path,
+                data,
+                self.config.output_schema.clone(),
+                parquet_props,
+                parquet_opts.maximum_parallel_row_group_writers,
+                parquet_opts.maximum_buffered_record_batches_per_stream,
+            )
+            .await?;
+            return Ok(row_count as u64);
+        }
+
         // Construct writer for each file group
         let mut writers = vec![];
         match self.config.writer_mode {
@@ -771,6 +812,344 @@ impl DataSink for ParquetSink {
     }
 }
 
+/// A [`std::io::Write`] implementation backed by a shared buffer, so that
+/// bytes written by a [`SerializedFileWriter`] can be taken out and streamed
+/// to an [`ObjectStore`] while the file is still being assembled
+#[derive(Clone, Default)]
+struct SharedBuffer {
+    buffer: Arc<Mutex<Vec<u8>>>,
+}
+
+impl SharedBuffer {
+    /// Takes all bytes written so far, leaving the buffer empty
+    fn take(&self) -> Vec<u8> {
+        std::mem::take(&mut *self.buffer.lock())
+    }
+}
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.buffer.lock().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Encodes `batches` as a standalone parquet file held in memory
+fn encode_row_group(
+    batches: Vec<RecordBatch>,
+    schema: SchemaRef,
+    props: WriterProperties,
+) -> Result<Bytes> {
+    let mut writer = ArrowWriter::try_new(Vec::new(), schema, Some(props))?;
+    for batch in batches {
+        writer.write(&batch)?;
+    }
+    Ok(Bytes::from(writer.into_inner()?))
+}
+
+/// Awaits an encoding task, propagating any panic it raised
+async fn join_encoding_task(handle: JoinHandle<Result<Bytes>>) -> Result<Bytes> {
+    match handle.await {
+        Ok(result) => result,
+        Err(e) => {
+            if e.is_panic() {
+                std::panic::resume_unwind(e.into_panic());
+            } else {
+                internal_err!("Non Panic Task error: {e}")
+            }
+        }
+    }
+}
+
+/// Whether `props` enable bloom filters for any column of `schema`
+///
+/// Bloom filters cannot be carried over from the encoded row groups by
+/// [`stitch_row_groups`], so such files are not written in parallel.
+fn has_bloom_filters(schema: &SchemaDescriptor, props: &WriterProperties) -> bool {
+    schema
+        .columns()
+        .iter()
+        .any(|column| props.bloom_filter_properties(column.path()).is_some())
+}
+
+/// Converts a decoded page [`Index`] back into the [`ColumnIndex`] that is
+/// written to the file, or `None` if the column has no column index
+fn to_column_index(index: &Index) -> Option<ColumnIndex> {
+    fn convert<T: AsBytes>(
+        indexes: &[PageIndex<T>],
+        boundary_order: BoundaryOrder,
+    ) -> ColumnIndex {
+        let to_bytes = |value: &Option<T>| value.as_ref().map(|v| v.as_bytes().to_vec());
+        ColumnIndex::new(
+            indexes.iter().map(|page| page.min.is_none()).collect(),
+            indexes
+                .iter()
+                .map(|page| to_bytes(&page.min).unwrap_or_default())
+                .collect(),
+            indexes
+                .iter()
+                .map(|page| to_bytes(&page.max).unwrap_or_default())
+                .collect(),
+            boundary_order,
+            indexes
+                .iter()
+                .map(|page| page.null_count)
+                .collect::<Option<Vec<_>>>(),
+        )
+    }
+
+    match index {
+        Index::NONE => None,
+        Index::BOOLEAN(index) => Some(convert(&index.indexes, index.boundary_order)),
+        Index::INT32(index) => Some(convert(&index.indexes, index.boundary_order)),
+        Index::INT64(index) => Some(convert(&index.indexes, index.boundary_order)),
+        Index::INT96(index) => Some(convert(&index.indexes, index.boundary_order)),
+        Index::FLOAT(index) => Some(convert(&index.indexes, index.boundary_order)),
+        Index::DOUBLE(index) => Some(convert(&index.indexes, index.boundary_order)),
+        Index::BYTE_ARRAY(index) => Some(convert(&index.indexes, index.boundary_order)),
+        Index::FIXED_LEN_BYTE_ARRAY(index) => {
+            Some(convert(&index.indexes, index.boundary_order))
+        }
+    }
+}
+
+/// Receives encoded row groups in the order they were spawned and appends
+/// their column chunks to a single parquet file, which is streamed to
+/// `object_store_writer` one row group at a time.
+async fn stitch_row_groups(
+    mut rx: Receiver<JoinHandle<Result<Bytes>>>,
+    mut object_store_writer: Box<dyn AsyncWrite + Send + Unpin>,
+    schema: SchemaRef,
+    props: WriterProperties,
+) -> Result<()> {
+    let parquet_schema = arrow_to_parquet_schema(&schema)?;
+    let merged_buffer = SharedBuffer::default();
+    let mut writer = SerializedFileWriter::new(
+        merged_buffer.clone(),
+        parquet_schema.root_schema_ptr(),
+        Arc::new(props),
+    )?;
+    let mut copied_key_value_metadata = false;
+
+    while let Some(handle) = rx.recv().await {
+        let encoded = join_encoding_task(handle).await?;
+        {
+            let metadata = parse_metadata(&encoded)?;
+
+            // Carry over the embedded arrow schema so readers restore the
+            // original arrow types
+            if !copied_key_value_metadata {
+                let key_value_metadata = metadata.file_metadata().key_value_metadata();
+                for kv in key_value_metadata.into_iter().flatten() {
+                    writer.append_key_value_metadata(kv.clone());
+                }
+                copied_key_value_metadata = true;
+            }
+
+            for row_group in metadata.row_groups() {
+                let mut row_group_writer = writer.next_row_group()?;
+                for column in row_group.columns() {
+                    let column_chunks = std::slice::from_ref(column);
+                    let column_index = read_columns_indexes(&encoded, column_chunks)?
+                        .first()
+                        .and_then(to_column_index);
+                    // The page offsets are relative to the encoded row group,
+                    // append_column maps them to the offsets in the merged file
+                    let offset_index = match column.offset_index_range() {
+                        Some(_) => read_pages_locations(&encoded, column_chunks)?
+                            .pop()
+                            .map(OffsetIndex::new),
+                        None => None,
+                    };
+                    let close = ColumnCloseResult {
+                        bytes_written: column.compressed_size() as _,
+                        rows_written: row_group.num_rows() as _,
+                        metadata: column.clone(),
+                        bloom_filter: None,
+                        column_index,
+                        offset_index,
+                    };
+                    row_group_writer.append_column(&encoded, close)?;
+                }
+                row_group_writer.close()?;
+            }
+        }
+
+        object_store_writer
+            .write_all(&merged_buffer.take())
+            .await
+            .map_err(DataFusionError::IoError)?;
+    }
+
+    writer.close()?;
+    object_store_writer
+        .write_all(&merged_buffer.take())
+        .await
+        .map_err(DataFusionError::IoError)?;
+    object_store_writer
+        .shutdown()
+        .await
+        .map_err(DataFusionError::IoError)?;
+    Ok(())
+}
+
+/// Writes all `data` streams to a single parquet file at `location`,
+/// encoding row groups in parallel.
+///
+/// The input streams are written one after another, in partition order, so
+/// the row order of the output file is deterministic. Each stream is still
+/// polled on its own task, buffering at most `max_buffered_batches_per_stream`
+/// batches ahead of the writer.
+///
+/// Batches are split into chunks of at most `max_row_group_size` rows, and
+/// each chunk is encoded on its own task. At most `max_parallel_row_groups`
+/// chunks are encoded at any point in time, and the encoded row groups are
+/// stitched together, in order, by a separate task which streams the
+/// resulting file to the object store with a multipart upload.
+///
+/// If writing fails the multipart upload is aborted, so that neither
+/// orphaned parts nor a partial object are left behind.
+async fn output_single_parquet_file_parallelized(
+    object_store: Arc<dyn ObjectStore>,
+    location: &Path,
+    data: Vec<SendableRecordBatchStream>,
+    output_schema: SchemaRef,
+    parquet_props: &WriterProperties,
+    max_parallel_row_groups: usize,
+    max_buffered_batches_per_stream: usize,
+) -> Result<usize> {
+    let (multipart_id, object_store_writer) = object_store
+        .put_multipart(location)
+        .await
+        .map_err(DataFusionError::ObjectStore)?;
+
+    let result = write_parallelized_row_groups(
+        object_store_writer,
+        data,
+        output_schema,
+        parquet_props,
+        max_parallel_row_groups,
+        max_buffered_batches_per_stream,
+    )
+    .await;
+
+    if result.is_err() {
+        // Do not leave orphaned parts or a partial object behind. The write
+        // error is more useful to the caller than a failure to abort.
+        if let Err(e) = object_store.abort_multipart(location, &multipart_id).await {
+            debug!("Failed to abort multipart upload to {location}: {e}");
+        }
+    }
+    result
+}
+
+/// Encodes and uploads the row groups for
+/// [`output_single_parquet_file_parallelized`] to `object_store_writer`,
+/// returning the number of rows written
+async fn write_parallelized_row_groups(
+    object_store_writer: Box<dyn AsyncWrite + Send + Unpin>,
+    data: Vec<SendableRecordBatchStream>,
+    output_schema: SchemaRef,
+    parquet_props: &WriterProperties,
+    max_parallel_row_groups: usize,
+    max_buffered_batches_per_stream: usize,
+) -> Result<usize> {
+    let max_row_group_size = parquet_props.max_row_group_size();
+
+    // Drive every input stream on its own task, buffering a bounded number
+    // of batches from each, and consume the streams in partition order
+    let inputs: Vec<_> = data
+        .into_iter()
+        .map(|mut data_stream| {
+            let mut builder = RecordBatchReceiverStream::builder(
+                output_schema.clone(),
+                max_buffered_batches_per_stream.max(1),
+            );
+            let tx = builder.tx();
+            builder.spawn(async move {
+                while let Some(item) = data_stream.next().await {
+                    let is_err = item.is_err();
+                    if tx.send(item).await.is_err() || is_err {
+                        return;
+                    }
+                }
+            });
+            builder.build()
+        })
+        .collect();
+    let mut input = futures::stream::iter(inputs).flatten();
+
+    let (tx, rx) = mpsc::channel(max_parallel_row_groups.max(1));
+    let stitch_task = tokio::spawn(stitch_row_groups(
+        rx,
+        object_store_writer,
+        output_schema.clone(),
+        parquet_props.clone(),
+    ));
+
+    let spawn_encoding = |batches: Vec<RecordBatch>| {
+        let schema = output_schema.clone();
+        let props = parquet_props.clone();
+        tokio::spawn(async move { encode_row_group(batches, schema, props) })
+    };
+
+    let mut row_count = 0;
+    let mut current_rows = 0;
+    let mut current_batches = vec![];
+    let result: Result<()> = async {
+        while let Some(batch) = input.next().await.transpose()? {
+            row_count += batch.num_rows();
+            let mut batch = batch;
+            // Split the batch so that no chunk exceeds max_row_group_size rows
+            while current_rows + batch.num_rows() >= max_row_group_size {
+                let take = max_row_group_size - current_rows;
+                current_batches.push(batch.slice(0, take));
+                batch = batch.slice(take, batch.num_rows() - take);
+                let chunk = std::mem::take(&mut current_batches);
+                current_rows = 0;
+                if tx.send(spawn_encoding(chunk)).await.is_err() {
+                    // The stitching task exited early, its error is reported below
+                    return Ok(());
+                }
+            }
+            if batch.num_rows() > 0 {
+                current_rows += batch.num_rows();
+                current_batches.push(batch);
+            }
+        }
+        if !current_batches.is_empty() {
+            let chunk = std::mem::take(&mut current_batches);
+            tx.send(spawn_encoding(chunk)).await.ok();
+        }
+        Ok(())
+    }
+    .await;
+    drop(tx);
+
+    if let Err(e) = result {
+        // Do not complete the multipart upload with partial data
+        stitch_task.abort();
+        return Err(e);
+    }
+
+    match stitch_task.await {
+        Ok(result) => result?,
+        Err(e) => {
+            if e.is_panic() {
+                std::panic::resume_unwind(e.into_panic());
+            } else {
+                return internal_err!("Non Panic Task error: {e}");
+            }
+        }
+    }
+
+    Ok(row_count)
+}
+
 #[cfg(test)]
 pub(crate) mod test_util {
     use super::*;
@@ -853,8 +1232,10 @@ mod tests {
     use crate::datasource::file_format::parquet::test_util::store_parquet;
     use crate::datasource::physical_plan::get_scan_files;
     use crate::physical_plan::metrics::MetricValue;
+    use crate::physical_plan::stream::RecordBatchStreamAdapter;
     use crate::prelude::{SessionConfig, SessionContext};
     use arrow::array::{Array, ArrayRef, StringArray};
+    use arrow::datatypes::Field;
     use arrow::record_batch::RecordBatch;
     use async_trait::async_trait;
     use bytes::Bytes;
@@ -867,9 +1248,12 @@ mod tests {
     use futures::StreamExt;
     use log::error;
     use object_store::local::LocalFileSystem;
+    use object_store::memory::InMemory;
     use object_store::path::Path;
     use object_store::{GetOptions, GetResult, ListResult, MultipartId};
-    use parquet::arrow::arrow_reader::ArrowReaderOptions;
+    use parquet::arrow::arrow_reader::{
+        ArrowReaderOptions, ParquetRecordBatchReaderBuilder,
+    };
     use parquet::arrow::ParquetRecordBatchStreamBuilder;
     use parquet::file::metadata::{ParquetColumnIndex, ParquetOffsetIndex};
     use parquet::file::page_index::index::Index;
@@ -897,20 +1281,26 @@ mod tests {
         let stats =
             fetch_statistics(store.as_ref(), schema.clone(), &meta[0], None).await?;
 
-        assert_eq!(stats.num_rows, Some(3));
+        assert_eq!(stats.num_rows, Precision::Exact(3));
         let c1_stats = &stats.column_statistics.as_ref().expect("missing c1 stats")[0];
         let c2_stats = &stats.column_statistics.as_ref().expect("missing c2 stats")[1];
-        assert_eq!(c1_stats.null_count, Some(1));
-        assert_eq!(c2_stats.null_count, Some(3));
+        assert_eq!(c1_stats.null_count, Precision::Exact(1));
+        assert_eq!(c2_stats.null_count, Precision::Exact(3));
 
         let stats = fetch_statistics(store.as_ref(), schema, &meta[1], None).await?;
-        assert_eq!(stats.num_rows, Some(3));
+        assert_eq!(stats.num_rows, Precision::Exact(3));
         let c1_stats = &stats.column_statistics.as_ref().expect("missing c1 stats")[0];
         let c2_stats = &stats.column_statistics.as_ref().expect("missing c2 stats")[1];
-        assert_eq!(c1_stats.null_count, Some(3));
-        assert_eq!(c2_stats.null_count, Some(1));
-        assert_eq!(c2_stats.max_value, Some(ScalarValue::Int64(Some(2))));
-        assert_eq!(c2_stats.min_value, Some(ScalarValue::Int64(Some(1))));
+        assert_eq!(c1_stats.null_count, Precision::Exact(3));
+        assert_eq!(c2_stats.null_count, Precision::Exact(1));
+        assert_eq!(
+            c2_stats.max_value,
+            Precision::Exact(ScalarValue::Int64(Some(2)))
+        );
+        assert_eq!(
+            c2_stats.min_value,
+            Precision::Exact(ScalarValue::Int64(Some(1)))
+        );
 
         Ok(())
     }
@@ -1046,11 +1436,11 @@ mod tests {
             fetch_statistics(store.upcast().as_ref(), schema.clone(), &meta[0], Some(9))
                 .await?;
 
-        assert_eq!(stats.num_rows, Some(3));
+        assert_eq!(stats.num_rows, Precision::Exact(3));
         let c1_stats = &stats.column_statistics.as_ref().expect("missing c1 stats")[0];
         let c2_stats = &stats.column_statistics.as_ref().expect("missing c2 stats")[1];
-        assert_eq!(c1_stats.null_count, Some(1));
-        assert_eq!(c2_stats.null_count, Some(3));
+        assert_eq!(c1_stats.null_count, Precision::Exact(1));
+        assert_eq!(c2_stats.null_count, Precision::Exact(3));
 
         let store = Arc::new(RequestCountingObjectStore::new(Arc::new(
             LocalFileSystem::new(),
@@ -1079,11 +1469,11 @@ mod tests {
         )
         .await?;
 
-        assert_eq!(stats.num_rows, Some(3));
+        assert_eq!(stats.num_rows, Precision::Exact(3));
         let c1_stats = &stats.column_statistics.as_ref().expect("missing c1 stats")[0];
         let c2_stats = &stats.column_statistics.as_ref().expect("missing c2 stats")[1];
-        assert_eq!(c1_stats.null_count, Some(1));
-        assert_eq!(c2_stats.null_count, Some(3));
+        assert_eq!(c1_stats.null_count, Precision::Exact(1));
+        assert_eq!(c2_stats.null_count, Precision::Exact(3));
 
         let store = Arc::new(RequestCountingObjectStore::new(Arc::new(
             LocalFileSystem::new(),
@@ -1123,8 +1513,8 @@ mod tests {
         assert_eq!(tt_batches, 4 /* 8/2 */);
 
         // test metadata
-        assert_eq!(exec.statistics().num_rows, Some(8));
-        assert_eq!(exec.statistics().total_byte_size, Some(671));
+        assert_eq!(exec.statistics().num_rows, Precision::Exact(8));
+        assert_eq!(exec.statistics().total_byte_size, Precision::Exact(671));
 
         Ok(())
     }
@@ -1165,9 +1555,8 @@ mod tests {
             get_exec(&state, "alltypes_plain.parquet", projection, Some(1)).await?;
 
         // note: even if the limit is set, the executor rounds up to the batch size
-        assert_eq!(exec.statistics().num_rows, Some(8));
-        assert_eq!(exec.statistics().total_byte_size, Some(671));
-        assert!(exec.statistics().is_exact);
+        assert_eq!(exec.statistics().num_rows, Precision::Exact(8));
+        assert_eq!(exec.statistics().total_byte_size, Precision::Exact(671));
         let batches = collect(exec, task_ctx).await?;
         assert_eq!(1, batches.len());
         assert_eq!(11, batches[0].num_columns());
@@ -1446,6 +1835,62 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn write_parquet_page_index_options() -> Result<()> {
+        for parallel in [false, true] {
+            let config = SessionConfig::new().set_bool(
+                "datafusion.execution.parquet.allow_single_file_parallelism",
+                parallel,
+            );
+            let ctx = SessionContext::with_config(config);
+            let tmp_dir = tempfile::TempDir::new()?;
+            let path = tmp_dir.path().join("page_index.parquet");
+            ctx.sql(&format!(
+                "COPY (VALUES (1, 'a', 1.5), (2, 'b', 2.5)) TO '{}' \
+                 (format parquet, single_file_output true, statistics_enabled page, \
+                 'statistics_enabled::column2' chunk, 'statistics_enabled::column3' none)",
+                path.display()
+            ))
+            .await?
+            .collect()
+            .await?;
+
+            let file = File::open(&path).await?;
+            let options = ArrowReaderOptions::new().with_page_index(true);
+            let metadata =
+                ParquetRecordBatchStreamBuilder::new_with_options(file, options)
+                    .await?
+                    .metadata()
+                    .clone();
+
+            // only column1 has page level statistics, and so a column index
+            let column_index = metadata.column_index().expect("missing column index");
+            match &column_index[0][0] {
+                Index::INT64(index) => {
+                    assert_eq!(index.indexes.len(), 1);
+                    assert_eq!(index.indexes[0].min, Some(1));
+                    assert_eq!(index.indexes[0].max, Some(2));
+                }
+                other => panic!("unexpected column index {other:?} for column1"),
+            }
+            assert_eq!(column_index[0][1], Index::NONE);
+            assert_eq!(column_index[0][2], Index::NONE);
+
+            let row_group = metadata.row_group(0);
+            assert!(row_group.column(0).statistics().is_some());
+            assert!(row_group.column(1).statistics().is_some());
+            assert!(row_group.column(2).statistics().is_none());
+
+            // every column has an offset index, pointing at its data page
+            let offset_index = metadata.offset_index().expect("missing offset index");
+            for (column, locations) in row_group.columns().iter().zip(&offset_index[0]) {
+                assert_eq!(locations.len(), 1);
+                assert_eq!(locations[0].offset, column.data_page_offset());
+            }
+        }
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_get_scan_files() -> Result<()> {
         let session_ctx = SessionContext::new();
@@ -1517,6 +1962,187 @@ mod tests {
         assert_eq!(actual, expected);
     }
 
+    /// Wraps an [`ObjectStore`], counting the multipart uploads it aborts
+    #[derive(Debug)]
+    struct AbortCountingObjectStore {
+        inner: Arc<dyn ObjectStore>,
+        abort_count: AtomicUsize,
+    }
+
+    impl Display for AbortCountingObjectStore {
+        fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "AbortCounting({})", self.inner)
+        }
+    }
+
+    #[async_trait]
+    impl ObjectStore for AbortCountingObjectStore {
+        async fn put(&self, location: &Path, bytes: Bytes) -> object_store::Result<()> {
+            self.inner.put(location, bytes).await
+        }
+
+        async fn put_multipart(
+            &self,
+            location: &Path,
+        ) -> object_store::Result<(MultipartId, Box<dyn AsyncWrite + Unpin + Send>)>
+        {
+            self.inner.put_multipart(location).await
+        }
+
+        async fn abort_multipart(
+            &self,
+            location: &Path,
+            multipart_id: &MultipartId,
+        ) -> object_store::Result<()> {
+            self.abort_count.fetch_add(1, Ordering::SeqCst);
+            self.inner.abort_multipart(location, multipart_id).await
+        }
+
+        async fn get_opts(
+            &self,
+            location: &Path,
+            options: GetOptions,
+        ) -> object_store::Result<GetResult> {
+            self.inner.get_opts(location, options).await
+        }
+
+        async fn head(&self, location: &Path) -> object_store::Result<ObjectMeta> {
+            self.inner.head(location).await
+        }
+
+        async fn delete(&self, location: &Path) -> object_store::Result<()> {
+            self.inner.delete(location).await
+        }
+
+        async fn list(
+            &self,
+            prefix: Option<&Path>,
+        ) -> object_store::Result<BoxStream<'_, object_store::Result<ObjectMeta>>>
+        {
+            self.inner.list(prefix).await
+        }
+
+        async fn list_with_delimiter(
+            &self,
+            prefix: Option<&Path>,
+        ) -> object_store::Result<ListResult> {
+            self.inner.list_with_delimiter(prefix).await
+        }
+
+        async fn copy(&self, from: &Path, to: &Path) -> object_store::Result<()> {
+            self.inner.copy(from, to).await
+        }
+
+        async fn copy_if_not_exists(
+            &self,
+            from: &Path,
+            to: &Path,
+        ) -> object_store::Result<()> {
+            self.inner.copy_if_not_exists(from, to).await
+        }
+    }
+
+    fn int_stream(
+        schema: SchemaRef,
+        items: Vec<Result<Vec<i32>>>,
+    ) -> SendableRecordBatchStream {
+        let batches: Vec<_> = items
+            .into_iter()
+            .map(|item| {
+                let values = Int32Array::from(item?);
+                Ok(RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(values)],
+                )?)
+            })
+            .collect();
+        Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::iter(batches),
+        ))
+    }
+
+    #[tokio::test]
+    async fn parallel_single_file_write_preserves_partition_order() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let store = Arc::new(AbortCountingObjectStore {
+            inner: Arc::new(InMemory::new()),
+            abort_count: AtomicUsize::new(0),
+        });
+        let location = Path::from("out.parquet");
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+
+        let data = vec![
+            int_stream(schema.clone(), vec![Ok(vec![0, 1, 2]), Ok(vec![3])]),
+            int_stream(schema.clone(), vec![Ok(vec![4]), Ok(vec![5, 6, 7, 8])]),
+            int_stream(schema.clone(), vec![Ok(vec![9])]),
+        ];
+        let row_count = output_single_parquet_file_parallelized(
+            store.clone(),
+            &location,
+            data,
+            schema,
+            &props,
+            2,
+            1,
+        )
+        .await?;
+        assert_eq!(row_count, 10);
+        assert_eq!(store.abort_count.load(Ordering::SeqCst), 0);
+
+        let bytes = store.get(&location).await?.bytes().await?;
+        let reader = ParquetRecordBatchReaderBuilder::try_new(bytes)?.build()?;
+        let mut values = vec![];
+        for batch in reader {
+            let batch = batch?;
+            values.extend(as_int32_array(batch.column(0))?.values().iter().copied());
+        }
+        assert_eq!(values, (0..10).collect::<Vec<_>>());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn parallel_single_file_write_aborts_upload_on_error() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, false)]));
+        let store = Arc::new(AbortCountingObjectStore {
+            inner: Arc::new(InMemory::new()),
+            abort_count: AtomicUsize::new(0),
+        });
+        let location = Path::from("out.parquet");
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(2)
+            .build();
+
+        // The error arrives after some row groups have already been uploaded
+        let data = vec![
+            int_stream(schema.clone(), vec![Ok(vec![0, 1, 2, 3]), Ok(vec![4, 5])]),
+            int_stream(
+                schema.clone(),
+                vec![
+                    Ok(vec![6, 7]),
+                    Err(DataFusionError::Execution("injected failure".to_string())),
+                ],
+            ),
+        ];
+        let err = output_single_parquet_file_parallelized(
+            store.clone(),
+            &location,
+            data,
+            schema,
+            &props,
+            2,
+            1,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("injected failure"), "{err}");
+        assert_eq!(store.abort_count.load(Ordering::SeqCst), 1);
+        assert!(store.head(&location).await.is_err());
+        Ok(())
+    }
+
     async fn get_exec(
         state: &SessionState,
         file_name: &str,
@@ -25,8 +25,8 @@ use arrow::datatypes::{DataType, Field, SchemaBuilder, SchemaRef};
 use arrow_schema::Schema;
 use async_trait::async_trait;
 use dashmap::DashMap;
-use datafusion_common::FileTypeWriterOptions;
 use datafusion_common::{internal_err, plan_err, project_schema, SchemaExt, ToDFSchema};
+use datafusion_common::{FileTypeWriterOptions, Precision};
 use datafusion_expr::expr::Sort;
 use datafusion_optimizer::utils::conjunction;
 use datafusion_physical_expr::{create_physical_expr, LexOrdering, PhysicalSortExpr};
@@ -42,6 +42,7 @@ use crate::datasource::{
     },
     get_statistics_with_limit,
     listing::ListingTableUrl,
+    schema_evolution::merge_schemas,
     TableProvider, TableType,
 };
 use crate::logical_expr::TableProviderFilterPushDown;
@@ -50,7 +51,7 @@ use crate::{
     error::{DataFusionError, Result},
     execution::context::SessionState,
     logical_expr::Expr,
-    physical_plan::{empty::EmptyExec, ExecutionPlan, Statistics},
+    physical_plan::{empty::EmptyExec, ColumnStatistics, ExecutionPlan, Statistics},
 };
 use datafusion_common::{FileCompressionType, FileType};
 
@@ -232,6 +233,10 @@ impl FromStr for ListingTableInsertMode {
         }
     }
 }
+/// The number of files whose schemas are inferred concurrently when schema
+/// evolution is enabled
+const SCHEMA_INFERENCE_CONCURRENCY: usize = 32;
+
 /// Options for creating a [`ListingTable`]
 #[derive(Clone, Debug)]
 pub struct ListingOptions {
@@ -278,6 +283,13 @@ pub struct ListingOptions {
     /// This setting holds file format specific options which should be used
     /// when inserting into this table.
     pub file_type_write_options: Option<FileTypeWriterOptions>,
+    /// When true, the schemas of the individual files are merged with type
+    /// widening when inferring the table schema.
+    /// See [Self::with_schema_evolution] for details
+    pub schema_evolution: bool,
+    /// Previous names of table columns, as `(column, previous name)` pairs.
+    /// See [Self::with_column_renames] for details
+    pub column_renames: Vec<(String, String)>,
 }
 
 impl ListingOptions {
@@ -299,6 +311,8 @@ impl ListingOptions {
             insert_mode: ListingTableInsertMode::AppendToFile,
             single_file: false,
             file_type_write_options: None,
+            schema_evolution: false,
+            column_renames: vec![],
         }
     }
 
@@ -488,6 +502,59 @@ impl ListingOptions {
         self
     }
 
+    /// Enable schema evolution when inferring the table schema.
+    ///
+    /// By default the schemas of all files must be compatible, i.e. the same
+    /// column must have the same type in every file. With schema evolution,
+    /// the schema of each file is inferred separately and the results are
+    /// merged using [`merge_schemas`]: differing column types are widened
+    /// (e.g. `Int32` and `Int64` to `Int64`) and columns missing from some
+    /// files become nullable. Files are adapted to the merged schema when
+    /// they are read.
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use datafusion::datasource::{listing::ListingOptions, file_format::parquet::ParquetFormat};
+    ///
+    /// let listing_options = ListingOptions::new(Arc::new(
+    ///     ParquetFormat::default()
+    ///   ))
+    ///   .with_schema_evolution(true);
+    ///
+    /// assert!(listing_options.schema_evolution);
+    /// ```
+    ///
+    /// [`merge_schemas`]: crate::datasource::schema_evolution::merge_schemas
+    pub fn with_schema_evolution(mut self, schema_evolution: bool) -> Self {
+        self.schema_evolution = schema_evolution;
+        self
+    }
+
+    /// Set the previous names of table columns, as `(column, previous name)`
+    /// pairs, on [`ListingOptions`] and returns self.
+    ///
+    /// Files written before a column was renamed store it under its previous
+    /// name. When such a file does not contain the column, it is read from
+    /// the column with the previous name instead. A column may have several
+    /// previous names. Currently only Parquet files are supported.
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use datafusion::datasource::{listing::ListingOptions, file_format::parquet::ParquetFormat};
+    ///
+    /// // `user_id` used to be called `uid`
+    /// let listing_options = ListingOptions::new(Arc::new(
+    ///     ParquetFormat::default()
+    ///   ))
+    ///   .with_column_renames(vec![("user_id".to_string(), "uid".to_string())]);
+    ///
+    /// assert_eq!(listing_options.column_renames.len(), 1);
+    /// ```
+    pub fn with_column_renames(mut self, column_renames: Vec<(String, String)>) -> Self {
+        self.column_renames = column_renames;
+        self
+    }
+
     /// Infer the schema of the files at the given path on the provided object store.
     /// The inferred schema does not include the partitioning columns.
     ///
@@ -506,7 +573,24 @@ impl ListingOptions {
             .try_collect()
             .await?;
 
-        self.format.infer_schema(state, &store, &files).await
+        if !self.schema_evolution {
+            return self.format.infer_schema(state, &store, &files).await;
+        }
+
+        let schemas: Vec<_> = stream::iter(files)
+            .map(|file| {
+                let store = &store;
+                async move {
+                    let schema = self.format.infer_schema(state, store, &[file]).await?;
+                    Ok(schema.as_ref().clone()) as Result<Schema>
+                }
+            })
+            .boxed() // Workaround https://github.com/rust-lang/rust/issues/64552
+            .buffered(SCHEMA_INFERENCE_CONCURRENCY)
+            .try_collect()
+            .await?;
+
+        Ok(Arc::new(merge_schemas(schemas)?))
     }
 }
 
@@ -616,7 +700,9 @@ pub struct ListingTable {
     table_schema: SchemaRef,
     options: ListingOptions,
     definition: Option<String>,
-    collected_statistics: StatisticsCache,
+    collected_statistics: Arc<StatisticsCache>,
+    /// Statistics of the whole table, e.g. collected by `ANALYZE TABLE`
+    analyzed_statistics: Option<Statistics>,
     infinite_source: bool,
 }
 
@@ -654,6 +740,7 @@ impl ListingTable {
             options,
             definition: None,
             collected_statistics: Default::default(),
+            analyzed_statistics: None,
             infinite_source,
         };
 
@@ -724,6 +811,23 @@ impl TableProvider for ListingTable {
         TableType::Base
     }
 
+    fn statistics(&self) -> Option<Statistics> {
+        self.analyzed_statistics.clone()
+    }
+
+    fn with_statistics(&self, statistics: Statistics) -> Option<Arc<dyn TableProvider>> {
+        Some(Arc::new(Self {
+            table_paths: self.table_paths.clone(),
+            file_schema: Arc::clone(&self.file_schema),
+            table_schema: Arc::clone(&self.table_schema),
+            options: self.options.clone(),
+            definition: self.definition.clone(),
+            collected_statistics: Arc::clone(&self.collected_statistics),
+            analyzed_statistics: Some(statistics),
+            infinite_source: self.infinite_source,
+        }))
+    }
+
     async fn scan(
         &self,
         state: &SessionState,
@@ -734,6 +838,17 @@ impl TableProvider for ListingTable {
         let (partitioned_file_lists, statistics) =
             self.list_files_for_scan(state, filters, limit).await?;
 
+        // The analyzed statistics describe the whole table, so they only
+        // apply if no files are skipped because of partition filters or limits
+        let scans_all_files = limit.is_none()
+            && (filters.is_empty() || self.options.table_partition_cols.is_empty());
+        let statistics = match &self.analyzed_statistics {
+            Some(analyzed) if scans_all_files => {
+                merge_analyzed_statistics(statistics, analyzed)
+            }
+            _ => statistics,
+        };
+
         // if no files need to be read, return an `EmptyExec`
         if partitioned_file_lists.is_empty() {
             let schema = self.schema();
@@ -791,6 +906,8 @@ impl TableProvider for ListingTable {
                     output_ordering: self.try_create_output_ordering()?,
                     table_partition_cols,
                     infinite_source: self.infinite_source,
+                    column_renames: self.options.column_renames.clone(),
+                    nested_projection: vec![],
                 },
                 filters.as_ref(),
             )
@@ -928,6 +1045,22 @@ impl TableProvider for ListingTable {
 }
 
 impl ListingTable {
+    /// Statistics are collected by column name, so are not known for columns
+    /// that may be stored under a previous name
+    fn without_renamed_column_statistics(
+        &self,
+        mut statistics: Statistics,
+    ) -> Statistics {
+        if let Some(column_statistics) = statistics.column_statistics.as_mut() {
+            for (column, _) in &self.options.column_renames {
+                if let Ok(idx) = self.file_schema.index_of(column) {
+                    column_statistics[idx] = ColumnStatistics::default();
+                }
+            }
+        }
+        statistics
+    }
+
     /// Get the list of files for a scan as well as the file level statistics.
     /// The list is grouped to let the execution plan know how the files should
     /// be distributed to different threads / executors.
@@ -973,6 +1106,8 @@ impl ListingTable {
                                 &part_file.object_meta,
                             )
                             .await?;
+                        let statistics =
+                            self.without_renamed_column_statistics(statistics);
                         self.collected_statistics
                             .save(part_file.object_meta.clone(), statistics.clone());
                         statistics
@@ -994,6 +1129,57 @@ impl ListingTable {
     }
 }
 
+/// Completes the file level `statistics` of a scan of the whole table with the
+/// `analyzed` statistics collected by `ANALYZE TABLE`.
+///
+/// The analyzed statistics are estimates, so they only replace the values
+/// that are not known exactly from the files, and exact values such as
+/// Parquet row counts can still be used to answer aggregates.
+fn merge_analyzed_statistics(
+    statistics: Statistics,
+    analyzed: &Statistics,
+) -> Statistics {
+    fn merge<T: Clone>(value: Precision<T>, analyzed: &Precision<T>) -> Precision<T> {
+        match (&value, analyzed) {
+            (Precision::Exact(_), _) | (_, Precision::Absent) => value,
+            _ => analyzed.clone(),
+        }
+    }
+
+    let column_statistics =
+        match (statistics.column_statistics, &analyzed.column_statistics) {
+            (Some(columns), Some(analyzed_columns)) => Some(
+                columns
+                    .into_iter()
+                    .zip(analyzed_columns)
+                    .map(|(column, analyzed)| ColumnStatistics {
+                        null_count: merge(column.null_count, &analyzed.null_count),
+                        max_value: merge(column.max_value, &analyzed.max_value),
+                        min_value: merge(column.min_value, &analyzed.min_value),
+                        distinct_count: merge(
+                            column.distinct_count,
+                            &analyzed.distinct_count,
+                        ),
+                        histogram: column
+                            .histogram
+                            .or_else(|| analyzed.histogram.clone()),
+                        most_common_values: column
+                            .most_common_values
+                            .or_else(|| analyzed.most_common_values.clone()),
+                    })
+                    .collect(),
+            ),
+            (None, analyzed_columns) => analyzed_columns.clone(),
+            (columns, None) => columns,
+        };
+
+    Statistics {
+        num_rows: merge(statistics.num_rows, &analyzed.num_rows),
+        total_byte_size: merge(statistics.total_byte_size, &analyzed.total_byte_size),
+        column_statistics,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -1013,6 +1199,7 @@ mod tests {
     use chrono::DateTime;
     use datafusion_common::assert_contains;
     use datafusion_common::GetExt;
+    use datafusion_common::Precision;
     use datafusion_expr::LogicalPlanBuilder;
     use rstest::*;
     use std::collections::HashMap;
@@ -1062,8 +1249,8 @@ mod tests {
         assert_eq!(exec.output_partitioning().partition_count(), 1);
 
         // test metadata
-        assert_eq!(exec.statistics().num_rows, Some(8));
-        assert_eq!(exec.statistics().total_byte_size, Some(671));
+        assert_eq!(exec.statistics().num_rows, Precision::Exact(8));
+        assert_eq!(exec.statistics().total_byte_size, Precision::Exact(671));
 
         Ok(())
     }
@@ -1085,8 +1272,8 @@ mod tests {
         let table = ListingTable::try_new(config)?;
 
         let exec = table.scan(&state, None, &[], None).await?;
-        assert_eq!(exec.statistics().num_rows, Some(8));
-        assert_eq!(exec.statistics().total_byte_size, Some(671));
+        assert_eq!(exec.statistics().num_rows, Precision::Exact(8));
+        assert_eq!(exec.statistics().total_byte_size, Precision::Exact(671));
 
         Ok(())
     }
@@ -1109,8 +1296,8 @@ mod tests {
         let table = ListingTable::try_new(config)?;
 
         let exec = table.scan(&state, None, &[], None).await?;
-        assert_eq!(exec.statistics().num_rows, None);
-        assert_eq!(exec.statistics().total_byte_size, None);
+        assert_eq!(exec.statistics().num_rows, Precision::Absent);
+        assert_eq!(exec.statistics().total_byte_size, Precision::Absent);
 
         Ok(())
     }
@@ -1646,6 +1833,39 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_insert_into_append_to_compressed_csv_file() -> Result<()> {
+        helper_test_insert_into_append_to_existing_files(
+            FileType::CSV,
+            FileCompressionType::GZIP,
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_insert_into_append_new_compressed_csv_files() -> Result<()> {
+        helper_test_append_new_files_to_table(
+            FileType::CSV,
+            FileCompressionType::ZSTD,
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_insert_into_append_new_compressed_json_files() -> Result<()> {
+        helper_test_append_new_files_to_table(
+            FileType::JSON,
+            FileCompressionType::GZIP,
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_insert_into_append_new_parquet_files_defaults() -> Result<()> {
         helper_test_append_new_files_to_table(
@@ -1682,6 +1902,30 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_insert_into_sql_csv_compressed() -> Result<()> {
+        helper_test_insert_into_sql(
+            "csv",
+            FileCompressionType::GZIP,
+            "OPTIONS (insert_mode 'append_new_files')",
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_insert_into_sql_json_compressed() -> Result<()> {
+        helper_test_insert_into_sql(
+            "json",
+            FileCompressionType::BZIP2,
+            "OPTIONS (insert_mode 'append_new_files')",
+            None,
+        )
+        .await?;
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_insert_into_sql_json_defaults() -> Result<()> {
         helper_test_insert_into_sql(
@@ -2101,6 +2345,7 @@ mod tests {
 
         // Register appropriate table depending on file_type we want to test
         let tmp_dir = TempDir::new()?;
+        let file_extension = file_type.get_ext_with_compression(file_compression_type)?;
         match file_type {
             FileType::CSV => {
                 session_ctx
@@ -2110,6 +2355,7 @@ mod tests {
                         CsvReadOptions::new()
                             .insert_mode(ListingTableInsertMode::AppendNewFiles)
                             .schema(schema.as_ref())
+                            .file_extension(&file_extension)
                             .file_compression_type(file_compression_type),
                     )
                     .await?;
@@ -2122,6 +2368,7 @@ mod tests {
                         NdJsonReadOptions::default()
                             .insert_mode(ListingTableInsertMode::AppendNewFiles)
                             .schema(schema.as_ref())
+                            .file_extension(&file_extension)
                             .file_compression_type(file_compression_type),
                     )
                     .await?;
@@ -2270,8 +2517,7 @@ mod tests {
     /// create external table + insert into statements
     async fn helper_test_insert_into_sql(
         file_type: &str,
-        // TODO test with create statement options such as compression
-        _file_compression_type: FileCompressionType,
+        file_compression_type: FileCompressionType,
         external_table_options: &str,
         session_config_map: Option<HashMap<String, String>>,
     ) -> Result<()> {
@@ -2288,10 +2534,19 @@ mod tests {
         let tmp_dir = TempDir::new()?;
         let tmp_path = tmp_dir.into_path();
         let str_path = tmp_path.to_str().expect("Temp path should convert to &str");
+        let compression_clause = if file_compression_type.is_compressed() {
+            format!(
+                "compression type {}",
+                file_compression_type.get_variant().to_string()
+            )
+        } else {
+            String::new()
+        };
         session_ctx
             .sql(&format!(
                 "create external table foo(a varchar, b varchar, c int) \
                         stored as {file_type} \
+                        {compression_clause} \
                         location '{str_path}' \
                         {external_table_options}"
             ))
@@ -28,6 +28,7 @@ pub mod listing_table_factory;
 pub mod memory;
 pub mod physical_plan;
 pub mod provider;
+pub mod schema_evolution;
 mod statistics;
 pub mod streaming;
 pub mod view;
@@ -43,4 +44,4 @@ pub use self::provider::TableProvider;
 pub use self::view::ViewTable;
 pub use crate::logical_expr::TableType;
 pub use statistics::get_statistics_with_limit;
-pub(crate) use statistics::{create_max_min_accs, get_col_stats};
+pub(crate) use statistics::{analyze_table, create_max_min_accs, get_col_stats};
@@ -283,6 +283,8 @@ mod tests {
             table_partition_cols: vec![],
             output_ordering: vec![],
             infinite_source: false,
+            column_renames: vec![],
+            nested_projection: vec![],
         });
         assert_eq!(avro_exec.output_partitioning().partition_count(), 1);
         let mut results = avro_exec
@@ -355,6 +357,8 @@ mod tests {
             table_partition_cols: vec![],
             output_ordering: vec![],
             infinite_source: false,
+            column_renames: vec![],
+            nested_projection: vec![],
         });
         assert_eq!(avro_exec.output_partitioning().partition_count(), 1);
 
@@ -427,6 +431,8 @@ mod tests {
             table_partition_cols: vec![("date".to_owned(), DataType::Utf8)],
             output_ordering: vec![],
             infinite_source: false,
+            column_renames: vec![],
+            nested_projection: vec![],
         });
         assert_eq!(avro_exec.output_partitioning().partition_count(), 1);
 
@@ -32,12 +32,12 @@ use arrow::array::{ArrayData, BufferBuilder};
 use arrow::buffer::Buffer;
 use arrow::datatypes::{ArrowNativeType, UInt16Type};
 use arrow_array::{ArrayRef, DictionaryArray, RecordBatch};
-use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use arrow_schema::{DataType, Field, Fields, Schema, SchemaRef};
 use datafusion_common::{
     exec_err,
     tree_node::{TreeNode, VisitRecursion},
 };
-use datafusion_common::{ColumnStatistics, Statistics};
+use datafusion_common::{ColumnStatistics, Precision, Statistics};
 use datafusion_physical_expr::LexOrdering;
 
 use itertools::Itertools;
@@ -126,14 +126,58 @@ pub struct FileScanConfig {
     pub output_ordering: Vec<LexOrdering>,
     /// Indicates whether this plan may produce an infinite stream of records.
     pub infinite_source: bool,
+    /// Previous names of columns in `file_schema`, as `(column, previous name)`
+    /// pairs. Files that do not contain a column are searched for one of its
+    /// previous names instead. Only honoured by formats that support schema
+    /// evolution (currently Parquet).
+    pub column_renames: Vec<(String, String)>,
+    /// Fields of struct columns in `file_schema` to read, as paths of field
+    /// names that start with the column name, e.g. `["s", "a"]` for field `a`
+    /// of struct column `s`. The types of struct columns with paths are
+    /// reduced to these fields, and only the leaves of these fields are read
+    /// from the files. Other columns are read entirely. Only honoured by
+    /// formats that support it (currently Parquet).
+    pub nested_projection: Vec<Vec<String>>,
 }
 
 impl FileScanConfig {
+    /// Returns `file_schema` with the types of struct columns reduced to the
+    /// fields in `nested_projection`
+    pub fn nested_file_schema(&self) -> SchemaRef {
+        if self.nested_projection.is_empty() {
+            return Arc::clone(&self.file_schema);
+        }
+
+        let fields: Fields = self
+            .file_schema
+            .fields()
+            .iter()
+            .map(|field| {
+                let paths: Vec<&[String]> = self
+                    .nested_projection
+                    .iter()
+                    .filter(|path| path.first() == Some(field.name()))
+                    .map(|path| &path[1..])
+                    .collect();
+                if paths.is_empty() {
+                    return field.clone();
+                }
+                let data_type = prune_struct_type(field.data_type(), &paths);
+                Arc::new(field.as_ref().clone().with_data_type(data_type))
+            })
+            .collect();
+        Arc::new(Schema::new_with_metadata(
+            fields,
+            self.file_schema.metadata().clone(),
+        ))
+    }
+
     /// Project the schema and the statistics on the given column indices
     pub fn project(&self) -> (SchemaRef, Statistics, Vec<LexOrdering>) {
+        let file_schema = self.nested_file_schema();
         if self.projection.is_none() && self.table_partition_cols.is_empty() {
             return (
-                Arc::clone(&self.file_schema),
+                file_schema,
                 self.statistics.clone(),
                 self.output_ordering.clone(),
             );
@@ -149,8 +193,8 @@ impl FileScanConfig {
         let mut table_fields = vec![];
         let mut table_cols_stats = vec![];
         for idx in proj_iter {
-            if idx < self.file_schema.fields().len() {
-                table_fields.push(self.file_schema.field(idx).clone());
+            if idx < file_schema.fields().len() {
+                table_fields.push(file_schema.field(idx).clone());
                 if let Some(file_cols_stats) = &self.statistics.column_statistics {
                     table_cols_stats.push(file_cols_stats[idx].clone())
                 } else {
@@ -170,9 +214,8 @@ impl FileScanConfig {
 
         let table_stats = Statistics {
             num_rows: self.statistics.num_rows,
-            is_exact: self.statistics.is_exact,
             // TODO correct byte size?
-            total_byte_size: None,
+            total_byte_size: Precision::Absent,
             column_statistics: Some(table_cols_stats),
         };
 
@@ -280,6 +323,39 @@ impl FileScanConfig {
     }
 }
 
+/// Reduces the struct type `data_type` to the fields at `paths`, which are
+/// relative to `data_type`. An empty path selects the whole value.
+pub(crate) fn prune_struct_type(data_type: &DataType, paths: &[&[String]]) -> DataType {
+    if paths.iter().any(|path| path.is_empty()) {
+        return data_type.clone();
+    }
+    let DataType::Struct(fields) = data_type else {
+        return data_type.clone();
+    };
+
+    let pruned: Fields = fields
+        .iter()
+        .filter_map(|field| {
+            let child_paths: Vec<&[String]> = paths
+                .iter()
+                .filter(|path| &path[0] == field.name())
+                .map(|path| &path[1..])
+                .collect();
+            if child_paths.is_empty() {
+                return None;
+            }
+            let data_type = prune_struct_type(field.data_type(), &child_paths);
+            Some(Arc::new(field.as_ref().clone().with_data_type(data_type)))
+        })
+        .collect();
+
+    if pruned.is_empty() {
+        data_type.clone()
+    } else {
+        DataType::Struct(pruned)
+    }
+}
+
 /// A helper that projects partition columns into the file record batches.
 ///
 /// One interesting trick is the usage of a cache for the key buffers of the partition column
@@ -565,13 +641,13 @@ mod tests {
             Arc::clone(&file_schema),
             Some(vec![file_schema.fields().len(), 0]),
             Statistics {
-                num_rows: Some(10),
+                num_rows: Precision::Inexact(10),
                 // assign the column index to distinct_count to help assert
                 // the source statistic after the projection
                 column_statistics: Some(
                     (0..file_schema.fields().len())
                         .map(|i| ColumnStatistics {
-                            distinct_count: Some(i),
+                            distinct_count: Precision::Inexact(i),
                             ..Default::default()
                         })
                         .collect(),
@@ -595,7 +671,7 @@ mod tests {
         assert_eq!(proj_stat_cols.len(), 2);
         // TODO implement tests for proj_stat_cols[0] once partition column
         // statistics are implemented
-        assert_eq!(proj_stat_cols[1].distinct_count, Some(0));
+        assert_eq!(proj_stat_cols[1].distinct_count, Precision::Inexact(0));
 
         let col_names = conf.projected_file_column_names();
         assert_eq!(col_names, Some(vec!["c1".to_owned()]));
@@ -604,6 +680,57 @@ mod tests {
         assert_eq!(col_indices, Some(vec![0]));
     }
 
+    #[test]
+    fn physical_plan_config_with_nested_projection() {
+        let inner = DataType::Struct(
+            vec![
+                Field::new("c", DataType::Utf8, true),
+                Field::new("d", DataType::Utf8, true),
+            ]
+            .into(),
+        );
+        let s = DataType::Struct(
+            vec![
+                Field::new("a", DataType::Int64, true),
+                Field::new("b", inner, true),
+            ]
+            .into(),
+        );
+        let file_schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("s", s, true),
+        ]));
+        let mut conf = config_for_projection(
+            Arc::clone(&file_schema),
+            Some(vec![1]),
+            Statistics::default(),
+            vec![],
+        );
+        conf.nested_projection = vec![
+            vec!["s".to_string(), "b".to_string(), "d".to_string()],
+            vec!["s".to_string(), "x".to_string()],
+        ];
+
+        let (proj_schema, _, _) = conf.project();
+        let expected = DataType::Struct(
+            vec![Field::new(
+                "b",
+                DataType::Struct(vec![Field::new("d", DataType::Utf8, true)].into()),
+                true,
+            )]
+            .into(),
+        );
+        assert_eq!(proj_schema.fields().len(), 1);
+        assert_eq!(proj_schema.field(0).data_type(), &expected);
+
+        // the file schema itself is not changed
+        assert_eq!(conf.file_schema, file_schema);
+        assert_eq!(
+            conf.nested_file_schema().field(0).data_type(),
+            &DataType::Int64
+        );
+    }
+
     #[test]
     fn partition_column_projector() {
         let file_batch = build_table_i32(
@@ -794,6 +921,8 @@ mod tests {
             table_partition_cols,
             output_ordering: vec![],
             infinite_source: false,
+            column_renames: vec![],
+            nested_projection: vec![],
         }
     }
 }
@@ -31,7 +31,7 @@ use crate::datasource::listing::PartitionedFile;
 use crate::datasource::physical_plan::{
     FileMeta, FileScanConfig, PartitionColumnProjector,
 };
-use crate::error::Result;
+use crate::error::{DataFusionError, Result};
 use crate::physical_plan::metrics::{
     BaselineMetrics, Count, ExecutionPlanMetricsSet, MetricBuilder, Time,
 };
@@ -427,6 +427,13 @@ impl<F: FileOpener> FileStream<F> {
                             self.file_stream_metrics.time_scanning_until_data.stop();
                             self.file_stream_metrics.time_scanning_total.stop();
 
+                            let err = reader_error(err);
+                            // a cancelled query must not move on to the next file
+                            if matches!(err, DataFusionError::Cancelled(_)) {
+                                self.state = FileStreamState::Error;
+                                return Poll::Ready(Some(Err(err)));
+                            }
+
                             match self.on_error {
                                 // If `OnError::Skip` we skip the file as soon as we hit the first error
                                 OnError::Skip => match mem::take(next) {
@@ -454,7 +461,7 @@ impl<F: FileOpener> FileStream<F> {
                                 },
                                 OnError::Fail => {
                                     self.state = FileStreamState::Error;
-                                    return Poll::Ready(Some(Err(err.into())));
+                                    return Poll::Ready(Some(Err(err)));
                                 }
                             }
                         }
@@ -496,6 +503,25 @@ impl<F: FileOpener> FileStream<F> {
     }
 }
 
+/// Converts an error returned by a file reader into a [`DataFusionError`].
+///
+/// Readers return [`ArrowError`]s, so a [`DataFusionError::Cancelled`]
+/// raised while reading arrives wrapped in an [`ArrowError::ExternalError`]
+/// and is unwrapped here. Other errors keep their [`ArrowError`] wrapping.
+fn reader_error(err: ArrowError) -> DataFusionError {
+    match err {
+        ArrowError::ExternalError(e)
+            if matches!(
+                e.downcast_ref::<DataFusionError>(),
+                Some(DataFusionError::Cancelled(_))
+            ) =>
+        {
+            *e.downcast::<DataFusionError>().unwrap()
+        }
+        err => DataFusionError::ArrowError(err),
+    }
+}
+
 impl<F: FileOpener> Stream for FileStream<F> {
     type Item = Result<RecordBatch>;
 
@@ -547,6 +573,8 @@ mod tests {
         error_opening_idx: Vec<usize>,
         /// Index in stream of files which should throw an error while scanning
         error_scanning_idx: Vec<usize>,
+        /// Index in stream of files whose scan should be cancelled
+        cancel_scanning_idx: Vec<usize>,
         /// Index of last file in stream
         current_idx: AtomicUsize,
         /// `RecordBatch` to return
@@ -565,6 +593,11 @@ mod tests {
                 )));
                 let stream = futures::stream::once(error).boxed();
                 Ok(futures::future::ready(Ok(stream)).boxed())
+            } else if self.cancel_scanning_idx.contains(&idx) {
+                let error = DataFusionError::Cancelled("cancelled by user".to_owned());
+                let error = futures::future::ready(Err(ArrowError::from(error)));
+                let stream = futures::stream::once(error).boxed();
+                Ok(futures::future::ready(Ok(stream)).boxed())
             } else {
                 let iterator = self.records.clone().into_iter().map(Ok);
                 let stream = futures::stream::iter(iterator).boxed();
@@ -616,6 +649,13 @@ mod tests {
             self
         }
 
+        /// Specify the index of files in the stream whose scan should be
+        /// cancelled
+        pub fn with_cancelled_scans(mut self, idx: Vec<usize>) -> Self {
+            self.opener.cancel_scanning_idx = idx;
+            self
+        }
+
         /// Specify the behavior of the stream when an error occurs
         pub fn with_on_error(mut self, on_error: OnError) -> Self {
             self.on_error = on_error;
@@ -667,6 +707,8 @@ mod tests {
                 table_partition_cols: vec![],
                 output_ordering: vec![],
                 infinite_source: false,
+                column_renames: vec![],
+                nested_projection: vec![],
             };
             let metrics_set = ExecutionPlanMetricsSet::new();
             let file_stream = FileStream::new(&config, 0, self.opener, &metrics_set)
@@ -692,6 +734,42 @@ mod tests {
             .expect("error executing stream")
     }
 
+    #[tokio::test]
+    async fn cancelled_scan_is_not_skipped() -> Result<()> {
+        let err = FileStreamTest::new()
+            .with_records(vec![make_partition(3), make_partition(2)])
+            .with_num_files(2)
+            .with_on_error(OnError::Skip)
+            .with_cancelled_scans(vec![0])
+            .result()
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, DataFusionError::Cancelled(_)), "{err:?}");
+        assert_eq!(err.to_string(), "Query cancelled: cancelled by user");
+        Ok(())
+    }
+
+    #[test]
+    fn reader_error_keeps_arrow_errors() {
+        // only cancellations are unwrapped
+        let error = DataFusionError::Execution("failed".to_owned());
+        let err = reader_error(ArrowError::from(error));
+        assert!(
+            matches!(
+                err,
+                DataFusionError::ArrowError(ArrowError::ExternalError(_))
+            ),
+            "{err:?}"
+        );
+
+        let err = reader_error(ArrowError::IoError("failed".to_owned()));
+        assert!(
+            matches!(err, DataFusionError::ArrowError(ArrowError::IoError(_))),
+            "{err:?}"
+        );
+    }
+
     #[tokio::test]
     async fn on_error_opening() -> Result<()> {
         let batches = FileStreamTest::new()
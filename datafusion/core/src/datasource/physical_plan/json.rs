@@ -467,6 +467,8 @@ mod tests {
                 table_partition_cols: vec![],
                 output_ordering: vec![],
                 infinite_source: false,
+                column_renames: vec![],
+                nested_projection: vec![],
             },
             file_compression_type.to_owned(),
         );
@@ -546,6 +548,8 @@ mod tests {
                 table_partition_cols: vec![],
                 output_ordering: vec![],
                 infinite_source: false,
+                column_renames: vec![],
+                nested_projection: vec![],
             },
             file_compression_type.to_owned(),
         );
@@ -594,6 +598,8 @@ mod tests {
                 table_partition_cols: vec![],
                 output_ordering: vec![],
                 infinite_source: false,
+                column_renames: vec![],
+                nested_projection: vec![],
             },
             file_compression_type.to_owned(),
         );
@@ -647,6 +653,8 @@ mod tests {
                 table_partition_cols: vec![],
                 output_ordering: vec![],
                 infinite_source: false,
+                column_renames: vec![],
+                nested_projection: vec![],
             },
             file_compression_type.to_owned(),
         );
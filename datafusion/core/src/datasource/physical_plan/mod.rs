@@ -30,7 +30,6 @@ pub(crate) use self::parquet::plan_to_parquet;
 pub use self::parquet::{ParquetExec, ParquetFileMetrics, ParquetFileReaderFactory};
 use arrow::{
     array::new_null_array,
-    compute::can_cast_types,
     datatypes::{DataType, Schema, SchemaRef},
     record_batch::{RecordBatch, RecordBatchOptions},
 };
@@ -41,11 +40,11 @@ pub use file_stream::{FileOpenFuture, FileOpener, FileStream, OnError};
 pub(crate) use json::plan_to_json;
 pub use json::{JsonOpener, NdJsonExec};
 mod file_scan_config;
-pub(crate) use file_scan_config::PartitionColumnProjector;
 pub use file_scan_config::{
     get_scan_files, wrap_partition_type_in_dict, wrap_partition_value_in_dict,
     FileScanConfig,
 };
+pub(crate) use file_scan_config::{prune_struct_type, PartitionColumnProjector};
 
 use crate::error::{DataFusionError, Result};
 use crate::{
@@ -63,7 +62,6 @@ use crate::{
 use datafusion_common::{file_options::FileTypeWriterOptions, plan_err};
 use datafusion_physical_expr::expressions::Column;
 
-use arrow::compute::cast;
 use log::debug;
 use object_store::path::Path;
 use object_store::ObjectMeta;
@@ -74,6 +72,9 @@ use std::{
 };
 
 use super::listing::ListingTableUrl;
+use super::schema_evolution::{
+    can_evolve_type, evolve_array, fields_match_by_name, find_field,
+};
 
 /// The base configurations to provide when creating a physical plan for
 /// writing to any given file format.
@@ -129,6 +130,15 @@ impl DisplayAs for FileScanConfig {
             write!(f, ", projection={}", ProjectSchemaDisplay(&schema))?;
         }
 
+        if !self.nested_projection.is_empty() {
+            let paths = self
+                .nested_projection
+                .iter()
+                .map(|path| path.join("."))
+                .collect::<Vec<_>>();
+            write!(f, ", nested_projection=[{}]", paths.join(", "))?;
+        }
+
         if let Some(limit) = self.limit {
             write!(f, ", limit={limit}")?;
         }
@@ -274,11 +284,26 @@ where
 pub(crate) struct SchemaAdapter {
     /// Schema for the table
     table_schema: SchemaRef,
+    /// Previous names of table columns, see [`FileScanConfig::column_renames`]
+    column_renames: Vec<(String, String)>,
 }
 
 impl SchemaAdapter {
     pub(crate) fn new(table_schema: SchemaRef) -> SchemaAdapter {
-        Self { table_schema }
+        Self {
+            table_schema,
+            column_renames: vec![],
+        }
+    }
+
+    /// Also look up table columns by their previous names in files that
+    /// do not contain them
+    pub(crate) fn with_column_renames(
+        mut self,
+        column_renames: Vec<(String, String)>,
+    ) -> Self {
+        self.column_renames = column_renames;
+        self
     }
 
     /// Map a column index in the table schema to a column index in a particular
@@ -291,14 +316,51 @@ impl SchemaAdapter {
         file_schema: &Schema,
     ) -> Option<usize> {
         let field = self.table_schema.field(index);
-        Some(file_schema.fields.find(field.name())?.0)
+        if let Some((file_idx, _)) = find_field(file_schema.fields(), field) {
+            return Some(file_idx);
+        }
+
+        self.column_renames
+            .iter()
+            .filter(|(column, _)| column == field.name())
+            .find_map(|(_, previous)| Some(file_schema.fields.find(previous)?.0))
+    }
+
+    /// Returns true if each table column is stored in `file_schema` under its
+    /// own name, or is missing from it, including the fields of nested structs.
+    ///
+    /// Parquet row filters and pruning predicates look up the file columns by
+    /// name, and would read the wrong column of a file for which this is not
+    /// the case.
+    pub(crate) fn maps_columns_by_name(&self, file_schema: &Schema) -> bool {
+        self.table_schema
+            .fields()
+            .iter()
+            .enumerate()
+            .all(|(idx, table_field)| {
+                match (
+                    self.map_column_index(idx, file_schema),
+                    file_schema.fields().find(table_field.name()),
+                ) {
+                    (None, None) => true,
+                    (Some(file_idx), Some((name_idx, file_field))) => {
+                        file_idx == name_idx
+                            && fields_match_by_name(
+                                file_field.data_type(),
+                                table_field.data_type(),
+                            )
+                    }
+                    _ => false,
+                }
+            })
     }
 
     /// Creates a `SchemaMapping` that can be used to cast or map the columns from the file schema to the table schema.
     ///
     /// If the provided `file_schema` contains columns of a different type to the expected
     /// `table_schema`, the method will attempt to cast the array data from the file schema
-    /// to the table schema where possible.
+    /// to the table schema where possible. Fields of nested structs are mapped by name,
+    /// and fields missing from the file are filled with nulls.
     ///
     /// Returns a [`SchemaMapping`] that can be applied to the output batch
     /// along with an ordered list of columns to project from the file
@@ -306,28 +368,34 @@ impl SchemaAdapter {
         &self,
         file_schema: &Schema,
     ) -> Result<(SchemaMapping, Vec<usize>)> {
-        let mut projection = Vec::with_capacity(file_schema.fields().len());
-        let mut field_mappings = vec![None; self.table_schema.fields().len()];
-
-        for (file_idx, file_field) in file_schema.fields.iter().enumerate() {
-            if let Some((table_idx, table_field)) =
-                self.table_schema.fields().find(file_field.name())
-            {
-                match can_cast_types(file_field.data_type(), table_field.data_type()) {
-                    true => {
-                        field_mappings[table_idx] = Some(projection.len());
-                        projection.push(file_idx);
-                    }
-                    false => {
-                        return plan_err!(
-                            "Cannot cast file schema field {} of type {:?} to table schema field of type {:?}",
-                            file_field.name(),
-                            file_field.data_type(),
-                            table_field.data_type()
-                        )
-                    }
-                }
+        // (table index, file index) of the columns present in the file
+        let mut matched = Vec::with_capacity(self.table_schema.fields().len());
+
+        for (table_idx, table_field) in self.table_schema.fields().iter().enumerate() {
+            let Some(file_idx) = self.map_column_index(table_idx, file_schema) else {
+                continue;
+            };
+            let file_field = file_schema.field(file_idx);
+            if !can_evolve_type(file_field.data_type(), table_field.data_type()) {
+                return plan_err!(
+                    "Cannot cast file schema field {} of type {:?} to table schema field of type {:?}",
+                    file_field.name(),
+                    file_field.data_type(),
+                    table_field.data_type()
+                );
             }
+            matched.push((table_idx, file_idx));
+        }
+
+        // The projected batch contains the columns in file order
+        let mut projection: Vec<usize> =
+            matched.iter().map(|(_, file_idx)| *file_idx).collect();
+        projection.sort_unstable();
+        projection.dedup();
+
+        let mut field_mappings = vec![None; self.table_schema.fields().len()];
+        for (table_idx, file_idx) in matched {
+            field_mappings[table_idx] = projection.binary_search(&file_idx).ok();
         }
 
         Ok((
@@ -351,6 +419,23 @@ pub struct SchemaMapping {
 }
 
 impl SchemaMapping {
+    /// Returns the file column index and the table type of each table column
+    /// that is read from the file, given the projection returned alongside
+    /// this mapping by [`SchemaAdapter::map_schema`]
+    pub(crate) fn mapped_columns<'a>(
+        &'a self,
+        projection: &[usize],
+    ) -> Vec<(usize, &'a DataType)> {
+        self.table_schema
+            .fields()
+            .iter()
+            .zip(&self.field_mappings)
+            .filter_map(|(field, batch_idx)| {
+                batch_idx.map(|batch_idx| (projection[batch_idx], field.data_type()))
+            })
+            .collect()
+    }
+
     /// Adapts a `RecordBatch` to match the `table_schema` using the stored mapping and conversions.
     fn map_batch(&self, batch: RecordBatch) -> Result<RecordBatch> {
         let batch_rows = batch.num_rows();
@@ -362,10 +447,12 @@ impl SchemaMapping {
             .iter()
             .zip(&self.field_mappings)
             .map(|(field, file_idx)| match file_idx {
-                Some(batch_idx) => cast(&batch_cols[*batch_idx], field.data_type()),
+                Some(batch_idx) => {
+                    evolve_array(&batch_cols[*batch_idx], field.data_type())
+                }
                 None => Ok(new_null_array(field.data_type(), batch_rows)),
             })
-            .collect::<Result<Vec<_>, _>>()?;
+            .collect::<Result<Vec<_>>>()?;
 
         // Necessary to handle empty batches
         let options = RecordBatchOptions::new().with_row_count(Some(batch.num_rows()));
@@ -502,7 +589,7 @@ fn get_projected_output_ordering(
 #[cfg(test)]
 mod tests {
     use arrow_array::cast::AsArray;
-    use arrow_array::types::{Float32Type, Float64Type, UInt32Type};
+    use arrow_array::types::{Float32Type, Float64Type, Int64Type, UInt32Type};
     use arrow_array::{
         BinaryArray, BooleanArray, Float32Array, Int32Array, Int64Array, StringArray,
         UInt64Array,
@@ -630,6 +717,47 @@ mod tests {
         assert_eq!(c4.value(2), 3.0_f32);
     }
 
+    #[test]
+    fn schema_adapter_map_schema_with_renames() {
+        let table_schema = Arc::new(Schema::new(vec![
+            Field::new("user_id", DataType::Int64, true),
+            Field::new("name", DataType::Utf8, true),
+        ]));
+
+        // columns in the file are in a different order than in the table
+        let file_schema = Schema::new(vec![
+            Field::new("name", DataType::Utf8, true),
+            Field::new("extra", DataType::Boolean, true),
+            Field::new("uid", DataType::Int32, true),
+        ]);
+
+        let adapter = SchemaAdapter::new(table_schema.clone())
+            .with_column_renames(vec![("user_id".to_string(), "uid".to_string())]);
+        assert_eq!(adapter.map_column_index(0, &file_schema), Some(2));
+
+        let (mapping, projection) = adapter.map_schema(&file_schema).unwrap();
+        assert_eq!(projection, vec![0, 2]);
+
+        let batch = RecordBatch::try_new(
+            Arc::new(file_schema),
+            vec![
+                Arc::new(StringArray::from(vec!["a", "b"])),
+                Arc::new(BooleanArray::from(vec![true, false])),
+                Arc::new(Int32Array::from(vec![1, 2])),
+            ],
+        )
+        .unwrap();
+        let mapped_batch = mapping
+            .map_batch(batch.project(&projection).unwrap())
+            .unwrap();
+
+        assert_eq!(mapped_batch.schema(), table_schema);
+        let user_id = mapped_batch.column(0).as_primitive::<Int64Type>();
+        let name = mapped_batch.column(1).as_string::<i32>();
+        assert_eq!(user_id, &Int64Array::from(vec![1, 2]));
+        assert_eq!(name, &StringArray::from(vec!["a", "b"]));
+    }
+
     #[test]
     fn file_groups_display_empty() {
         let expected = "{0 groups: []}";
@@ -804,6 +932,8 @@ mod tests {
                     table_partition_cols: vec![],
                     output_ordering: vec![],
                     infinite_source: false,
+                    column_renames: vec![],
+                    nested_projection: vec![],
                 },
                 None,
                 None,
@@ -867,6 +997,8 @@ mod tests {
                             table_partition_cols: vec![],
                             output_ordering: vec![],
                             infinite_source: false,
+                            column_renames: vec![],
+                            nested_projection: vec![],
                         },
                         None,
                         None,
@@ -901,6 +1033,8 @@ mod tests {
                     table_partition_cols: vec![],
                     output_ordering: vec![],
                     infinite_source: false,
+                    column_renames: vec![],
+                    nested_projection: vec![],
                 },
                 None,
                 None,
@@ -938,6 +1072,8 @@ mod tests {
                     table_partition_cols: vec![],
                     output_ordering: vec![],
                     infinite_source: false,
+                    column_renames: vec![],
+                    nested_projection: vec![],
                 },
                 None,
                 None,
@@ -981,6 +1117,8 @@ mod tests {
                     table_partition_cols: vec![],
                     output_ordering: vec![],
                     infinite_source: false,
+                    column_renames: vec![],
+                    nested_projection: vec![],
                 },
                 None,
                 None,
@@ -1020,6 +1158,8 @@ mod tests {
                     table_partition_cols: vec![],
                     output_ordering: vec![],
                     infinite_source: false,
+                    column_renames: vec![],
+                    nested_projection: vec![],
                 },
                 None,
                 None,
@@ -1060,6 +1200,8 @@ mod tests {
                     table_partition_cols: vec![],
                     output_ordering: vec![],
                     infinite_source: false,
+                    column_renames: vec![],
+                    nested_projection: vec![],
                 },
                 None,
                 None,
@@ -1089,6 +1231,8 @@ mod tests {
                     table_partition_cols: vec![],
                     output_ordering: vec![],
                     infinite_source: false,
+                    column_renames: vec![],
+                    nested_projection: vec![],
                 },
                 None,
                 None,
@@ -24,18 +24,24 @@ use crate::datasource::physical_plan::{
     parquet::page_filter::PagePruningPredicate, DisplayAs, FileMeta, FileScanConfig,
     SchemaAdapter,
 };
+use crate::datasource::schema_evolution::find_field;
 use crate::{
     config::ConfigOptions,
     datasource::listing::ListingTableUrl,
     error::{DataFusionError, Result},
-    execution::context::TaskContext,
+    execution::{context::TaskContext, CancellationToken},
     physical_optimizer::pruning::PruningPredicate,
     physical_plan::{
+        joins::runtime_filter::{
+            filter_stream, runtime_filters_predicate, RuntimeFilter,
+        },
         metrics::{ExecutionPlanMetricsSet, MetricBuilder, MetricsSet},
         DisplayFormatType, ExecutionPlan, Partitioning, SendableRecordBatchStream,
         Statistics,
     },
 };
+use datafusion_expr::Operator;
+use datafusion_physical_expr::expressions::BinaryExpr;
 use datafusion_physical_expr::{
     ordering_equivalence_properties_helper, PhysicalSortExpr,
 };
@@ -47,7 +53,7 @@ use std::ops::Range;
 use std::sync::Arc;
 use tokio::task::JoinSet;
 
-use arrow::datatypes::{DataType, SchemaRef};
+use arrow::datatypes::{DataType, Schema, SchemaRef};
 use arrow::error::ArrowError;
 use datafusion_physical_expr::{
     LexOrdering, OrderingEquivalenceProperties, PhysicalExpr,
@@ -63,7 +69,7 @@ use parquet::arrow::async_reader::{AsyncFileReader, ParquetObjectReader};
 use parquet::arrow::{AsyncArrowWriter, ParquetRecordBatchStreamBuilder, ProjectionMask};
 use parquet::basic::{ConvertedType, LogicalType};
 use parquet::file::{metadata::ParquetMetaData, properties::WriterProperties};
-use parquet::schema::types::ColumnDescriptor;
+use parquet::schema::types::{ColumnDescriptor, SchemaDescriptor};
 
 mod metrics;
 pub mod page_filter;
@@ -101,6 +107,8 @@ pub struct ParquetExec {
     metadata_size_hint: Option<usize>,
     /// Optional user defined parquet file reader factory
     parquet_file_reader_factory: Option<Arc<dyn ParquetFileReaderFactory>>,
+    /// Filters published by joins at runtime
+    runtime_filters: Vec<RuntimeFilter>,
 }
 
 impl ParquetExec {
@@ -163,6 +171,7 @@ impl ParquetExec {
             page_pruning_predicate,
             metadata_size_hint,
             parquet_file_reader_factory: None,
+            runtime_filters: vec![],
         }
     }
 
@@ -176,11 +185,42 @@ impl ParquetExec {
         self.predicate.as_ref()
     }
 
+    /// Returns a copy of this plan that only reads the fields of struct
+    /// columns at `nested_projection`, see [`FileScanConfig::nested_projection`].
+    ///
+    /// Only the Parquet leaves of these fields are read from the files, so
+    /// that queries that access a few fields of a wide struct column do not
+    /// decode the others.
+    pub fn with_nested_projection(&self, nested_projection: Vec<Vec<String>>) -> Self {
+        let mut exec = self.clone();
+        exec.base_config.nested_projection = nested_projection;
+        let (projected_schema, _, _) = exec.base_config.project();
+        exec.projected_schema = projected_schema;
+        exec
+    }
+
     /// Optional reference to this parquet scan's pruning predicate
     pub fn pruning_predicate(&self) -> Option<&Arc<PruningPredicate>> {
         self.pruning_predicate.as_ref()
     }
 
+    /// Also skips the data rejected by `runtime_filters`, whose columns refer
+    /// to the output schema of this scan, once the joins they belong to have
+    /// published them.
+    ///
+    /// The row groups and pages of files opened after a filter is published
+    /// are pruned with it, and it removes rows from all batches read after it
+    /// is published.
+    pub fn with_runtime_filters(mut self, runtime_filters: Vec<RuntimeFilter>) -> Self {
+        self.runtime_filters = runtime_filters;
+        self
+    }
+
+    /// The filters published by joins at runtime that are applied
+    pub fn runtime_filters(&self) -> &[RuntimeFilter] {
+        &self.runtime_filters
+    }
+
     /// Optional user defined parquet file reader factory.
     ///
     /// `ParquetFileReaderFactory` complements `TableProvider`, It enables users to provide custom
@@ -360,6 +400,12 @@ impl ExecutionPlan for ParquetExec {
 
         let config_options = ctx.session_config().options();
 
+        let runtime_filters = self
+            .runtime_filters
+            .iter()
+            .filter_map(|filter| filter.with_schema(&self.base_config.file_schema))
+            .collect();
+
         let opener = ParquetOpener {
             partition_index,
             projection: Arc::from(projection),
@@ -368,19 +414,22 @@ impl ExecutionPlan for ParquetExec {
             predicate: self.predicate.clone(),
             pruning_predicate: self.pruning_predicate.clone(),
             page_pruning_predicate: self.page_pruning_predicate.clone(),
-            table_schema: self.base_config.file_schema.clone(),
+            table_schema: self.base_config.nested_file_schema(),
             metadata_size_hint: self.metadata_size_hint,
             metrics: self.metrics.clone(),
             parquet_file_reader_factory,
             pushdown_filters: self.pushdown_filters(config_options),
             reorder_filters: self.reorder_filters(config_options),
             enable_page_index: self.enable_page_index(config_options),
+            column_renames: self.base_config.column_renames.clone(),
+            cancellation_token: ctx.cancellation_token().clone(),
+            runtime_filters,
         };
 
         let stream =
             FileStream::new(&self.base_config, partition_index, opener, &self.metrics)?;
 
-        Ok(Box::pin(stream))
+        Ok(filter_stream(Box::pin(stream), &self.runtime_filters))
     }
 
     fn metrics(&self) -> Option<MetricsSet> {
@@ -412,6 +461,70 @@ struct ParquetOpener {
     pushdown_filters: bool,
     reorder_filters: bool,
     enable_page_index: bool,
+    column_renames: Vec<(String, String)>,
+    cancellation_token: CancellationToken,
+    /// Filters published by joins at runtime, bound to the table schema
+    runtime_filters: Vec<RuntimeFilter>,
+}
+
+impl ParquetOpener {
+    /// Returns the predicates to prune the row groups and pages of a file
+    /// with, which include the runtime filters published so far
+    fn pruning_predicates(
+        &self,
+    ) -> (
+        Option<Arc<PruningPredicate>>,
+        Option<Arc<PagePruningPredicate>>,
+    ) {
+        let runtime_predicate =
+            match runtime_filters_predicate(&self.runtime_filters, &self.table_schema) {
+                Ok(Some(predicate)) => predicate,
+                Ok(None) => {
+                    return (
+                        self.pruning_predicate.clone(),
+                        self.page_pruning_predicate.clone(),
+                    )
+                }
+                Err(e) => {
+                    debug!("Ignoring error building runtime filter predicate: {e}");
+                    return (
+                        self.pruning_predicate.clone(),
+                        self.page_pruning_predicate.clone(),
+                    );
+                }
+            };
+
+        let predicate: Arc<dyn PhysicalExpr> = match &self.predicate {
+            Some(predicate) => Arc::new(BinaryExpr::new(
+                predicate.clone(),
+                Operator::And,
+                runtime_predicate,
+            )),
+            None => runtime_predicate,
+        };
+
+        let pruning_predicate =
+            match PruningPredicate::try_new(predicate.clone(), self.table_schema.clone())
+            {
+                Ok(pruning_predicate) => Some(Arc::new(pruning_predicate))
+                    .filter(|p| !p.allways_true())
+                    .or_else(|| self.pruning_predicate.clone()),
+                Err(e) => {
+                    debug!("Could not create runtime pruning predicate: {e}");
+                    self.pruning_predicate.clone()
+                }
+            };
+        let page_pruning_predicate =
+            match PagePruningPredicate::try_new(&predicate, self.table_schema.clone()) {
+                Ok(pruning_predicate) => Some(Arc::new(pruning_predicate)),
+                Err(e) => {
+                    debug!("Could not create runtime page pruning predicate: {e}");
+                    self.page_pruning_predicate.clone()
+                }
+            };
+
+        (pruning_predicate, page_pruning_predicate)
+    }
 }
 
 impl FileOpener for ParquetOpener {
@@ -435,20 +548,22 @@ impl FileOpener for ParquetOpener {
         let batch_size = self.batch_size;
         let projection = self.projection.clone();
         let projected_schema = SchemaRef::from(self.table_schema.project(&projection)?);
-        let schema_adapter = SchemaAdapter::new(projected_schema);
+        let schema_adapter = SchemaAdapter::new(projected_schema)
+            .with_column_renames(self.column_renames.clone());
         let predicate = self.predicate.clone();
-        let pruning_predicate = self.pruning_predicate.clone();
-        let page_pruning_predicate = self.page_pruning_predicate.clone();
+        let (pruning_predicate, page_pruning_predicate) = self.pruning_predicates();
         let table_schema = self.table_schema.clone();
+        let table_schema_adapter = SchemaAdapter::new(table_schema.clone())
+            .with_column_renames(self.column_renames.clone());
         let reorder_predicates = self.reorder_filters;
         let pushdown_filters = self.pushdown_filters;
-        let enable_page_index = should_enable_page_index(
-            self.enable_page_index,
-            &self.page_pruning_predicate,
-        );
+        let enable_page_index =
+            should_enable_page_index(self.enable_page_index, &page_pruning_predicate);
         let limit = self.limit;
+        let cancellation_token = self.cancellation_token.clone();
 
         Ok(Box::pin(async move {
+            cancellation_token.check()?;
             let options = ArrowReaderOptions::new().with_page_index(enable_page_index);
             let mut builder =
                 ParquetRecordBatchStreamBuilder::new_with_options(reader, options)
@@ -458,11 +573,23 @@ impl FileOpener for ParquetOpener {
                 schema_adapter.map_schema(builder.schema())?;
             // let predicate = predicate.map(|p| reassign_predicate_columns(p, builder.schema(), true)).transpose()?;
 
-            let mask = ProjectionMask::roots(
+            let mask = leaf_projection_mask(
                 builder.parquet_schema(),
-                adapted_projections.iter().cloned(),
+                builder.schema(),
+                &schema_mapping.mapped_columns(&adapted_projections),
             );
 
+            // Row filters and pruning predicates look up the file columns by
+            // name, so they can not be used on a file storing some table
+            // columns under another name (renamed columns), or storing other
+            // columns under their names (columns dropped and re-added)
+            let (pruning_predicate, page_pruning_predicate, pushdown_filters) =
+                if table_schema_adapter.maps_columns_by_name(builder.schema()) {
+                    (pruning_predicate, page_pruning_predicate, pushdown_filters)
+                } else {
+                    (None, None, false)
+                };
+
             // Filter pushdown: evaluate predicates during scan
             if let Some(predicate) = pushdown_filters.then_some(predicate).flatten() {
                 let row_filter = row_filter::build_row_filter(
@@ -524,7 +651,12 @@ impl FileOpener for ParquetOpener {
             let adapted = stream
                 .map_err(|e| ArrowError::ExternalError(Box::new(e)))
                 .map(move |maybe_batch| {
-                    maybe_batch
+                    // stop decoding further batches once the query is cancelled,
+                    // the `FileStream` unwraps the `DataFusionError::Cancelled`
+                    cancellation_token
+                        .check()
+                        .map_err(Into::into)
+                        .and(maybe_batch)
                         .and_then(|b| schema_mapping.map_batch(b).map_err(Into::into))
                 });
 
@@ -545,6 +677,92 @@ fn should_enable_page_index(
             .unwrap_or(false)
 }
 
+/// Builds a [`ProjectionMask`] that reads the file columns in `columns`,
+/// given as pairs of file column index and table type.
+///
+/// For struct columns, only the leaves of the fields that are present in the
+/// table type are read. The table type of a struct column may have been
+/// reduced to the fields accessed by a query (see
+/// [`ParquetExec::with_nested_projection`]).
+fn leaf_projection_mask(
+    schema_descr: &SchemaDescriptor,
+    file_schema: &Schema,
+    columns: &[(usize, &DataType)],
+) -> ProjectionMask {
+    // index of the first leaf of each column of the file
+    let mut offsets = Vec::with_capacity(file_schema.fields().len());
+    let mut num_leaves = 0;
+    for field in file_schema.fields() {
+        offsets.push(num_leaves);
+        num_leaves += count_leaves(field.data_type());
+    }
+
+    // the arrow schema does not map to the parquet leaves as expected
+    if num_leaves != schema_descr.num_columns() {
+        return ProjectionMask::roots(
+            schema_descr,
+            columns.iter().map(|(file_idx, _)| *file_idx),
+        );
+    }
+
+    let mut leaves = vec![];
+    for (file_idx, table_type) in columns {
+        select_leaves(
+            file_schema.field(*file_idx).data_type(),
+            table_type,
+            offsets[*file_idx],
+            &mut leaves,
+        );
+    }
+    ProjectionMask::leaves(schema_descr, leaves)
+}
+
+/// Adds the leaves of `file_type`, starting at `offset`, that are needed
+/// to read `table_type` to `leaves`
+fn select_leaves(
+    file_type: &DataType,
+    table_type: &DataType,
+    offset: usize,
+    leaves: &mut Vec<usize>,
+) {
+    match (file_type, table_type) {
+        (DataType::Struct(file_fields), DataType::Struct(table_fields)) => {
+            let selected = leaves.len();
+            let mut child_offset = offset;
+            for file_field in file_fields {
+                if let Some((_, table_field)) = find_field(table_fields, file_field) {
+                    select_leaves(
+                        file_field.data_type(),
+                        table_field.data_type(),
+                        child_offset,
+                        leaves,
+                    );
+                }
+                child_offset += count_leaves(file_field.data_type());
+            }
+            // read at least one leaf, so that the nulls of the struct are known
+            if leaves.len() == selected && child_offset > offset {
+                leaves.push(offset);
+            }
+        }
+        _ => leaves.extend(offset..offset + count_leaves(file_type)),
+    }
+}
+
+/// Returns the number of parquet leaves of a column of type `data_type`
+fn count_leaves(data_type: &DataType) -> usize {
+    match data_type {
+        DataType::Struct(fields) => {
+            fields.iter().map(|f| count_leaves(f.data_type())).sum()
+        }
+        DataType::List(field)
+        | DataType::LargeList(field)
+        | DataType::FixedSizeList(field, _)
+        | DataType::Map(field, _) => count_leaves(field.data_type()),
+        _ => 1,
+    }
+}
+
 /// Factory of parquet file readers.
 ///
 /// Provides means to implement custom data access interface.
@@ -758,7 +976,8 @@ mod tests {
         array::{Int64Array, Int8Array, StringArray},
         datatypes::{DataType, Field, SchemaBuilder},
     };
-    use arrow_array::Date64Array;
+    use arrow_array::cast::AsArray;
+    use arrow_array::{Date64Array, StructArray};
     use chrono::{TimeZone, Utc};
     use datafusion_common::ScalarValue;
     use datafusion_common::{assert_contains, ToDFSchema};
@@ -769,6 +988,7 @@ mod tests {
     use object_store::local::LocalFileSystem;
     use object_store::path::Path;
     use object_store::ObjectMeta;
+    use parquet::arrow::arrow_to_parquet_schema;
     use std::fs::{self, File};
     use std::io::Write;
     use tempfile::TempDir;
@@ -791,6 +1011,7 @@ mod tests {
         predicate: Option<Expr>,
         pushdown_predicate: bool,
         page_index_predicate: bool,
+        nested_projection: Vec<Vec<String>>,
     }
 
     impl RoundTrip {
@@ -808,6 +1029,11 @@ mod tests {
             self
         }
 
+        fn with_nested_projection(mut self, nested_projection: Vec<Vec<String>>) -> Self {
+            self.nested_projection = nested_projection;
+            self
+        }
+
         fn with_predicate(mut self, predicate: Expr) -> Self {
             self.predicate = Some(predicate);
             self
@@ -839,6 +1065,7 @@ mod tests {
                 predicate,
                 pushdown_predicate,
                 page_index_predicate,
+                nested_projection,
             } = self;
 
             let file_schema = match schema {
@@ -871,6 +1098,8 @@ mod tests {
                     table_partition_cols: vec![],
                     output_ordering: vec![],
                     infinite_source: false,
+                    column_renames: vec![],
+                    nested_projection,
                 },
                 predicate,
                 None,
@@ -979,6 +1208,120 @@ mod tests {
         assert_batches_sorted_eq!(expected, &read);
     }
 
+    #[tokio::test]
+    async fn read_pruned_struct() {
+        let s: ArrayRef = Arc::new(StructArray::from(vec![
+            (
+                Arc::new(Field::new("a", DataType::Int32, true)),
+                Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef,
+            ),
+            (
+                Arc::new(Field::new("b", DataType::Utf8, true)),
+                Arc::new(StringArray::from(vec!["x", "y", "z"])) as ArrayRef,
+            ),
+        ]));
+        let batch = create_batch(vec![("s", s)]);
+
+        let full = RoundTrip::new().round_trip(vec![batch.clone()]).await;
+
+        // only read field `b` of `s`
+        let pruned = RoundTrip::new()
+            .with_nested_projection(vec![vec!["s".to_string(), "b".to_string()]])
+            .round_trip(vec![batch])
+            .await;
+
+        let pruned_type =
+            DataType::Struct(vec![Field::new("b", DataType::Utf8, true)].into());
+        let schema = Arc::new(Schema::new(vec![Field::new("s", pruned_type, true)]));
+        assert_eq!(pruned.parquet_exec.schema(), schema);
+
+        // the leaf of field `a` is not read
+        assert!(
+            get_value(&pruned.parquet_exec.metrics().unwrap(), "bytes_scanned")
+                < get_value(&full.parquet_exec.metrics().unwrap(), "bytes_scanned")
+        );
+
+        let read = pruned.batches.unwrap();
+        assert_eq!(read.len(), 1);
+        assert_eq!(read[0].schema(), schema);
+        let s = read[0].column(0).as_struct();
+        assert_eq!(s.num_columns(), 1);
+        assert_eq!(
+            s.column(0).as_string::<i32>(),
+            &StringArray::from(vec!["x", "y", "z"])
+        );
+    }
+
+    #[test]
+    fn leaf_projection_mask_for_pruned_struct() {
+        let inner = DataType::Struct(
+            vec![
+                Field::new("c", DataType::Int32, true),
+                Field::new("d", DataType::Int32, true),
+            ]
+            .into(),
+        );
+        let file_schema = Schema::new(vec![
+            Field::new("id", DataType::Int32, true),
+            Field::new(
+                "s",
+                DataType::Struct(
+                    vec![
+                        Field::new("a", DataType::Int32, true),
+                        Field::new("b", inner, true),
+                        Field::new(
+                            "l",
+                            DataType::List(Arc::new(Field::new(
+                                "item",
+                                DataType::Int32,
+                                true,
+                            ))),
+                            true,
+                        ),
+                    ]
+                    .into(),
+                ),
+                true,
+            ),
+            Field::new("z", DataType::Int32, true),
+        ]);
+        // leaves: id, s.a, s.b.c, s.b.d, s.l, z
+        let schema_descr = arrow_to_parquet_schema(&file_schema).unwrap();
+        let leaves = |mask: ProjectionMask| -> Vec<usize> {
+            (0..schema_descr.num_columns())
+                .filter(|i| mask.leaf_included(*i))
+                .collect()
+        };
+
+        let table_type = DataType::Struct(
+            vec![
+                Field::new(
+                    "b",
+                    DataType::Struct(vec![Field::new("d", DataType::Int64, true)].into()),
+                    true,
+                ),
+                Field::new(
+                    "l",
+                    DataType::List(Arc::new(Field::new("item", DataType::Int32, true))),
+                    true,
+                ),
+            ]
+            .into(),
+        );
+        let mask = leaf_projection_mask(
+            &schema_descr,
+            &file_schema,
+            &[(1, &table_type), (2, &DataType::Int32)],
+        );
+        assert_eq!(leaves(mask), vec![3, 4, 5]);
+
+        // none of the fields of the struct are in the file
+        let table_type =
+            DataType::Struct(vec![Field::new("x", DataType::Int32, true)].into());
+        let mask = leaf_projection_mask(&schema_descr, &file_schema, &[(1, &table_type)]);
+        assert_eq!(leaves(mask), vec![1]);
+    }
+
     #[tokio::test]
     async fn evolved_schema_inconsistent_order() {
         let c1: ArrayRef =
@@ -1281,6 +1624,57 @@ mod tests {
         assert_eq!(get_value(&metrics, "pushdown_rows_filtered"), 5);
     }
 
+    fn field_with_id(name: &str, id: i32) -> Field {
+        Field::new(name, DataType::Int64, true).with_metadata(
+            std::collections::HashMap::from([(
+                "PARQUET:field_id".to_string(),
+                id.to_string(),
+            )]),
+        )
+    }
+
+    #[tokio::test]
+    async fn evolved_schema_renamed_field_id_with_filter_pushdown() {
+        // the file stores the table column `c2` as `c1`, with the same field id
+        let file_schema = Arc::new(Schema::new(vec![field_with_id("c1", 1)]));
+        let c1: ArrayRef = Arc::new(Int64Array::from(vec![Some(1), Some(2), None]));
+        let batch = RecordBatch::try_new(file_schema, vec![c1]).unwrap();
+
+        let table_schema = Arc::new(Schema::new(vec![field_with_id("c2", 1)]));
+        let rt = RoundTrip::new()
+            .with_schema(table_schema)
+            .with_predicate(col("c2").eq(lit(2_i64)))
+            .with_pushdown_predicate()
+            .round_trip(vec![batch])
+            .await;
+
+        let expected = ["+----+", "| c2 |", "+----+", "| 2  |", "+----+"];
+        assert_batches_sorted_eq!(expected, &rt.batches.unwrap());
+    }
+
+    #[tokio::test]
+    async fn evolved_schema_readded_field_id_with_filter_pushdown() {
+        // the file stores a dropped column under the name of the table column
+        // `c1`, which was added again with another field id and is not in the
+        // file
+        let file_schema = Arc::new(Schema::new(vec![field_with_id("c1", 1)]));
+        let c1: ArrayRef = Arc::new(Int64Array::from(vec![Some(1), Some(2), Some(3)]));
+        let batch = RecordBatch::try_new(file_schema, vec![c1]).unwrap();
+
+        let table_schema = Arc::new(Schema::new(vec![field_with_id("c1", 2)]));
+        let rt = RoundTrip::new()
+            .with_schema(table_schema)
+            .with_predicate(col("c1").is_null())
+            .with_pushdown_predicate()
+            .round_trip(vec![batch])
+            .await;
+
+        let expected = [
+            "+----+", "| c1 |", "+----+", "|    |", "|    |", "|    |", "+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &rt.batches.unwrap());
+    }
+
     #[tokio::test]
     async fn evolved_schema_disjoint_schema_with_page_index_pushdown() {
         let c1: ArrayRef = Arc::new(StringArray::from(vec![
@@ -1528,6 +1922,8 @@ mod tests {
                     table_partition_cols: vec![],
                     output_ordering: vec![],
                     infinite_source: false,
+                    column_renames: vec![],
+                    nested_projection: vec![],
                 },
                 None,
                 None,
@@ -1642,6 +2038,8 @@ mod tests {
                 ],
                 output_ordering: vec![],
                 infinite_source: false,
+                column_renames: vec![],
+                nested_projection: vec![],
             },
             None,
             None,
@@ -1705,6 +2103,8 @@ mod tests {
                 table_partition_cols: vec![],
                 output_ordering: vec![],
                 infinite_source: false,
+                column_renames: vec![],
+                nested_projection: vec![],
             },
             None,
             None,
@@ -103,6 +103,13 @@ pub trait TableProvider: Sync + Send {
         None
     }
 
+    /// Returns a copy of this table whose scans report `statistics`, such
+    /// as the ones collected by `ANALYZE TABLE`, or `None` if not supported
+    #[allow(unused_variables)]
+    fn with_statistics(&self, statistics: Statistics) -> Option<Arc<dyn TableProvider>> {
+        None
+    }
+
     /// Return an [`ExecutionPlan`] to insert data into this table, if
     /// supported.
     ///
@@ -0,0 +1,490 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Schema evolution for tables made of files written at different points
+//! in the table's history.
+//!
+//! The rules applied are:
+//!
+//! * Columns are matched by their Parquet field id when both sides carry one,
+//!   and by name otherwise. Fields of nested structs are matched the same way.
+//! * Columns missing from a file are read as NULL, including missing fields
+//!   of nested structs.
+//! * When merging the schemas of several files, columns that are not present
+//!   in every file become nullable, and differing types are widened (see
+//!   [`widen_types`]).
+
+use std::cmp::max;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use arrow::compute::{can_cast_types, cast};
+use arrow_array::{new_null_array, Array, ArrayRef, ListArray, StructArray};
+use arrow_schema::{DataType, Field, FieldRef, Fields, Schema};
+use datafusion_common::cast::{as_list_array, as_struct_array};
+use datafusion_common::{plan_err, DataFusionError, Result};
+
+/// Field metadata key under which the Parquet reader exposes field ids
+const FIELD_ID_META_KEY: &str = "PARQUET:field_id";
+
+/// Merges the schemas of the files of a table into a single table schema,
+/// widening the types of columns that differ between files.
+///
+/// Unlike [`Schema::try_merge`], this accepts files in which the same
+/// column has been stored with different (compatible) types, and marks
+/// columns that are missing from some of the files as nullable.
+pub fn merge_schemas(schemas: impl IntoIterator<Item = Schema>) -> Result<Schema> {
+    let mut merged: Option<Vec<Field>> = None;
+    let mut metadata = HashMap::new();
+
+    for schema in schemas {
+        for (key, value) in schema.metadata {
+            metadata.entry(key).or_insert(value);
+        }
+        merged = Some(match merged {
+            None => schema.fields.iter().map(|f| f.as_ref().clone()).collect(),
+            Some(fields) => merge_fields(&fields, &schema.fields)?,
+        });
+    }
+
+    Ok(Schema::new_with_metadata(
+        merged.unwrap_or_default(),
+        metadata,
+    ))
+}
+
+/// Returns the narrowest type that both `a` and `b` can be losslessly
+/// (or, for integers that do not fit in 64 bits and for floats, nearly
+/// losslessly) converted to, if any:
+///
+/// * integers widen to the larger width; mixing signed and unsigned
+///   integers widens to a signed integer wide enough for both
+/// * mixing floating point numbers with other numbers widens to `Float64`
+/// * decimals widen to a precision and scale that hold both
+/// * `Utf8` and `LargeUtf8` widen to `LargeUtf8`, and likewise for binary
+/// * lists and structs are widened element / field wise
+pub fn widen_types(a: &DataType, b: &DataType) -> Option<DataType> {
+    use DataType::*;
+
+    if a == b {
+        return Some(a.clone());
+    }
+
+    match (a, b) {
+        (Null, other) | (other, Null) => Some(other.clone()),
+        (Utf8 | LargeUtf8, Utf8 | LargeUtf8) => Some(LargeUtf8),
+        (Binary | LargeBinary, Binary | LargeBinary) => Some(LargeBinary),
+        (Decimal128(p1, s1), Decimal128(p2, s2)) => {
+            let scale = max(*s1, *s2);
+            let integer_digits = max(*p1 as i16 - *s1 as i16, *p2 as i16 - *s2 as i16);
+            let precision = integer_digits + scale as i16;
+            (precision <= 38).then_some(Decimal128(precision as u8, scale))
+        }
+        _ if is_float(a) && is_number(b) || is_number(a) && is_float(b) => Some(Float64),
+        (List(a), List(b)) => Some(List(Arc::new(merge_field(a, b).ok()?))),
+        (Struct(a), Struct(b)) => {
+            let a: Vec<Field> = a.iter().map(|f| f.as_ref().clone()).collect();
+            Some(Struct(merge_fields(&a, b).ok()?.into()))
+        }
+        _ => widen_integers(a, b),
+    }
+}
+
+/// Merges `other` into `fields`: fields present in both are widened, and
+/// fields present in only one of them become nullable.
+///
+/// Fields are matched like [`find_field`] does, so a column renamed between
+/// files keeps its field id and is merged under the name it has in `fields`.
+/// A field of `other` with the same name as a field of `fields` but another
+/// field id is a column that was dropped and re-added, and replaces it.
+fn merge_fields(fields: &[Field], other: &Fields) -> Result<Vec<Field>> {
+    let mut merged = Vec::with_capacity(fields.len());
+    for field in fields {
+        merged.push(match find_field(other, field) {
+            Some((_, other_field)) => merge_field(field, other_field)?,
+            None => match other.find(field.name()) {
+                Some((_, other_field)) => other_field.as_ref().clone(),
+                None => field.clone(),
+            }
+            .with_nullable(true),
+        });
+    }
+
+    let fields: Fields = fields.iter().cloned().collect();
+    for other_field in other.iter() {
+        if find_field(&fields, other_field).is_none()
+            && !merged.iter().any(|f| f.name() == other_field.name())
+        {
+            merged.push(other_field.as_ref().clone().with_nullable(true));
+        }
+    }
+    Ok(merged)
+}
+
+fn merge_field(a: &Field, b: &Field) -> Result<Field> {
+    match widen_types(a.data_type(), b.data_type()) {
+        Some(data_type) => Ok(a
+            .clone()
+            .with_data_type(data_type)
+            .with_nullable(a.is_nullable() || b.is_nullable())),
+        None => plan_err!(
+            "Cannot merge field {} of type {:?} with type {:?}",
+            a.name(),
+            a.data_type(),
+            b.data_type()
+        ),
+    }
+}
+
+fn is_float(data_type: &DataType) -> bool {
+    matches!(
+        data_type,
+        DataType::Float16 | DataType::Float32 | DataType::Float64
+    )
+}
+
+fn is_number(data_type: &DataType) -> bool {
+    is_float(data_type)
+        || integer_width(data_type).is_some()
+        || matches!(data_type, DataType::Decimal128(_, _))
+}
+
+/// Returns the bit width of an integer type, and whether it is signed
+fn integer_width(data_type: &DataType) -> Option<(u8, bool)> {
+    match data_type {
+        DataType::Int8 => Some((8, true)),
+        DataType::Int16 => Some((16, true)),
+        DataType::Int32 => Some((32, true)),
+        DataType::Int64 => Some((64, true)),
+        DataType::UInt8 => Some((8, false)),
+        DataType::UInt16 => Some((16, false)),
+        DataType::UInt32 => Some((32, false)),
+        DataType::UInt64 => Some((64, false)),
+        _ => None,
+    }
+}
+
+fn widen_integers(a: &DataType, b: &DataType) -> Option<DataType> {
+    let (a_width, a_signed) = integer_width(a)?;
+    let (b_width, b_signed) = integer_width(b)?;
+
+    let (width, signed) = match (a_signed, b_signed) {
+        (true, true) | (false, false) => (max(a_width, b_width), a_signed),
+        // a signed integer needs one more bit than the unsigned one
+        (true, false) => (max(a_width, b_width.saturating_mul(2)).min(64), true),
+        (false, true) => (max(b_width, a_width.saturating_mul(2)).min(64), true),
+    };
+
+    Some(match (width, signed) {
+        (8, true) => DataType::Int8,
+        (16, true) => DataType::Int16,
+        (32, true) => DataType::Int32,
+        (64, true) => DataType::Int64,
+        (8, false) => DataType::UInt8,
+        (16, false) => DataType::UInt16,
+        (32, false) => DataType::UInt32,
+        _ => DataType::UInt64,
+    })
+}
+
+fn field_id(field: &Field) -> Option<&String> {
+    field.metadata().get(FIELD_ID_META_KEY)
+}
+
+/// Finds the field in `fields` that stores `field`, first by field id
+/// and then by name.
+///
+/// A field with the same name but a different field id is a different
+/// column (e.g. one that was dropped and re-added), and is not matched.
+pub(crate) fn find_field<'a>(
+    fields: &'a Fields,
+    field: &Field,
+) -> Option<(usize, &'a FieldRef)> {
+    let id = field_id(field);
+    if let Some(id) = id {
+        let found = fields
+            .iter()
+            .enumerate()
+            .find(|(_, f)| field_id(f) == Some(id));
+        if found.is_some() {
+            return found;
+        }
+    }
+
+    fields
+        .find(field.name())
+        .filter(|(_, f)| id.is_none() || field_id(f).is_none())
+}
+
+/// Returns true if the nested fields of the table type `to` are stored in the
+/// file type `from` under their own names, or are missing from it
+pub(crate) fn fields_match_by_name(from: &DataType, to: &DataType) -> bool {
+    match (from, to) {
+        (DataType::Struct(from_fields), DataType::Struct(to_fields)) => {
+            to_fields.iter().all(|to_field| {
+                match (
+                    find_field(from_fields, to_field),
+                    from_fields.find(to_field.name()),
+                ) {
+                    (None, None) => true,
+                    (Some((idx, from_field)), Some((name_idx, _))) => {
+                        idx == name_idx
+                            && fields_match_by_name(
+                                from_field.data_type(),
+                                to_field.data_type(),
+                            )
+                    }
+                    _ => false,
+                }
+            })
+        }
+        (DataType::List(from_field), DataType::List(to_field)) => {
+            fields_match_by_name(from_field.data_type(), to_field.data_type())
+        }
+        _ => true,
+    }
+}
+
+/// Returns true if data of type `from`, read from a file, can be converted
+/// to the table type `to` by [`evolve_array`]
+pub(crate) fn can_evolve_type(from: &DataType, to: &DataType) -> bool {
+    match (from, to) {
+        (DataType::Struct(from_fields), DataType::Struct(to_fields)) => to_fields
+            .iter()
+            .all(|to_field| match find_field(from_fields, to_field) {
+                Some((_, from_field)) => {
+                    can_evolve_type(from_field.data_type(), to_field.data_type())
+                }
+                None => to_field.is_nullable(),
+            }),
+        (DataType::List(from_field), DataType::List(to_field)) => {
+            can_evolve_type(from_field.data_type(), to_field.data_type())
+        }
+        _ => can_cast_types(from, to),
+    }
+}
+
+/// Converts `array`, read from a file, to the table type `to_type`.
+///
+/// Struct fields are matched by field id or name, and fields missing from
+/// `array` are filled with nulls. Other types are cast.
+pub(crate) fn evolve_array(array: &ArrayRef, to_type: &DataType) -> Result<ArrayRef> {
+    match (array.data_type(), to_type) {
+        (DataType::Struct(from_fields), DataType::Struct(to_fields)) => {
+            let struct_array = as_struct_array(array.as_ref())?;
+            let columns = to_fields
+                .iter()
+                .map(|to_field| match find_field(from_fields, to_field) {
+                    Some((idx, _)) => {
+                        evolve_array(struct_array.column(idx), to_field.data_type())
+                    }
+                    None => Ok(new_null_array(to_field.data_type(), array.len())),
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            Ok(Arc::new(StructArray::try_new(
+                to_fields.clone(),
+                columns,
+                struct_array.nulls().cloned(),
+            )?))
+        }
+        (DataType::List(_), DataType::List(to_field)) => {
+            let list_array = as_list_array(array.as_ref())?;
+            let values = evolve_array(list_array.values(), to_field.data_type())?;
+
+            Ok(Arc::new(ListArray::try_new(
+                to_field.clone(),
+                list_array.offsets().clone(),
+                values,
+                list_array.nulls().cloned(),
+            )?))
+        }
+        _ => Ok(cast(array, to_type)?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Int64Type;
+    use arrow_array::{Int32Array, Int64Array, StringArray};
+
+    fn field_with_id(name: &str, data_type: DataType, id: i32) -> Field {
+        Field::new(name, data_type, true).with_metadata(HashMap::from([(
+            FIELD_ID_META_KEY.to_string(),
+            id.to_string(),
+        )]))
+    }
+
+    #[test]
+    fn widen() {
+        use DataType::*;
+        let cases = vec![
+            (Int32, Int64, Some(Int64)),
+            (Int8, UInt8, Some(Int16)),
+            (UInt32, Int16, Some(Int64)),
+            (UInt64, Int64, Some(Int64)),
+            (UInt16, UInt32, Some(UInt32)),
+            (Int32, Float32, Some(Float64)),
+            (Float32, Float64, Some(Float64)),
+            (Decimal128(10, 2), Decimal128(5, 4), Some(Decimal128(12, 4))),
+            (Decimal128(38, 0), Decimal128(38, 10), None),
+            (Utf8, LargeUtf8, Some(LargeUtf8)),
+            (Null, Boolean, Some(Boolean)),
+            (Utf8, Int32, None),
+            (Boolean, Int32, None),
+        ];
+
+        for (a, b, expected) in cases {
+            assert_eq!(widen_types(&a, &b), expected, "{a:?} and {b:?}");
+            assert_eq!(widen_types(&b, &a), expected, "{b:?} and {a:?}");
+        }
+    }
+
+    #[test]
+    fn merge() {
+        let old = Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new(
+                "s",
+                DataType::Struct(vec![Field::new("x", DataType::Int32, false)].into()),
+                true,
+            ),
+        ]);
+        let new = Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new(
+                "s",
+                DataType::Struct(
+                    vec![
+                        Field::new("x", DataType::Float32, false),
+                        Field::new("y", DataType::Utf8, false),
+                    ]
+                    .into(),
+                ),
+                true,
+            ),
+            Field::new("b", DataType::Utf8, false),
+        ]);
+
+        let merged = merge_schemas([old, new]).unwrap();
+        let expected = Schema::new(vec![
+            Field::new("a", DataType::Int64, false),
+            Field::new(
+                "s",
+                DataType::Struct(
+                    vec![
+                        Field::new("x", DataType::Float64, false),
+                        Field::new("y", DataType::Utf8, true),
+                    ]
+                    .into(),
+                ),
+                true,
+            ),
+            Field::new("b", DataType::Utf8, true),
+        ]);
+        assert_eq!(merged, expected);
+
+        let err = merge_schemas([
+            Schema::new(vec![Field::new("a", DataType::Int32, false)]),
+            Schema::new(vec![Field::new("a", DataType::Utf8, false)]),
+        ])
+        .unwrap_err();
+        assert_eq!(
+            err.strip_backtrace(),
+            "Error during planning: Cannot merge field a of type Int32 with type Utf8"
+        );
+    }
+
+    #[test]
+    fn merge_by_field_id() {
+        let old = Schema::new(vec![
+            field_with_id("a", DataType::Int32, 1),
+            field_with_id("b", DataType::Utf8, 2),
+        ]);
+        // `a` was renamed to `c`, and `b` was dropped and re-added
+        let new = Schema::new(vec![
+            field_with_id("c", DataType::Int64, 1),
+            field_with_id("b", DataType::Utf8, 3),
+        ]);
+
+        let merged = merge_schemas([old, new]).unwrap();
+        let expected = Schema::new(vec![
+            field_with_id("a", DataType::Int64, 1),
+            field_with_id("b", DataType::Utf8, 3),
+        ]);
+        assert_eq!(merged, expected);
+    }
+
+    #[test]
+    fn find_by_field_id() {
+        let fields: Fields = vec![
+            field_with_id("renamed", DataType::Int32, 1),
+            field_with_id("b", DataType::Int32, 3),
+        ]
+        .into();
+
+        let (idx, _) =
+            find_field(&fields, &field_with_id("a", DataType::Int32, 1)).unwrap();
+        assert_eq!(idx, 0);
+        // same name, different id: the column was dropped and re-added
+        assert!(find_field(&fields, &field_with_id("b", DataType::Int32, 2)).is_none());
+        // no id on the table side: match by name
+        let (idx, _) =
+            find_field(&fields, &Field::new("b", DataType::Int32, true)).unwrap();
+        assert_eq!(idx, 1);
+    }
+
+    #[test]
+    fn evolve_struct() {
+        let from_fields: Fields = vec![
+            Field::new("y", DataType::Utf8, true),
+            Field::new("x", DataType::Int32, true),
+        ]
+        .into();
+        let array: ArrayRef = Arc::new(StructArray::new(
+            from_fields.clone(),
+            vec![
+                Arc::new(StringArray::from(vec!["a", "b"])),
+                Arc::new(Int32Array::from(vec![1, 2])),
+            ],
+            None,
+        ));
+
+        let to_type = DataType::Struct(
+            vec![
+                Field::new("x", DataType::Int64, true),
+                Field::new("z", DataType::Boolean, true),
+            ]
+            .into(),
+        );
+        assert!(can_evolve_type(array.data_type(), &to_type));
+
+        let evolved = evolve_array(&array, &to_type).unwrap();
+        assert_eq!(evolved.data_type(), &to_type);
+        let evolved = evolved.as_struct();
+        let x = evolved.column(0).as_primitive::<Int64Type>();
+        assert_eq!(x, &Int64Array::from(vec![1, 2]));
+        assert_eq!(evolved.column(1).null_count(), 2);
+
+        // a missing field that is not nullable cannot be filled
+        let to_type =
+            DataType::Struct(vec![Field::new("z", DataType::Boolean, false)].into());
+        assert!(!can_evolve_type(&DataType::Struct(from_fields), &to_type));
+    }
+}
@@ -15,12 +15,29 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use crate::arrow::datatypes::{Schema, SchemaRef};
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use crate::arrow::array::{Array, ArrayRef};
+use crate::arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+use crate::arrow::record_batch::RecordBatch;
+use crate::datasource::TableProvider;
 use crate::error::Result;
-use crate::physical_plan::expressions::{MaxAccumulator, MinAccumulator};
-use crate::physical_plan::{Accumulator, ColumnStatistics, Statistics};
+use crate::execution::context::SessionContext;
+use crate::execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use crate::physical_plan::expressions::{
+    ApproxDistinct, Column as PhysicalColumn, MaxAccumulator, MinAccumulator,
+};
+use crate::physical_plan::{Accumulator, AggregateExpr, ColumnStatistics, Statistics};
+use datafusion_common::{
+    internal_err, Column, Histogram, HistogramBucket, Precision, ScalarValue,
+    ValueFrequency,
+};
+use datafusion_expr::Expr;
 use futures::Stream;
 use futures::StreamExt;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 
 use super::listing::PartitionedFile;
 
@@ -38,38 +55,35 @@ pub async fn get_statistics_with_limit(
     let mut has_statistics = false;
     let (mut max_values, mut min_values) = create_max_min_accs(&file_schema);
 
-    let mut is_exact = true;
+    // Whether the column statistics of all the files are exact
+    let mut exact_columns = true;
 
     // The number of rows and the total byte size can be calculated as long as
     // at least one file has them. If none of the files provide them, then they
     // will be omitted from the statistics. The missing values will be counted
-    // as zero.
-    let mut num_rows = None;
-    let mut total_byte_size = None;
+    // as zero, which makes the sums inexact.
+    let mut num_rows = Precision::Absent;
+    let mut total_byte_size = Precision::Absent;
 
     // fusing the stream allows us to call next safely even once it is finished
     let mut all_files = Box::pin(all_files.fuse());
     while let Some(res) = all_files.next().await {
         let (file, file_stats) = res?;
+        let first_file = result_files.is_empty();
         result_files.push(file);
-        is_exact &= file_stats.is_exact;
-        num_rows = if let Some(num_rows) = num_rows {
-            Some(num_rows + file_stats.num_rows.unwrap_or(0))
-        } else {
-            file_stats.num_rows
-        };
-        total_byte_size = if let Some(total_byte_size) = total_byte_size {
-            Some(total_byte_size + file_stats.total_byte_size.unwrap_or(0))
-        } else {
-            file_stats.total_byte_size
-        };
+        num_rows = add_file_statistic(num_rows, file_stats.num_rows, first_file);
+        total_byte_size =
+            add_file_statistic(total_byte_size, file_stats.total_byte_size, first_file);
         if let Some(vec) = &file_stats.column_statistics {
             has_statistics = true;
             for (i, cs) in vec.iter().enumerate() {
-                null_counts[i] += cs.null_count.unwrap_or(0);
+                exact_columns &= cs.null_count.is_exact() == Some(true)
+                    && cs.max_value.is_exact() != Some(false)
+                    && cs.min_value.is_exact() != Some(false);
+                null_counts[i] += cs.null_count.get_value().copied().unwrap_or(0);
 
                 if let Some(max_value) = &mut max_values[i] {
-                    if let Some(file_max) = cs.max_value.clone() {
+                    if let Some(file_max) = cs.max_value.get_value().cloned() {
                         match max_value.update_batch(&[file_max.to_array()]) {
                             Ok(_) => {}
                             Err(_) => {
@@ -82,7 +96,7 @@ pub async fn get_statistics_with_limit(
                 }
 
                 if let Some(min_value) = &mut min_values[i] {
-                    if let Some(file_min) = cs.min_value.clone() {
+                    if let Some(file_min) = cs.min_value.get_value().cloned() {
                         match min_value.update_batch(&[file_min.to_array()]) {
                             Ok(_) => {}
                             Err(_) => {
@@ -94,30 +108,32 @@ pub async fn get_statistics_with_limit(
                     }
                 }
             }
+        } else {
+            exact_columns = false;
         }
 
         // If the number of rows exceeds the limit, we can stop processing
         // files. This only applies when we know the number of rows. It also
         // currently ignores tables that have no statistics regarding the
         // number of rows.
-        if num_rows.unwrap_or(usize::MIN) > limit.unwrap_or(usize::MAX) {
+        if num_rows.get_value().copied().unwrap_or(usize::MIN)
+            > limit.unwrap_or(usize::MAX)
+        {
             break;
         }
     }
-    // if we still have files in the stream, it means that the limit kicked
-    // in and that the statistic could have been different if we processed
-    // the files in a different order.
-    if all_files.next().await.is_some() {
-        is_exact = false;
-    }
 
     let column_stats = if has_statistics {
-        Some(get_col_stats(
-            &file_schema,
-            null_counts,
-            &mut max_values,
-            &mut min_values,
-        ))
+        let column_stats =
+            get_col_stats(&file_schema, null_counts, &mut max_values, &mut min_values);
+        Some(if exact_columns {
+            column_stats
+        } else {
+            column_stats
+                .into_iter()
+                .map(ColumnStatistics::into_inexact)
+                .collect()
+        })
     } else {
         None
     };
@@ -126,12 +142,34 @@ pub async fn get_statistics_with_limit(
         num_rows,
         total_byte_size,
         column_statistics: column_stats,
-        is_exact,
     };
 
+    // if we still have files in the stream, it means that the limit kicked
+    // in and that the statistic could have been different if we processed
+    // the files in a different order.
+    if all_files.next().await.is_some() {
+        return Ok((result_files, statistics.into_inexact()));
+    }
+
     Ok((result_files, statistics))
 }
 
+/// Adds the statistic of a file to the `sum` of the statistics of the files
+/// before it. A file missing the statistic counts as zero, which makes the sum
+/// inexact.
+fn add_file_statistic(
+    sum: Precision<usize>,
+    file: Precision<usize>,
+    first_file: bool,
+) -> Precision<usize> {
+    match (sum, file) {
+        _ if first_file => file,
+        (Precision::Absent, Precision::Absent) => Precision::Absent,
+        (Precision::Absent, value) | (value, Precision::Absent) => value.to_inexact(),
+        (sum, file) => sum.add(&file),
+    }
+}
+
 pub(crate) fn create_max_min_accs(
     schema: &Schema,
 ) -> (Vec<Option<MaxAccumulator>>, Vec<Option<MinAccumulator>>) {
@@ -165,11 +203,420 @@ pub(crate) fn get_col_stats(
                 None => None,
             };
             ColumnStatistics {
-                null_count: Some(null_counts[i]),
-                max_value,
-                min_value,
-                distinct_count: None,
+                null_count: Precision::Exact(null_counts[i]),
+                max_value: Precision::exact_or_absent(max_value),
+                min_value: Precision::exact_or_absent(min_value),
+                distinct_count: Precision::Absent,
+                histogram: None,
+                most_common_values: None,
             }
         })
         .collect()
 }
+
+/// Maximum number of buckets of the histograms computed by `ANALYZE TABLE`
+const HISTOGRAM_BUCKETS: usize = 64;
+
+/// Maximum number of most common values computed by `ANALYZE TABLE`
+const MOST_COMMON_VALUES: usize = 16;
+
+/// Maximum number of values per column sampled by `ANALYZE TABLE` to build
+/// the histogram and most common values of the column
+const SAMPLE_SIZE: usize = 30_000;
+
+/// Computes the [`Statistics`] of `table` for `ANALYZE TABLE` by scanning
+/// it: the number of rows, and the null count, min/max, approximate number
+/// of distinct values, histogram and most common values of `columns` (of all
+/// columns if `None`).
+///
+/// The table is scanned once. Histograms and most common values are built
+/// from a bounded random sample of each column, which is accounted to the
+/// memory pool, and are exact if the sample holds all values of the column.
+///
+/// The statistics of the other columns are kept from `previous`, if any.
+pub(crate) async fn analyze_table(
+    ctx: &SessionContext,
+    table: Arc<dyn TableProvider>,
+    columns: Option<&[String]>,
+    previous: Option<Statistics>,
+) -> Result<Statistics> {
+    let schema = table.schema();
+    let mut indices = match columns {
+        Some(columns) => columns
+            .iter()
+            .map(|c| schema.index_of(c))
+            .collect::<Result<Vec<_>, _>>()?,
+        None => (0..schema.fields().len()).collect(),
+    };
+    indices.sort_unstable();
+    indices.dedup();
+
+    let mut analyzers = indices
+        .iter()
+        .enumerate()
+        .map(|(i, idx)| ColumnAnalyzer::try_new(i, schema.field(*idx)))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut df = ctx.read_table(table)?;
+    if !indices.is_empty() {
+        df = df.select(
+            indices
+                .iter()
+                .map(|idx| {
+                    Expr::Column(Column::new_unqualified(schema.field(*idx).name()))
+                })
+                .collect(),
+        )?;
+    }
+    let mut stream = df.execute_stream().await?;
+
+    let mut reservation =
+        MemoryConsumer::new("AnalyzeTable").register(&ctx.runtime_env().memory_pool);
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut num_rows = 0;
+    while let Some(batch) = stream.next().await.transpose()? {
+        num_rows += batch.num_rows();
+        for analyzer in &mut analyzers {
+            analyzer.update(&batch, &mut rng, &mut reservation)?;
+        }
+    }
+
+    let mut column_statistics = previous
+        .and_then(|previous| previous.column_statistics)
+        .filter(|previous| previous.len() == schema.fields().len())
+        .unwrap_or_else(|| vec![ColumnStatistics::default(); schema.fields().len()]);
+    for (idx, analyzer) in indices.into_iter().zip(analyzers) {
+        column_statistics[idx] = analyzer.finish(num_rows)?;
+    }
+
+    Ok(Statistics {
+        num_rows: Precision::Inexact(num_rows),
+        total_byte_size: Precision::Absent,
+        column_statistics: Some(column_statistics),
+    })
+}
+
+/// Accumulates the statistics of a single column for `ANALYZE TABLE`
+struct ColumnAnalyzer {
+    /// Index of the column in the scanned batches
+    index: usize,
+    non_null_count: usize,
+    min: Option<MinAccumulator>,
+    max: Option<MaxAccumulator>,
+    distinct: Option<Box<dyn Accumulator>>,
+    sample: Option<ValueSample>,
+}
+
+impl ColumnAnalyzer {
+    fn try_new(index: usize, field: &Field) -> Result<Self> {
+        let data_type = field.data_type();
+        let (min, max, sample) = if supports_min_max(data_type) {
+            (
+                Some(MinAccumulator::try_new(data_type)?),
+                Some(MaxAccumulator::try_new(data_type)?),
+                Some(ValueSample::default()),
+            )
+        } else {
+            (None, None, None)
+        };
+        let distinct = if supports_approx_distinct(data_type) {
+            let expr = Arc::new(PhysicalColumn::new(field.name(), index));
+            Some(
+                ApproxDistinct::new(expr, field.name(), data_type.clone())
+                    .create_accumulator()?,
+            )
+        } else {
+            None
+        };
+        Ok(Self {
+            index,
+            non_null_count: 0,
+            min,
+            max,
+            distinct,
+            sample,
+        })
+    }
+
+    fn update(
+        &mut self,
+        batch: &RecordBatch,
+        rng: &mut StdRng,
+        reservation: &mut MemoryReservation,
+    ) -> Result<()> {
+        let array = batch.column(self.index);
+        self.non_null_count += array.len() - array.null_count();
+        let values = [array.clone()];
+        if let Some(min) = &mut self.min {
+            min.update_batch(&values)?;
+        }
+        if let Some(max) = &mut self.max {
+            max.update_batch(&values)?;
+        }
+        if let Some(distinct) = &mut self.distinct {
+            distinct.update_batch(&values)?;
+        }
+        if let Some(sample) = &mut self.sample {
+            sample.update(array, rng, reservation)?;
+        }
+        Ok(())
+    }
+
+    fn finish(self, num_rows: usize) -> Result<ColumnStatistics> {
+        let min_value = self.min.map(|min| min.evaluate()).transpose()?;
+        let max_value = self.max.map(|max| max.evaluate()).transpose()?;
+        let distinct_count = self
+            .distinct
+            .map(|distinct| count_value(Some(distinct.evaluate()?)))
+            .transpose()?;
+        let (histogram, most_common_values) = match self.sample {
+            Some(sample) => {
+                let frequencies = sample.frequencies(self.non_null_count);
+                let most_common_values =
+                    most_common_values(&frequencies, MOST_COMMON_VALUES);
+                (
+                    Some(build_histogram(&frequencies, HISTOGRAM_BUCKETS)),
+                    (!most_common_values.is_empty()).then_some(most_common_values),
+                )
+            }
+            None => (None, None),
+        };
+        // the table may change after it has been analyzed
+        Ok(ColumnStatistics {
+            null_count: Precision::Inexact(num_rows - self.non_null_count),
+            min_value: min_value
+                .filter(|v| !v.is_null())
+                .map_or(Precision::Absent, Precision::Inexact),
+            max_value: max_value
+                .filter(|v| !v.is_null())
+                .map_or(Precision::Absent, Precision::Inexact),
+            distinct_count: distinct_count.map_or(Precision::Absent, Precision::Inexact),
+            histogram,
+            most_common_values,
+        })
+    }
+}
+
+/// A uniform random sample of at most [`SAMPLE_SIZE`] of the non-null values
+/// of a column, maintained with reservoir sampling
+#[derive(Default)]
+struct ValueSample {
+    values: Vec<ScalarValue>,
+    /// Number of non-null values offered to the sample so far
+    num_seen: usize,
+}
+
+impl ValueSample {
+    fn update(
+        &mut self,
+        array: &ArrayRef,
+        rng: &mut StdRng,
+        reservation: &mut MemoryReservation,
+    ) -> Result<()> {
+        for row in 0..array.len() {
+            if array.is_null(row) {
+                continue;
+            }
+            let slot = if self.values.len() < SAMPLE_SIZE {
+                self.values.len()
+            } else {
+                rng.gen_range(0..=self.num_seen)
+            };
+            self.num_seen += 1;
+            if slot >= SAMPLE_SIZE {
+                continue;
+            }
+
+            let value = ScalarValue::try_from_array(array, row)?;
+            reservation.try_grow(value.size())?;
+            if slot == self.values.len() {
+                self.values.push(value);
+            } else {
+                let replaced = std::mem::replace(&mut self.values[slot], value);
+                reservation.shrink(replaced.size());
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the number of occurrences of each sampled value, in ascending
+    /// order of the values, scaled up to the `num_values` non-null values of
+    /// the column
+    fn frequencies(mut self, num_values: usize) -> Vec<ValueFrequency> {
+        self.values
+            .sort_unstable_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+        let scale = num_values as f64 / self.values.len().max(1) as f64;
+
+        let mut frequencies: Vec<ValueFrequency> = vec![];
+        for value in self.values {
+            match frequencies.last_mut() {
+                Some(last) if last.value == value => last.count += 1,
+                _ => frequencies.push(ValueFrequency { value, count: 1 }),
+            }
+        }
+        if self.num_seen > SAMPLE_SIZE {
+            for frequency in &mut frequencies {
+                frequency.count =
+                    ((frequency.count as f64 * scale).round() as usize).max(1);
+            }
+        }
+        frequencies
+    }
+}
+
+/// Builds an equi-depth histogram with at most about `num_buckets` buckets
+/// from value `frequencies` sorted by value. A value never spans more than
+/// one bucket, so a frequent value may fill a bucket on its own.
+fn build_histogram(frequencies: &[ValueFrequency], num_buckets: usize) -> Histogram {
+    let num_rows: usize = frequencies.iter().map(|f| f.count).sum();
+    let depth = ((num_rows + num_buckets - 1) / num_buckets).max(1);
+
+    let mut buckets = vec![];
+    let mut current: Option<HistogramBucket> = None;
+    for frequency in frequencies {
+        let bucket = current.get_or_insert_with(|| HistogramBucket {
+            lower: frequency.value.clone(),
+            upper: frequency.value.clone(),
+            num_rows: 0,
+            distinct_count: 0,
+        });
+        bucket.upper = frequency.value.clone();
+        bucket.num_rows += frequency.count;
+        bucket.distinct_count += 1;
+        if bucket.num_rows >= depth {
+            buckets.extend(current.take());
+        }
+    }
+    buckets.extend(current);
+    Histogram { buckets }
+}
+
+/// Returns up to `limit` values of `frequencies` that are more common than
+/// the average value, in descending order of frequency
+fn most_common_values(
+    frequencies: &[ValueFrequency],
+    limit: usize,
+) -> Vec<ValueFrequency> {
+    let num_rows: usize = frequencies.iter().map(|f| f.count).sum();
+    let mut values = frequencies
+        .iter()
+        .filter(|f| f.count * frequencies.len() > num_rows)
+        .cloned()
+        .collect::<Vec<_>>();
+    values.sort_by(|a, b| b.count.cmp(&a.count));
+    values.truncate(limit);
+    values
+}
+
+/// Converts the result of a `COUNT` or `APPROX_DISTINCT` aggregate to a `usize`
+fn count_value(value: Option<ScalarValue>) -> Result<usize> {
+    match value {
+        Some(ScalarValue::Int64(Some(v))) => Ok(v as usize),
+        Some(ScalarValue::UInt64(Some(v))) => Ok(v as usize),
+        other => internal_err!("Expected a count, got {other:?}"),
+    }
+}
+
+/// Whether the `MIN` and `MAX` aggregates support columns of `data_type`
+fn supports_min_max(data_type: &DataType) -> bool {
+    use DataType::*;
+    matches!(
+        data_type,
+        Boolean
+            | Int8
+            | Int16
+            | Int32
+            | Int64
+            | UInt8
+            | UInt16
+            | UInt32
+            | UInt64
+            | Float32
+            | Float64
+            | Decimal128(_, _)
+            | Date32
+            | Date64
+            | Time32(_)
+            | Time64(_)
+            | Timestamp(_, _)
+            | Utf8
+            | LargeUtf8
+            | Binary
+            | LargeBinary
+    )
+}
+
+/// Whether the `APPROX_DISTINCT` aggregate supports columns of `data_type`
+fn supports_approx_distinct(data_type: &DataType) -> bool {
+    use DataType::*;
+    matches!(
+        data_type,
+        Int8 | Int16
+            | Int32
+            | Int64
+            | UInt8
+            | UInt16
+            | UInt32
+            | UInt64
+            | Utf8
+            | LargeUtf8
+            | Binary
+            | LargeBinary
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arrow::array::Int32Array;
+    use crate::execution::memory_pool::{GreedyMemoryPool, MemoryPool};
+
+    #[test]
+    fn value_sample_is_bounded() -> Result<()> {
+        let pool: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(usize::MAX));
+        let mut reservation = MemoryConsumer::new("test").register(&pool);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut sample = ValueSample::default();
+
+        // 10 batches of 10_000 rows, where every other value is 0
+        for _ in 0..10 {
+            let array: ArrayRef =
+                Arc::new(Int32Array::from_iter_values((0..10_000).map(|i| {
+                    if i % 2 == 0 {
+                        0
+                    } else {
+                        i
+                    }
+                })));
+            sample.update(&array, &mut rng, &mut reservation)?;
+        }
+        assert_eq!(sample.values.len(), SAMPLE_SIZE);
+        assert_eq!(sample.num_seen, 100_000);
+        assert_eq!(
+            reservation.size(),
+            sample.values.iter().map(|v| v.size()).sum::<usize>()
+        );
+
+        let frequencies = sample.frequencies(100_000);
+        let total: usize = frequencies.iter().map(|f| f.count).sum();
+        assert!((95_000..=105_000).contains(&total), "{total}");
+        let zeros = &frequencies[0];
+        assert_eq!(zeros.value, ScalarValue::Int32(Some(0)));
+        assert!((45_000..=55_000).contains(&zeros.count), "{}", zeros.count);
+        Ok(())
+    }
+
+    #[test]
+    fn value_sample_respects_memory_pool() {
+        let pool: Arc<dyn MemoryPool> = Arc::new(GreedyMemoryPool::new(1024));
+        let mut reservation = MemoryConsumer::new("test").register(&pool);
+        let mut rng = StdRng::seed_from_u64(0);
+        let mut sample = ValueSample::default();
+
+        let array: ArrayRef = Arc::new(Int32Array::from_iter_values(0..10_000));
+        let err = sample
+            .update(&array, &mut rng, &mut reservation)
+            .unwrap_err();
+        assert!(err.to_string().contains("Resources exhausted"), "{err}");
+    }
+}
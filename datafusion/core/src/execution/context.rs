@@ -62,14 +62,15 @@ use crate::catalog::{
 };
 use crate::dataframe::DataFrame;
 use crate::datasource::{
+    analyze_table,
     listing::{ListingTableConfig, ListingTableUrl},
     provider_as_source, TableProvider,
 };
 use crate::error::{DataFusionError, Result};
 use crate::logical_expr::{
-    CreateCatalog, CreateCatalogSchema, CreateExternalTable, CreateMemoryTable,
-    CreateView, DropCatalogSchema, DropTable, DropView, Explain, LogicalPlan,
-    LogicalPlanBuilder, SetVariable, TableSource, TableType, UNNAMED_TABLE,
+    AnalyzeTable, CreateCatalog, CreateCatalogSchema, CreateExternalTable,
+    CreateMemoryTable, CreateView, DropCatalogSchema, DropTable, DropView, Explain,
+    LogicalPlan, LogicalPlanBuilder, SetVariable, TableSource, TableType, UNNAMED_TABLE,
 };
 use crate::optimizer::OptimizerRule;
 use datafusion_sql::{
@@ -81,7 +82,9 @@ use sqlparser::dialect::dialect_from_str;
 
 use crate::config::ConfigOptions;
 use crate::datasource::physical_plan::{plan_to_csv, plan_to_json, plan_to_parquet};
-use crate::execution::{runtime_env::RuntimeEnv, FunctionRegistry};
+use crate::execution::{
+    query_handle::QueryHandle, runtime_env::RuntimeEnv, FunctionRegistry,
+};
 use crate::physical_plan::udaf::AggregateUDF;
 use crate::physical_plan::udf::ScalarUDF;
 use crate::physical_plan::ExecutionPlan;
@@ -406,6 +409,30 @@ impl SessionContext {
         self.sql_with_options(sql, SQLOptions::new()).await
     }
 
+    /// Plans and executes a SQL statement, returning a [`QueryHandle`]
+    /// that streams its results and can be used to cancel it.
+    ///
+    /// This is a shorthand for [`Self::sql`] followed by
+    /// [`DataFrame::execute_with_handle`]; see there for how
+    /// `datafusion.execution.statement_timeout` is enforced.
+    ///
+    /// ```
+    /// use datafusion::prelude::*;
+    /// # use datafusion::error::Result;
+    /// # use futures::StreamExt;
+    /// # #[tokio::main]
+    /// # async fn main() -> Result<()> {
+    /// let ctx = SessionContext::new();
+    /// let mut handle = ctx.sql_with_handle("SELECT 1").await?;
+    /// handle.cancel();
+    /// assert!(handle.next().await.unwrap().is_err());
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn sql_with_handle(&self, sql: &str) -> Result<QueryHandle> {
+        self.sql(sql).await?.execute_with_handle().await
+    }
+
     /// Creates a [`DataFrame`] from SQL query text, first validating
     /// that the queries are allowed by `options`
     ///
@@ -467,6 +494,7 @@ impl SessionContext {
                 DdlStatement::DropTable(cmd) => self.drop_table(cmd).await,
                 DdlStatement::DropView(cmd) => self.drop_view(cmd).await,
                 DdlStatement::DropCatalogSchema(cmd) => self.drop_schema(cmd).await,
+                DdlStatement::AnalyzeTable(cmd) => self.analyze_table(cmd).await,
             },
             // TODO what about the other statements (like TransactionStart and TransactionEnd)
             LogicalPlan::Statement(Statement::SetVariable(stmt)) => {
@@ -685,7 +713,7 @@ impl SessionContext {
                 self.state
                     .write()
                     .catalog_list
-                    .register_catalog(catalog_name, new_catalog);
+                    .try_register_catalog(catalog_name, new_catalog)?;
                 self.return_empty_dataframe()
             }
             (false, Some(_)) => exec_err!("Catalog '{catalog_name}' already exists"),
@@ -747,6 +775,25 @@ impl SessionContext {
         }
     }
 
+    async fn analyze_table(&self, cmd: AnalyzeTable) -> Result<DataFrame> {
+        let AnalyzeTable { name, columns, .. } = cmd;
+        let state = self.state();
+        let schema = state.resolve_schema_for_ref(&name).await?;
+        let table_name = name.table();
+        let Some(table) = schema.table(table_name).await else {
+            return exec_err!("Table '{name}' doesn't exist.");
+        };
+        let statistics = analyze_table(
+            self,
+            table,
+            columns.as_deref(),
+            schema.table_statistics(table_name),
+        )
+        .await?;
+        schema.register_table_statistics(table_name, statistics)?;
+        self.return_empty_dataframe()
+    }
+
     fn schema_doesnt_exist_err(
         &self,
         schemaref: SchemaReference<'_>,
@@ -793,13 +840,15 @@ impl SessionContext {
     ) -> Result<bool> {
         let table_ref = table_ref.into();
         let table = table_ref.table().to_owned();
-        let maybe_schema = {
-            let state = self.state.read();
-            let resolved = state.resolve_table_ref(table_ref);
-            state
-                .catalog_list
-                .catalog(&resolved.catalog)
-                .and_then(|c| c.schema(&resolved.schema))
+        let state = self.state();
+        let resolved = state.resolve_table_ref(table_ref);
+        let maybe_schema = match state
+            .catalog_list
+            .resolve_catalog(&resolved.catalog)
+            .await?
+        {
+            Some(catalog) => catalog.resolve_schema(&resolved.schema).await?,
+            None => None,
         };
 
         if let Some(schema) = maybe_schema {
@@ -1252,9 +1301,10 @@ impl SessionContext {
     ) -> Result<Arc<dyn TableProvider>> {
         let table_ref = table_ref.into();
         let table = table_ref.table().to_string();
-        let schema = self.state.read().schema_for_ref(table_ref)?;
-        match schema.table(&table).await {
-            Some(ref provider) => Ok(Arc::clone(provider)),
+        let state = self.state();
+        let schema = state.resolve_schema_for_ref(table_ref).await?;
+        match table_with_statistics(&schema, &table).await {
+            Some(provider) => Ok(provider),
             _ => plan_err!("No table named '{table}'"),
         }
     }
@@ -1609,6 +1659,47 @@ impl SessionState {
             })
     }
 
+    /// Like [`Self::schema_for_ref`], but resolves the catalog and schema
+    /// with [`CatalogList::resolve_catalog`] and
+    /// [`CatalogProvider::resolve_schema`], which do not block on remote
+    /// catalogs
+    pub(crate) async fn resolve_schema_for_ref<'a>(
+        &'a self,
+        table_ref: impl Into<TableReference<'a>>,
+    ) -> Result<Arc<dyn SchemaProvider>> {
+        let resolved_ref = self.resolve_table_ref(table_ref);
+        self.try_resolve_schema(&resolved_ref)
+            .await?
+            .ok_or_else(|| {
+                DataFusionError::Plan(format!(
+                    "failed to resolve schema: {}.{}",
+                    resolved_ref.catalog, resolved_ref.schema
+                ))
+            })
+    }
+
+    /// Resolve the schema of `resolved_ref`, returning `None` if either its
+    /// catalog or its schema does not exist
+    async fn try_resolve_schema(
+        &self,
+        resolved_ref: &ResolvedTableReference<'_>,
+    ) -> Result<Option<Arc<dyn SchemaProvider>>> {
+        if self.config.information_schema() && resolved_ref.schema == INFORMATION_SCHEMA {
+            return Ok(Some(Arc::new(InformationSchemaProvider::new(
+                self.catalog_list.clone(),
+            ))));
+        }
+
+        match self
+            .catalog_list
+            .resolve_catalog(&resolved_ref.catalog)
+            .await?
+        {
+            Some(catalog) => catalog.resolve_schema(&resolved_ref.schema).await,
+            None => Ok(None),
+        }
+    }
+
     /// Replace the random session id.
     pub fn with_session_id(mut self, session_id: String) -> Self {
         self.session_id = session_id;
@@ -1841,8 +1932,10 @@ impl SessionState {
             let table = reference.table();
             let resolved = self.resolve_table_ref(&reference);
             if let Entry::Vacant(v) = provider.tables.entry(resolved.to_string()) {
-                if let Ok(schema) = self.schema_for_ref(resolved) {
-                    if let Some(table) = schema.table(table).await {
+                // only the referenced tables are fetched, so that remote
+                // catalogs don't need to load all their tables upfront
+                if let Some(schema) = self.try_resolve_schema(&resolved).await? {
+                    if let Some(table) = table_with_statistics(&schema, table).await {
                         v.insert(provider_as_source(table));
                     }
                 }
@@ -2029,6 +2122,19 @@ impl SessionState {
     }
 }
 
+/// Retrieves the table `name` from `schema`, reporting the statistics
+/// collected for it by `ANALYZE TABLE` if the table supports this
+async fn table_with_statistics(
+    schema: &Arc<dyn SchemaProvider>,
+    name: &str,
+) -> Option<Arc<dyn TableProvider>> {
+    let table = schema.table(name).await?;
+    match schema.table_statistics(name) {
+        Some(statistics) => Some(table.with_statistics(statistics).unwrap_or(table)),
+        None => Some(table),
+    }
+}
+
 struct SessionContextProvider<'a> {
     state: &'a SessionState,
     tables: HashMap<String, Arc<dyn TableSource>>,
@@ -2271,12 +2377,16 @@ mod tests {
     use crate::execution::memory_pool::MemoryConsumer;
     use crate::execution::runtime_env::RuntimeConfig;
     use crate::physical_plan::expressions::AvgAccumulator;
+    use crate::physical_plan::ColumnStatistics;
     use crate::test;
     use crate::test_util::parquet_test_data;
     use crate::variable::VarType;
     use arrow::array::ArrayRef;
     use arrow::record_batch::RecordBatch;
     use async_trait::async_trait;
+    use datafusion_common::{
+        Histogram, HistogramBucket, Precision, ScalarValue, ValueFrequency,
+    };
     use datafusion_expr::{create_udaf, create_udf, Expr, Volatility};
     use datafusion_physical_expr::functions::make_scalar_function;
     use std::fs::File;
@@ -2458,6 +2568,155 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn analyze_table() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let ctx = create_ctx(&tmp_dir, 2).await?;
+        let schema = ctx.state().schema_for_ref("test")?;
+        assert_eq!(schema.table_statistics("test"), None);
+
+        ctx.sql("ANALYZE TABLE test COMPUTE STATISTICS FOR COLUMNS c1, c3")
+            .await?
+            .collect()
+            .await?;
+        let statistics = schema.table_statistics("test").unwrap();
+        assert_eq!(statistics.num_rows, Precision::Inexact(22));
+        let column_statistics = statistics.column_statistics.unwrap();
+        let bucket = |value: ScalarValue, num_rows: usize| HistogramBucket {
+            lower: value.clone(),
+            upper: value,
+            num_rows,
+            distinct_count: 1,
+        };
+        assert_eq!(
+            column_statistics,
+            vec![
+                ColumnStatistics {
+                    null_count: Precision::Inexact(0),
+                    min_value: Precision::Inexact(ScalarValue::UInt32(Some(0))),
+                    max_value: Precision::Inexact(ScalarValue::UInt32(Some(1))),
+                    distinct_count: Precision::Inexact(2),
+                    histogram: Some(Histogram {
+                        buckets: vec![
+                            bucket(ScalarValue::UInt32(Some(0)), 11),
+                            bucket(ScalarValue::UInt32(Some(1)), 11),
+                        ],
+                    }),
+                    most_common_values: None,
+                },
+                ColumnStatistics::default(),
+                ColumnStatistics {
+                    null_count: Precision::Inexact(0),
+                    min_value: Precision::Inexact(ScalarValue::Boolean(Some(false))),
+                    max_value: Precision::Inexact(ScalarValue::Boolean(Some(true))),
+                    distinct_count: Precision::Absent,
+                    histogram: Some(Histogram {
+                        buckets: vec![
+                            bucket(ScalarValue::Boolean(Some(false)), 10),
+                            bucket(ScalarValue::Boolean(Some(true)), 12),
+                        ],
+                    }),
+                    most_common_values: Some(vec![ValueFrequency {
+                        value: ScalarValue::Boolean(Some(true)),
+                        count: 12,
+                    }]),
+                },
+            ]
+        );
+
+        // analyzing other columns keeps the statistics of the analyzed ones
+        ctx.sql("ANALYZE TABLE test COMPUTE STATISTICS FOR COLUMNS c2")
+            .await?
+            .collect()
+            .await?;
+        let column_statistics = schema
+            .table_statistics("test")
+            .unwrap()
+            .column_statistics
+            .unwrap();
+        assert_eq!(column_statistics[0].distinct_count, Precision::Inexact(2));
+        assert_eq!(
+            column_statistics[1],
+            ColumnStatistics {
+                null_count: Precision::Inexact(0),
+                min_value: Precision::Inexact(ScalarValue::UInt64(Some(0))),
+                max_value: Precision::Inexact(ScalarValue::UInt64(Some(10))),
+                distinct_count: Precision::Inexact(11),
+                histogram: Some(Histogram {
+                    buckets: (0..=10)
+                        .map(|i| bucket(ScalarValue::UInt64(Some(i)), 2))
+                        .collect(),
+                }),
+                most_common_values: None,
+            }
+        );
+
+        // the statistics are reported by scans of the table
+        let plan = ctx
+            .table_provider("test")
+            .await?
+            .scan(&ctx.state(), None, &[], None)
+            .await?;
+        assert_eq!(plan.statistics().num_rows, Precision::Inexact(22));
+        assert_eq!(
+            plan.statistics().column_statistics.unwrap()[1].distinct_count,
+            Precision::Inexact(11)
+        );
+
+        // the statistics are dropped with the table
+        ctx.sql("DROP TABLE test").await?;
+        assert_eq!(schema.table_statistics("test"), None);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn analyze_table_keeps_exact_file_statistics() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let ctx = SessionContext::with_config(
+            SessionConfig::new().with_collect_statistics(true),
+        );
+        ctx.sql(&format!(
+            "CREATE EXTERNAL TABLE t (a BIGINT) STORED AS PARQUET LOCATION '{}' \
+             OPTIONS (create_local_path 'true')",
+            tmp_dir.path().to_str().unwrap()
+        ))
+        .await?;
+        ctx.sql("INSERT INTO t VALUES (1), (2), (2), (3)")
+            .await?
+            .collect()
+            .await?;
+        ctx.sql("ANALYZE TABLE t COMPUTE STATISTICS FOR COLUMNS a")
+            .await?
+            .collect()
+            .await?;
+        let schema = ctx.state().schema_for_ref("t")?;
+        assert!(schema.table_statistics("t").is_some());
+
+        // the exact row count and bounds of the files are kept, the estimates
+        // of `ANALYZE TABLE` only fill in what the files do not provide
+        let plan = ctx
+            .table_provider("t")
+            .await?
+            .scan(&ctx.state(), None, &[], None)
+            .await?;
+        let statistics = plan.statistics();
+        assert_eq!(statistics.num_rows, Precision::Exact(4));
+        let column_statistics = &statistics.column_statistics.unwrap()[0];
+        assert_eq!(
+            column_statistics.max_value,
+            Precision::Exact(ScalarValue::Int64(Some(3)))
+        );
+        assert_eq!(column_statistics.distinct_count, Precision::Inexact(3));
+        assert!(column_statistics.histogram.is_some());
+
+        // inserting into the table drops the statistics it made stale
+        ctx.sql("INSERT INTO t VALUES (4)").await?.collect().await?;
+        assert_eq!(schema.table_statistics("t"), None);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn query_csv_with_custom_partition_extension() -> Result<()> {
         let tmp_dir = TempDir::new()?;
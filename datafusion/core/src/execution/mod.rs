@@ -18,8 +18,10 @@
 //! Shared state for query planning and execution.
 
 pub mod context;
+pub mod query_handle;
 // backwards compatibility
 pub use crate::datasource::file_format::options;
 
 // backwards compatibility
 pub use datafusion_execution::*;
+pub use query_handle::QueryHandle;
@@ -0,0 +1,245 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`QueryHandle`]: a cancellable stream over the results of a query
+
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_execution::cancellation::Cancelled;
+use datafusion_execution::disk_manager::SpillUsage;
+use datafusion_execution::CancellationToken;
+use futures::{FutureExt, Stream};
+use tokio::task::JoinHandle;
+
+use crate::error::Result;
+use crate::physical_plan::{RecordBatchStream, SendableRecordBatchStream};
+
+/// A running query, returned by [`DataFrame::execute_with_handle`] and
+/// [`SessionContext::sql_with_handle`].
+///
+/// A [`QueryHandle`] is a [`RecordBatchStream`] over the results of the
+/// query that can be cancelled at any point, either through
+/// [`QueryHandle::cancel`] or from another task through a clone of its
+/// [`CancellationToken`]. Once cancelled, the operators of the query stop
+/// at their next cancellation check, the underlying stream (and with it
+/// any spawned task) is dropped, and the handle yields a single
+/// [`DataFusionError::Cancelled`] error.
+///
+/// If `datafusion.execution.statement_timeout` is set, the query is
+/// cancelled automatically once the timeout elapses.
+///
+/// [`DataFrame::execute_with_handle`]: crate::dataframe::DataFrame::execute_with_handle
+/// [`SessionContext::sql_with_handle`]: crate::execution::context::SessionContext::sql_with_handle
+/// [`DataFusionError::Cancelled`]: crate::error::DataFusionError::Cancelled
+pub struct QueryHandle {
+    schema: SchemaRef,
+    /// The results of the query, `None` once the query finished or was cancelled
+    stream: Option<SendableRecordBatchStream>,
+    token: CancellationToken,
+    /// Wakes up the handle once the query is cancelled
+    cancelled: Cancelled,
+    spill_usage: SpillUsage,
+    /// Keeps the statement timeout alive for as long as the query runs
+    _timeout: Option<StatementTimeout>,
+}
+
+impl QueryHandle {
+    /// Wrap `stream`, which must have been executed with a
+    /// [`TaskContext`] holding `token`
+    ///
+    /// [`TaskContext`]: crate::execution::TaskContext
+    pub fn new(stream: SendableRecordBatchStream, token: CancellationToken) -> Self {
+        Self {
+            schema: stream.schema(),
+            stream: Some(stream),
+            cancelled: token.cancelled(),
+            token,
+            spill_usage: SpillUsage::new(),
+            _timeout: None,
+        }
+    }
+
+    pub(crate) fn with_timeout(mut self, timeout: Option<StatementTimeout>) -> Self {
+        self._timeout = timeout;
+        self
+    }
+
+    pub(crate) fn with_spill_usage(mut self, spill_usage: SpillUsage) -> Self {
+        self.spill_usage = spill_usage;
+        self
+    }
+
+    /// Cancel the query
+    pub fn cancel(&self) {
+        self.token.cancel()
+    }
+
+    /// Returns true if the query has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Return the [`CancellationToken`] of the query, which can be used to
+    /// cancel it from another task
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.token
+    }
+
+    /// Return the disk space used by the spill files of the query
+    pub fn spill_usage(&self) -> &SpillUsage {
+        &self.spill_usage
+    }
+}
+
+impl std::fmt::Debug for QueryHandle {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("QueryHandle")
+            .field("schema", &self.schema)
+            .field("finished", &self.stream.is_none())
+            .field("token", &self.token)
+            .finish()
+    }
+}
+
+impl Stream for QueryHandle {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let this = &mut *self;
+        let Some(stream) = this.stream.as_mut() else {
+            return Poll::Ready(None);
+        };
+
+        // wake up on cancellation even if the query is waiting on input
+        if this.cancelled.poll_unpin(cx).is_ready() {
+            this.stream = None;
+            let err = this.token.check().unwrap_err();
+            return Poll::Ready(Some(Err(err)));
+        }
+
+        let poll = stream.as_mut().poll_next(cx);
+        if let Poll::Ready(None) = poll {
+            this.stream = None;
+        }
+        poll
+    }
+}
+
+impl RecordBatchStream for QueryHandle {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+/// Cancels a query once its `statement_timeout` elapses. The timer is
+/// stopped when this is dropped.
+#[derive(Debug)]
+pub(crate) struct StatementTimeout {
+    task: JoinHandle<()>,
+}
+
+impl StatementTimeout {
+    /// Start a timer cancelling `token` after `timeout_ms` milliseconds,
+    /// returning `None` if `timeout_ms` is 0
+    pub(crate) fn start(token: &CancellationToken, timeout_ms: u64) -> Option<Self> {
+        if timeout_ms == 0 {
+            return None;
+        }
+
+        let token = token.clone();
+        let task = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(timeout_ms)).await;
+            token.cancel_with_reason(format!(
+                "statement timeout of {timeout_ms} ms exceeded"
+            ));
+        });
+        Some(Self { task })
+    }
+}
+
+impl Drop for StatementTimeout {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::assert_batches_eq;
+    use crate::error::DataFusionError;
+    use crate::physical_plan::stream::RecordBatchStreamAdapter;
+    use crate::prelude::{SessionConfig, SessionContext};
+    use arrow::datatypes::Schema;
+    use futures::{StreamExt, TryStreamExt};
+    use std::sync::Arc;
+
+    #[tokio::test]
+    async fn cancel_query() -> Result<()> {
+        let ctx = SessionContext::new();
+        let mut handle = ctx
+            .sql_with_handle("SELECT * FROM (VALUES (2), (1)) ORDER BY 1")
+            .await?;
+
+        handle.cancellation_token().clone().cancel();
+        assert!(handle.is_cancelled());
+
+        let err = handle.next().await.unwrap().unwrap_err();
+        assert!(matches!(err, DataFusionError::Cancelled(_)));
+        assert!(handle.next().await.is_none());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn statement_timeout() -> Result<()> {
+        let token = CancellationToken::new();
+        let stream = RecordBatchStreamAdapter::new(
+            Arc::new(Schema::empty()),
+            futures::stream::pending::<Result<RecordBatch>>(),
+        );
+        let mut handle = QueryHandle::new(Box::pin(stream), token.clone())
+            .with_timeout(StatementTimeout::start(&token, 10));
+
+        let err = handle.next().await.unwrap().unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Query cancelled: statement timeout of 10 ms exceeded"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn statement_timeout_not_exceeded() -> Result<()> {
+        let config = SessionConfig::new()
+            .set_u64("datafusion.execution.statement_timeout", 60_000);
+        let ctx = SessionContext::with_config(config);
+
+        let handle = ctx.sql_with_handle("SELECT 1 AS a").await?;
+        let batches: Vec<_> = handle.try_collect().await?;
+
+        let expected = ["+---+", "| a |", "+---+", "| 1 |", "+---+"];
+        assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+}
@@ -20,6 +20,7 @@ use std::sync::Arc;
 
 use crate::config::ConfigOptions;
 use datafusion_common::tree_node::TreeNode;
+use datafusion_common::Precision;
 use datafusion_expr::utils::COUNT_STAR_EXPANSION;
 
 use crate::physical_plan::aggregates::{AggregateExec, AggregateMode};
@@ -107,7 +108,7 @@ impl PhysicalOptimizerRule for AggregateStatistics {
 /// assert if the node passed as argument is a final `AggregateExec` node that can be optimized:
 /// - its child (with possible intermediate layers) is a partial `AggregateExec` node
 /// - they both have no grouping expression
-/// - the statistics are exact
+/// - the number of rows of the input is exact
 /// If this is the case, return a ref to the partial `AggregateExec`, else `None`.
 /// We would have preferred to return a casted ref to AggregateExec but the recursion requires
 /// the `ExecutionPlan.children()` method that returns an owned reference.
@@ -126,7 +127,7 @@ fn take_optimizable(node: &dyn ExecutionPlan) -> Option<Arc<dyn ExecutionPlan>>
                         && partial_agg_exec.filter_expr().iter().all(|e| e.is_none())
                     {
                         let stats = partial_agg_exec.input().statistics();
-                        if stats.is_exact {
+                        if stats.num_rows.is_exact().unwrap_or(false) {
                             return Some(child);
                         }
                     }
@@ -142,13 +143,13 @@ fn take_optimizable(node: &dyn ExecutionPlan) -> Option<Arc<dyn ExecutionPlan>>
     None
 }
 
-/// If this agg_expr is a count that is defined in the statistics, return it
+/// If this agg_expr is a count and the row count is exact in the statistics, return it
 fn take_optimizable_table_count(
     agg_expr: &dyn AggregateExpr,
     stats: &Statistics,
 ) -> Option<(ScalarValue, &'static str)> {
-    if let (Some(num_rows), Some(casted_expr)) = (
-        stats.num_rows,
+    if let (&Precision::Exact(num_rows), Some(casted_expr)) = (
+        &stats.num_rows,
         agg_expr.as_any().downcast_ref::<expressions::Count>(),
     ) {
         // TODO implementing Eq on PhysicalExpr would help a lot here
@@ -169,13 +170,13 @@ fn take_optimizable_table_count(
     None
 }
 
-/// If this agg_expr is a count that can be derived from the statistics, return it
+/// If this agg_expr is a count that can be derived from exact statistics, return it
 fn take_optimizable_column_count(
     agg_expr: &dyn AggregateExpr,
     stats: &Statistics,
 ) -> Option<(ScalarValue, String)> {
-    if let (Some(num_rows), Some(col_stats), Some(casted_expr)) = (
-        stats.num_rows,
+    if let (&Precision::Exact(num_rows), Some(col_stats), Some(casted_expr)) = (
+        &stats.num_rows,
         &stats.column_statistics,
         agg_expr.as_any().downcast_ref::<expressions::Count>(),
     ) {
@@ -186,7 +187,7 @@ fn take_optimizable_column_count(
                 .downcast_ref::<expressions::Column>()
             {
                 if let ColumnStatistics {
-                    null_count: Some(val),
+                    null_count: Precision::Exact(val),
                     ..
                 } = &col_stats[col_expr.index()]
                 {
@@ -201,7 +202,7 @@ fn take_optimizable_column_count(
     None
 }
 
-/// If this agg_expr is a min that is defined in the statistics, return it
+/// If this agg_expr is a min that is exactly defined in the statistics, return it
 fn take_optimizable_min(
     agg_expr: &dyn AggregateExpr,
     stats: &Statistics,
@@ -217,7 +218,7 @@ fn take_optimizable_min(
                 .downcast_ref::<expressions::Column>()
             {
                 if let ColumnStatistics {
-                    min_value: Some(val),
+                    min_value: Precision::Exact(val),
                     ..
                 } = &col_stats[col_expr.index()]
                 {
@@ -229,7 +230,7 @@ fn take_optimizable_min(
     None
 }
 
-/// If this agg_expr is a max that is defined in the statistics, return it
+/// If this agg_expr is a max that is exactly defined in the statistics, return it
 fn take_optimizable_max(
     agg_expr: &dyn AggregateExpr,
     stats: &Statistics,
@@ -245,7 +246,7 @@ fn take_optimizable_max(
                 .downcast_ref::<expressions::Column>()
             {
                 if let ColumnStatistics {
-                    max_value: Some(val),
+                    max_value: Precision::Exact(val),
                     ..
                 } = &col_stats[col_expr.index()]
                 {
@@ -278,6 +279,7 @@ mod tests {
     use crate::physical_plan::filter::FilterExec;
     use crate::physical_plan::memory::MemoryExec;
     use crate::prelude::SessionContext;
+    use crate::test::exec::StatisticsExec;
 
     /// Mock data using a MemoryExec which has an exact count statistic
     fn mock_data() -> Result<Arc<MemoryExec>> {
@@ -625,4 +627,61 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_no_optimization_with_inexact_row_count() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        // the row count is only estimated, so the input may not be a plain
+        // scan and its column statistics can not be trusted either
+        let source = Arc::new(StatisticsExec::new(
+            Statistics {
+                num_rows: Precision::Inexact(100),
+                column_statistics: Some(vec![ColumnStatistics {
+                    max_value: Precision::Exact(ScalarValue::Int32(Some(10))),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            },
+            schema.clone(),
+        ));
+        let schema = source.schema();
+
+        let aggregate = |agg_expr: Arc<dyn AggregateExpr>| -> Result<AggregateExec> {
+            let partial_agg = AggregateExec::try_new(
+                AggregateMode::Partial,
+                PhysicalGroupBy::default(),
+                vec![agg_expr.clone()],
+                vec![None],
+                vec![None],
+                source.clone(),
+                Arc::clone(&schema),
+            )?;
+            AggregateExec::try_new(
+                AggregateMode::Final,
+                PhysicalGroupBy::default(),
+                vec![agg_expr],
+                vec![None],
+                vec![None],
+                Arc::new(partial_agg),
+                Arc::clone(&schema),
+            )
+        };
+
+        let conf = ConfigOptions::new();
+        let max: Arc<dyn AggregateExpr> = Arc::new(expressions::Max::new(
+            expressions::col("a", &schema)?,
+            "MAX(a)",
+            DataType::Int32,
+        ));
+        let optimized =
+            AggregateStatistics::new().optimize(Arc::new(aggregate(max)?), &conf)?;
+        assert!(optimized.as_any().is::<AggregateExec>());
+
+        let count = TestAggregate::new_count_star().count_expr();
+        let optimized =
+            AggregateStatistics::new().optimize(Arc::new(aggregate(count)?), &conf)?;
+        assert!(optimized.as_any().is::<AggregateExec>());
+
+        Ok(())
+    }
 }
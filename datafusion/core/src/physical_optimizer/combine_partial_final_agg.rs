@@ -269,6 +269,8 @@ mod tests {
                 table_partition_cols: vec![],
                 output_ordering: vec![],
                 infinite_source: false,
+                column_renames: vec![],
+                nested_projection: vec![],
             },
             None,
             None,
@@ -803,7 +803,7 @@ fn new_join_conditions(
 /// operators to satisfy distribution requirements. Since this function
 /// takes care of such requirements, we should avoid manually adding data
 /// exchange operators in other places.
-fn ensure_distribution(
+pub(crate) fn ensure_distribution(
     plan: Arc<dyn ExecutionPlan>,
     target_partitions: usize,
 ) -> Result<Transformed<Arc<dyn ExecutionPlan>>> {
@@ -1017,6 +1017,8 @@ mod tests {
                 table_partition_cols: vec![],
                 output_ordering,
                 infinite_source: false,
+                column_renames: vec![],
+                nested_projection: vec![],
             },
             None,
             None,
@@ -1041,6 +1043,8 @@ mod tests {
                 table_partition_cols: vec![],
                 output_ordering,
                 infinite_source: false,
+                column_renames: vec![],
+                nested_projection: vec![],
             },
             None,
             None,
@@ -40,7 +40,7 @@ use crate::physical_plan::ExecutionPlan;
 use arrow_schema::Schema;
 use datafusion_common::internal_err;
 use datafusion_common::tree_node::{Transformed, TreeNode};
-use datafusion_common::{DataFusionError, JoinType};
+use datafusion_common::{DataFusionError, JoinType, Precision};
 use datafusion_physical_expr::expressions::Column;
 use datafusion_physical_expr::PhysicalExpr;
 
@@ -68,11 +68,13 @@ fn should_swap_join_order(left: &dyn ExecutionPlan, right: &dyn ExecutionPlan) -
         left.statistics().total_byte_size,
         right.statistics().total_byte_size,
     ) {
-        (Some(l), Some(r)) => (Some(l), Some(r)),
-        _ => (left.statistics().num_rows, right.statistics().num_rows),
+        (Precision::Absent, _) | (_, Precision::Absent) => {
+            (left.statistics().num_rows, right.statistics().num_rows)
+        }
+        sizes => sizes,
     };
 
-    match (left_size, right_size) {
+    match (left_size.get_value(), right_size.get_value()) {
         (Some(l), Some(r)) => l > r,
         _ => false,
     }
@@ -84,9 +86,10 @@ fn supports_collect_by_size(
 ) -> bool {
     // Currently we do not trust the 0 value from stats, due to stats collection might have bug
     // TODO check the logic in datasource::get_statistics_with_limit()
-    if let Some(size) = plan.statistics().total_byte_size {
+    let stats = plan.statistics();
+    if let Some(&size) = stats.total_byte_size.get_value() {
         size != 0 && size < collection_size_threshold
-    } else if let Some(row_count) = plan.statistics().num_rows {
+    } else if let Some(&row_count) = stats.num_rows.get_value() {
         row_count != 0 && row_count < collection_size_threshold
     } else {
         false
@@ -597,8 +600,8 @@ mod tests_statistical {
     fn create_big_and_small() -> (Arc<dyn ExecutionPlan>, Arc<dyn ExecutionPlan>) {
         let big = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: Some(10),
-                total_byte_size: Some(100000),
+                num_rows: Precision::Inexact(10),
+                total_byte_size: Precision::Inexact(100000),
                 ..Default::default()
             },
             Schema::new(vec![Field::new("big_col", DataType::Int32, false)]),
@@ -606,8 +609,8 @@ mod tests_statistical {
 
         let small = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: Some(100000),
-                total_byte_size: Some(10),
+                num_rows: Precision::Inexact(100000),
+                total_byte_size: Precision::Inexact(10),
                 ..Default::default()
             },
             Schema::new(vec![Field::new("small_col", DataType::Int32, false)]),
@@ -644,7 +647,7 @@ mod tests_statistical {
     ) {
         let big = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: Some(100_000),
+                num_rows: Precision::Inexact(100_000),
                 column_statistics: create_column_stats(
                     Some(0),
                     Some(50_000),
@@ -657,7 +660,7 @@ mod tests_statistical {
 
         let medium = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: Some(10_000),
+                num_rows: Precision::Inexact(10_000),
                 column_statistics: create_column_stats(
                     Some(1000),
                     Some(5000),
@@ -670,7 +673,7 @@ mod tests_statistical {
 
         let small = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: Some(1000),
+                num_rows: Precision::Inexact(1000),
                 column_statistics: create_column_stats(
                     Some(0),
                     Some(100_000),
@@ -725,10 +728,13 @@ mod tests_statistical {
             .downcast_ref::<HashJoinExec>()
             .expect("The type of the plan should not be changed");
 
-        assert_eq!(swapped_join.left().statistics().total_byte_size, Some(10));
+        assert_eq!(
+            swapped_join.left().statistics().total_byte_size,
+            Precision::Inexact(10)
+        );
         assert_eq!(
             swapped_join.right().statistics().total_byte_size,
-            Some(100000)
+            Precision::Inexact(100000)
         );
     }
 
@@ -775,9 +781,12 @@ mod tests_statistical {
 
         assert_eq!(
             swapped_join.left().statistics().total_byte_size,
-            Some(100000)
+            Precision::Inexact(100000)
+        );
+        assert_eq!(
+            swapped_join.right().statistics().total_byte_size,
+            Precision::Inexact(10)
         );
-        assert_eq!(swapped_join.right().statistics().total_byte_size, Some(10));
     }
 
     #[tokio::test]
@@ -815,10 +824,13 @@ mod tests_statistical {
 
             assert_eq!(swapped_join.schema().fields().len(), 1);
 
-            assert_eq!(swapped_join.left().statistics().total_byte_size, Some(10));
+            assert_eq!(
+                swapped_join.left().statistics().total_byte_size,
+                Precision::Inexact(10)
+            );
             assert_eq!(
                 swapped_join.right().statistics().total_byte_size,
-                Some(100000)
+                Precision::Inexact(100000)
             );
 
             assert_eq!(original_schema, swapped_join.schema());
@@ -893,9 +905,9 @@ mod tests_statistical {
             "  HashJoinExec: mode=CollectLeft, join_type=Right, on=[(small_col@1, medium_col@0)]",
             "    ProjectionExec: expr=[big_col@1 as big_col, small_col@0 as small_col]",
             "      HashJoinExec: mode=CollectLeft, join_type=Inner, on=[(small_col@0, big_col@0)]",
-            "        StatisticsExec: col_count=1, row_count=Some(1000)",
-            "        StatisticsExec: col_count=1, row_count=Some(100000)",
-            "    StatisticsExec: col_count=1, row_count=Some(10000)",
+            "        StatisticsExec: col_count=1, row_count=Inexact(1000)",
+            "        StatisticsExec: col_count=1, row_count=Inexact(100000)",
+            "    StatisticsExec: col_count=1, row_count=Inexact(10000)",
             "",
         ];
         assert_optimized!(expected, join);
@@ -927,10 +939,13 @@ mod tests_statistical {
             .downcast_ref::<HashJoinExec>()
             .expect("The type of the plan should not be changed");
 
-        assert_eq!(swapped_join.left().statistics().total_byte_size, Some(10));
+        assert_eq!(
+            swapped_join.left().statistics().total_byte_size,
+            Precision::Inexact(10)
+        );
         assert_eq!(
             swapped_join.right().statistics().total_byte_size,
-            Some(100000)
+            Precision::Inexact(100000)
         );
     }
 
@@ -973,8 +988,8 @@ mod tests_statistical {
     async fn test_join_selection_collect_left() {
         let big = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: Some(10000000),
-                total_byte_size: Some(10000000),
+                num_rows: Precision::Inexact(10000000),
+                total_byte_size: Precision::Inexact(10000000),
                 ..Default::default()
             },
             Schema::new(vec![Field::new("big_col", DataType::Int32, false)]),
@@ -982,8 +997,8 @@ mod tests_statistical {
 
         let small = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: Some(10),
-                total_byte_size: Some(10),
+                num_rows: Precision::Inexact(10),
+                total_byte_size: Precision::Inexact(10),
                 ..Default::default()
             },
             Schema::new(vec![Field::new("small_col", DataType::Int32, false)]),
@@ -991,8 +1006,8 @@ mod tests_statistical {
 
         let empty = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: None,
-                total_byte_size: None,
+                num_rows: Precision::Absent,
+                total_byte_size: Precision::Absent,
                 ..Default::default()
             },
             Schema::new(vec![Field::new("empty_col", DataType::Int32, false)]),
@@ -1051,8 +1066,8 @@ mod tests_statistical {
     async fn test_join_selection_partitioned() {
         let big1 = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: Some(10000000),
-                total_byte_size: Some(10000000),
+                num_rows: Precision::Inexact(10000000),
+                total_byte_size: Precision::Inexact(10000000),
                 ..Default::default()
             },
             Schema::new(vec![Field::new("big_col1", DataType::Int32, false)]),
@@ -1060,8 +1075,8 @@ mod tests_statistical {
 
         let big2 = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: Some(20000000),
-                total_byte_size: Some(20000000),
+                num_rows: Precision::Inexact(20000000),
+                total_byte_size: Precision::Inexact(20000000),
                 ..Default::default()
             },
             Schema::new(vec![Field::new("big_col2", DataType::Int32, false)]),
@@ -1069,8 +1084,8 @@ mod tests_statistical {
 
         let empty = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: None,
-                total_byte_size: None,
+                num_rows: Precision::Absent,
+                total_byte_size: Precision::Absent,
                 ..Default::default()
             },
             Schema::new(vec![Field::new("empty_col", DataType::Int32, false)]),
@@ -26,11 +26,14 @@ pub mod coalesce_batches;
 pub mod combine_partial_final_agg;
 pub mod dist_enforcement;
 pub mod join_selection;
+pub mod nested_projection_pushdown;
 pub mod optimizer;
 pub mod pipeline_checker;
 pub mod pruning;
 pub mod repartition;
 pub mod replace_with_order_preserving_variants;
+pub mod runtime_join_filter;
+pub mod skew_repartition;
 pub mod sort_enforcement;
 mod sort_pushdown;
 mod utils;
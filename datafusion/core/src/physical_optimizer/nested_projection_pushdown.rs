@@ -0,0 +1,569 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! NestedProjectionPushdown optimizer that restricts Parquet scans to the
+//! fields of struct columns that a query accesses
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use crate::config::ConfigOptions;
+use crate::datasource::physical_plan::{prune_struct_type, ParquetExec};
+use crate::error::Result;
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use crate::physical_plan::filter::FilterExec;
+use crate::physical_plan::limit::{GlobalLimitExec, LocalLimitExec};
+use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::repartition::RepartitionExec;
+use crate::physical_plan::sorts::partial_sort::PartialSortExec;
+use crate::physical_plan::sorts::sort::SortExec;
+use crate::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use crate::physical_plan::{ExecutionPlan, Partitioning};
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::ScalarValue;
+use datafusion_physical_expr::expressions::{
+    Column, GetFieldAccessExpr, GetIndexedFieldExpr,
+};
+use datafusion_physical_expr::utils::collect_columns;
+use datafusion_physical_expr::{PhysicalExpr, PhysicalSortExpr};
+
+/// Optimizer rule that pushes the struct fields accessed by a projection
+/// down into the [`ParquetExec`] below it.
+///
+/// A projection such as `s['a']` only needs the Parquet leaves of the
+/// field `a` of struct column `s`. The accessed fields of struct columns for
+/// which every use is such a field access are set as the nested projection
+/// of the scan, which then only reads the leaves of those fields (see
+/// [`ParquetExec::with_nested_projection`]).
+///
+/// The uses of columns are tracked from a [`ProjectionExec`] down to the
+/// scan through other projections, which may rename or compute columns, and
+/// through operators that pass the columns of their input through unchanged
+/// and whose expressions can be inspected, such as filters, sorts,
+/// repartitions and limits. Any other operator stops the pushdown.
+#[derive(Default)]
+pub struct NestedProjectionPushdown {}
+
+impl NestedProjectionPushdown {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PhysicalOptimizerRule for NestedProjectionPushdown {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !config.optimizer.enable_nested_projection_pushdown {
+            return Ok(plan);
+        }
+
+        plan.transform_down(&|plan| {
+            Ok(match push_down_nested_projection(&plan)? {
+                Some(new_plan) => Transformed::Yes(new_plan),
+                None => Transformed::No(plan),
+            })
+        })
+    }
+
+    fn name(&self) -> &str {
+        "NestedProjectionPushdown"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// How the output columns of an operator are used by the operators above it
+#[derive(Debug, Clone, PartialEq)]
+enum ColumnUsage {
+    Unused,
+    /// Only the fields at these paths are accessed
+    Fields(Vec<Vec<String>>),
+    /// The whole column is used
+    All,
+}
+
+impl ColumnUsage {
+    fn add_path(&mut self, path: Vec<String>) {
+        match self {
+            ColumnUsage::Unused => *self = ColumnUsage::Fields(vec![path]),
+            ColumnUsage::Fields(paths) => {
+                if !paths.contains(&path) {
+                    paths.push(path)
+                }
+            }
+            ColumnUsage::All => {}
+        }
+    }
+
+    /// Adds the uses of a column that is the field at `prefix` of this column
+    fn add_nested(&mut self, prefix: &[String], usage: &ColumnUsage) {
+        match usage {
+            ColumnUsage::Unused => {}
+            ColumnUsage::Fields(paths) => {
+                for path in paths {
+                    self.add_path(prefix.iter().chain(path).cloned().collect());
+                }
+            }
+            ColumnUsage::All if prefix.is_empty() => *self = ColumnUsage::All,
+            ColumnUsage::All => self.add_path(prefix.to_vec()),
+        }
+    }
+}
+
+fn push_down_nested_projection(
+    plan: &Arc<dyn ExecutionPlan>,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    let Some(projection) = plan.as_any().downcast_ref::<ProjectionExec>() else {
+        return Ok(None);
+    };
+
+    let mut usages =
+        vec![ColumnUsage::Unused; projection.input().schema().fields().len()];
+    for (expr, _) in projection.expr() {
+        collect_usage(expr, &mut usages);
+    }
+
+    // operators between the projection and the scan, top down
+    let mut intermediates = vec![];
+    let mut input = projection.input().clone();
+    let parquet = loop {
+        if input.as_any().is::<ParquetExec>() {
+            break input;
+        }
+
+        if let Some(inner) = input.as_any().downcast_ref::<ProjectionExec>() {
+            usages = projection_input_usage(inner, &usages);
+        } else {
+            let Some(exprs) = pass_through_expressions(&input) else {
+                return Ok(None);
+            };
+            for expr in &exprs {
+                collect_usage(expr, &mut usages);
+            }
+        }
+
+        intermediates.push(input.clone());
+        input = input.children().swap_remove(0);
+    };
+    let Some(parquet) = parquet.as_any().downcast_ref::<ParquetExec>() else {
+        return Ok(None);
+    };
+
+    // the sort order of a struct column refers to all its fields
+    if let Some(ordering) = parquet.output_ordering() {
+        for sort_expr in ordering {
+            collect_usage(&sort_expr.expr, &mut usages);
+        }
+    }
+
+    let Some(nested_projection) = nested_projection(parquet, &usages) else {
+        return Ok(None);
+    };
+
+    let mut new_plan: Arc<dyn ExecutionPlan> =
+        Arc::new(parquet.with_nested_projection(nested_projection));
+    for intermediate in intermediates.into_iter().rev() {
+        new_plan = intermediate.with_new_children(vec![new_plan])?;
+    }
+    Ok(Some(plan.clone().with_new_children(vec![new_plan])?))
+}
+
+/// Returns the expressions of `plan` if it outputs the columns of its only
+/// input unchanged, or `None` if it does not, or is not known to
+fn pass_through_expressions(
+    plan: &Arc<dyn ExecutionPlan>,
+) -> Option<Vec<Arc<dyn PhysicalExpr>>> {
+    let sort_exprs = |exprs: &[PhysicalSortExpr]| {
+        Some(
+            exprs
+                .iter()
+                .map(|sort_expr| sort_expr.expr.clone())
+                .collect(),
+        )
+    };
+
+    let any = plan.as_any();
+    if let Some(filter) = any.downcast_ref::<FilterExec>() {
+        Some(vec![filter.predicate().clone()])
+    } else if let Some(repartition) = any.downcast_ref::<RepartitionExec>() {
+        match repartition.partitioning() {
+            Partitioning::Hash(exprs, _) => Some(exprs.clone()),
+            _ => Some(vec![]),
+        }
+    } else if let Some(sort) = any.downcast_ref::<SortExec>() {
+        sort_exprs(sort.expr())
+    } else if let Some(sort) = any.downcast_ref::<PartialSortExec>() {
+        sort_exprs(sort.expr())
+    } else if let Some(merge) = any.downcast_ref::<SortPreservingMergeExec>() {
+        sort_exprs(merge.expr())
+    } else if any.is::<CoalesceBatchesExec>()
+        || any.is::<CoalescePartitionsExec>()
+        || any.is::<GlobalLimitExec>()
+        || any.is::<LocalLimitExec>()
+    {
+        Some(vec![])
+    } else {
+        None
+    }
+}
+
+/// Returns how the input columns of `projection` are used, given the
+/// `usages` of its output columns
+fn projection_input_usage(
+    projection: &ProjectionExec,
+    usages: &[ColumnUsage],
+) -> Vec<ColumnUsage> {
+    let mut input_usages =
+        vec![ColumnUsage::Unused; projection.input().schema().fields().len()];
+    for ((expr, _), usage) in projection.expr().iter().zip(usages) {
+        if usage == &ColumnUsage::Unused {
+            continue;
+        }
+
+        // columns and field accesses pass the uses of their output through
+        if let Some(column) = expr.as_any().downcast_ref::<Column>() {
+            input_usages[column.index()].add_nested(&[], usage);
+        } else if let Some((index, path)) = field_path(expr) {
+            input_usages[index].add_nested(&path, usage);
+        } else {
+            collect_usage(expr, &mut input_usages);
+        }
+    }
+    input_usages
+}
+
+/// Records in `usages` how `expr` uses the columns of its input
+fn collect_usage(expr: &Arc<dyn PhysicalExpr>, usages: &mut [ColumnUsage]) {
+    if let Some((index, path)) = field_path(expr) {
+        usages[index].add_path(path);
+        return;
+    }
+
+    if let Some(column) = expr.as_any().downcast_ref::<Column>() {
+        usages[column.index()] = ColumnUsage::All;
+        return;
+    }
+
+    // The keys of list accesses are not children of the expression
+    if let Some(get_field) = expr.as_any().downcast_ref::<GetIndexedFieldExpr>() {
+        match get_field.field() {
+            GetFieldAccessExpr::NamedStructField { .. } => {}
+            GetFieldAccessExpr::ListIndex { key } => collect_usage(key, usages),
+            GetFieldAccessExpr::ListRange { start, stop } => {
+                collect_usage(start, usages);
+                collect_usage(stop, usages);
+            }
+        }
+    }
+
+    for child in expr.children() {
+        collect_usage(&child, usages);
+    }
+}
+
+/// If `expr` is a chain of struct field accesses on a column, such as
+/// `s['a']['b']`, returns the index of the column and the accessed path
+fn field_path(expr: &Arc<dyn PhysicalExpr>) -> Option<(usize, Vec<String>)> {
+    let get_field = expr.as_any().downcast_ref::<GetIndexedFieldExpr>()?;
+    let GetFieldAccessExpr::NamedStructField {
+        name: ScalarValue::Utf8(Some(name)),
+    } = get_field.field()
+    else {
+        return None;
+    };
+
+    let arg = get_field.arg();
+    if let Some(column) = arg.as_any().downcast_ref::<Column>() {
+        return Some((column.index(), vec![name.clone()]));
+    }
+
+    let (index, mut path) = field_path(arg)?;
+    path.push(name.clone());
+    Some((index, path))
+}
+
+/// Returns the nested projection of `parquet` extended with the fields of
+/// struct columns in `usages`, or `None` if no more fields can be pruned
+fn nested_projection(
+    parquet: &ParquetExec,
+    usages: &[ColumnUsage],
+) -> Option<Vec<Vec<String>>> {
+    let config = parquet.base_config();
+    let file_schema = &config.file_schema;
+
+    // the predicate is evaluated against whole columns
+    let predicate_columns: HashSet<String> = parquet
+        .predicate()
+        .map(|predicate| {
+            collect_columns(predicate)
+                .into_iter()
+                .map(|column| column.name().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let projection = config
+        .projection
+        .clone()
+        .unwrap_or_else(|| (0..file_schema.fields().len()).collect());
+
+    let mut nested_projection = config.nested_projection.clone();
+    let mut pruned = false;
+    for (usage, file_idx) in usages.iter().zip(projection) {
+        // indexes past the file schema are partition columns
+        let (ColumnUsage::Fields(paths), Some(field)) =
+            (usage, file_schema.fields().get(file_idx))
+        else {
+            continue;
+        };
+        // columns that are already pruned keep their fields, as the uses
+        // refer to the pruned type
+        if predicate_columns.contains(field.name())
+            || nested_projection
+                .iter()
+                .any(|path| path.first() == Some(field.name()))
+        {
+            continue;
+        }
+
+        let relative_paths: Vec<&[String]> =
+            paths.iter().map(|path| path.as_slice()).collect();
+        if &prune_struct_type(field.data_type(), &relative_paths) != field.data_type() {
+            nested_projection.extend(paths.iter().map(|path| {
+                std::iter::once(field.name().clone())
+                    .chain(path.iter().cloned())
+                    .collect()
+            }));
+            pruned = true;
+        }
+    }
+
+    pruned.then_some(nested_projection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::datasource::listing::PartitionedFile;
+    use crate::datasource::object_store::ObjectStoreUrl;
+    use crate::datasource::physical_plan::FileScanConfig;
+    use crate::physical_plan::expressions::{col, lit, BinaryExpr};
+    use crate::physical_plan::union::UnionExec;
+    use arrow::datatypes::Field;
+    use datafusion_common::Statistics;
+    use datafusion_expr::Operator;
+
+    fn struct_type() -> DataType {
+        DataType::Struct(
+            vec![
+                Field::new("a", DataType::Int64, true),
+                Field::new(
+                    "b",
+                    DataType::Struct(
+                        vec![
+                            Field::new("c", DataType::Utf8, true),
+                            Field::new("d", DataType::Utf8, true),
+                        ]
+                        .into(),
+                    ),
+                    true,
+                ),
+                Field::new("e", DataType::Boolean, true),
+            ]
+            .into(),
+        )
+    }
+
+    fn parquet_exec() -> Arc<ParquetExec> {
+        let file_schema = Arc::new(Schema::new(vec![
+            Field::new("id", DataType::Int64, true),
+            Field::new("s", struct_type(), true),
+        ]));
+        Arc::new(ParquetExec::new(
+            FileScanConfig {
+                object_store_url: ObjectStoreUrl::parse("test:///").unwrap(),
+                file_schema,
+                file_groups: vec![vec![PartitionedFile::new("x".to_string(), 100)]],
+                statistics: Statistics::default(),
+                projection: None,
+                limit: None,
+                table_partition_cols: vec![],
+                output_ordering: vec![],
+                infinite_source: false,
+                column_renames: vec![],
+                nested_projection: vec![],
+            },
+            None,
+            None,
+        ))
+    }
+
+    fn field(arg: Arc<dyn PhysicalExpr>, name: &str) -> Arc<dyn PhysicalExpr> {
+        Arc::new(GetIndexedFieldExpr::new_field(arg, name))
+    }
+
+    /// Returns the type of column `s` read by the scan of the optimized plan
+    fn optimized_scan_type(plan: Arc<dyn ExecutionPlan>) -> DataType {
+        let optimized = NestedProjectionPushdown::new()
+            .optimize(plan, &ConfigOptions::new())
+            .unwrap();
+
+        let mut plan = optimized;
+        while !plan.children().is_empty() {
+            plan = plan.children().swap_remove(0);
+        }
+        plan.schema()
+            .field_with_name("s")
+            .unwrap()
+            .data_type()
+            .clone()
+    }
+
+    #[test]
+    fn push_down_struct_fields() -> Result<()> {
+        let scan = parquet_exec();
+        let schema = scan.schema();
+        let s = col("s", &schema)?;
+
+        // SELECT s['a'], s['b']['c'] FROM t WHERE s['b']['d'] = 'x'
+        let filter = Arc::new(FilterExec::try_new(
+            Arc::new(BinaryExpr::new(
+                field(field(s.clone(), "b"), "d"),
+                Operator::Eq,
+                lit("x"),
+            )),
+            scan,
+        )?);
+        let projection = Arc::new(ProjectionExec::try_new(
+            vec![
+                (field(s.clone(), "a"), "a".to_string()),
+                (field(field(s, "b"), "c"), "c".to_string()),
+            ],
+            filter,
+        )?);
+
+        let expected = DataType::Struct(
+            vec![
+                Field::new("a", DataType::Int64, true),
+                Field::new(
+                    "b",
+                    DataType::Struct(
+                        vec![
+                            Field::new("c", DataType::Utf8, true),
+                            Field::new("d", DataType::Utf8, true),
+                        ]
+                        .into(),
+                    ),
+                    true,
+                ),
+            ]
+            .into(),
+        );
+        assert_eq!(optimized_scan_type(projection), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn push_down_through_projection_and_sort() -> Result<()> {
+        let scan = parquet_exec();
+        let schema = scan.schema();
+        let s = col("s", &schema)?;
+
+        // SELECT x['c'] FROM (SELECT s['b'] AS x, s['a'] AS a FROM t ORDER BY a)
+        let sort = Arc::new(SortExec::new(
+            vec![PhysicalSortExpr {
+                expr: field(s.clone(), "a"),
+                options: Default::default(),
+            }],
+            scan,
+        ));
+        let inner = Arc::new(ProjectionExec::try_new(
+            vec![
+                (field(s.clone(), "b"), "x".to_string()),
+                (field(s, "a"), "a".to_string()),
+            ],
+            sort,
+        )?);
+        let x = col("x", &inner.schema())?;
+        let projection = Arc::new(ProjectionExec::try_new(
+            vec![(field(x, "c"), "c".to_string())],
+            inner,
+        )?);
+
+        let expected = DataType::Struct(
+            vec![
+                Field::new("a", DataType::Int64, true),
+                Field::new(
+                    "b",
+                    DataType::Struct(vec![Field::new("c", DataType::Utf8, true)].into()),
+                    true,
+                ),
+            ]
+            .into(),
+        );
+        assert_eq!(optimized_scan_type(projection), expected);
+        Ok(())
+    }
+
+    #[test]
+    fn unknown_operator_stops_pushdown() -> Result<()> {
+        let scan = parquet_exec();
+        let schema = scan.schema();
+        let s = col("s", &schema)?;
+
+        // SELECT s['a'] FROM (SELECT * FROM t UNION ALL SELECT * FROM t)
+        let union = Arc::new(UnionExec::new(vec![
+            scan.clone() as Arc<dyn ExecutionPlan>,
+            scan,
+        ]));
+        let projection = Arc::new(ProjectionExec::try_new(
+            vec![(field(s, "a"), "a".to_string())],
+            union,
+        )?);
+
+        assert_eq!(optimized_scan_type(projection), struct_type());
+        Ok(())
+    }
+
+    #[test]
+    fn whole_struct_is_not_pruned() -> Result<()> {
+        let scan = parquet_exec();
+        let schema = scan.schema();
+        let s = col("s", &schema)?;
+
+        // SELECT s['a'], s FROM t
+        let projection = Arc::new(ProjectionExec::try_new(
+            vec![
+                (field(s.clone(), "a"), "a".to_string()),
+                (s, "s".to_string()),
+            ],
+            scan,
+        )?);
+
+        assert_eq!(optimized_scan_type(projection), struct_type());
+        Ok(())
+    }
+}
@@ -25,8 +25,11 @@ use crate::physical_optimizer::coalesce_batches::CoalesceBatches;
 use crate::physical_optimizer::combine_partial_final_agg::CombinePartialFinalAggregate;
 use crate::physical_optimizer::dist_enforcement::EnforceDistribution;
 use crate::physical_optimizer::join_selection::JoinSelection;
+use crate::physical_optimizer::nested_projection_pushdown::NestedProjectionPushdown;
 use crate::physical_optimizer::pipeline_checker::PipelineChecker;
 use crate::physical_optimizer::repartition::Repartition;
+use crate::physical_optimizer::runtime_join_filter::RuntimeJoinFilterPushdown;
+use crate::physical_optimizer::skew_repartition::SkewAwareRepartition;
 use crate::physical_optimizer::sort_enforcement::EnforceSorting;
 use crate::{error::Result, physical_plan::ExecutionPlan};
 
@@ -88,6 +91,10 @@ impl PhysicalOptimizer {
             Arc::new(EnforceDistribution::new()),
             // The CombinePartialFinalAggregate rule should be applied after the EnforceDistribution rule
             Arc::new(CombinePartialFinalAggregate::new()),
+            // The SkewAwareRepartition rule replaces hash repartitions added by the
+            // EnforceDistribution rule, and re-adds those it invalidates. It should run
+            // after the CombinePartialFinalAggregate rule, which removes some of them.
+            Arc::new(SkewAwareRepartition::new()),
             // The EnforceSorting rule is for adding essential local sorting to satisfy the required
             // ordering. Please make sure that the whole plan tree is determined before this rule.
             // Note that one should always run this rule after running the EnforceDistribution rule
@@ -96,6 +103,14 @@ impl PhysicalOptimizer {
             // The CoalesceBatches rule will not influence the distribution and ordering of the
             // whole plan tree. Therefore, to avoid influencing other rules, it should run last.
             Arc::new(CoalesceBatches::new()),
+            // The NestedProjectionPushdown rule only changes the types of struct columns
+            // read by Parquet scans, so it does not influence the other rules. It runs
+            // after CoalesceBatches so that it can look through the operators added by it.
+            Arc::new(NestedProjectionPushdown::new()),
+            // The RuntimeJoinFilterPushdown rule only attaches filters to existing
+            // operators, without changing the plan. It runs after the rules that
+            // replace operators, which might drop these filters.
+            Arc::new(RuntimeJoinFilterPushdown::new()),
             // The PipelineChecker rule will reject non-runnable query plans that use
             // pipeline-breaking operators on infinite input(s). The rule generates a
             // diagnostic error message when this happens. It makes no changes to the
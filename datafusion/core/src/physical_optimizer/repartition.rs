@@ -17,6 +17,7 @@
 
 //! Repartition optimizer that introduces repartition nodes to increase the level of parallelism available
 use datafusion_common::tree_node::Transformed;
+use datafusion_common::Precision;
 use std::sync::Arc;
 
 use super::optimizer::PhysicalOptimizerRule;
@@ -222,13 +223,14 @@ fn optimize_partitions(
         // we don't want to introduce partitioning after hash partitioning
         // as the plan will likely depend on this
         Hash(_, _) => false,
+        // nor after range partitioning, which the plan may use to produce a
+        // globally sorted output
+        Range(_, _) => false,
     };
 
     // Don't need to apply when the returned row count is not greater than 1
-    let stats = new_plan.statistics();
-    if stats.is_exact {
-        could_repartition = could_repartition
-            && stats.num_rows.map(|num_rows| num_rows > 1).unwrap_or(true);
+    if let Precision::Exact(num_rows) = new_plan.statistics().num_rows {
+        could_repartition = could_repartition && num_rows > 1;
     }
 
     // don't repartition root of the plan
@@ -382,6 +384,10 @@ mod tests {
             table_partition_cols: vec![],
             output_ordering: if sorted { vec![sort_exprs] } else { vec![] },
             infinite_source: false,
+            column_renames: vec![],
+            nested_projection: vec![],
+            column_renames: vec![],
+            nested_projection: vec![],
         }
     }
 
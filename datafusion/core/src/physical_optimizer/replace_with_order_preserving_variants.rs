@@ -902,6 +902,8 @@ mod tests {
                 table_partition_cols: vec![],
                 output_ordering: vec![sort_exprs],
                 infinite_source,
+                column_renames: vec![],
+                nested_projection: vec![],
             },
             true,
             0,
@@ -0,0 +1,427 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! RuntimeJoinFilterPushdown optimizer that pushes the filters published by
+//! hash joins at runtime down to the operators on their probe side
+
+use std::sync::Arc;
+
+use crate::config::ConfigOptions;
+use crate::datasource::physical_plan::ParquetExec;
+use crate::error::Result;
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
+use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use crate::physical_plan::filter::FilterExec;
+use crate::physical_plan::joins::runtime_filter::{RuntimeFilter, RuntimeJoinFilter};
+use crate::physical_plan::joins::{HashJoinExec, PartitionMode};
+use crate::physical_plan::projection::ProjectionExec;
+use crate::physical_plan::repartition::RepartitionExec;
+use crate::physical_plan::sorts::sort::SortExec;
+use crate::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
+use crate::physical_plan::ExecutionPlan;
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::JoinType;
+use datafusion_physical_expr::expressions::Column;
+
+/// Optimizer rule that pushes the bounds of the build side join keys of a
+/// [`HashJoinExec`] down to the [`ParquetExec`]s and [`FilterExec`]s on its
+/// probe side.
+///
+/// The join publishes the bounds once its build side has been collected (see
+/// [`HashJoinExec::with_runtime_filter`]), after which the probe side scans
+/// prune the row groups and pages, and skip the rows, that cannot match any
+/// build side row. This applies to joins in [`PartitionMode::CollectLeft`]
+/// that do not output unmatched probe side rows.
+///
+/// The filters are pushed through operators that neither change the values of
+/// the join key columns nor depend on the rows that are removed: filters,
+/// repartitions, sorts without a limit, column projections and the inputs of
+/// inner hash joins. A filter is attached to the scan it reaches, or else to
+/// the lowest [`FilterExec`] on its way.
+#[derive(Default)]
+pub struct RuntimeJoinFilterPushdown {}
+
+impl RuntimeJoinFilterPushdown {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PhysicalOptimizerRule for RuntimeJoinFilterPushdown {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        if !config.optimizer.enable_runtime_join_filters {
+            return Ok(plan);
+        }
+
+        plan.transform_down(&|plan| {
+            Ok(match push_down_runtime_filter(&plan)? {
+                Some(new_plan) => Transformed::Yes(new_plan),
+                None => Transformed::No(plan),
+            })
+        })
+    }
+
+    fn name(&self) -> &str {
+        "RuntimeJoinFilterPushdown"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+fn push_down_runtime_filter(
+    plan: &Arc<dyn ExecutionPlan>,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    let Some(join) = plan.as_any().downcast_ref::<HashJoinExec>() else {
+        return Ok(None);
+    };
+
+    // Unmatched probe side rows must not be removed if they are output, and
+    // every output partition must see the same build side
+    let filters_probe_side = matches!(
+        join.join_type(),
+        JoinType::Inner
+            | JoinType::Left
+            | JoinType::LeftSemi
+            | JoinType::LeftAnti
+            | JoinType::RightSemi
+    );
+    if !filters_probe_side
+        || join.partition_mode() != &PartitionMode::CollectLeft
+        || join.null_equals_null()
+        || join.runtime_filter().is_some()
+    {
+        return Ok(None);
+    }
+
+    let join_filter = Arc::new(RuntimeJoinFilter::new());
+    let columns = join
+        .on()
+        .iter()
+        .enumerate()
+        .map(|(key, (_, right))| (key, right.clone()))
+        .collect();
+    let Some(right) = attach_runtime_filter(join.right(), columns, &join_filter)? else {
+        return Ok(None);
+    };
+
+    let join = HashJoinExec::try_new(
+        join.left().clone(),
+        right,
+        join.on().to_vec(),
+        join.filter().cloned(),
+        join.join_type(),
+        *join.partition_mode(),
+        join.null_equals_null(),
+    )?
+    .with_runtime_filter(join_filter);
+    Ok(Some(Arc::new(join)))
+}
+
+/// Attaches a filter of the join key `columns` of `plan` to the operators
+/// below it, returning the new plan, or `None` if it could not be attached
+fn attach_runtime_filter(
+    plan: &Arc<dyn ExecutionPlan>,
+    columns: Vec<(usize, Column)>,
+    join_filter: &Arc<RuntimeJoinFilter>,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    if columns.is_empty() {
+        return Ok(None);
+    }
+
+    let plan_any = plan.as_any();
+    if let Some(parquet) = plan_any.downcast_ref::<ParquetExec>() {
+        let mut runtime_filters = parquet.runtime_filters().to_vec();
+        runtime_filters.push(RuntimeFilter::new(join_filter.clone(), columns));
+        let parquet = parquet.clone().with_runtime_filters(runtime_filters);
+        return Ok(Some(Arc::new(parquet)));
+    }
+
+    if let Some(filter) = plan_any.downcast_ref::<FilterExec>() {
+        if let Some(input) =
+            attach_runtime_filter(filter.input(), columns.clone(), join_filter)?
+        {
+            return plan.clone().with_new_children(vec![input]).map(Some);
+        }
+
+        let mut runtime_filters = filter.runtime_filters().to_vec();
+        runtime_filters.push(RuntimeFilter::new(join_filter.clone(), columns));
+        let filter =
+            FilterExec::try_new(filter.predicate().clone(), filter.input().clone())?
+                .with_runtime_filters(runtime_filters);
+        return Ok(Some(Arc::new(filter)));
+    }
+
+    if let Some(projection) = plan_any.downcast_ref::<ProjectionExec>() {
+        let columns = columns
+            .into_iter()
+            .filter_map(|(key, column)| {
+                let (expr, _) = &projection.expr()[column.index()];
+                let column = expr.as_any().downcast_ref::<Column>()?;
+                Some((key, column.clone()))
+            })
+            .collect();
+        return attach_to_child(plan, projection.input(), columns, join_filter);
+    }
+
+    if let Some(join) = plan_any.downcast_ref::<HashJoinExec>() {
+        if join.join_type() != &JoinType::Inner {
+            return Ok(None);
+        }
+
+        // The output of an inner join holds the columns of the left input
+        // followed by the columns of the right input
+        let left_len = join.left().schema().fields().len();
+        let (left_columns, right_columns): (Vec<_>, Vec<_>) =
+            columns.into_iter().partition(|(_, c)| c.index() < left_len);
+        let right_columns = right_columns
+            .into_iter()
+            .map(|(key, c)| (key, Column::new(c.name(), c.index() - left_len)))
+            .collect();
+
+        let left = attach_runtime_filter(join.left(), left_columns, join_filter)?;
+        let right = attach_runtime_filter(join.right(), right_columns, join_filter)?;
+        if left.is_none() && right.is_none() {
+            return Ok(None);
+        }
+        let children = vec![
+            left.unwrap_or_else(|| join.left().clone()),
+            right.unwrap_or_else(|| join.right().clone()),
+        ];
+        return plan.clone().with_new_children(children).map(Some);
+    }
+
+    let passes_rows_through = plan_any.is::<CoalesceBatchesExec>()
+        || plan_any.is::<CoalescePartitionsExec>()
+        || plan_any.is::<RepartitionExec>()
+        || plan_any
+            .downcast_ref::<SortExec>()
+            .map_or(false, |sort| sort.fetch().is_none())
+        || plan_any
+            .downcast_ref::<SortPreservingMergeExec>()
+            .map_or(false, |merge| merge.fetch().is_none());
+    if passes_rows_through {
+        let input = plan.children().swap_remove(0);
+        return attach_to_child(plan, &input, columns, join_filter);
+    }
+
+    Ok(None)
+}
+
+/// Attaches the filter below the single child `input` of `plan`
+fn attach_to_child(
+    plan: &Arc<dyn ExecutionPlan>,
+    input: &Arc<dyn ExecutionPlan>,
+    columns: Vec<(usize, Column)>,
+    join_filter: &Arc<RuntimeJoinFilter>,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    match attach_runtime_filter(input, columns, join_filter)? {
+        Some(input) => plan.clone().with_new_children(vec![input]).map(Some),
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_optimizer::test_utils::{
+        coalesce_batches_exec, filter_exec, global_limit_exec, memory_exec, parquet_exec,
+        repartition_exec,
+    };
+    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use datafusion_expr::Operator;
+    use datafusion_physical_expr::expressions::{col, lit, BinaryExpr};
+    use datafusion_physical_expr::PhysicalExpr;
+
+    fn schema(names: &[&str]) -> SchemaRef {
+        let fields = names
+            .iter()
+            .map(|name| Field::new(*name, DataType::Int32, true))
+            .collect::<Vec<_>>();
+        Arc::new(Schema::new(fields))
+    }
+
+    fn fact_scan() -> Arc<ParquetExec> {
+        parquet_exec(&schema(&["id", "d1", "d2"]))
+    }
+
+    fn collect_left_join(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        left_key: &str,
+        right_key: &str,
+        join_type: JoinType,
+    ) -> Arc<dyn ExecutionPlan> {
+        let on = vec![(
+            Column::new_with_schema(left_key, &left.schema()).unwrap(),
+            Column::new_with_schema(right_key, &right.schema()).unwrap(),
+        )];
+        Arc::new(
+            HashJoinExec::try_new(
+                left,
+                right,
+                on,
+                None,
+                &join_type,
+                PartitionMode::CollectLeft,
+                false,
+            )
+            .unwrap(),
+        )
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        RuntimeJoinFilterPushdown::new()
+            .optimize(plan, &ConfigOptions::new())
+            .unwrap()
+    }
+
+    fn as_join(plan: &Arc<dyn ExecutionPlan>) -> &HashJoinExec {
+        plan.as_any().downcast_ref::<HashJoinExec>().unwrap()
+    }
+
+    /// Returns the leaf below `plan`, following the last child
+    fn probe_leaf(plan: &Arc<dyn ExecutionPlan>) -> Arc<dyn ExecutionPlan> {
+        match plan.children().pop() {
+            Some(child) => probe_leaf(&child),
+            None => plan.clone(),
+        }
+    }
+
+    fn runtime_filters(plan: &Arc<dyn ExecutionPlan>) -> &[RuntimeFilter] {
+        let plan_any = plan.as_any();
+        match plan_any.downcast_ref::<ParquetExec>() {
+            Some(parquet) => parquet.runtime_filters(),
+            None => plan_any
+                .downcast_ref::<FilterExec>()
+                .unwrap()
+                .runtime_filters(),
+        }
+    }
+
+    fn positive(name: &str, schema: &Schema) -> Arc<dyn PhysicalExpr> {
+        Arc::new(BinaryExpr::new(
+            col(name, schema).unwrap(),
+            Operator::Gt,
+            lit(0),
+        ))
+    }
+
+    #[test]
+    fn push_down_to_parquet_scan() {
+        let fact = fact_scan();
+        let probe = coalesce_batches_exec(repartition_exec(filter_exec(
+            positive("id", &fact.schema()),
+            fact,
+        )));
+        let dim = memory_exec(&schema(&["d1_id", "name"]));
+        let plan = optimize(collect_left_join(
+            dim,
+            probe,
+            "d1_id",
+            "d1",
+            JoinType::Inner,
+        ));
+
+        let join_filter = as_join(&plan).runtime_filter().unwrap();
+        let scan = probe_leaf(&plan);
+        let filters = runtime_filters(&scan);
+        assert_eq!(filters.len(), 1);
+        assert!(Arc::ptr_eq(filters[0].join_filter(), join_filter));
+        assert_eq!(filters[0].columns(), &[(0, Column::new("d1", 1))]);
+
+        // the filter is not also applied by the filter above the scan
+        let filter = plan.children()[1].children()[0].children()[0].clone();
+        assert!(runtime_filters(&filter).is_empty());
+    }
+
+    #[test]
+    fn push_down_star_join() {
+        let dim1 = memory_exec(&schema(&["d1_id"]));
+        let dim2 = memory_exec(&schema(&["d2_id"]));
+        let inner = collect_left_join(dim1, fact_scan(), "d1_id", "d1", JoinType::Inner);
+        let plan = optimize(collect_left_join(
+            dim2,
+            inner,
+            "d2_id",
+            "d2",
+            JoinType::Inner,
+        ));
+
+        let scan = probe_leaf(&plan);
+        let columns: Vec<_> = runtime_filters(&scan)
+            .iter()
+            .map(|filter| filter.columns().to_vec())
+            .collect();
+        assert_eq!(
+            columns,
+            vec![
+                vec![(0, Column::new("d2", 2))],
+                vec![(0, Column::new("d1", 1))],
+            ]
+        );
+        assert!(as_join(&plan).runtime_filter().is_some());
+        assert!(as_join(&plan.children()[1]).runtime_filter().is_some());
+    }
+
+    #[test]
+    fn push_down_to_filter_above_limit() {
+        let fact = fact_scan();
+        let probe = filter_exec(positive("id", &fact.schema()), global_limit_exec(fact));
+        let dim = memory_exec(&schema(&["d1_id"]));
+        let plan = optimize(collect_left_join(
+            dim,
+            probe,
+            "d1_id",
+            "d1",
+            JoinType::Inner,
+        ));
+
+        let filter = plan.children()[1].clone();
+        assert_eq!(runtime_filters(&filter).len(), 1);
+        assert!(runtime_filters(&probe_leaf(&plan)).is_empty());
+    }
+
+    #[test]
+    fn no_push_down_for_outer_probe_side() {
+        for join_type in [JoinType::Right, JoinType::Full, JoinType::RightAnti] {
+            let dim = memory_exec(&schema(&["d1_id"]));
+            let join = collect_left_join(dim, fact_scan(), "d1_id", "d1", join_type);
+            let plan = optimize(join);
+
+            assert!(as_join(&plan).runtime_filter().is_none());
+            assert!(runtime_filters(&probe_leaf(&plan)).is_empty());
+        }
+    }
+
+    #[test]
+    fn no_push_down_without_target() {
+        let dim = memory_exec(&schema(&["d1_id"]));
+        let fact = memory_exec(&schema(&["id", "d1"]));
+        let plan = optimize(collect_left_join(dim, fact, "d1_id", "d1", JoinType::Inner));
+
+        assert!(as_join(&plan).runtime_filter().is_none());
+    }
+}
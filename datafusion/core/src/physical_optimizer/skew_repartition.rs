@@ -0,0 +1,565 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! SkewAwareRepartition optimizer that routes the rows holding heavy hitters
+//! of the hash partitioning keys of joins and aggregations around the hash
+//! partitioning, so that they do not all end up in a single partition
+
+use std::cell::Cell;
+use std::sync::Arc;
+
+use crate::config::ConfigOptions;
+use crate::error::Result;
+use crate::physical_optimizer::dist_enforcement::ensure_distribution;
+use crate::physical_optimizer::PhysicalOptimizerRule;
+use crate::physical_plan::aggregates::{AggregateExec, AggregateMode};
+use crate::physical_plan::joins::{HashJoinExec, PartitionMode};
+use crate::physical_plan::repartition::{HeavyHitters, RepartitionExec, SkewHandling};
+use crate::physical_plan::{ExecutionPlan, Partitioning};
+
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{JoinType, ScalarValue};
+use datafusion_physical_expr::expressions::Column;
+use datafusion_physical_expr::PhysicalExpr;
+
+/// Optimizer rule that detects heavy hitters of the keys of hash partitioned
+/// joins and aggregations from the most common values in the statistics of
+/// their inputs, and routes the rows holding them with a [`SkewHandling`].
+///
+/// For a [`HashJoinExec`] in [`PartitionMode::Partitioned`] that does not
+/// output unmatched rows of its build side, the probe side rows holding heavy
+/// hitters are spread over all the partitions, and the build side rows
+/// holding them are sent to every partition. The join then requires its
+/// inputs to be partitioned this way, see [`HashJoinExec::with_heavy_hitters`].
+///
+/// For an aggregation in [`AggregateMode::FinalPartitioned`], the partially
+/// aggregated rows holding heavy hitters are spread over all the partitions
+/// and combined by an aggregation in [`AggregateMode::PartialReduce`], before
+/// they are hash partitioned for the final aggregation:
+///
+/// ```text
+/// AggregateExec: mode=FinalPartitioned
+///   RepartitionExec: partitioning=Hash(..)
+///     AggregateExec: mode=PartialReduce
+///       RepartitionExec: partitioning=Hash(..), skew_handling=Spread(..)
+///         AggregateExec: mode=Partial
+/// ```
+///
+/// The output of a join whose inputs are not hash partitioned is not hash
+/// partitioned either, so repartitions are added above it where a hash
+/// partitioning is required.
+#[derive(Default)]
+pub struct SkewAwareRepartition {}
+
+impl SkewAwareRepartition {
+    #[allow(missing_docs)]
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PhysicalOptimizerRule for SkewAwareRepartition {
+    fn optimize(
+        &self,
+        plan: Arc<dyn ExecutionPlan>,
+        config: &ConfigOptions,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let options = &config.optimizer;
+        if !options.enable_skew_join && !options.enable_skew_aggregate {
+            return Ok(plan);
+        }
+
+        let target_partitions = config.execution.target_partitions;
+        let fraction = options.skew_heavy_hitter_fraction;
+        let rewritten = Cell::new(false);
+        plan.transform_up(&|plan| {
+            let new_plan = if options.enable_skew_join {
+                handle_join_skew(&plan, fraction)?
+            } else {
+                None
+            };
+            let new_plan = match new_plan {
+                None if options.enable_skew_aggregate => {
+                    handle_aggregate_skew(&plan, fraction)?
+                }
+                new_plan => new_plan,
+            };
+            if let Some(new_plan) = new_plan {
+                rewritten.set(true);
+                return Ok(Transformed::Yes(new_plan));
+            }
+
+            // The output of the joins with skew handling is not hash
+            // partitioned anymore
+            if rewritten.get() && !distribution_satisfied(&plan) {
+                ensure_distribution(plan, target_partitions)
+            } else {
+                Ok(Transformed::No(plan))
+            }
+        })
+    }
+
+    fn name(&self) -> &str {
+        "SkewAwareRepartition"
+    }
+
+    fn schema_check(&self) -> bool {
+        true
+    }
+}
+
+/// Returns true if the output partitioning of all the children of `plan`
+/// satisfies their required distribution
+fn distribution_satisfied(plan: &Arc<dyn ExecutionPlan>) -> bool {
+    plan.children()
+        .iter()
+        .zip(plan.required_input_distribution())
+        .all(|(child, required)| {
+            child
+                .output_partitioning()
+                .satisfy(required, || child.equivalence_properties())
+        })
+}
+
+/// Spreads the probe side rows and broadcasts the build side rows of the
+/// heavy hitters of the probe side join keys of a partitioned hash join
+fn handle_join_skew(
+    plan: &Arc<dyn ExecutionPlan>,
+    fraction: f64,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    let Some(join) = plan.as_any().downcast_ref::<HashJoinExec>() else {
+        return Ok(None);
+    };
+
+    // Build side rows are sent to several partitions, so the join must not
+    // output unmatched build side rows
+    let outputs_build_side = !matches!(
+        join.join_type(),
+        JoinType::Inner | JoinType::Right | JoinType::RightSemi | JoinType::RightAnti
+    );
+    if outputs_build_side || join.partition_mode() != &PartitionMode::Partitioned {
+        return Ok(None);
+    }
+
+    let (Some(left), Some(right)) = (
+        hash_repartition(join.left()),
+        hash_repartition(join.right()),
+    ) else {
+        return Ok(None);
+    };
+    let (left_columns, right_columns): (Vec<_>, Vec<_>) =
+        join.on().iter().cloned().unzip();
+    if !partitioned_by(left, &left_columns) || !partitioned_by(right, &right_columns) {
+        return Ok(None);
+    }
+
+    let left_schema = left.schema();
+    let num_partitions = right.partitioning().partition_count();
+    let heavy_hitters =
+        heavy_hitters(right.input(), &right_columns, num_partitions, fraction)
+            .into_iter()
+            .filter(|(index, values)| {
+                // the build side keys are compared to values of the probe side keys
+                let data_type = left_columns[*index].data_type(&left_schema).ok();
+                values
+                    .iter()
+                    .all(|value| data_type.as_ref() == Some(&value.get_datatype()))
+            })
+            .collect::<Vec<_>>();
+    if heavy_hitters.is_empty() {
+        return Ok(None);
+    }
+    let heavy_hitters = HeavyHitters::new(heavy_hitters);
+
+    let left = with_skew_handling(left, SkewHandling::Broadcast(heavy_hitters.clone()))?;
+    let right = with_skew_handling(right, SkewHandling::Spread(heavy_hitters.clone()))?;
+    let join = HashJoinExec::try_new(
+        left,
+        right,
+        join.on().to_vec(),
+        join.filter().cloned(),
+        join.join_type(),
+        PartitionMode::Partitioned,
+        join.null_equals_null(),
+    )?
+    .with_heavy_hitters(heavy_hitters)?;
+    Ok(Some(Arc::new(join)))
+}
+
+/// Spreads the partially aggregated rows of the heavy hitters of the grouping
+/// keys of a partitioned final aggregation, and combines them before they are
+/// hash partitioned
+fn handle_aggregate_skew(
+    plan: &Arc<dyn ExecutionPlan>,
+    fraction: f64,
+) -> Result<Option<Arc<dyn ExecutionPlan>>> {
+    let Some(aggregate) = plan.as_any().downcast_ref::<AggregateExec>() else {
+        return Ok(None);
+    };
+    if aggregate.mode() != &AggregateMode::FinalPartitioned
+        || !aggregate.group_expr().null_expr().is_empty()
+        || aggregate.order_by_expr().iter().any(|o| o.is_some())
+    {
+        return Ok(None);
+    }
+    let Some(repartition) = hash_repartition(aggregate.input()) else {
+        return Ok(None);
+    };
+    let Some(partial) = repartition.input().as_any().downcast_ref::<AggregateExec>()
+    else {
+        return Ok(None);
+    };
+    if partial.mode() != &AggregateMode::Partial {
+        return Ok(None);
+    }
+
+    // The statistics of the grouping keys are those of the columns of the
+    // input of the partial aggregation
+    let Partitioning::Hash(exprs, _) = repartition.partitioning() else {
+        return Ok(None);
+    };
+    let group_expr = partial.group_expr().expr();
+    let input_columns = exprs
+        .iter()
+        .map(|expr| {
+            let column = expr.as_any().downcast_ref::<Column>()?;
+            let (expr, _) = group_expr.get(column.index())?;
+            expr.as_any().downcast_ref::<Column>().cloned()
+        })
+        .collect::<Option<Vec<_>>>();
+    let Some(input_columns) = input_columns else {
+        return Ok(None);
+    };
+
+    let num_partitions = repartition.partitioning().partition_count();
+    let heavy_hitters =
+        heavy_hitters(partial.input(), &input_columns, num_partitions, fraction);
+    if heavy_hitters.is_empty() {
+        return Ok(None);
+    }
+    let heavy_hitters = HeavyHitters::new(heavy_hitters);
+
+    let spread = with_skew_handling(repartition, SkewHandling::Spread(heavy_hitters))?;
+    let reduce = AggregateExec::try_new(
+        AggregateMode::PartialReduce,
+        aggregate.group_expr().clone(),
+        aggregate.aggr_expr().to_vec(),
+        vec![None; aggregate.aggr_expr().len()],
+        aggregate.order_by_expr().to_vec(),
+        spread,
+        aggregate.input_schema(),
+    )?;
+    let repartition =
+        RepartitionExec::try_new(Arc::new(reduce), repartition.partitioning().clone())?;
+    plan.clone()
+        .with_new_children(vec![Arc::new(repartition)])
+        .map(Some)
+}
+
+/// Returns `plan` if it is a hash [`RepartitionExec`] without skew handling
+fn hash_repartition(plan: &Arc<dyn ExecutionPlan>) -> Option<&RepartitionExec> {
+    let repartition = plan.as_any().downcast_ref::<RepartitionExec>()?;
+    let is_hash = matches!(repartition.partitioning(), Partitioning::Hash(_, _));
+    (is_hash && !repartition.preserve_order() && repartition.skew_handling().is_none())
+        .then_some(repartition)
+}
+
+/// Returns true if `repartition` hash partitions its input on `columns`, in
+/// order
+fn partitioned_by(repartition: &RepartitionExec, columns: &[Column]) -> bool {
+    let Partitioning::Hash(exprs, _) = repartition.partitioning() else {
+        return false;
+    };
+    exprs.len() == columns.len()
+        && exprs
+            .iter()
+            .zip(columns)
+            .all(|(expr, column)| expr.as_any().downcast_ref::<Column>() == Some(column))
+}
+
+/// Returns the index in `columns` and the heavy hitters of each of the
+/// `columns` of `plan` that has heavy hitters, when the rows of `plan` are
+/// hash partitioned into `num_partitions` partitions.
+///
+/// A value is a heavy hitter when the number of rows holding it, taken from
+/// the most common values of the column statistics, exceeds `fraction` of the
+/// number of rows each partition receives when the rows are evenly
+/// distributed.
+fn heavy_hitters(
+    plan: &Arc<dyn ExecutionPlan>,
+    columns: &[Column],
+    num_partitions: usize,
+    fraction: f64,
+) -> Vec<(usize, Vec<ScalarValue>)> {
+    let statistics = plan.statistics();
+    let (Some(num_rows), Some(column_statistics)) = (
+        statistics.num_rows.get_value(),
+        statistics.column_statistics.as_ref(),
+    ) else {
+        return vec![];
+    };
+    let threshold = fraction * *num_rows as f64 / num_partitions.max(1) as f64;
+
+    columns
+        .iter()
+        .enumerate()
+        .filter_map(|(index, column)| {
+            let values = column_statistics
+                .get(column.index())?
+                .most_common_values
+                .as_ref()?
+                .iter()
+                .filter(|frequency| frequency.count as f64 > threshold)
+                .map(|frequency| frequency.value.clone())
+                .collect::<Vec<_>>();
+            (!values.is_empty()).then_some((index, values))
+        })
+        .collect()
+}
+
+fn with_skew_handling(
+    repartition: &RepartitionExec,
+    skew_handling: SkewHandling,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    let repartition = RepartitionExec::try_new(
+        repartition.input().clone(),
+        repartition.partitioning().clone(),
+    )?
+    .with_skew_handling(skew_handling)?;
+    Ok(Arc::new(repartition))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_optimizer::test_utils::get_plan_string;
+    use crate::physical_plan::aggregates::PhysicalGroupBy;
+    use crate::physical_plan::expressions::{col, Count};
+    use crate::test::exec::StatisticsExec;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_common::tree_node::VisitRecursion;
+    use datafusion_common::{ColumnStatistics, Precision, Statistics, ValueFrequency};
+    use datafusion_physical_expr::AggregateExpr;
+
+    fn schema(names: &[&str]) -> Schema {
+        let fields = names
+            .iter()
+            .map(|name| Field::new(*name, DataType::Int32, true))
+            .collect::<Vec<_>>();
+        Schema::new(fields)
+    }
+
+    /// A scan of 1000 rows, 600 of which hold 1 and 100 of which hold 2 in
+    /// the first column
+    fn skewed_exec(names: &[&str]) -> Arc<dyn ExecutionPlan> {
+        let frequency = |value: i32, count: usize| ValueFrequency {
+            value: ScalarValue::Int32(Some(value)),
+            count,
+        };
+        let mut column_statistics = vec![ColumnStatistics::default(); names.len()];
+        column_statistics[0].most_common_values =
+            Some(vec![frequency(1, 600), frequency(2, 100)]);
+        let statistics = Statistics {
+            num_rows: Precision::Inexact(1000),
+            total_byte_size: Precision::Absent,
+            column_statistics: Some(column_statistics),
+        };
+        Arc::new(StatisticsExec::new(statistics, schema(names)))
+    }
+
+    fn exec(names: &[&str]) -> Arc<dyn ExecutionPlan> {
+        Arc::new(StatisticsExec::new(Statistics::default(), schema(names)))
+    }
+
+    fn hash_repartition_exec(
+        input: Arc<dyn ExecutionPlan>,
+        name: &str,
+    ) -> Arc<dyn ExecutionPlan> {
+        let partitioning =
+            Partitioning::Hash(vec![col(name, &input.schema()).unwrap()], 4);
+        Arc::new(RepartitionExec::try_new(input, partitioning).unwrap())
+    }
+
+    fn partitioned_join(
+        left: Arc<dyn ExecutionPlan>,
+        right: Arc<dyn ExecutionPlan>,
+        left_key: &str,
+        right_key: &str,
+        join_type: JoinType,
+    ) -> Arc<dyn ExecutionPlan> {
+        let on = vec![(
+            Column::new_with_schema(left_key, &left.schema()).unwrap(),
+            Column::new_with_schema(right_key, &right.schema()).unwrap(),
+        )];
+        Arc::new(
+            HashJoinExec::try_new(
+                left,
+                right,
+                on,
+                None,
+                &join_type,
+                PartitionMode::Partitioned,
+                false,
+            )
+            .unwrap(),
+        )
+    }
+
+    fn optimize(plan: Arc<dyn ExecutionPlan>) -> Vec<String> {
+        let mut config = ConfigOptions::new();
+        config.execution.target_partitions = 4;
+        config.optimizer.enable_skew_join = true;
+        config.optimizer.enable_skew_aggregate = true;
+        let plan = SkewAwareRepartition::new().optimize(plan, &config).unwrap();
+
+        // the inputs of every operator are distributed as it requires
+        plan.apply(&mut |plan| {
+            assert!(
+                distribution_satisfied(plan),
+                "{}",
+                get_plan_string(plan).join("\n")
+            );
+            Ok(VisitRecursion::Continue)
+        })
+        .unwrap();
+        get_plan_string(&plan)
+    }
+
+    #[test]
+    fn skewed_join() {
+        let join = partitioned_join(
+            hash_repartition_exec(exec(&["a", "b"]), "a"),
+            hash_repartition_exec(skewed_exec(&["c", "d"]), "c"),
+            "a",
+            "c",
+            JoinType::Inner,
+        );
+        let plan = partitioned_join(
+            join,
+            hash_repartition_exec(exec(&["e", "f"]), "e"),
+            "a",
+            "e",
+            JoinType::Inner,
+        );
+
+        // only 1 is a heavy hitter, the outer join is hash partitioned again
+        let expected = vec![
+            "HashJoinExec: mode=Partitioned, join_type=Inner, on=[(a@0, e@0)]",
+            "  RepartitionExec: partitioning=Hash([a@0], 4), input_partitions=4",
+            "    HashJoinExec: mode=Partitioned, join_type=Inner, on=[(a@0, c@0)], heavy_hitters={0: [1]}",
+            "      RepartitionExec: partitioning=Hash([a@0], 4), input_partitions=2, skew_handling=Broadcast({0: [1]})",
+            "        StatisticsExec: col_count=2, row_count=Absent",
+            "      RepartitionExec: partitioning=Hash([c@0], 4), input_partitions=2, skew_handling=Spread({0: [1]})",
+            "        StatisticsExec: col_count=2, row_count=Inexact(1000)",
+            "  RepartitionExec: partitioning=Hash([e@0], 4), input_partitions=2",
+            "    StatisticsExec: col_count=2, row_count=Absent",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+
+    #[test]
+    fn skewed_join_not_handled() {
+        // unmatched build side rows are output
+        let plan = partitioned_join(
+            hash_repartition_exec(exec(&["a", "b"]), "a"),
+            hash_repartition_exec(skewed_exec(&["c", "d"]), "c"),
+            "a",
+            "c",
+            JoinType::Left,
+        );
+        let expected = get_plan_string(&plan);
+        assert_eq!(optimize(plan), expected);
+
+        // the heavy hitters are on the build side only
+        let plan = partitioned_join(
+            hash_repartition_exec(skewed_exec(&["a", "b"]), "a"),
+            hash_repartition_exec(exec(&["c", "d"]), "c"),
+            "a",
+            "c",
+            JoinType::Inner,
+        );
+        let expected = get_plan_string(&plan);
+        assert_eq!(optimize(plan), expected);
+
+        // skew handling is disabled by default
+        let plan = partitioned_join(
+            hash_repartition_exec(exec(&["a", "b"]), "a"),
+            hash_repartition_exec(skewed_exec(&["c", "d"]), "c"),
+            "a",
+            "c",
+            JoinType::Inner,
+        );
+        let expected = get_plan_string(&plan);
+        let plan = SkewAwareRepartition::new()
+            .optimize(plan, &ConfigOptions::new())
+            .unwrap();
+        assert_eq!(get_plan_string(&plan), expected);
+    }
+
+    #[test]
+    fn skewed_aggregate() {
+        let input = skewed_exec(&["a", "b"]);
+        let input_schema = input.schema();
+        let aggr_expr: Vec<Arc<dyn AggregateExpr>> = vec![Arc::new(Count::new(
+            col("b", &input_schema).unwrap(),
+            "COUNT(b)",
+            DataType::Int64,
+        ))];
+        let group_by = PhysicalGroupBy::new_single(vec![(
+            col("a", &input_schema).unwrap(),
+            "a".into(),
+        )]);
+        let partial = Arc::new(
+            AggregateExec::try_new(
+                AggregateMode::Partial,
+                group_by,
+                aggr_expr.clone(),
+                vec![None],
+                vec![None],
+                input,
+                input_schema.clone(),
+            )
+            .unwrap(),
+        );
+        let group_by = PhysicalGroupBy::new_single(vec![(
+            col("a", &partial.schema()).unwrap(),
+            "a".into(),
+        )]);
+        let plan = Arc::new(
+            AggregateExec::try_new(
+                AggregateMode::FinalPartitioned,
+                group_by,
+                aggr_expr,
+                vec![None],
+                vec![None],
+                hash_repartition_exec(partial, "a"),
+                input_schema,
+            )
+            .unwrap(),
+        );
+
+        let expected = vec![
+            "AggregateExec: mode=FinalPartitioned, gby=[a@0 as a], aggr=[COUNT(b)]",
+            "  RepartitionExec: partitioning=Hash([a@0], 4), input_partitions=4",
+            "    AggregateExec: mode=PartialReduce, gby=[a@0 as a], aggr=[COUNT(b)]",
+            "      RepartitionExec: partitioning=Hash([a@0], 4), input_partitions=2, skew_handling=Spread({0: [1]})",
+            "        AggregateExec: mode=Partial, gby=[a@0 as a], aggr=[COUNT(b)]",
+            "          StatisticsExec: col_count=2, row_count=Inexact(1000)",
+        ];
+        assert_eq!(optimize(plan), expected);
+    }
+}
@@ -19,7 +19,9 @@
 //! to local sorting requirements and does the following:
 //! - Adds a [SortExec] when a requirement is not met,
 //! - Removes an already-existing [SortExec] if it is possible to prove
-//!   that this sort is unnecessary
+//!   that this sort is unnecessary,
+//! - Replaces a [SortExec] whose input is already sorted on a prefix of the
+//!   sort expressions with a [PartialSortExec]
 //! The rule can work on valid *and* invalid physical plans with respect to
 //! sorting requirements, but always produces a valid physical plan in this sense.
 //!
@@ -33,6 +35,8 @@
 //!
 //! in the physical plan. The first sort is unnecessary since its result is overwritten
 //! by another [`SortExec`]. Therefore, this rule removes it from the physical plan.
+//!
+//! [PartialSortExec]: crate::physical_plan::sorts::partial_sort::PartialSortExec
 
 use crate::config::ConfigOptions;
 use crate::error::Result;
@@ -47,12 +51,17 @@ use crate::physical_optimizer::utils::{
 };
 use crate::physical_optimizer::PhysicalOptimizerRule;
 use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
+use crate::physical_plan::repartition::RepartitionExec;
+use crate::physical_plan::sorts::ordered_concat::OrderedConcatExec;
+use crate::physical_plan::sorts::partial_sort::PartialSortExec;
 use crate::physical_plan::sorts::sort::SortExec;
 use crate::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
 use crate::physical_plan::windows::{
     BoundedWindowAggExec, PartitionSearchMode, WindowAggExec,
 };
-use crate::physical_plan::{with_new_children_if_necessary, Distribution, ExecutionPlan};
+use crate::physical_plan::{
+    with_new_children_if_necessary, Distribution, ExecutionPlan, Partitioning,
+};
 use arrow::datatypes::SchemaRef;
 use datafusion_common::tree_node::{Transformed, TreeNode, VisitRecursion};
 use datafusion_common::utils::{get_at_indices, longest_consecutive_prefix};
@@ -363,8 +372,9 @@ impl PhysicalOptimizerRule for EnforceSorting {
         let new_plan = if config.optimizer.repartition_sorts {
             let plan_with_coalesce_partitions =
                 PlanWithCorrespondingCoalescePartitions::new(adjusted.plan);
-            let parallel =
-                plan_with_coalesce_partitions.transform_up(&parallelize_sorts)?;
+            let by_range = config.optimizer.repartition_sorts_by_range;
+            let parallel = plan_with_coalesce_partitions
+                .transform_up(&|plan| parallelize_sorts(plan, by_range))?;
             parallel.plan
         } else {
             adjusted.plan
@@ -384,7 +394,7 @@ impl PhysicalOptimizerRule for EnforceSorting {
         // missed by the bottom-up traversal:
         let sort_pushdown = SortPushDown::init(updated_plan.plan);
         let adjusted = sort_pushdown.transform_down(&pushdown_sorts)?;
-        Ok(adjusted.plan)
+        adjusted.plan.transform_up(&replace_with_partial_sort)
     }
 
     fn name(&self) -> &str {
@@ -410,8 +420,18 @@ impl PhysicalOptimizerRule for EnforceSorting {
 /// ```
 /// by following connections from [`CoalescePartitionsExec`]s to [`SortExec`]s.
 /// By performing sorting in parallel, we can increase performance in some scenarios.
+///
+/// When `by_range` is set and the sort has no fetch, the sorted partitions are
+/// range partitioned beforehand, so that they can simply be concatenated:
+/// ```text
+///      "OrderedConcatExec: \[a@0 ASC\]",
+///      "  SortExec: expr=\[a@0 ASC\]",
+///      "    RepartitionExec: partitioning=Range(\[a@0 ASC\], 8), input_partitions=8",
+///      "      RepartitionExec: partitioning=RoundRobinBatch(8), input_partitions=1",
+/// ```
 fn parallelize_sorts(
     requirements: PlanWithCorrespondingCoalescePartitions,
+    by_range: bool,
 ) -> Result<Transformed<PlanWithCorrespondingCoalescePartitions>> {
     let plan = requirements.plan;
     let mut coalesce_onwards = requirements.coalesce_onwards;
@@ -435,11 +455,26 @@ fn parallelize_sorts(
         let mut prev_layer = plan.clone();
         update_child_to_remove_coalesce(&mut prev_layer, &mut coalesce_onwards[0])?;
         let (sort_exprs, fetch) = get_sort_exprs(&plan)?;
-        add_sort_above(&mut prev_layer, sort_exprs.to_vec(), fetch)?;
-        let spm = SortPreservingMergeExec::new(sort_exprs.to_vec(), prev_layer)
-            .with_fetch(fetch);
+        let partition_count = prev_layer.output_partitioning().partition_count();
+        let plan: Arc<dyn ExecutionPlan> = if by_range
+            && fetch.is_none()
+            && partition_count > 1
+        {
+            // Range partition the input so that the sorted partitions
+            // can be concatenated instead of merged on a single core
+            let partitioning = Partitioning::Range(sort_exprs.to_vec(), partition_count);
+            prev_layer = Arc::new(RepartitionExec::try_new(prev_layer, partitioning)?);
+            add_sort_above(&mut prev_layer, sort_exprs.to_vec(), None)?;
+            Arc::new(OrderedConcatExec::new(sort_exprs.to_vec(), prev_layer))
+        } else {
+            add_sort_above(&mut prev_layer, sort_exprs.to_vec(), fetch)?;
+            Arc::new(
+                SortPreservingMergeExec::new(sort_exprs.to_vec(), prev_layer)
+                    .with_fetch(fetch),
+            )
+        };
         return Ok(Transformed::Yes(PlanWithCorrespondingCoalescePartitions {
-            plan: Arc::new(spm),
+            plan,
             coalesce_onwards: vec![None],
         }));
     } else if is_coalesce_partitions(&plan) {
@@ -963,6 +998,43 @@ fn check_alignment(
     })
 }
 
+/// Replaces a [`SortExec`] whose input is already sorted on a prefix of its
+/// sort expressions with a [`PartialSortExec`], which only buffers the rows
+/// sharing the same prefix values. Unlike a full sort, this does not break
+/// the pipeline, so it can also run on unbounded inputs.
+fn replace_with_partial_sort(
+    plan: Arc<dyn ExecutionPlan>,
+) -> Result<Transformed<Arc<dyn ExecutionPlan>>> {
+    let Some(sort_exec) = plan.as_any().downcast_ref::<SortExec>() else {
+        return Ok(Transformed::No(plan));
+    };
+    let child = sort_exec.input();
+
+    // Find the longest prefix of the sort expressions satisfied by the input,
+    // taking its (ordering) equivalence properties into account
+    let sort_exprs = sort_exec.expr();
+    let common_prefix_length = (1..sort_exprs.len())
+        .take_while(|length| {
+            ordering_satisfy(
+                child.output_ordering(),
+                Some(&sort_exprs[..*length]),
+                || child.equivalence_properties(),
+                || child.ordering_equivalence_properties(),
+            )
+        })
+        .last()
+        .unwrap_or(0);
+    if common_prefix_length == 0 {
+        return Ok(Transformed::No(plan));
+    }
+
+    let partial_sort =
+        PartialSortExec::new(sort_exprs.to_vec(), child.clone(), common_prefix_length)
+            .with_fetch(sort_exec.fetch())
+            .with_preserve_partitioning(sort_exec.preserve_partitioning());
+    Ok(Transformed::Yes(Arc::new(partial_sort)))
+}
+
 // Get output (un)boundedness information for the given `plan`.
 pub(crate) fn unbounded_output(plan: &Arc<dyn ExecutionPlan>) -> bool {
     let result = if plan.children().is_empty() {
@@ -1928,7 +2000,7 @@ mod tests {
 
         let expected_optimized = ["SortPreservingMergeExec: [nullable_col@0 ASC,non_nullable_col@1 ASC]",
             "  UnionExec",
-            "    SortExec: expr=[nullable_col@0 ASC,non_nullable_col@1 ASC]",
+            "    PartialSortExec: expr=[nullable_col@0 ASC,non_nullable_col@1 ASC], common_prefix_length=1",
             "      ParquetExec: file_groups={1 group: [[x]]}, projection=[nullable_col, non_nullable_col], output_ordering=[nullable_col@0 ASC]",
             "    SortExec: expr=[nullable_col@0 ASC,non_nullable_col@1 ASC]",
             "      ParquetExec: file_groups={1 group: [[x]]}, projection=[nullable_col, non_nullable_col]"];
@@ -2010,7 +2082,7 @@ mod tests {
             "  UnionExec",
             "    SortExec: expr=[nullable_col@0 ASC,non_nullable_col@1 ASC]",
             "      ParquetExec: file_groups={1 group: [[x]]}, projection=[nullable_col, non_nullable_col]",
-            "    SortExec: expr=[nullable_col@0 ASC,non_nullable_col@1 ASC]",
+            "    PartialSortExec: expr=[nullable_col@0 ASC,non_nullable_col@1 ASC], common_prefix_length=1",
             "      ParquetExec: file_groups={1 group: [[x]]}, projection=[nullable_col, non_nullable_col], output_ordering=[nullable_col@0 ASC]",
             "    SortExec: expr=[nullable_col@0 ASC,non_nullable_col@1 ASC]",
             "      ParquetExec: file_groups={1 group: [[x]]}, projection=[nullable_col, non_nullable_col]"];
@@ -2605,6 +2677,44 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_parallelize_sort_by_range() -> Result<()> {
+        let schema = create_test_schema()?;
+        let config = SessionConfig::new()
+            .with_repartition_sorts(true)
+            .with_repartition_sorts_by_range(true);
+        let session_ctx = SessionContext::with_config(config);
+        let state = session_ctx.state();
+
+        let coalesce = Arc::new(CoalescePartitionsExec::new(repartition_exec(
+            parquet_exec(&schema),
+        )));
+        let sort_exprs = vec![sort_expr("nullable_col", &schema)];
+        let physical_plan = sort_exec(sort_exprs.clone(), coalesce.clone());
+
+        let optimized =
+            EnforceSorting::new().optimize(physical_plan, state.config_options())?;
+        let expected = ["OrderedConcatExec: [nullable_col@0 ASC]",
+            "  SortExec: expr=[nullable_col@0 ASC]",
+            "    RepartitionExec: partitioning=Range([nullable_col@0 ASC], 10), input_partitions=10",
+            "      RepartitionExec: partitioning=RoundRobinBatch(10), input_partitions=1",
+            "        ParquetExec: file_groups={1 group: [[x]]}, projection=[nullable_col, non_nullable_col]"];
+        assert_eq!(get_plan_string(&optimized), expected);
+
+        // sorts with a fetch are still merged
+        let physical_plan =
+            Arc::new(SortExec::new(sort_exprs, coalesce).with_fetch(Some(10))) as _;
+        let optimized =
+            EnforceSorting::new().optimize(physical_plan, state.config_options())?;
+        let expected = ["SortPreservingMergeExec: [nullable_col@0 ASC], fetch=10",
+            "  SortExec: fetch=10, expr=[nullable_col@0 ASC]",
+            "    RepartitionExec: partitioning=RoundRobinBatch(10), input_partitions=1",
+            "      ParquetExec: file_groups={1 group: [[x]]}, projection=[nullable_col, non_nullable_col]"];
+        assert_eq!(get_plan_string(&optimized), expected);
+
+        Ok(())
+    }
+
     #[tokio::test]
     // With new change in SortEnforcement EnforceSorting->EnforceDistribution->EnforceSorting
     // should produce same result with EnforceDistribution+EnforceSorting
@@ -2764,4 +2874,58 @@ mod tests {
         assert_optimized!(expected_input, expected_optimized, physical_plan, false);
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_partial_sort_unbounded() -> Result<()> {
+        let schema = create_test_schema3()?;
+        let source = csv_exec_sorted(&schema, vec![sort_expr("a", &schema)], true);
+        let physical_plan = sort_exec(
+            vec![sort_expr("a", &schema), sort_expr("c", &schema)],
+            source,
+        );
+
+        let expected_input = ["SortExec: expr=[a@0 ASC,c@2 ASC]",
+            "  CsvExec: file_groups={1 group: [[x]]}, projection=[a, b, c, d, e], infinite_source=true, output_ordering=[a@0 ASC], has_header=false"];
+        let expected_optimized = ["PartialSortExec: expr=[a@0 ASC,c@2 ASC], common_prefix_length=1",
+            "  CsvExec: file_groups={1 group: [[x]]}, projection=[a, b, c, d, e], infinite_source=true, output_ordering=[a@0 ASC], has_header=false"];
+        assert_optimized!(expected_input, expected_optimized, physical_plan, true);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_partial_sort_bounded() -> Result<()> {
+        let schema = create_test_schema3()?;
+        let source = csv_exec_sorted(&schema, vec![sort_expr("a", &schema)], false);
+        let physical_plan = sort_exec(
+            vec![sort_expr("a", &schema), sort_expr("c", &schema)],
+            source,
+        );
+
+        // Bounded inputs are partially sorted as well
+        let expected_input = ["SortExec: expr=[a@0 ASC,c@2 ASC]",
+            "  CsvExec: file_groups={1 group: [[x]]}, projection=[a, b, c, d, e], output_ordering=[a@0 ASC], has_header=false"];
+        let expected_optimized = ["PartialSortExec: expr=[a@0 ASC,c@2 ASC], common_prefix_length=1",
+            "  CsvExec: file_groups={1 group: [[x]]}, projection=[a, b, c, d, e], output_ordering=[a@0 ASC], has_header=false"];
+        assert_optimized!(expected_input, expected_optimized, physical_plan, true);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_partial_sort_unbounded_no_common_prefix() -> Result<()> {
+        let schema = create_test_schema3()?;
+        let source = csv_exec_sorted(&schema, vec![sort_expr("a", &schema)], true);
+        let physical_plan = sort_exec(
+            vec![sort_expr("c", &schema), sort_expr("a", &schema)],
+            source,
+        );
+
+        // The input is not sorted on a prefix, the sort is kept and the plan
+        // is rejected later by the pipeline checker
+        let expected_input = ["SortExec: expr=[c@2 ASC,a@0 ASC]",
+            "  CsvExec: file_groups={1 group: [[x]]}, projection=[a, b, c, d, e], infinite_source=true, output_ordering=[a@0 ASC], has_header=false"];
+        let expected_optimized = ["SortExec: expr=[c@2 ASC,a@0 ASC]",
+            "  CsvExec: file_groups={1 group: [[x]]}, projection=[a, b, c, d, e], infinite_source=true, output_ordering=[a@0 ASC], has_header=false"];
+        assert_optimized!(expected_input, expected_optimized, physical_plan, true);
+        Ok(())
+    }
 }
@@ -279,6 +279,8 @@ pub fn parquet_exec(schema: &SchemaRef) -> Arc<ParquetExec> {
             table_partition_cols: vec![],
             output_ordering: vec![],
             infinite_source: false,
+            column_renames: vec![],
+            nested_projection: vec![],
         },
         None,
         None,
@@ -303,6 +305,8 @@ pub fn parquet_exec_sorted(
             table_partition_cols: vec![],
             output_ordering: vec![sort_exprs],
             infinite_source: false,
+            column_renames: vec![],
+            nested_projection: vec![],
         },
         None,
         None,
@@ -0,0 +1,715 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::physical_plan::aggregates::group_values::GroupValues;
+use ahash::RandomState;
+use arrow::array::BooleanBufferBuilder;
+use arrow::buffer::{Buffer, NullBuffer, OffsetBuffer, ScalarBuffer};
+use arrow::compute::cast;
+use arrow::datatypes::{
+    ArrowDictionaryKeyType, ArrowNativeType, ByteArrayType, GenericBinaryType,
+    GenericStringType, Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type, UInt32Type,
+    UInt64Type, UInt8Type,
+};
+use arrow_array::cast::AsArray;
+use arrow_array::{
+    downcast_primitive, Array, ArrayRef, ArrowNativeTypeOp, ArrowPrimitiveType,
+    GenericByteArray, PrimitiveArray,
+};
+use arrow_schema::{DataType, Schema, SchemaRef};
+use datafusion_common::{internal_err, Result};
+use datafusion_execution::memory_pool::proxy::{RawTableAllocExt, VecAllocExt};
+use datafusion_physical_expr::hash_utils::create_hashes;
+use datafusion_physical_expr::EmitTo;
+use hashbrown::raw::RawTable;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// A [`GroupValues`] storing each group column in its own typed buffer
+///
+/// Group keys made of several primitive, string or binary columns, or of
+/// dictionary encoded columns with such values, are compared and stored
+/// column by column. This avoids converting every input row to the
+/// [`Row`] format, as done by [`GroupValuesRows`], and the output columns
+/// are built directly from the buffers.
+///
+/// [`Row`]: arrow::row::Row
+/// [`GroupValuesRows`]: super::GroupValuesRows
+pub struct GroupValuesColumn {
+    /// The schema of the group columns
+    schema: SchemaRef,
+
+    /// Logically maps group values to a group_index in
+    /// [`Self::group_values`] and in each accumulator
+    ///
+    /// keys: u64 hashes of the GroupValue
+    /// values: (hash, group_index)
+    map: RawTable<(u64, usize)>,
+
+    /// The size of `map` in bytes
+    map_size: usize,
+
+    /// The group values, one [`GroupColumn`] per group column.
+    /// `group_values[c]` holds the value of column `c` for each group index
+    group_values: Vec<Box<dyn GroupColumn>>,
+
+    /// buffer to be reused to store hashes
+    hashes_buffer: Vec<u64>,
+
+    /// Random state for creating hashes
+    random_state: RandomState,
+}
+
+impl GroupValuesColumn {
+    /// Returns true if [`GroupValuesColumn`] supports group columns of
+    /// `schema`
+    pub fn supported(schema: &Schema) -> bool {
+        !schema.fields().is_empty()
+            && schema
+                .fields()
+                .iter()
+                .all(|f| new_group_column(f.data_type()).is_some())
+    }
+
+    /// Creates a new [`GroupValuesColumn`] for group columns of `schema`
+    pub fn try_new(schema: SchemaRef) -> Result<Self> {
+        let group_values = new_group_columns(&schema)?;
+        Ok(Self {
+            schema,
+            map: RawTable::with_capacity(0),
+            map_size: 0,
+            group_values,
+            hashes_buffer: Default::default(),
+            random_state: Default::default(),
+        })
+    }
+}
+
+/// Creates an empty [`GroupColumn`] for each field of `schema`
+fn new_group_columns(schema: &Schema) -> Result<Vec<Box<dyn GroupColumn>>> {
+    schema
+        .fields()
+        .iter()
+        .map(|f| match new_group_column(f.data_type()) {
+            Some(column) => Ok(column),
+            None => internal_err!("Unsupported group column type {}", f.data_type()),
+        })
+        .collect()
+}
+
+impl GroupValues for GroupValuesColumn {
+    fn intern(&mut self, cols: &[ArrayRef], groups: &mut Vec<usize>) -> Result<()> {
+        let n_rows = cols[0].len();
+
+        // tracks to which group each of the input rows belongs
+        groups.clear();
+
+        // 1.1 Calculate the group keys for the group values
+        let batch_hashes = &mut self.hashes_buffer;
+        batch_hashes.clear();
+        batch_hashes.resize(n_rows, 0);
+        create_hashes(cols, &self.random_state, batch_hashes)?;
+
+        for (row, &hash) in batch_hashes.iter().enumerate() {
+            let group_values = &self.group_values;
+            let entry = self.map.get_mut(hash, |(_hash, group_idx)| {
+                // verify that the group with this hash has the same key
+                // values as the input row, column by column
+                group_values.iter().zip(cols).all(|(group_column, col)| {
+                    group_column.equal_to(*group_idx, col, row)
+                })
+            });
+
+            let group_idx = match entry {
+                // Existing group_index for this group value
+                Some((_hash, group_idx)) => *group_idx,
+                //  1.2 Need to create new entry for the group
+                None => {
+                    let group_idx = self.group_values[0].len();
+                    for (group_column, col) in self.group_values.iter_mut().zip(cols) {
+                        group_column.append_val(col, row);
+                    }
+
+                    // for hasher function, use precomputed hash value
+                    self.map.insert_accounted(
+                        (hash, group_idx),
+                        |(hash, _group_index)| *hash,
+                        &mut self.map_size,
+                    );
+                    group_idx
+                }
+            };
+            groups.push(group_idx);
+        }
+
+        Ok(())
+    }
+
+    fn size(&self) -> usize {
+        let group_values_size: usize = self.group_values.iter().map(|v| v.size()).sum();
+        group_values_size + self.map_size + self.hashes_buffer.allocated_size()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn len(&self) -> usize {
+        self.group_values.first().map(|v| v.len()).unwrap_or(0)
+    }
+
+    fn emit(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        match emit_to {
+            EmitTo::All => {
+                self.map.clear();
+                self.map_size = self.map.allocation_info().1.size();
+                let group_values = std::mem::replace(
+                    &mut self.group_values,
+                    new_group_columns(&self.schema)?,
+                );
+                group_values.into_iter().map(|v| v.build()).collect()
+            }
+            EmitTo::First(n) => {
+                let output = self
+                    .group_values
+                    .iter_mut()
+                    .map(|v| v.take_n(n))
+                    .collect::<Result<Vec<_>>>()?;
+
+                // SAFETY: self.map outlives iterator and is not modified concurrently
+                unsafe {
+                    for bucket in self.map.iter() {
+                        // Decrement group index by n
+                        match bucket.as_ref().1.checked_sub(n) {
+                            // Group index was >= n, shift value down
+                            Some(sub) => bucket.as_mut().1 = sub,
+                            // Group index was < n, so remove from table
+                            None => self.map.erase(bucket),
+                        }
+                    }
+                }
+                Ok(output)
+            }
+        }
+    }
+}
+
+/// Stores the values of one group column, one per group index
+trait GroupColumn: Send {
+    /// Returns true if the value of group `lhs_row` equals the value at
+    /// `rhs_row` of `array`. Null values are equal to each other.
+    fn equal_to(&self, lhs_row: usize, array: &ArrayRef, rhs_row: usize) -> bool;
+
+    /// Returns true if the value of group `row` is null
+    fn is_null(&self, row: usize) -> bool;
+
+    /// Appends the value at `row` of `array` as the value of a new group
+    fn append_val(&mut self, array: &ArrayRef, row: usize);
+
+    /// Appends a null value as the value of a new group
+    fn append_null(&mut self);
+
+    /// The number of groups stored
+    fn len(&self) -> usize;
+
+    /// Returns the number of bytes used by this [`GroupColumn`]
+    fn size(&self) -> usize;
+
+    /// Builds the values of all the groups
+    fn build(self: Box<Self>) -> Result<ArrayRef>;
+
+    /// Builds the values of the first `n` groups and removes them
+    fn take_n(&mut self, n: usize) -> Result<ArrayRef>;
+}
+
+/// Creates an empty [`GroupColumn`] for values of `data_type`, or `None` if
+/// it is not supported
+fn new_group_column(data_type: &DataType) -> Option<Box<dyn GroupColumn>> {
+    macro_rules! downcast_helper {
+        ($t:ty, $d:ident) => {
+            return Some(Box::new(PrimitiveGroupColumn::<$t>::new($d.clone())))
+        };
+    }
+
+    downcast_primitive! {
+        data_type => (downcast_helper, data_type),
+        _ => {}
+    }
+
+    match data_type {
+        DataType::Utf8 => {
+            Some(Box::new(ByteGroupColumn::<GenericStringType<i32>>::new()))
+        }
+        DataType::LargeUtf8 => {
+            Some(Box::new(ByteGroupColumn::<GenericStringType<i64>>::new()))
+        }
+        DataType::Binary => {
+            Some(Box::new(ByteGroupColumn::<GenericBinaryType<i32>>::new()))
+        }
+        DataType::LargeBinary => {
+            Some(Box::new(ByteGroupColumn::<GenericBinaryType<i64>>::new()))
+        }
+        DataType::Dictionary(key_type, value_type) => {
+            if matches!(value_type.as_ref(), DataType::Dictionary(_, _)) {
+                return None;
+            }
+            let values = new_group_column(value_type)?;
+            let data_type = data_type.clone();
+            match key_type.as_ref() {
+                DataType::Int8 => Some(DictionaryGroupColumn::<Int8Type>::new_boxed(
+                    data_type, values,
+                )),
+                DataType::Int16 => Some(DictionaryGroupColumn::<Int16Type>::new_boxed(
+                    data_type, values,
+                )),
+                DataType::Int32 => Some(DictionaryGroupColumn::<Int32Type>::new_boxed(
+                    data_type, values,
+                )),
+                DataType::Int64 => Some(DictionaryGroupColumn::<Int64Type>::new_boxed(
+                    data_type, values,
+                )),
+                DataType::UInt8 => Some(DictionaryGroupColumn::<UInt8Type>::new_boxed(
+                    data_type, values,
+                )),
+                DataType::UInt16 => Some(DictionaryGroupColumn::<UInt16Type>::new_boxed(
+                    data_type, values,
+                )),
+                DataType::UInt32 => Some(DictionaryGroupColumn::<UInt32Type>::new_boxed(
+                    data_type, values,
+                )),
+                DataType::UInt64 => Some(DictionaryGroupColumn::<UInt64Type>::new_boxed(
+                    data_type, values,
+                )),
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+/// Builds a [`NullBuffer`] from the validity `nulls`, or `None` if there are
+/// no nulls
+fn build_nulls(mut nulls: BooleanBufferBuilder) -> Option<NullBuffer> {
+    Some(NullBuffer::new(nulls.finish())).filter(|nulls| nulls.null_count() > 0)
+}
+
+/// Splits the validity of the first `n` values off `nulls`
+fn take_n_nulls(nulls: &mut BooleanBufferBuilder, n: usize) -> Option<NullBuffer> {
+    let buffer = nulls.finish();
+    let mut remaining = BooleanBufferBuilder::new(buffer.len() - n);
+    remaining.append_packed_range(n..buffer.len(), buffer.values());
+    *nulls = remaining;
+    Some(NullBuffer::new(buffer.slice(0, n))).filter(|nulls| nulls.null_count() > 0)
+}
+
+/// A [`GroupColumn`] of primitive values
+struct PrimitiveGroupColumn<T: ArrowPrimitiveType> {
+    /// The data type of the output array
+    data_type: DataType,
+    /// The value of each group, the default value for null values
+    values: Vec<T::Native>,
+    /// The validity of each group
+    nulls: BooleanBufferBuilder,
+}
+
+impl<T: ArrowPrimitiveType> PrimitiveGroupColumn<T> {
+    fn new(data_type: DataType) -> Self {
+        Self {
+            data_type,
+            values: vec![],
+            nulls: BooleanBufferBuilder::new(0),
+        }
+    }
+
+    fn build_array(&self, values: Vec<T::Native>, nulls: Option<NullBuffer>) -> ArrayRef {
+        let array = PrimitiveArray::<T>::new(ScalarBuffer::from(values), nulls);
+        Arc::new(array.with_data_type(self.data_type.clone()))
+    }
+}
+
+impl<T: ArrowPrimitiveType> GroupColumn for PrimitiveGroupColumn<T> {
+    fn equal_to(&self, lhs_row: usize, array: &ArrayRef, rhs_row: usize) -> bool {
+        let array = array.as_primitive::<T>();
+        match (self.is_null(lhs_row), array.is_null(rhs_row)) {
+            (false, false) => self.values[lhs_row].is_eq(array.value(rhs_row)),
+            (lhs_null, rhs_null) => lhs_null && rhs_null,
+        }
+    }
+
+    fn is_null(&self, row: usize) -> bool {
+        !self.nulls.get_bit(row)
+    }
+
+    fn append_val(&mut self, array: &ArrayRef, row: usize) {
+        let array = array.as_primitive::<T>();
+        if array.is_null(row) {
+            self.append_null();
+        } else {
+            self.values.push(array.value(row));
+            self.nulls.append(true);
+        }
+    }
+
+    fn append_null(&mut self) {
+        self.values.push(T::Native::default());
+        self.nulls.append(false);
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn size(&self) -> usize {
+        self.values.allocated_size() + self.nulls.capacity() / 8
+    }
+
+    fn build(mut self: Box<Self>) -> Result<ArrayRef> {
+        let values = std::mem::take(&mut self.values);
+        let nulls = build_nulls(std::mem::replace(
+            &mut self.nulls,
+            BooleanBufferBuilder::new(0),
+        ));
+        Ok(self.build_array(values, nulls))
+    }
+
+    fn take_n(&mut self, n: usize) -> Result<ArrayRef> {
+        let remaining = self.values.split_off(n);
+        let values = std::mem::replace(&mut self.values, remaining);
+        let nulls = take_n_nulls(&mut self.nulls, n);
+        Ok(self.build_array(values, nulls))
+    }
+}
+
+/// A [`GroupColumn`] of string or binary values
+struct ByteGroupColumn<B: ByteArrayType> {
+    /// The offsets of the value of each group in `buffer`
+    offsets: Vec<B::Offset>,
+    /// The bytes of the values of all groups
+    buffer: Vec<u8>,
+    /// The validity of each group
+    nulls: BooleanBufferBuilder,
+    phantom: PhantomData<B>,
+}
+
+impl<B: ByteArrayType> ByteGroupColumn<B> {
+    fn new() -> Self {
+        Self {
+            offsets: vec![B::Offset::default()],
+            buffer: vec![],
+            nulls: BooleanBufferBuilder::new(0),
+            phantom: PhantomData,
+        }
+    }
+
+    /// The bytes of the value of group `row`
+    fn value(&self, row: usize) -> &[u8] {
+        let start = self.offsets[row].as_usize();
+        let end = self.offsets[row + 1].as_usize();
+        &self.buffer[start..end]
+    }
+
+    fn build_array(
+        offsets: Vec<B::Offset>,
+        buffer: Vec<u8>,
+        nulls: Option<NullBuffer>,
+    ) -> ArrayRef {
+        let offsets = OffsetBuffer::new(ScalarBuffer::from(offsets));
+        // SAFETY: the values were copied from arrays of the same type
+        let array = unsafe {
+            GenericByteArray::<B>::new_unchecked(offsets, Buffer::from_vec(buffer), nulls)
+        };
+        Arc::new(array)
+    }
+}
+
+impl<B: ByteArrayType> GroupColumn for ByteGroupColumn<B> {
+    fn equal_to(&self, lhs_row: usize, array: &ArrayRef, rhs_row: usize) -> bool {
+        let array = array.as_bytes::<B>();
+        match (self.is_null(lhs_row), array.is_null(rhs_row)) {
+            (false, false) => {
+                let rhs: &[u8] = array.value(rhs_row).as_ref();
+                self.value(lhs_row) == rhs
+            }
+            (lhs_null, rhs_null) => lhs_null && rhs_null,
+        }
+    }
+
+    fn is_null(&self, row: usize) -> bool {
+        !self.nulls.get_bit(row)
+    }
+
+    fn append_val(&mut self, array: &ArrayRef, row: usize) {
+        let array = array.as_bytes::<B>();
+        if array.is_null(row) {
+            self.append_null();
+        } else {
+            let value: &[u8] = array.value(row).as_ref();
+            self.buffer.extend_from_slice(value);
+            self.offsets
+                .push(B::Offset::from_usize(self.buffer.len()).unwrap());
+            self.nulls.append(true);
+        }
+    }
+
+    fn append_null(&mut self) {
+        self.offsets
+            .push(B::Offset::from_usize(self.buffer.len()).unwrap());
+        self.nulls.append(false);
+    }
+
+    fn len(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    fn size(&self) -> usize {
+        self.offsets.allocated_size()
+            + self.buffer.allocated_size()
+            + self.nulls.capacity() / 8
+    }
+
+    fn build(mut self: Box<Self>) -> Result<ArrayRef> {
+        let offsets = std::mem::take(&mut self.offsets);
+        let buffer = std::mem::take(&mut self.buffer);
+        let nulls = build_nulls(std::mem::replace(
+            &mut self.nulls,
+            BooleanBufferBuilder::new(0),
+        ));
+        Ok(Self::build_array(offsets, buffer, nulls))
+    }
+
+    fn take_n(&mut self, n: usize) -> Result<ArrayRef> {
+        let end = self.offsets[n];
+        let end_usize = end.as_usize();
+
+        let mut offsets = Vec::with_capacity(self.offsets.len() - n);
+        offsets.push(B::Offset::default());
+        offsets.extend(self.offsets[n + 1..].iter().map(|offset| *offset - end));
+        let offsets = std::mem::replace(&mut self.offsets, offsets);
+        let first_offsets = offsets[..=n].to_vec();
+
+        let remaining = self.buffer.split_off(end_usize);
+        let buffer = std::mem::replace(&mut self.buffer, remaining);
+        let nulls = take_n_nulls(&mut self.nulls, n);
+        Ok(Self::build_array(first_offsets, buffer, nulls))
+    }
+}
+
+/// A [`GroupColumn`] of dictionary encoded values, which stores the decoded
+/// values and encodes them again when building the output
+struct DictionaryGroupColumn<K: ArrowDictionaryKeyType> {
+    /// The dictionary data type of the output array
+    data_type: DataType,
+    /// The decoded value of each group
+    values: Box<dyn GroupColumn>,
+    phantom: PhantomData<K>,
+}
+
+impl<K: ArrowDictionaryKeyType> DictionaryGroupColumn<K> {
+    fn new_boxed(
+        data_type: DataType,
+        values: Box<dyn GroupColumn>,
+    ) -> Box<dyn GroupColumn> {
+        Box::new(Self {
+            data_type,
+            values,
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<K: ArrowDictionaryKeyType> GroupColumn for DictionaryGroupColumn<K> {
+    fn equal_to(&self, lhs_row: usize, array: &ArrayRef, rhs_row: usize) -> bool {
+        let array = array.as_dictionary::<K>();
+        match array.key(rhs_row) {
+            Some(key) => self.values.equal_to(lhs_row, array.values(), key),
+            None => self.values.is_null(lhs_row),
+        }
+    }
+
+    fn is_null(&self, row: usize) -> bool {
+        self.values.is_null(row)
+    }
+
+    fn append_val(&mut self, array: &ArrayRef, row: usize) {
+        let array = array.as_dictionary::<K>();
+        match array.key(row) {
+            Some(key) => self.values.append_val(array.values(), key),
+            None => self.values.append_null(),
+        }
+    }
+
+    fn append_null(&mut self) {
+        self.values.append_null()
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn size(&self) -> usize {
+        self.values.size()
+    }
+
+    fn build(self: Box<Self>) -> Result<ArrayRef> {
+        let Self {
+            data_type, values, ..
+        } = *self;
+        Ok(cast(&values.build()?, &data_type)?)
+    }
+
+    fn take_n(&mut self, n: usize) -> Result<ArrayRef> {
+        let values = self.values.take_n(n)?;
+        Ok(cast(&values, &self.data_type)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::aggregates::group_values::GroupValuesRows;
+    use arrow_array::{DictionaryArray, Int32Array, Int64Array, StringArray};
+    use arrow_schema::Field;
+
+    fn test_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Utf8, true),
+            Field::new(
+                "c",
+                DataType::Dictionary(Box::new(DataType::Int32), Box::new(DataType::Utf8)),
+                true,
+            ),
+        ]))
+    }
+
+    fn test_batches() -> Vec<Vec<ArrayRef>> {
+        let batch = |a: Vec<Option<i64>>, b: Vec<Option<&str>>, c: Vec<Option<&str>>| {
+            let c: DictionaryArray<Int32Type> = c.into_iter().collect();
+            vec![
+                Arc::new(Int64Array::from(a)) as ArrayRef,
+                Arc::new(StringArray::from(b)) as ArrayRef,
+                Arc::new(c) as ArrayRef,
+            ]
+        };
+        vec![
+            batch(
+                vec![Some(1), Some(1), None, Some(2), None, Some(1)],
+                vec![Some("x"), Some("y"), None, Some("x"), None, Some("x")],
+                vec![Some("p"), Some("p"), Some("q"), None, Some("q"), Some("p")],
+            ),
+            batch(
+                vec![Some(2), None, Some(3), Some(1), Some(1)],
+                vec![Some("x"), None, Some(""), Some("y"), None],
+                vec![None, Some("q"), Some("r"), Some("p"), None],
+            ),
+        ]
+    }
+
+    /// Compares the emitted group values, ignoring how dictionaries are encoded
+    fn assert_same_values(left: Vec<ArrayRef>, right: Vec<ArrayRef>) -> Result<()> {
+        assert_eq!(left.len(), right.len());
+        for (left, right) in left.iter().zip(&right) {
+            let data_type = match left.data_type() {
+                DataType::Dictionary(_, value_type) => value_type.as_ref().clone(),
+                data_type => data_type.clone(),
+            };
+            assert_eq!(&cast(left, &data_type)?, &cast(right, &data_type)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn supported() {
+        assert!(GroupValuesColumn::supported(&test_schema()));
+
+        let schema = Schema::new(vec![
+            Field::new("a", DataType::Int64, true),
+            Field::new("b", DataType::Boolean, true),
+        ]);
+        assert!(!GroupValuesColumn::supported(&schema));
+        assert!(!GroupValuesColumn::supported(&Schema::empty()));
+    }
+
+    #[test]
+    fn intern_matches_rows() -> Result<()> {
+        let schema = test_schema();
+        let mut column = GroupValuesColumn::try_new(schema.clone())?;
+        let mut rows = GroupValuesRows::try_new(schema)?;
+
+        let mut column_groups = vec![];
+        let mut rows_groups = vec![];
+        for cols in test_batches() {
+            column.intern(&cols, &mut column_groups)?;
+            rows.intern(&cols, &mut rows_groups)?;
+            assert_eq!(column_groups, rows_groups);
+        }
+        assert_eq!(column_groups, vec![3, 2, 4, 1, 5]);
+        assert_eq!(column.len(), 6);
+        assert!(column.size() > 0);
+
+        let emitted = column.emit(EmitTo::All)?;
+        assert_eq!(emitted[2].data_type(), test_schema().field(2).data_type());
+        assert_same_values(emitted, rows.emit(EmitTo::All)?)?;
+        assert!(column.is_empty());
+
+        // the emitted groups are interned again from scratch
+        let cols = test_batches().remove(0);
+        column.intern(&cols, &mut column_groups)?;
+        assert_eq!(column_groups, vec![0, 1, 2, 3, 2, 0]);
+        Ok(())
+    }
+
+    #[test]
+    fn emit_first() -> Result<()> {
+        let schema = test_schema();
+        let mut column = GroupValuesColumn::try_new(schema.clone())?;
+        let mut rows = GroupValuesRows::try_new(schema)?;
+
+        let mut column_groups = vec![];
+        let mut rows_groups = vec![];
+        let batches = test_batches();
+        column.intern(&batches[0], &mut column_groups)?;
+        rows.intern(&batches[0], &mut rows_groups)?;
+
+        assert_same_values(column.emit(EmitTo::First(2))?, rows.emit(EmitTo::First(2))?)?;
+        assert_eq!(column.len(), 2);
+
+        column.intern(&batches[1], &mut column_groups)?;
+        rows.intern(&batches[1], &mut rows_groups)?;
+        assert_eq!(column_groups, rows_groups);
+        assert_same_values(column.emit(EmitTo::All)?, rows.emit(EmitTo::All)?)?;
+        Ok(())
+    }
+
+    #[test]
+    fn primitive_group_column_nulls() -> Result<()> {
+        let array: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None, Some(0)]));
+        let mut column = new_group_column(&DataType::Int32).unwrap();
+        column.append_val(&array, 0);
+        column.append_val(&array, 1);
+
+        assert!(column.equal_to(0, &array, 0));
+        assert!(column.equal_to(1, &array, 1));
+        // the null group stores the default value, which must not match 0
+        assert!(!column.equal_to(1, &array, 2));
+        assert!(!column.equal_to(0, &array, 1));
+
+        let output = column.build()?;
+        assert_eq!(output.as_ref(), &Int32Array::from(vec![Some(1), None]));
+        Ok(())
+    }
+}
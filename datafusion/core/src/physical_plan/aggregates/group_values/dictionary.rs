@@ -0,0 +1,190 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::physical_plan::aggregates::group_values::{new_group_values, GroupValues};
+use arrow::compute::{cast, take};
+use arrow::datatypes::ArrowDictionaryKeyType;
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, ArrayRef, UInt64Array};
+use arrow_schema::{DataType, Field, Schema};
+use datafusion_common::{internal_err, Result};
+use datafusion_execution::memory_pool::proxy::VecAllocExt;
+use datafusion_physical_expr::EmitTo;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// A [`GroupValues`] for a single dictionary encoded column
+///
+/// Rather than decoding the value of every row, each batch is grouped on
+/// its dictionary keys: the values of the distinct keys present in the
+/// batch are interned once, in order of their first appearance, into a
+/// [`GroupValues`] for the value type, and every row then looks up the
+/// group of its key. The group values are emitted with the dictionary type
+/// of the input.
+pub struct GroupValuesDictionary<K: ArrowDictionaryKeyType> {
+    /// The dictionary data type of the output array
+    data_type: DataType,
+    /// The group values, interned by value
+    values: Box<dyn GroupValues>,
+    /// The index in `distinct_groups` of each dictionary key of the current
+    /// batch, or `None` if the key is not present
+    key_slots: Vec<Option<usize>>,
+    /// The group index of each distinct key of the current batch
+    distinct_groups: Vec<usize>,
+    phantom: PhantomData<K>,
+}
+
+impl<K: ArrowDictionaryKeyType> GroupValuesDictionary<K> {
+    /// Creates a new [`GroupValuesDictionary`] for values of the dictionary
+    /// type `data_type`
+    pub fn try_new(data_type: DataType) -> Result<Self> {
+        let DataType::Dictionary(_, value_type) = &data_type else {
+            return internal_err!("Expected dictionary type, got {data_type}");
+        };
+        let schema = Schema::new(vec![Field::new("value", *value_type.clone(), true)]);
+        let values = new_group_values(Arc::new(schema), true)?;
+        Ok(Self {
+            data_type,
+            values,
+            key_slots: vec![],
+            distinct_groups: vec![],
+            phantom: PhantomData,
+        })
+    }
+}
+
+impl<K: ArrowDictionaryKeyType> GroupValues for GroupValuesDictionary<K> {
+    fn intern(&mut self, cols: &[ArrayRef], groups: &mut Vec<usize>) -> Result<()> {
+        assert_eq!(cols.len(), 1);
+        let array = cols[0].as_dictionary::<K>();
+
+        // 1. Collect the distinct keys in order of their first appearance,
+        // a null index stands for the null keys
+        self.key_slots.clear();
+        self.key_slots.resize(array.values().len(), None);
+        let mut null_slot = None;
+        let mut distinct_keys = vec![];
+        for row in 0..array.len() {
+            match array.key(row) {
+                Some(key) => {
+                    if self.key_slots[key].is_none() {
+                        self.key_slots[key] = Some(distinct_keys.len());
+                        distinct_keys.push(Some(key as u64));
+                    }
+                }
+                None => {
+                    if null_slot.is_none() {
+                        null_slot = Some(distinct_keys.len());
+                        distinct_keys.push(None);
+                    }
+                }
+            }
+        }
+
+        // 2. Intern the values of the distinct keys, once per batch
+        let distinct_keys = UInt64Array::from(distinct_keys);
+        let distinct_values = take(array.values().as_ref(), &distinct_keys, None)?;
+        self.values
+            .intern(&[distinct_values], &mut self.distinct_groups)?;
+
+        // 3. Map every row to the group of its key
+        groups.clear();
+        groups.extend((0..array.len()).map(|row| {
+            let slot = match array.key(row) {
+                Some(key) => self.key_slots[key],
+                None => null_slot,
+            };
+            self.distinct_groups[slot.unwrap()]
+        }));
+        Ok(())
+    }
+
+    fn size(&self) -> usize {
+        self.values.size()
+            + self.key_slots.allocated_size()
+            + self.distinct_groups.allocated_size()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    fn emit(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>> {
+        self.values
+            .emit(emit_to)?
+            .iter()
+            .map(|values| Ok(cast(values, &self.data_type)?))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::datatypes::Int8Type;
+    use arrow_array::{DictionaryArray, Int8Array, StringArray};
+
+    fn dictionary(keys: Vec<Option<i8>>, values: Vec<Option<&str>>) -> ArrayRef {
+        let keys = Int8Array::from(keys);
+        let values = Arc::new(StringArray::from(values));
+        Arc::new(DictionaryArray::<Int8Type>::try_new(keys, values).unwrap())
+    }
+
+    #[test]
+    fn intern() -> Result<()> {
+        let data_type =
+            DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8));
+        let mut group_values = GroupValuesDictionary::<Int8Type>::try_new(data_type)?;
+        let mut groups = vec![];
+
+        // the value "c" is not referenced and must not create a group
+        let array = dictionary(
+            vec![Some(1), Some(0), None, Some(1), Some(3)],
+            vec![Some("a"), Some("b"), Some("c"), None],
+        );
+        group_values.intern(&[array], &mut groups)?;
+        assert_eq!(groups, vec![0, 1, 2, 0, 2]);
+        assert_eq!(group_values.len(), 3);
+
+        // the same values with different keys map to the same groups
+        let array = dictionary(
+            vec![Some(0), Some(2), Some(1), Some(2)],
+            vec![Some("b"), Some("a"), Some("d")],
+        );
+        group_values.intern(&[array], &mut groups)?;
+        assert_eq!(groups, vec![0, 3, 1, 3]);
+        assert_eq!(group_values.len(), 4);
+
+        let first = group_values.emit(EmitTo::First(2))?;
+        assert_eq!(first[0].data_type(), &group_values.data_type);
+        let first = cast(&first[0], &DataType::Utf8)?;
+        assert_eq!(first.as_string::<i32>(), &StringArray::from(vec!["b", "a"]));
+
+        let rest = group_values.emit(EmitTo::All)?;
+        let rest = cast(&rest[0], &DataType::Utf8)?;
+        assert_eq!(
+            rest.as_string::<i32>(),
+            &StringArray::from(vec![None, Some("d")])
+        );
+        assert!(group_values.is_empty());
+        Ok(())
+    }
+}
@@ -15,11 +15,23 @@
 // specific language governing permissions and limitations
 // under the License.
 
+//! [`GroupValues`] implementations used to intern group keys of a hash aggregation
+
+use arrow::datatypes::{
+    ArrowDictionaryKeyType, Int16Type, Int32Type, Int64Type, Int8Type, UInt16Type,
+    UInt32Type, UInt64Type, UInt8Type,
+};
 use arrow_array::{downcast_primitive, ArrayRef};
-use arrow_schema::SchemaRef;
+use arrow_schema::{DataType, Schema, SchemaRef};
 use datafusion_common::Result;
 use datafusion_physical_expr::EmitTo;
 
+mod column;
+use column::GroupValuesColumn;
+
+mod dictionary;
+use dictionary::GroupValuesDictionary;
+
 mod primitive;
 use primitive::GroupValuesPrimitive;
 
@@ -41,10 +53,44 @@ pub trait GroupValues: Send {
     fn len(&self) -> usize;
 
     /// Emits the group values
+    ///
+    /// Group columns with dictionary types are emitted with the same
+    /// dictionary type, so at most [`max_emit_groups`] groups can be emitted
+    /// at once.
     fn emit(&mut self, emit_to: EmitTo) -> Result<Vec<ArrayRef>>;
 }
 
-pub fn new_group_values(schema: SchemaRef) -> Result<Box<dyn GroupValues>> {
+/// Returns the maximum number of groups that can be emitted at once with the
+/// group columns of `schema`, if it is limited by the number of distinct
+/// values the keys of a dictionary column can refer to
+pub fn max_emit_groups(schema: &Schema) -> Option<usize> {
+    schema
+        .fields()
+        .iter()
+        .filter_map(|field| match field.data_type() {
+            DataType::Dictionary(key_type, _) => match key_type.as_ref() {
+                DataType::Int8 => Some(i8::MAX as usize + 1),
+                DataType::Int16 => Some(i16::MAX as usize + 1),
+                DataType::UInt8 => Some(u8::MAX as usize + 1),
+                DataType::UInt16 => Some(u16::MAX as usize + 1),
+                _ => None,
+            },
+            _ => None,
+        })
+        .min()
+}
+
+/// Returns a [`GroupValues`] specialized for the group columns of `schema`
+///
+/// A single primitive column uses [`GroupValuesPrimitive`], and a single
+/// dictionary column [`GroupValuesDictionary`]. If `intern_columns` is true,
+/// group columns of primitive, string, binary or dictionary types use
+/// [`GroupValuesColumn`], and all others fall back to the row based
+/// [`GroupValuesRows`]
+pub fn new_group_values(
+    schema: SchemaRef,
+    intern_columns: bool,
+) -> Result<Box<dyn GroupValues>> {
     if schema.fields.len() == 1 {
         let d = schema.fields[0].data_type();
 
@@ -58,7 +104,35 @@ pub fn new_group_values(schema: SchemaRef) -> Result<Box<dyn GroupValues>> {
             d => (downcast_helper, d),
             _ => {}
         }
+
+        if let DataType::Dictionary(key_type, _) = d {
+            if GroupValuesColumn::supported(&schema) {
+                return match key_type.as_ref() {
+                    DataType::Int8 => dictionary_group_values::<Int8Type>(d),
+                    DataType::Int16 => dictionary_group_values::<Int16Type>(d),
+                    DataType::Int32 => dictionary_group_values::<Int32Type>(d),
+                    DataType::Int64 => dictionary_group_values::<Int64Type>(d),
+                    DataType::UInt8 => dictionary_group_values::<UInt8Type>(d),
+                    DataType::UInt16 => dictionary_group_values::<UInt16Type>(d),
+                    DataType::UInt32 => dictionary_group_values::<UInt32Type>(d),
+                    DataType::UInt64 => dictionary_group_values::<UInt64Type>(d),
+                    _ => Ok(Box::new(GroupValuesRows::try_new(schema.clone())?)),
+                };
+            }
+        }
+    }
+
+    if intern_columns && GroupValuesColumn::supported(&schema) {
+        return Ok(Box::new(GroupValuesColumn::try_new(schema)?));
     }
 
     Ok(Box::new(GroupValuesRows::try_new(schema)?))
 }
+
+fn dictionary_group_values<K: ArrowDictionaryKeyType>(
+    data_type: &DataType,
+) -> Result<Box<dyn GroupValues>> {
+    Ok(Box::new(GroupValuesDictionary::<K>::try_new(
+        data_type.clone(),
+    )?))
+}
@@ -62,6 +62,7 @@ pub struct GroupValuesRows {
 }
 
 impl GroupValuesRows {
+    /// Creates a new [`GroupValuesRows`] for group columns of `schema`
     pub fn try_new(schema: SchemaRef) -> Result<Self> {
         let row_converter = RowConverter::new(
             schema
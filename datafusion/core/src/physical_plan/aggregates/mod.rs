@@ -19,6 +19,7 @@
 
 use crate::physical_plan::aggregates::{
     no_grouping::AggregateStream, row_hash::GroupedHashAggregateStream,
+    sorted::SortedAggregateStream,
 };
 use crate::physical_plan::metrics::{ExecutionPlanMetricsSet, MetricsSet};
 use crate::physical_plan::{
@@ -30,7 +31,7 @@ use arrow::array::ArrayRef;
 use arrow::datatypes::{Field, Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
 use datafusion_common::utils::longest_consecutive_prefix;
-use datafusion_common::{not_impl_err, plan_err, DataFusionError, Result};
+use datafusion_common::{not_impl_err, plan_err, DataFusionError, Precision, Result};
 use datafusion_execution::TaskContext;
 use datafusion_expr::Accumulator;
 use datafusion_physical_expr::{
@@ -50,6 +51,7 @@ mod group_values;
 mod no_grouping;
 mod order;
 mod row_hash;
+mod sorted;
 
 pub use datafusion_expr::AggregateFunction;
 use datafusion_physical_expr::aggregate::is_order_sensitive;
@@ -84,6 +86,15 @@ pub enum AggregateMode {
     /// two operators.
     /// This mode requires tha the input is partitioned by group key (like FinalPartitioned)
     SinglePartitioned,
+    /// Merges partial aggregates into partial aggregates, producing the same
+    /// output as a `Partial` aggregate.
+    ///
+    /// This combines the partial aggregates of a group key whose rows were
+    /// spread over several partitions, before the final aggregation (see
+    /// [`SkewHandling::Spread`]). It places no requirements on its input.
+    ///
+    /// [`SkewHandling::Spread`]: crate::physical_plan::repartition::SkewHandling::Spread
+    PartialReduce,
 }
 
 impl AggregateMode {
@@ -95,7 +106,9 @@ impl AggregateMode {
             AggregateMode::Partial
             | AggregateMode::Single
             | AggregateMode::SinglePartitioned => true,
-            AggregateMode::Final | AggregateMode::FinalPartitioned => false,
+            AggregateMode::Final
+            | AggregateMode::FinalPartitioned
+            | AggregateMode::PartialReduce => false,
         }
     }
 }
@@ -125,6 +138,10 @@ pub enum GroupByOrderMode {
     /// For example, if the input is ordered by `a, b, c, d` and we group by b, a,
     /// `Ordered` means that all of the of group by expressions appear
     ///  as a preset for the existing ordering, in this case `a, b`.
+    ///
+    /// In this mode the complete groups are emitted as soon as the next group
+    /// starts. When some aggregates have no `GroupsAccumulator`, the groups
+    /// are aggregated one at a time, without a hash table.
     FullyOrdered,
 }
 
@@ -229,6 +246,7 @@ impl PartialEq for PhysicalGroupBy {
 enum StreamType {
     AggregateStream(AggregateStream),
     GroupedHashAggregateStream(GroupedHashAggregateStream),
+    SortedAggregateStream(SortedAggregateStream),
 }
 
 impl From<StreamType> for SendableRecordBatchStream {
@@ -236,6 +254,7 @@ impl From<StreamType> for SendableRecordBatchStream {
         match stream {
             StreamType::AggregateStream(stream) => Box::pin(stream),
             StreamType::GroupedHashAggregateStream(stream) => Box::pin(stream),
+            StreamType::SortedAggregateStream(stream) => Box::pin(stream),
         }
     }
 }
@@ -721,6 +740,25 @@ impl AggregateExec {
             Ok(StreamType::AggregateStream(AggregateStream::new(
                 self, context, partition,
             )?))
+        } else if matches!(
+            self.aggregation_ordering,
+            Some(AggregationOrdering {
+                mode: GroupByOrderMode::FullyOrdered,
+                ..
+            })
+        ) && !self
+            .aggr_expr
+            .iter()
+            .all(|expr| expr.groups_accumulator_supported())
+        {
+            // The rows of each group are contiguous, so only the current
+            // group needs to be kept. Vectorized `GroupsAccumulator`s are
+            // faster, and emit the complete groups early with a fully
+            // ordered input, so this is only used for row based
+            // accumulators such as DISTINCT ones.
+            Ok(StreamType::SortedAggregateStream(
+                SortedAggregateStream::new(self, context, partition)?,
+            ))
         } else {
             Ok(StreamType::GroupedHashAggregateStream(
                 GroupedHashAggregateStream::new(self, context, partition)?,
@@ -832,6 +870,19 @@ impl ExecutionPlan for AggregateExec {
                             .collect::<Vec<_>>();
                         Partitioning::Hash(normalized_exprs, part)
                     }
+                    Partitioning::Range(sort_exprs, part) => {
+                        let normalized_exprs = sort_exprs
+                            .into_iter()
+                            .map(|sort_expr| PhysicalSortExpr {
+                                expr: normalize_out_expr_with_columns_map(
+                                    sort_expr.expr,
+                                    &self.columns_map,
+                                ),
+                                options: sort_expr.options,
+                            })
+                            .collect::<Vec<_>>();
+                        Partitioning::Range(normalized_exprs, part)
+                    }
                     _ => input_partition,
                 }
             }
@@ -866,7 +917,7 @@ impl ExecutionPlan for AggregateExec {
 
     fn required_input_distribution(&self) -> Vec<Distribution> {
         match &self.mode {
-            AggregateMode::Partial => {
+            AggregateMode::Partial | AggregateMode::PartialReduce => {
                 vec![Distribution::UnspecifiedDistribution]
             }
             AggregateMode::FinalPartitioned | AggregateMode::SinglePartitioned => {
@@ -935,15 +986,13 @@ impl ExecutionPlan for AggregateExec {
                 if self.group_by.expr.is_empty() =>
             {
                 Statistics {
-                    num_rows: Some(1),
-                    is_exact: true,
+                    num_rows: Precision::Exact(1),
                     ..Default::default()
                 }
             }
             _ => Statistics {
                 // the output row count is surely not larger than its input row count
-                num_rows: self.input.statistics().num_rows,
-                is_exact: false,
+                num_rows: self.input.statistics().num_rows.to_inexact(),
                 ..Default::default()
             },
         }
@@ -970,7 +1019,7 @@ fn create_schema(
     }
 
     match mode {
-        AggregateMode::Partial => {
+        AggregateMode::Partial | AggregateMode::PartialReduce => {
             // in partial mode, the fields of the accumulator's state
             for expr in aggr_expr {
                 fields.extend(expr.state_fields()?.iter().cloned())
@@ -1027,7 +1076,9 @@ fn aggregate_expressions(
             })
             .collect()),
         // in this mode, we build the merge expressions of the aggregation
-        AggregateMode::Final | AggregateMode::FinalPartitioned => {
+        AggregateMode::Final
+        | AggregateMode::FinalPartitioned
+        | AggregateMode::PartialReduce => {
             let mut col_idx_base = col_idx_base;
             Ok(aggr_expr
                 .iter()
@@ -1071,13 +1122,14 @@ fn create_accumulators(
 }
 
 /// returns a vector of ArrayRefs, where each entry corresponds to either the
-/// final value (mode = Final, FinalPartitioned and Single) or states (mode = Partial)
+/// final value (mode = Final, FinalPartitioned and Single) or states (mode = Partial
+/// and PartialReduce)
 fn finalize_aggregation(
     accumulators: &[AccumulatorItem],
     mode: &AggregateMode,
 ) -> Result<Vec<ArrayRef>> {
     match mode {
-        AggregateMode::Partial => {
+        AggregateMode::Partial | AggregateMode::PartialReduce => {
             // build the vector of states
             let a = accumulators
                 .iter()
@@ -1191,19 +1243,20 @@ mod tests {
     };
     use crate::physical_plan::coalesce_batches::CoalesceBatchesExec;
     use crate::physical_plan::coalesce_partitions::CoalescePartitionsExec;
-    use crate::physical_plan::expressions::{col, Avg};
+    use crate::physical_plan::expressions::{col, Avg, DistinctCount};
     use crate::physical_plan::memory::MemoryExec;
     use crate::physical_plan::{
-        DisplayAs, ExecutionPlan, Partitioning, RecordBatchStream,
+        displayable, DisplayAs, ExecutionPlan, Partitioning, RecordBatchStream,
         SendableRecordBatchStream, Statistics,
     };
+    use crate::prelude::SessionConfig;
     use crate::test::exec::{assert_strong_count_converges_to_zero, BlockingExec};
     use crate::test::{assert_is_pending, csv_exec_sorted};
     use crate::{assert_batches_eq, assert_batches_sorted_eq, physical_plan::common};
 
-    use arrow::array::{Float64Array, UInt32Array};
+    use arrow::array::{DictionaryArray, Float64Array, UInt32Array};
     use arrow::compute::{concat_batches, SortOptions};
-    use arrow::datatypes::{DataType, Field, Schema, SchemaRef};
+    use arrow::datatypes::{DataType, Field, Int8Type, Schema, SchemaRef};
     use arrow::record_batch::RecordBatch;
     use datafusion_common::{internal_err, DataFusionError, Result, ScalarValue};
     use datafusion_execution::runtime_env::{RuntimeConfig, RuntimeEnv};
@@ -1591,6 +1644,269 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn skip_partial_aggregation() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::UInt32, false),
+            Field::new("b", DataType::Float64, false),
+        ]));
+        let batch = |a: Vec<u32>, b: Vec<f64>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(UInt32Array::from(a)),
+                    Arc::new(Float64Array::from(b)),
+                ],
+            )
+        };
+        // every group of the first batch is unique, so the partial aggregation
+        // passes the second batch through
+        let input = Arc::new(MemoryExec::try_new(
+            &[vec![
+                batch(vec![1, 2, 3, 4], vec![1.0, 2.0, 3.0, 4.0])?,
+                batch(vec![1, 1, 5, 4], vec![5.0, 6.0, 7.0, 8.0])?,
+            ]],
+            schema.clone(),
+            None,
+        )?);
+
+        let group_by =
+            PhysicalGroupBy::new_single(vec![(col("a", &schema)?, "a".into())]);
+        let aggregates: Vec<Arc<dyn AggregateExpr>> = vec![
+            Arc::new(Avg::new(col("b", &schema)?, "AVG(b)", DataType::Float64)),
+            Arc::new(Count::new(col("b", &schema)?, "COUNT(b)", DataType::Int64)),
+        ];
+        let partial_aggregate = Arc::new(AggregateExec::try_new(
+            AggregateMode::Partial,
+            group_by,
+            aggregates.clone(),
+            vec![None; 2],
+            vec![None; 2],
+            input,
+            schema.clone(),
+        )?);
+
+        let mut session_config = SessionConfig::new();
+        let options = &mut session_config.options_mut().execution.aggregate;
+        options.skip_partial_aggregation_probe_rows_threshold = 4;
+        options.skip_partial_aggregation_probe_ratio_threshold = 0.5;
+        let task_ctx =
+            Arc::new(TaskContext::default().with_session_config(session_config));
+
+        let result =
+            common::collect(partial_aggregate.execute(0, task_ctx.clone())?).await?;
+        let expected = [
+            "+---+---------------+-------------+-----------------+",
+            "| a | AVG(b)[count] | AVG(b)[sum] | COUNT(b)[count] |",
+            "+---+---------------+-------------+-----------------+",
+            "| 1 | 1             | 1.0         | 1               |",
+            "| 2 | 1             | 2.0         | 1               |",
+            "| 3 | 1             | 3.0         | 1               |",
+            "| 4 | 1             | 4.0         | 1               |",
+            "| 1 | 1             | 5.0         | 1               |",
+            "| 1 | 1             | 6.0         | 1               |",
+            "| 5 | 1             | 7.0         | 1               |",
+            "| 4 | 1             | 8.0         | 1               |",
+            "+---+---------------+-------------+-----------------+",
+        ];
+        assert_batches_eq!(expected, &result);
+
+        let metrics = partial_aggregate.metrics().unwrap();
+        let metric = |name| metrics.sum_by_name(name).map(|m| m.as_usize());
+        assert_eq!(metric("rows_aggregated_before_skip"), Some(4));
+        assert_eq!(metric("skipped_aggregation_rows"), Some(4));
+
+        let final_group_by =
+            PhysicalGroupBy::new_single(vec![(col("a", &schema)?, "a".into())]);
+        let final_aggregate = Arc::new(AggregateExec::try_new(
+            AggregateMode::Final,
+            final_group_by,
+            aggregates,
+            vec![None; 2],
+            vec![None; 2],
+            partial_aggregate,
+            schema,
+        )?);
+        let result = common::collect(final_aggregate.execute(0, task_ctx)?).await?;
+        let expected = [
+            "+---+--------+----------+",
+            "| a | AVG(b) | COUNT(b) |",
+            "+---+--------+----------+",
+            "| 1 | 4.0    | 3        |",
+            "| 2 | 2.0    | 1        |",
+            "| 3 | 3.0    | 1        |",
+            "| 4 | 6.0    | 2        |",
+            "| 5 | 7.0    | 1        |",
+            "+---+--------+----------+",
+        ];
+        assert_batches_sorted_eq!(expected, &result);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn narrow_dictionary_keys() -> Result<()> {
+        let data_type =
+            DataType::Dictionary(Box::new(DataType::Int8), Box::new(DataType::Utf8));
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", data_type.clone(), true),
+            Field::new("b", DataType::UInt32, false),
+        ]));
+        // 300 distinct values of a, more than Int8 keys can refer to
+        let batches = (0..3)
+            .map(|i| {
+                let values = (0..100)
+                    .map(|j| format!("v{}", i * 100 + j))
+                    .collect::<Vec<_>>();
+                let a: DictionaryArray<Int8Type> =
+                    values.iter().map(|v| v.as_str()).collect();
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(a), Arc::new(UInt32Array::from_iter_values(0..100))],
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let input = Arc::new(MemoryExec::try_new(&[batches], schema.clone(), None)?);
+
+        // a single dictionary column and a dictionary column among others
+        for names in [vec!["a"], vec!["a", "b"]] {
+            let group_by = names
+                .iter()
+                .map(|name| Ok((col(name, &schema)?, name.to_string())))
+                .collect::<Result<Vec<_>>>()?;
+            let aggregate = Arc::new(AggregateExec::try_new(
+                AggregateMode::Single,
+                PhysicalGroupBy::new_single(group_by),
+                vec![Arc::new(Count::new(
+                    col("b", &schema)?,
+                    "COUNT(b)",
+                    DataType::Int64,
+                ))],
+                vec![None],
+                vec![None],
+                input.clone(),
+                schema.clone(),
+            )?);
+
+            let task_ctx = Arc::new(TaskContext::default());
+            let result = common::collect(aggregate.execute(0, task_ctx)?).await?;
+            let num_rows = result.iter().map(|batch| batch.num_rows()).sum::<usize>();
+            assert_eq!(num_rows, 300);
+            for batch in &result {
+                assert!(batch.num_rows() <= 128, "{}", batch.num_rows());
+                assert_eq!(batch.column(0).data_type(), &data_type);
+            }
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn sorted_aggregation() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::UInt32, true),
+            Field::new("b", DataType::Float64, false),
+        ]));
+        let batch = |a: Vec<Option<u32>>, b: Vec<f64>| {
+            RecordBatch::try_new(
+                schema.clone(),
+                vec![
+                    Arc::new(UInt32Array::from(a)),
+                    Arc::new(Float64Array::from(b)),
+                ],
+            )
+        };
+        // the group a=2 spans both batches
+        let input = Arc::new(
+            MemoryExec::try_new(
+                &[vec![
+                    batch(
+                        vec![None, Some(1), Some(1), Some(2)],
+                        vec![7.0, 1.0, 1.0, 2.0],
+                    )?,
+                    batch(
+                        vec![Some(2), Some(2), Some(3), Some(4)],
+                        vec![3.0, 2.0, 4.0, 5.0],
+                    )?,
+                ]],
+                schema.clone(),
+                None,
+            )?
+            .with_sort_information(vec![vec![sort_expr("a", &schema)]]),
+        );
+
+        let group_by =
+            PhysicalGroupBy::new_single(vec![(col("a", &schema)?, "a".into())]);
+        let aggregates: Vec<Arc<dyn AggregateExpr>> = vec![
+            Arc::new(DistinctCount::new(
+                DataType::Float64,
+                col("b", &schema)?,
+                "COUNT(DISTINCT b)".to_string(),
+            )),
+            Arc::new(Count::new(col("b", &schema)?, "COUNT(b)", DataType::Int64)),
+        ];
+        let partial_aggregate = Arc::new(AggregateExec::try_new(
+            AggregateMode::Partial,
+            group_by.clone(),
+            aggregates.clone(),
+            vec![None; 2],
+            vec![None; 2],
+            input.clone(),
+            schema.clone(),
+        )?);
+        let final_aggregate = Arc::new(AggregateExec::try_new(
+            AggregateMode::Final,
+            group_by.clone(),
+            aggregates,
+            vec![None; 2],
+            vec![None; 2],
+            partial_aggregate,
+            schema.clone(),
+        )?);
+
+        // both aggregations run on input sorted on the group keys
+        let plan = displayable(final_aggregate.as_ref())
+            .indent(false)
+            .to_string();
+        assert_eq!(plan.matches("ordering_mode=FullyOrdered").count(), 2);
+
+        let task_ctx = Arc::new(TaskContext::default());
+        let result = common::collect(final_aggregate.execute(0, task_ctx)?).await?;
+        let expected = [
+            "+---+-------------------+----------+",
+            "| a | COUNT(DISTINCT b) | COUNT(b) |",
+            "+---+-------------------+----------+",
+            "|   | 1                 | 1        |",
+            "| 1 | 1                 | 2        |",
+            "| 2 | 2                 | 3        |",
+            "| 3 | 1                 | 1        |",
+            "| 4 | 1                 | 1        |",
+            "+---+-------------------+----------+",
+        ];
+        assert_batches_eq!(expected, &result);
+
+        // aggregates with a `GroupsAccumulator` keep the vectorized stream
+        let count_aggregate = AggregateExec::try_new(
+            AggregateMode::Partial,
+            group_by,
+            vec![Arc::new(Count::new(
+                col("b", &schema)?,
+                "COUNT(b)",
+                DataType::Int64,
+            ))],
+            vec![None],
+            vec![None],
+            input,
+            schema,
+        )?;
+        let task_ctx = Arc::new(TaskContext::default());
+        assert!(matches!(
+            count_aggregate.execute_typed(0, task_ctx)?,
+            StreamType::GroupedHashAggregateStream(_)
+        ));
+
+        Ok(())
+    }
+
     /// Define a test source that can yield back to runtime before returning its first item ///
 
     #[derive(Debug)]
@@ -28,12 +28,16 @@ use std::vec;
 use futures::ready;
 use futures::stream::{Stream, StreamExt};
 
-use crate::physical_plan::aggregates::group_values::{new_group_values, GroupValues};
+use crate::physical_plan::aggregates::group_values::{
+    max_emit_groups, new_group_values, GroupValues,
+};
 use crate::physical_plan::aggregates::{
     evaluate_group_by, evaluate_many, evaluate_optional, group_schema, AggregateMode,
     PhysicalGroupBy,
 };
-use crate::physical_plan::metrics::{BaselineMetrics, RecordOutput};
+use crate::physical_plan::metrics::{
+    BaselineMetrics, Count, Gauge, MetricBuilder, RecordOutput,
+};
 use crate::physical_plan::{aggregates, PhysicalExpr};
 use crate::physical_plan::{RecordBatchStream, SendableRecordBatchStream};
 use arrow::array::*;
@@ -50,6 +54,9 @@ pub(crate) enum ExecutionState {
     /// When producing output, the remaining rows to output are stored
     /// here and are sliced off as needed in batch_size chunks
     ProducingOutput(RecordBatch),
+    /// The partial aggregation does not reduce the number of rows enough,
+    /// so the state of each input row is passed through
+    SkippingAggregation,
     Done,
 }
 
@@ -125,6 +132,9 @@ pub(crate) struct GroupedHashAggregateStream {
     input: SendableRecordBatchStream,
     mode: AggregateMode,
 
+    /// The aggregate expressions, which create the accumulators
+    aggregate_exprs: Vec<Arc<dyn AggregateExpr>>,
+
     /// Accumulators, one for each `AggregateExpr` in the query
     ///
     /// For example, if the query has aggregates, `SUM(x)`,
@@ -158,6 +168,9 @@ pub(crate) struct GroupedHashAggregateStream {
     /// An interning store of group keys
     group_values: Box<dyn GroupValues>,
 
+    /// The maximum number of groups emitted at once, see [`max_emit_groups`]
+    max_emit_groups: Option<usize>,
+
     /// scratch space for the current input [`RecordBatch`] being
     /// processed. Reused across batches here to avoid reallocations
     current_group_indices: Vec<usize>,
@@ -168,6 +181,9 @@ pub(crate) struct GroupedHashAggregateStream {
     /// Execution metrics
     baseline_metrics: BaselineMetrics,
 
+    /// Peak memory reserved by this stream
+    mem_used: Gauge,
+
     /// max rows in output RecordBatches
     batch_size: usize,
 
@@ -178,6 +194,77 @@ pub(crate) struct GroupedHashAggregateStream {
 
     /// Have we seen the end of the input
     input_done: bool,
+
+    /// Decides whether a partial aggregation should stop aggregating, if it
+    /// may
+    skip_aggregation_probe: Option<SkipAggregationProbe>,
+}
+
+/// Tracks the ratio of the number of groups to the number of input rows of a
+/// partial aggregation, to decide whether it reduces the number of rows
+/// enough to be worth aggregating.
+///
+/// The decision is made once, after `probe_rows_threshold` input rows.
+#[derive(Debug)]
+struct SkipAggregationProbe {
+    /// Number of input rows aggregated so far
+    input_rows: usize,
+    /// Number of input rows after which the decision is made
+    probe_rows_threshold: usize,
+    /// Ratio of groups to input rows from which the aggregation is skipped
+    probe_ratio_threshold: f64,
+    /// Whether the decision has been made
+    is_locked: bool,
+    /// Whether the aggregation should be skipped
+    should_skip: bool,
+    /// Number of input rows aggregated before skipping the aggregation
+    rows_aggregated_before_skip: Gauge,
+    /// Number of input rows whose state was passed through
+    skipped_aggregation_rows: Count,
+}
+
+impl SkipAggregationProbe {
+    fn new(
+        probe_rows_threshold: usize,
+        probe_ratio_threshold: f64,
+        rows_aggregated_before_skip: Gauge,
+        skipped_aggregation_rows: Count,
+    ) -> Self {
+        Self {
+            input_rows: 0,
+            probe_rows_threshold,
+            probe_ratio_threshold,
+            is_locked: false,
+            should_skip: false,
+            rows_aggregated_before_skip,
+            skipped_aggregation_rows,
+        }
+    }
+
+    /// Records that `input_rows` more rows were aggregated into `num_groups`
+    /// groups in total
+    fn update_state(&mut self, input_rows: usize, num_groups: usize) {
+        if self.is_locked {
+            return;
+        }
+        self.input_rows += input_rows;
+        if self.input_rows >= self.probe_rows_threshold {
+            self.should_skip =
+                num_groups as f64 / self.input_rows as f64 >= self.probe_ratio_threshold;
+            self.is_locked = true;
+            if self.should_skip {
+                self.rows_aggregated_before_skip.set(self.input_rows);
+            }
+        }
+    }
+
+    fn should_skip(&self) -> bool {
+        self.should_skip
+    }
+
+    fn record_skipped(&mut self, batch: &RecordBatch) {
+        self.skipped_aggregation_rows.add(batch.num_rows());
+    }
 }
 
 impl GroupedHashAggregateStream {
@@ -195,10 +282,12 @@ impl GroupedHashAggregateStream {
         let batch_size = context.session_config().batch_size();
         let input = agg.input.execute(partition, Arc::clone(&context))?;
         let baseline_metrics = BaselineMetrics::new(&agg.metrics, partition);
+        let mem_used = MetricBuilder::new(&agg.metrics).mem_used(partition);
 
         let timer = baseline_metrics.elapsed_compute().timer();
 
         let aggregate_exprs = agg.aggr_expr.clone();
+        let options = &context.session_config().options().execution.aggregate;
 
         // arguments for each aggregate, one vec of expressions per
         // aggregate
@@ -212,9 +301,9 @@ impl GroupedHashAggregateStream {
             AggregateMode::Partial
             | AggregateMode::Single
             | AggregateMode::SinglePartitioned => agg_filter_expr,
-            AggregateMode::Final | AggregateMode::FinalPartitioned => {
-                vec![None; agg.aggr_expr.len()]
-            }
+            AggregateMode::Final
+            | AggregateMode::FinalPartitioned
+            | AggregateMode::PartialReduce => vec![None; agg.aggr_expr.len()],
         };
 
         // Instantiate the accumulators
@@ -238,7 +327,27 @@ impl GroupedHashAggregateStream {
             .transpose()?
             .unwrap_or(GroupOrdering::None);
 
-        let group_values = new_group_values(group_schema)?;
+        // Passing the state of each input row through requires a single
+        // grouping set, and is pointless if groups are emitted early anyway
+        let skip_aggregation_probe = (agg.mode == AggregateMode::Partial
+            && agg_group_by.groups.len() == 1
+            && matches!(group_ordering, GroupOrdering::None)
+            && aggregate_exprs
+                .iter()
+                .all(|expr| expr.groups_accumulator_supported()))
+        .then(|| {
+            SkipAggregationProbe::new(
+                options.skip_partial_aggregation_probe_rows_threshold,
+                options.skip_partial_aggregation_probe_ratio_threshold,
+                MetricBuilder::new(&agg.metrics)
+                    .gauge("rows_aggregated_before_skip", partition),
+                MetricBuilder::new(&agg.metrics)
+                    .counter("skipped_aggregation_rows", partition),
+            )
+        });
+
+        let max_emit_groups = max_emit_groups(&group_schema);
+        let group_values = new_group_values(group_schema, options.intern_group_columns)?;
         timer.done();
 
         let exec_state = ExecutionState::ReadingInput;
@@ -247,18 +356,22 @@ impl GroupedHashAggregateStream {
             schema: agg_schema,
             input,
             mode: agg.mode,
+            aggregate_exprs,
             accumulators,
             aggregate_arguments,
             filter_expressions,
             group_by: agg_group_by,
             reservation,
             group_values,
+            max_emit_groups,
             current_group_indices: Default::default(),
             exec_state,
             baseline_metrics,
+            mem_used,
             batch_size,
             group_ordering,
             input_done: false,
+            skip_aggregation_probe,
         })
     }
 }
@@ -320,6 +433,11 @@ impl Stream for GroupedHashAggregateStream {
                             if let Some(to_emit) = self.group_ordering.emit_to() {
                                 let batch = extract_ok!(self.emit(to_emit));
                                 self.exec_state = ExecutionState::ProducingOutput(batch);
+                            } else if self.should_skip_aggregation() {
+                                // emit the groups aggregated so far before
+                                // passing the input rows through
+                                let batch = extract_ok!(self.emit(EmitTo::All));
+                                self.exec_state = ExecutionState::ProducingOutput(batch);
                             }
                             timer.done();
                         }
@@ -342,8 +460,19 @@ impl Stream for GroupedHashAggregateStream {
                 ExecutionState::ProducingOutput(batch) => {
                     // slice off a part of the batch, if needed
                     let output_batch = if batch.num_rows() <= self.batch_size {
-                        if self.input_done {
+                        if (self.input_done || self.should_skip_aggregation())
+                            && !self.group_values.is_empty()
+                        {
+                            // emit the groups left by an emission limited
+                            // to `max_emit_groups`
+                            let timer = elapsed_compute.timer();
+                            let batch = extract_ok!(self.emit(EmitTo::All));
+                            self.exec_state = ExecutionState::ProducingOutput(batch);
+                            timer.done();
+                        } else if self.input_done {
                             self.exec_state = ExecutionState::Done;
+                        } else if self.should_skip_aggregation() {
+                            self.exec_state = ExecutionState::SkippingAggregation
                         } else {
                             self.exec_state = ExecutionState::ReadingInput
                         }
@@ -360,6 +489,24 @@ impl Stream for GroupedHashAggregateStream {
                     )));
                 }
 
+                ExecutionState::SkippingAggregation => {
+                    match ready!(self.input.poll_next_unpin(cx)) {
+                        Some(Ok(batch)) => {
+                            let timer = elapsed_compute.timer();
+                            let states = extract_ok!(self.transform_to_states(batch));
+                            timer.done();
+                            return Poll::Ready(Some(Ok(
+                                states.record_output(&self.baseline_metrics)
+                            )));
+                        }
+                        Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                        None => {
+                            self.input_done = true;
+                            self.exec_state = ExecutionState::Done;
+                        }
+                    }
+                }
+
                 ExecutionState::Done => return Poll::Ready(None),
             }
         }
@@ -424,7 +571,9 @@ impl GroupedHashAggregateStream {
                             total_num_groups,
                         )?;
                     }
-                    AggregateMode::FinalPartitioned | AggregateMode::Final => {
+                    AggregateMode::FinalPartitioned
+                    | AggregateMode::Final
+                    | AggregateMode::PartialReduce => {
                         // if aggregation is over intermediate states,
                         // use merge
                         acc.merge_batch(
@@ -438,16 +587,58 @@ impl GroupedHashAggregateStream {
             }
         }
 
+        if let Some(probe) = self.skip_aggregation_probe.as_mut() {
+            probe.update_state(batch.num_rows(), self.group_values.len());
+        }
+
         self.update_memory_reservation()
     }
 
+    /// Whether the partial aggregation stopped aggregating, see
+    /// [`SkipAggregationProbe`]
+    fn should_skip_aggregation(&self) -> bool {
+        self.skip_aggregation_probe
+            .as_ref()
+            .map_or(false, |probe| probe.should_skip())
+    }
+
+    /// Converts each row of `batch` to the group values and accumulator
+    /// states it would have as the only row of its group, as passed to the
+    /// final aggregation
+    fn transform_to_states(&mut self, batch: RecordBatch) -> Result<RecordBatch> {
+        let mut group_by_values = evaluate_group_by(&self.group_by, &batch)?;
+        let input_values = evaluate_many(&self.aggregate_arguments, &batch)?;
+        let filter_values = evaluate_optional(&self.filter_expressions, &batch)?;
+
+        let mut output = group_by_values.swap_remove(0);
+        let group_indices = (0..batch.num_rows()).collect::<Vec<_>>();
+        let t = self
+            .aggregate_exprs
+            .iter()
+            .zip(input_values.iter())
+            .zip(filter_values.iter());
+        for ((expr, values), opt_filter) in t {
+            let opt_filter = opt_filter.as_ref().map(|filter| filter.as_boolean());
+            let mut acc = create_group_accumulator(expr)?;
+            acc.update_batch(values, &group_indices, opt_filter, batch.num_rows())?;
+            output.extend(acc.state(EmitTo::All)?);
+        }
+
+        if let Some(probe) = self.skip_aggregation_probe.as_mut() {
+            probe.record_skipped(&batch);
+        }
+        Ok(RecordBatch::try_new(self.schema(), output)?)
+    }
+
     fn update_memory_reservation(&mut self) -> Result<()> {
         let acc = self.accumulators.iter().map(|x| x.size()).sum::<usize>();
-        self.reservation.try_resize(
+        let result = self.reservation.try_resize(
             acc + self.group_values.size()
                 + self.group_ordering.size()
                 + self.current_group_indices.allocated_size(),
-        )
+        );
+        self.mem_used.set_max(self.reservation.size());
+        result
     }
 
     /// Create an output RecordBatch with the group keys and
@@ -457,6 +648,16 @@ impl GroupedHashAggregateStream {
             return Ok(RecordBatch::new_empty(self.schema()));
         }
 
+        // the dictionary keys of the group columns may not be able to refer
+        // to the values of all the groups, the others are emitted later
+        let emit_to = match (emit_to, self.max_emit_groups) {
+            (EmitTo::All, Some(max)) if self.group_values.len() > max => {
+                EmitTo::First(max)
+            }
+            (EmitTo::First(n), Some(max)) if n > max => EmitTo::First(max),
+            (emit_to, _) => emit_to,
+        };
+
         let mut output = self.group_values.emit(emit_to)?;
         if let EmitTo::First(n) = emit_to {
             self.group_ordering.remove_groups(n);
@@ -465,7 +666,9 @@ impl GroupedHashAggregateStream {
         // Next output each aggregate value
         for acc in self.accumulators.iter_mut() {
             match self.mode {
-                AggregateMode::Partial => output.extend(acc.state(emit_to)?),
+                AggregateMode::Partial | AggregateMode::PartialReduce => {
+                    output.extend(acc.state(emit_to)?)
+                }
                 AggregateMode::Final
                 | AggregateMode::FinalPartitioned
                 | AggregateMode::Single
@@ -0,0 +1,331 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Sort based aggregation
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use arrow::compute::{filter, SortColumn};
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use datafusion_common::cast::as_boolean_array;
+use datafusion_common::utils::{evaluate_partition_ranges, get_row_at_idx};
+use datafusion_common::{Result, ScalarValue};
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::{AggregateExpr, PhysicalExpr};
+use futures::ready;
+use futures::stream::{Stream, StreamExt};
+use log::debug;
+
+use crate::physical_plan::aggregates::{
+    aggregate_expressions, create_accumulators, evaluate_group_by, evaluate_many,
+    evaluate_optional, AccumulatorItem, AggregateMode, PhysicalGroupBy,
+};
+use crate::physical_plan::metrics::{
+    BaselineMetrics, Gauge, MetricBuilder, RecordOutput,
+};
+use crate::physical_plan::{RecordBatchStream, SendableRecordBatchStream};
+
+use super::AggregateExec;
+
+/// Sort based Grouping Aggregator
+///
+/// Used when the input is sorted on all the `GROUP BY` expressions
+/// ([`GroupByOrderMode::FullyOrdered`]), so that the rows of each group
+/// are contiguous. Only the state of the group being aggregated is kept:
+/// as soon as a row of the next group arrives, the current group is
+/// complete and its result is computed and buffered until the end of the
+/// input batch. The memory used is thus independent of the number of
+/// groups.
+///
+/// ```text
+///   Input sorted on a           Accumulators            Output
+///
+///   a=1 b=3 ┐                ┌──────────────┐
+///   a=1 b=1 ┘ group 1 ──────▶│ SUM(b)       │─────▶   a=1 SUM(b)=4
+///   a=2 b=5 ┐                │ COUNT(b)     │
+///   a=2 b=2 ┘ group 2 ──────▶│   ...        │─────▶   a=2 SUM(b)=7
+///                            └──────────────┘
+/// ```
+///
+/// Unlike [`GroupedHashAggregateStream`], the aggregates are computed with
+/// one row based [`Accumulator`] per aggregate expression, which is created
+/// anew for each group. This avoids keeping an adapted [`Accumulator`] per
+/// group for aggregates without a `GroupsAccumulator`, such as `DISTINCT`
+/// ones, but is slower than [`GroupedHashAggregateStream`] for vectorized
+/// aggregates with many small groups, which is therefore used for those,
+/// see the `aggregate_sorted` benchmark.
+///
+/// [`GroupByOrderMode::FullyOrdered`]: super::GroupByOrderMode::FullyOrdered
+/// [`GroupedHashAggregateStream`]: super::row_hash::GroupedHashAggregateStream
+/// [`Accumulator`]: datafusion_expr::Accumulator
+pub(crate) struct SortedAggregateStream {
+    schema: SchemaRef,
+    input: SendableRecordBatchStream,
+    mode: AggregateMode,
+
+    /// The aggregate expressions, which create the accumulators
+    aggregate_exprs: Vec<Arc<dyn AggregateExpr>>,
+
+    /// Arguments to pass to each accumulator, see
+    /// [`aggregate_expressions`]
+    aggregate_arguments: Vec<Vec<Arc<dyn PhysicalExpr>>>,
+
+    /// Optional filter expression to evaluate, one for each for
+    /// accumulator
+    filter_expressions: Vec<Option<Arc<dyn PhysicalExpr>>>,
+
+    /// GROUP BY expressions
+    group_by: PhysicalGroupBy,
+
+    /// The values of the `GROUP BY` expressions of the group being
+    /// aggregated, `None` before the first input row
+    current_group: Option<Vec<ScalarValue>>,
+
+    /// Accumulators of the group being aggregated, one for each
+    /// `AggregateExpr` in the query
+    accumulators: Vec<AccumulatorItem>,
+
+    /// The output values of the complete groups that were not emitted yet,
+    /// one `Vec` per output column
+    completed_groups: Vec<Vec<ScalarValue>>,
+
+    /// The memory reservation for the current group and the complete groups
+    reservation: MemoryReservation,
+
+    /// Execution metrics
+    baseline_metrics: BaselineMetrics,
+
+    /// Peak memory reserved by this stream
+    mem_used: Gauge,
+
+    /// Have we seen the end of the input
+    input_done: bool,
+}
+
+impl SortedAggregateStream {
+    /// Create a new SortedAggregateStream
+    pub fn new(
+        agg: &AggregateExec,
+        context: Arc<TaskContext>,
+        partition: usize,
+    ) -> Result<Self> {
+        debug!("Creating SortedAggregateStream");
+        let input = agg.input.execute(partition, Arc::clone(&context))?;
+        let baseline_metrics = BaselineMetrics::new(&agg.metrics, partition);
+        let mem_used = MetricBuilder::new(&agg.metrics).mem_used(partition);
+
+        let aggregate_arguments =
+            aggregate_expressions(&agg.aggr_expr, &agg.mode, agg.group_by.expr.len())?;
+
+        let filter_expressions = match agg.mode {
+            AggregateMode::Partial
+            | AggregateMode::Single
+            | AggregateMode::SinglePartitioned => agg.filter_expr.clone(),
+            AggregateMode::Final
+            | AggregateMode::FinalPartitioned
+            | AggregateMode::PartialReduce => vec![None; agg.aggr_expr.len()],
+        };
+
+        let accumulators = create_accumulators(&agg.aggr_expr)?;
+
+        let name = format!("SortedAggregateStream[{partition}]");
+        let reservation = MemoryConsumer::new(name).register(context.memory_pool());
+
+        Ok(SortedAggregateStream {
+            schema: Arc::clone(&agg.schema),
+            input,
+            mode: agg.mode,
+            aggregate_exprs: agg.aggr_expr.clone(),
+            aggregate_arguments,
+            filter_expressions,
+            group_by: agg.group_by.clone(),
+            current_group: None,
+            accumulators,
+            completed_groups: vec![vec![]; agg.schema.fields().len()],
+            reservation,
+            baseline_metrics,
+            mem_used,
+            input_done: false,
+        })
+    }
+
+    /// Aggregates `batch`, completing the current group if the batch
+    /// starts a new one
+    fn aggregate_batch(&mut self, batch: &RecordBatch) -> Result<()> {
+        if batch.num_rows() == 0 {
+            return Ok(());
+        }
+
+        // `AggregateExec` only uses this stream with a single grouping set
+        let group_by_values = evaluate_group_by(&self.group_by, batch)?.swap_remove(0);
+        let input_values = evaluate_many(&self.aggregate_arguments, batch)?;
+        let filter_values = evaluate_optional(&self.filter_expressions, batch)?;
+
+        let sort_columns = group_by_values
+            .iter()
+            .map(|values| SortColumn {
+                values: values.clone(),
+                options: None,
+            })
+            .collect::<Vec<_>>();
+
+        // The input is sorted on the group values, so each range of equal
+        // group values is a whole group, or the continuation of the current
+        // group for the first range
+        for range in evaluate_partition_ranges(batch.num_rows(), &sort_columns)? {
+            let group = get_row_at_idx(&group_by_values, range.start)?;
+            if self.current_group.as_ref() != Some(&group) {
+                self.complete_group()?;
+                self.current_group = Some(group);
+            }
+
+            let length = range.end - range.start;
+            let t = self
+                .accumulators
+                .iter_mut()
+                .zip(input_values.iter())
+                .zip(filter_values.iter());
+            for ((acc, values), opt_filter) in t {
+                let mut values = values
+                    .iter()
+                    .map(|values| values.slice(range.start, length))
+                    .collect::<Vec<_>>();
+                if let Some(opt_filter) = opt_filter {
+                    let opt_filter = opt_filter.slice(range.start, length);
+                    let opt_filter = as_boolean_array(&opt_filter)?;
+                    values = values
+                        .iter()
+                        .map(|values| Ok(filter(values, opt_filter)?))
+                        .collect::<Result<_>>()?;
+                }
+
+                match self.mode {
+                    AggregateMode::Partial
+                    | AggregateMode::Single
+                    | AggregateMode::SinglePartitioned => acc.update_batch(&values)?,
+                    AggregateMode::Final
+                    | AggregateMode::FinalPartitioned
+                    | AggregateMode::PartialReduce => acc.merge_batch(&values)?,
+                }
+            }
+        }
+
+        self.update_memory_reservation()
+    }
+
+    /// Computes the output values of the current group, if any, and resets
+    /// the accumulators for the next group
+    fn complete_group(&mut self) -> Result<()> {
+        let Some(mut output) = self.current_group.take() else {
+            return Ok(());
+        };
+        let accumulators = std::mem::replace(
+            &mut self.accumulators,
+            create_accumulators(&self.aggregate_exprs)?,
+        );
+        for acc in accumulators.iter() {
+            match self.mode {
+                AggregateMode::Partial | AggregateMode::PartialReduce => {
+                    output.extend(acc.state()?)
+                }
+                AggregateMode::Final
+                | AggregateMode::FinalPartitioned
+                | AggregateMode::Single
+                | AggregateMode::SinglePartitioned => output.push(acc.evaluate()?),
+            }
+        }
+        self.completed_groups
+            .iter_mut()
+            .zip(output)
+            .for_each(|(column, value)| column.push(value));
+        Ok(())
+    }
+
+    /// Creates an output `RecordBatch` with the complete groups, if any
+    fn emit(&mut self) -> Result<Option<RecordBatch>> {
+        if self.completed_groups.iter().all(|column| column.is_empty()) {
+            return Ok(None);
+        }
+        let columns = self
+            .completed_groups
+            .iter_mut()
+            .map(|column| ScalarValue::iter_to_array(std::mem::take(column)))
+            .collect::<Result<Vec<_>>>()?;
+        self.update_memory_reservation()?;
+        Ok(Some(RecordBatch::try_new(self.schema.clone(), columns)?))
+    }
+
+    fn update_memory_reservation(&mut self) -> Result<()> {
+        let acc = self.accumulators.iter().map(|x| x.size()).sum::<usize>();
+        let completed = self
+            .completed_groups
+            .iter()
+            .flatten()
+            .map(|value| value.size())
+            .sum::<usize>();
+        let result = self.reservation.try_resize(acc + completed);
+        self.mem_used.set_max(self.reservation.size());
+        result
+    }
+}
+
+impl Stream for SortedAggregateStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let elapsed_compute = self.baseline_metrics.elapsed_compute().clone();
+
+        while !self.input_done {
+            let result = match ready!(self.input.poll_next_unpin(cx)) {
+                // new batch to aggregate
+                Some(Ok(batch)) => {
+                    let _timer = elapsed_compute.timer();
+                    self.aggregate_batch(&batch).and_then(|_| self.emit())
+                }
+                Some(Err(e)) => Err(e),
+                None => {
+                    // inner is done, complete the last group
+                    self.input_done = true;
+                    let _timer = elapsed_compute.timer();
+                    self.complete_group().and_then(|_| self.emit())
+                }
+            };
+            match result {
+                Ok(Some(batch)) => {
+                    return Poll::Ready(Some(Ok(
+                        batch.record_output(&self.baseline_metrics)
+                    )))
+                }
+                Ok(None) => continue,
+                Err(e) => return Poll::Ready(Some(Err(e))),
+            }
+        }
+        Poll::Ready(None)
+    }
+}
+
+impl RecordBatchStream for SortedAggregateStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
@@ -22,8 +22,10 @@ use crate::physical_plan::stream::RecordBatchReceiverStream;
 use crate::physical_plan::{ColumnStatistics, ExecutionPlan, Statistics};
 use arrow::datatypes::Schema;
 use arrow::ipc::writer::{FileWriter, IpcWriteOptions};
+use arrow::ipc::CompressionType;
 use arrow::record_batch::RecordBatch;
-use datafusion_common::{plan_err, DataFusionError, Result};
+use datafusion_common::config::ConfigOptions;
+use datafusion_common::{plan_err, DataFusionError, Precision, Result};
 use datafusion_execution::memory_pool::MemoryReservation;
 use datafusion_physical_expr::expressions::{BinaryExpr, Column};
 use datafusion_physical_expr::{PhysicalExpr, PhysicalSortExpr};
@@ -142,22 +144,27 @@ pub fn compute_record_batch_statistics(
         None => (0..schema.fields().len()).collect(),
     };
 
-    let mut column_statistics = vec![ColumnStatistics::default(); projection.len()];
+    let mut null_counts = vec![0; projection.len()];
 
     for partition in batches.iter() {
         for batch in partition {
             for (stat_index, col_index) in projection.iter().enumerate() {
-                *column_statistics[stat_index].null_count.get_or_insert(0) +=
-                    batch.column(*col_index).null_count();
+                null_counts[stat_index] += batch.column(*col_index).null_count();
             }
         }
     }
+    let column_statistics = null_counts
+        .into_iter()
+        .map(|null_count| ColumnStatistics {
+            null_count: Precision::Exact(null_count),
+            ..Default::default()
+        })
+        .collect();
 
     Statistics {
-        num_rows: Some(nb_rows),
-        total_byte_size: Some(total_byte_size),
+        num_rows: Precision::Exact(nb_rows),
+        total_byte_size: Precision::Exact(total_byte_size),
         column_statistics: Some(column_statistics),
-        is_exact: true,
     }
 }
 
@@ -360,6 +367,24 @@ impl IPCWriter {
     }
 }
 
+/// Returns the [`IpcWriteOptions`] for spill files, compressed with the codec
+/// configured by `datafusion.execution.spill_compression`
+pub fn spill_write_options(options: &ConfigOptions) -> Result<IpcWriteOptions> {
+    let codec = options.execution.spill_compression.to_lowercase();
+    let compression = match codec.as_str() {
+        "uncompressed" => None,
+        "lz4_frame" => Some(CompressionType::LZ4_FRAME),
+        "zstd" => Some(CompressionType::ZSTD),
+        _ => {
+            return Err(DataFusionError::Configuration(format!(
+                "Unknown spill compression '{codec}', valid values are: \
+                 uncompressed, lz4_frame and zstd"
+            )))
+        }
+    };
+    Ok(IpcWriteOptions::default().try_with_compression(compression)?)
+}
+
 /// Returns the total number of bytes of memory occupied physically by this batch.
 #[deprecated(since = "28.0.0", note = "RecordBatch::get_array_memory_size")]
 pub fn batch_byte_size(batch: &RecordBatch) -> usize {
@@ -667,9 +692,8 @@ mod tests {
         ]));
         let stats = compute_record_batch_statistics(&[], &schema, Some(vec![0, 1]));
 
-        assert_eq!(stats.num_rows, Some(0));
-        assert!(stats.is_exact);
-        assert_eq!(stats.total_byte_size, Some(0));
+        assert_eq!(stats.num_rows, Precision::Exact(0));
+        assert_eq!(stats.total_byte_size, Precision::Exact(0));
         Ok(())
     }
 
@@ -690,21 +714,24 @@ mod tests {
             compute_record_batch_statistics(&[vec![batch]], &schema, Some(vec![0, 1]));
 
         let mut expected = Statistics {
-            is_exact: true,
-            num_rows: Some(3),
-            total_byte_size: Some(464), // this might change a bit if the way we compute the size changes
+            num_rows: Precision::Exact(3),
+            total_byte_size: Precision::Exact(464), // this might change a bit if the way we compute the size changes
             column_statistics: Some(vec![
                 ColumnStatistics {
-                    distinct_count: None,
-                    max_value: None,
-                    min_value: None,
-                    null_count: Some(0),
+                    distinct_count: Precision::Absent,
+                    max_value: Precision::Absent,
+                    min_value: Precision::Absent,
+                    null_count: Precision::Exact(0),
+                    histogram: None,
+                    most_common_values: None,
                 },
                 ColumnStatistics {
-                    distinct_count: None,
-                    max_value: None,
-                    min_value: None,
-                    null_count: Some(0),
+                    distinct_count: Precision::Absent,
+                    max_value: Precision::Absent,
+                    min_value: Precision::Absent,
+                    null_count: Precision::Exact(0),
+                    histogram: None,
+                    most_common_values: None,
                 },
             ]),
         };
@@ -724,4 +751,22 @@ mod tests {
         assert_eq!(expected, transposed);
         Ok(())
     }
+
+    #[test]
+    fn test_spill_write_options() -> Result<()> {
+        let mut options = ConfigOptions::new();
+        for codec in ["uncompressed", "lz4_frame", "ZSTD"] {
+            options.execution.spill_compression = codec.to_string();
+            spill_write_options(&options)?;
+        }
+
+        options.execution.spill_compression = "snappy".to_string();
+        let err = spill_write_options(&options).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid or Unsupported Configuration: Unknown spill compression 'snappy', \
+             valid values are: uncompressed, lz4_frame and zstd"
+        );
+        Ok(())
+    }
 }
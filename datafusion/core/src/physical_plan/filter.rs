@@ -28,6 +28,7 @@ use super::{
     ColumnStatistics, DisplayAs, RecordBatchStream, SendableRecordBatchStream, Statistics,
 };
 
+use crate::physical_plan::joins::runtime_filter::{filter_stream, RuntimeFilter};
 use crate::physical_plan::{
     metrics::{BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet},
     Column, DisplayFormatType, EquivalenceProperties, ExecutionPlan, Partitioning,
@@ -37,7 +38,7 @@ use arrow::compute::filter_record_batch;
 use arrow::datatypes::{DataType, SchemaRef};
 use arrow::record_batch::RecordBatch;
 use datafusion_common::cast::as_boolean_array;
-use datafusion_common::{plan_err, DataFusionError, Result};
+use datafusion_common::{plan_err, DataFusionError, Precision, Result};
 use datafusion_execution::TaskContext;
 use datafusion_expr::Operator;
 use datafusion_physical_expr::expressions::BinaryExpr;
@@ -60,6 +61,8 @@ pub struct FilterExec {
     input: Arc<dyn ExecutionPlan>,
     /// Execution metrics
     metrics: ExecutionPlanMetricsSet,
+    /// Filters published by joins at runtime, applied to the input batches
+    runtime_filters: Vec<RuntimeFilter>,
 }
 
 impl FilterExec {
@@ -73,6 +76,7 @@ impl FilterExec {
                 predicate,
                 input: input.clone(),
                 metrics: ExecutionPlanMetricsSet::new(),
+                runtime_filters: vec![],
             }),
             other => {
                 plan_err!("Filter predicate must return boolean values, not {other:?}")
@@ -89,6 +93,19 @@ impl FilterExec {
     pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
         &self.input
     }
+
+    /// Also filters out the rows rejected by `runtime_filters` once the joins
+    /// they belong to have published them. These filters are not part of the
+    /// predicate, and are not displayed.
+    pub fn with_runtime_filters(mut self, runtime_filters: Vec<RuntimeFilter>) -> Self {
+        self.runtime_filters = runtime_filters;
+        self
+    }
+
+    /// The filters published by joins at runtime that are applied
+    pub fn runtime_filters(&self) -> &[RuntimeFilter] {
+        &self.runtime_filters
+    }
 }
 
 impl DisplayAs for FilterExec {
@@ -160,10 +177,10 @@ impl ExecutionPlan for FilterExec {
         self: Arc<Self>,
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(FilterExec::try_new(
-            self.predicate.clone(),
-            children[0].clone(),
-        )?))
+        Ok(Arc::new(
+            FilterExec::try_new(self.predicate.clone(), children[0].clone())?
+                .with_runtime_filters(self.runtime_filters.clone()),
+        ))
     }
 
     fn execute(
@@ -176,7 +193,10 @@ impl ExecutionPlan for FilterExec {
         Ok(Box::pin(FilterExecStream {
             schema: self.input.schema(),
             predicate: self.predicate.clone(),
-            input: self.input.execute(partition, context)?,
+            input: filter_stream(
+                self.input.execute(partition, context)?,
+                &self.runtime_filters,
+            ),
             baseline_metrics,
         }))
     }
@@ -210,12 +230,10 @@ impl ExecutionPlan for FilterExec {
 
         let selectivity = analysis_ctx.selectivity.unwrap_or(1.0);
 
-        let num_rows = input_stats
-            .num_rows
-            .map(|num| (num as f64 * selectivity).ceil() as usize);
+        let num_rows = input_stats.num_rows.with_estimated_selectivity(selectivity);
         let total_byte_size = input_stats
             .total_byte_size
-            .map(|size| (size as f64 * selectivity).ceil() as usize);
+            .with_estimated_selectivity(selectivity);
 
         let column_statistics = if let Some(analysis_boundaries) = analysis_ctx.boundaries
         {
@@ -228,7 +246,6 @@ impl ExecutionPlan for FilterExec {
             num_rows,
             total_byte_size,
             column_statistics: Some(column_statistics),
-            is_exact: Default::default(),
         }
     }
 }
@@ -256,11 +273,31 @@ fn collect_new_statistics(
                 },
             )| {
                 let closed_interval = interval.close_bounds();
+                let input_stats = &input_column_stats[idx];
+                let (max_value, min_value) = if nonempty_columns {
+                    (
+                        Precision::Inexact(closed_interval.upper.value),
+                        Precision::Inexact(closed_interval.lower.value),
+                    )
+                } else {
+                    (Precision::Absent, Precision::Absent)
+                };
+                // The value distribution of a column is only kept if the
+                // predicate does not narrow down its boundaries
+                let unchanged = max_value.get_value()
+                    == input_stats.max_value.get_value()
+                    && min_value.get_value() == input_stats.min_value.get_value();
                 ColumnStatistics {
-                    null_count: input_column_stats[idx].null_count,
-                    max_value: nonempty_columns.then_some(closed_interval.upper.value),
-                    min_value: nonempty_columns.then_some(closed_interval.lower.value),
-                    distinct_count,
+                    null_count: input_stats.null_count.to_inexact(),
+                    max_value,
+                    min_value,
+                    distinct_count: distinct_count
+                        .map(Precision::Inexact)
+                        .unwrap_or_default(),
+                    histogram: unchanged.then(|| input_stats.histogram.clone()).flatten(),
+                    most_common_values: unchanged
+                        .then(|| input_stats.most_common_values.clone())
+                        .flatten(),
                 }
             },
         )
@@ -386,8 +423,10 @@ mod tests {
     use crate::test_util;
     use arrow::datatypes::{DataType, Field, Schema};
     use datafusion_common::utils::DataPtr;
-    use datafusion_common::ColumnStatistics;
     use datafusion_common::ScalarValue;
+    use datafusion_common::{
+        ColumnStatistics, Histogram, HistogramBucket, ValueFrequency,
+    };
     use datafusion_expr::Operator;
     use std::iter::Iterator;
     use std::sync::Arc;
@@ -489,6 +528,70 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_filter_statistics_histogram() -> Result<()> {
+        // Table:
+        //      a: min=1, max=11, 90% of the rows hold the value 1
+        let bytes_per_row = 4;
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
+        let bucket = |lower: i32, upper: i32, num_rows: usize| HistogramBucket {
+            lower: ScalarValue::Int32(Some(lower)),
+            upper: ScalarValue::Int32(Some(upper)),
+            num_rows,
+            distinct_count: (upper - lower + 1) as usize,
+        };
+        let input = Arc::new(StatisticsExec::new(
+            Statistics {
+                num_rows: Precision::Inexact(100),
+                total_byte_size: Precision::Inexact(100 * bytes_per_row),
+                column_statistics: Some(vec![ColumnStatistics {
+                    min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
+                    max_value: Precision::Inexact(ScalarValue::Int32(Some(11))),
+                    histogram: Some(Histogram {
+                        buckets: vec![bucket(1, 1, 90), bucket(2, 11, 10)],
+                    }),
+                    most_common_values: Some(vec![ValueFrequency {
+                        value: ScalarValue::Int32(Some(1)),
+                        count: 90,
+                    }]),
+                    ..Default::default()
+                }]),
+                ..Default::default()
+            },
+            schema.clone(),
+        ));
+
+        // WHERE a = 1
+        let predicate = binary(col("a", &schema)?, Operator::Eq, lit(1i32), &schema)?;
+        let filter = FilterExec::try_new(predicate, input.clone())?;
+        let statistics = filter.statistics();
+        assert_eq!(statistics.num_rows, Precision::Inexact(90));
+        assert_eq!(
+            statistics.total_byte_size,
+            Precision::Inexact(90 * bytes_per_row)
+        );
+
+        // WHERE a >= 2
+        let predicate = binary(col("a", &schema)?, Operator::GtEq, lit(2i32), &schema)?;
+        let filter = FilterExec::try_new(predicate, input)?;
+        let statistics = filter.statistics();
+        assert_eq!(statistics.num_rows, Precision::Inexact(10));
+        assert_eq!(
+            statistics.total_byte_size,
+            Precision::Inexact(10 * bytes_per_row)
+        );
+        assert_eq!(
+            statistics.column_statistics,
+            Some(vec![ColumnStatistics {
+                min_value: Precision::Inexact(ScalarValue::Int32(Some(2))),
+                max_value: Precision::Inexact(ScalarValue::Int32(Some(11))),
+                ..Default::default()
+            }])
+        );
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_filter_statistics_basic_expr() -> Result<()> {
         // Table:
@@ -497,11 +600,11 @@ mod tests {
         let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
         let input = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: Some(100),
-                total_byte_size: Some(100 * bytes_per_row),
+                num_rows: Precision::Inexact(100),
+                total_byte_size: Precision::Inexact(100 * bytes_per_row),
                 column_statistics: Some(vec![ColumnStatistics {
-                    min_value: Some(ScalarValue::Int32(Some(1))),
-                    max_value: Some(ScalarValue::Int32(Some(100))),
+                    min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
+                    max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
                     ..Default::default()
                 }]),
                 ..Default::default()
@@ -518,13 +621,16 @@ mod tests {
             Arc::new(FilterExec::try_new(predicate, input)?);
 
         let statistics = filter.statistics();
-        assert_eq!(statistics.num_rows, Some(25));
-        assert_eq!(statistics.total_byte_size, Some(25 * bytes_per_row));
+        assert_eq!(statistics.num_rows, Precision::Inexact(25));
+        assert_eq!(
+            statistics.total_byte_size,
+            Precision::Inexact(25 * bytes_per_row)
+        );
         assert_eq!(
             statistics.column_statistics,
             Some(vec![ColumnStatistics {
-                min_value: Some(ScalarValue::Int32(Some(1))),
-                max_value: Some(ScalarValue::Int32(Some(25))),
+                min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
+                max_value: Precision::Inexact(ScalarValue::Int32(Some(25))),
                 ..Default::default()
             }])
         );
@@ -539,10 +645,10 @@ mod tests {
         let schema = Schema::new(vec![Field::new("a", DataType::Int32, false)]);
         let input = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: Some(100),
+                num_rows: Precision::Inexact(100),
                 column_statistics: Some(vec![ColumnStatistics {
-                    min_value: Some(ScalarValue::Int32(Some(1))),
-                    max_value: Some(ScalarValue::Int32(Some(100))),
+                    min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
+                    max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
                     ..Default::default()
                 }]),
                 ..Default::default()
@@ -565,12 +671,12 @@ mod tests {
         )?);
 
         let statistics = filter.statistics();
-        assert_eq!(statistics.num_rows, Some(16));
+        assert_eq!(statistics.num_rows, Precision::Inexact(16));
         assert_eq!(
             statistics.column_statistics,
             Some(vec![ColumnStatistics {
-                min_value: Some(ScalarValue::Int32(Some(10))),
-                max_value: Some(ScalarValue::Int32(Some(25))),
+                min_value: Precision::Inexact(ScalarValue::Int32(Some(10))),
+                max_value: Precision::Inexact(ScalarValue::Int32(Some(25))),
                 ..Default::default()
             }])
         );
@@ -589,16 +695,16 @@ mod tests {
         ]);
         let input = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: Some(100),
+                num_rows: Precision::Inexact(100),
                 column_statistics: Some(vec![
                     ColumnStatistics {
-                        min_value: Some(ScalarValue::Int32(Some(1))),
-                        max_value: Some(ScalarValue::Int32(Some(100))),
+                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
+                        max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
                         ..Default::default()
                     },
                     ColumnStatistics {
-                        min_value: Some(ScalarValue::Int32(Some(1))),
-                        max_value: Some(ScalarValue::Int32(Some(50))),
+                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
+                        max_value: Precision::Inexact(ScalarValue::Int32(Some(50))),
                         ..Default::default()
                     },
                 ]),
@@ -631,18 +737,18 @@ mod tests {
         //
         // Which would result with a selectivity of  '15/100 * 5/50' or 0.015
         // and that means about %1.5 of the all rows (rounded up to 2 rows).
-        assert_eq!(statistics.num_rows, Some(2));
+        assert_eq!(statistics.num_rows, Precision::Inexact(2));
         assert_eq!(
             statistics.column_statistics,
             Some(vec![
                 ColumnStatistics {
-                    min_value: Some(ScalarValue::Int32(Some(10))),
-                    max_value: Some(ScalarValue::Int32(Some(25))),
+                    min_value: Precision::Inexact(ScalarValue::Int32(Some(10))),
+                    max_value: Precision::Inexact(ScalarValue::Int32(Some(25))),
                     ..Default::default()
                 },
                 ColumnStatistics {
-                    min_value: Some(ScalarValue::Int32(Some(46))),
-                    max_value: Some(ScalarValue::Int32(Some(50))),
+                    min_value: Precision::Inexact(ScalarValue::Int32(Some(46))),
+                    max_value: Precision::Inexact(ScalarValue::Int32(Some(50))),
                     ..Default::default()
                 }
             ])
@@ -675,7 +781,7 @@ mod tests {
             Arc::new(FilterExec::try_new(predicate, input)?);
 
         let statistics = filter.statistics();
-        assert_eq!(statistics.num_rows, None);
+        assert_eq!(statistics.num_rows, Precision::Absent);
 
         Ok(())
     }
@@ -693,22 +799,22 @@ mod tests {
         ]);
         let input = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: Some(1000),
-                total_byte_size: Some(4000),
+                num_rows: Precision::Inexact(1000),
+                total_byte_size: Precision::Inexact(4000),
                 column_statistics: Some(vec![
                     ColumnStatistics {
-                        min_value: Some(ScalarValue::Int32(Some(1))),
-                        max_value: Some(ScalarValue::Int32(Some(100))),
+                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
+                        max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
                         ..Default::default()
                     },
                     ColumnStatistics {
-                        min_value: Some(ScalarValue::Int32(Some(1))),
-                        max_value: Some(ScalarValue::Int32(Some(3))),
+                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
+                        max_value: Precision::Inexact(ScalarValue::Int32(Some(3))),
                         ..Default::default()
                     },
                     ColumnStatistics {
-                        min_value: Some(ScalarValue::Float32(Some(1000.0))),
-                        max_value: Some(ScalarValue::Float32(Some(1100.0))),
+                        min_value: Precision::Inexact(ScalarValue::Float32(Some(1000.0))),
+                        max_value: Precision::Inexact(ScalarValue::Float32(Some(1100.0))),
                         ..Default::default()
                     },
                 ]),
@@ -752,22 +858,22 @@ mod tests {
         // 0.5 (from a) * 0.333333... (from b) * 0.798387... (from c) ≈ 0.1330...
         // num_rows after ceil => 133.0... => 134
         // total_byte_size after ceil => 532.0... => 533
-        assert_eq!(statistics.num_rows, Some(134));
-        assert_eq!(statistics.total_byte_size, Some(533));
+        assert_eq!(statistics.num_rows, Precision::Inexact(134));
+        assert_eq!(statistics.total_byte_size, Precision::Inexact(533));
         let exp_col_stats = vec![
             ColumnStatistics {
-                min_value: Some(ScalarValue::Int32(Some(4))),
-                max_value: Some(ScalarValue::Int32(Some(53))),
+                min_value: Precision::Inexact(ScalarValue::Int32(Some(4))),
+                max_value: Precision::Inexact(ScalarValue::Int32(Some(53))),
                 ..Default::default()
             },
             ColumnStatistics {
-                min_value: Some(ScalarValue::Int32(Some(3))),
-                max_value: Some(ScalarValue::Int32(Some(3))),
+                min_value: Precision::Inexact(ScalarValue::Int32(Some(3))),
+                max_value: Precision::Inexact(ScalarValue::Int32(Some(3))),
                 ..Default::default()
             },
             ColumnStatistics {
-                min_value: Some(ScalarValue::Float32(Some(1000.0))),
-                max_value: Some(ScalarValue::Float32(Some(1075.0))),
+                min_value: Precision::Inexact(ScalarValue::Float32(Some(1000.0))),
+                max_value: Precision::Inexact(ScalarValue::Float32(Some(1075.0))),
                 ..Default::default()
             },
         ];
@@ -777,17 +883,17 @@ mod tests {
             .map(|(expected, actual)| {
                 if actual
                     .min_value
-                    .clone()
+                    .get_value()
                     .unwrap()
                     .get_datatype()
                     .is_floating()
                 {
                     // Windows rounds arithmetic operation results differently for floating point numbers.
                     // Therefore, we check if the actual values are in an epsilon range.
-                    let actual_min = actual.min_value.unwrap();
-                    let actual_max = actual.max_value.unwrap();
-                    let expected_min = expected.min_value.unwrap();
-                    let expected_max = expected.max_value.unwrap();
+                    let actual_min = actual.min_value.get_value().unwrap().clone();
+                    let actual_max = actual.max_value.get_value().unwrap().clone();
+                    let expected_min = expected.min_value.get_value().unwrap().clone();
+                    let expected_max = expected.max_value.get_value().unwrap().clone();
                     let eps = ScalarValue::Float32(Some(1e-6));
 
                     assert!(actual_min.sub(&expected_min).unwrap() < eps);
@@ -814,17 +920,17 @@ mod tests {
         ]);
         let input = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: Some(1000),
-                total_byte_size: Some(4000),
+                num_rows: Precision::Inexact(1000),
+                total_byte_size: Precision::Inexact(4000),
                 column_statistics: Some(vec![
                     ColumnStatistics {
-                        min_value: Some(ScalarValue::Int32(Some(1))),
-                        max_value: Some(ScalarValue::Int32(Some(100))),
+                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
+                        max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
                         ..Default::default()
                     },
                     ColumnStatistics {
-                        min_value: Some(ScalarValue::Int32(Some(1))),
-                        max_value: Some(ScalarValue::Int32(Some(3))),
+                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
+                        max_value: Precision::Inexact(ScalarValue::Int32(Some(3))),
                         ..Default::default()
                     },
                 ]),
@@ -852,8 +958,8 @@ mod tests {
             Arc::new(FilterExec::try_new(predicate, input)?);
         let statistics = filter.statistics();
 
-        assert_eq!(statistics.num_rows, Some(1000));
-        assert_eq!(statistics.total_byte_size, Some(4000));
+        assert_eq!(statistics.num_rows, Precision::Inexact(1000));
+        assert_eq!(statistics.total_byte_size, Precision::Inexact(4000));
         assert_eq!(statistics.column_statistics, expected);
 
         Ok(())
@@ -870,17 +976,17 @@ mod tests {
         ]);
         let input = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: Some(1000),
-                total_byte_size: Some(4000),
+                num_rows: Precision::Inexact(1000),
+                total_byte_size: Precision::Inexact(4000),
                 column_statistics: Some(vec![
                     ColumnStatistics {
-                        min_value: Some(ScalarValue::Int32(Some(1))),
-                        max_value: Some(ScalarValue::Int32(Some(100))),
+                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
+                        max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
                         ..Default::default()
                     },
                     ColumnStatistics {
-                        min_value: Some(ScalarValue::Int32(Some(1))),
-                        max_value: Some(ScalarValue::Int32(Some(3))),
+                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
+                        max_value: Precision::Inexact(ScalarValue::Int32(Some(3))),
                         ..Default::default()
                     },
                 ]),
@@ -906,19 +1012,19 @@ mod tests {
             Arc::new(FilterExec::try_new(predicate, input)?);
         let statistics = filter.statistics();
 
-        assert_eq!(statistics.num_rows, Some(0));
-        assert_eq!(statistics.total_byte_size, Some(0));
+        assert_eq!(statistics.num_rows, Precision::Inexact(0));
+        assert_eq!(statistics.total_byte_size, Precision::Inexact(0));
         assert_eq!(
             statistics.column_statistics,
             Some(vec![
                 ColumnStatistics {
-                    min_value: None,
-                    max_value: None,
+                    min_value: Precision::Absent,
+                    max_value: Precision::Absent,
                     ..Default::default()
                 },
                 ColumnStatistics {
-                    min_value: None,
-                    max_value: None,
+                    min_value: Precision::Absent,
+                    max_value: Precision::Absent,
                     ..Default::default()
                 },
             ])
@@ -935,17 +1041,17 @@ mod tests {
         ]);
         let input = Arc::new(StatisticsExec::new(
             Statistics {
-                num_rows: Some(1000),
-                total_byte_size: Some(4000),
+                num_rows: Precision::Inexact(1000),
+                total_byte_size: Precision::Inexact(4000),
                 column_statistics: Some(vec![
                     ColumnStatistics {
-                        min_value: Some(ScalarValue::Int32(Some(1))),
-                        max_value: Some(ScalarValue::Int32(Some(100))),
+                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
+                        max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
                         ..Default::default()
                     },
                     ColumnStatistics {
-                        min_value: Some(ScalarValue::Int32(Some(1))),
-                        max_value: Some(ScalarValue::Int32(Some(100))),
+                        min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
+                        max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
                         ..Default::default()
                     },
                 ]),
@@ -963,19 +1069,19 @@ mod tests {
             Arc::new(FilterExec::try_new(predicate, input)?);
         let statistics = filter.statistics();
 
-        assert_eq!(statistics.num_rows, Some(490));
-        assert_eq!(statistics.total_byte_size, Some(1960));
+        assert_eq!(statistics.num_rows, Precision::Inexact(490));
+        assert_eq!(statistics.total_byte_size, Precision::Inexact(1960));
         assert_eq!(
             statistics.column_statistics,
             Some(vec![
                 ColumnStatistics {
-                    min_value: Some(ScalarValue::Int32(Some(1))),
-                    max_value: Some(ScalarValue::Int32(Some(49))),
+                    min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
+                    max_value: Precision::Inexact(ScalarValue::Int32(Some(49))),
                     ..Default::default()
                 },
                 ColumnStatistics {
-                    min_value: Some(ScalarValue::Int32(Some(1))),
-                    max_value: Some(ScalarValue::Int32(Some(100))),
+                    min_value: Precision::Inexact(ScalarValue::Int32(Some(1))),
+                    max_value: Precision::Inexact(ScalarValue::Int32(Some(100))),
                     ..Default::default()
                 },
             ])
@@ -37,6 +37,7 @@ use crate::physical_plan::{
     expressions::PhysicalSortExpr,
     hash_utils::create_hashes,
     joins::hash_join_utils::{JoinHashMap, JoinHashMapType},
+    joins::runtime_filter::{replace_join_filter, KeyBounds, RuntimeJoinFilter},
     joins::utils::{
         adjust_right_output_partitioning, build_join_schema, check_join_is_valid,
         combine_join_equivalence_properties, estimate_join_statistics,
@@ -44,6 +45,7 @@ use crate::physical_plan::{
         JoinFilter, JoinOn,
     },
     metrics::{ExecutionPlanMetricsSet, MetricsSet},
+    repartition::HeavyHitters,
     DisplayFormatType, Distribution, EquivalenceProperties, ExecutionPlan, Partitioning,
     PhysicalExpr, RecordBatchStream, SendableRecordBatchStream, Statistics,
 };
@@ -110,6 +112,11 @@ pub struct HashJoinExec {
     column_indices: Vec<ColumnIndex>,
     /// If null_equals_null is true, null == null else null != null
     pub(crate) null_equals_null: bool,
+    /// Filter to publish the bounds of the build side join keys to
+    runtime_filter: Option<Arc<RuntimeJoinFilter>>,
+    /// Heavy hitters of the join keys the inputs are partitioned with skew
+    /// handling for
+    heavy_hitters: Option<HeavyHitters>,
 }
 
 impl HashJoinExec {
@@ -162,9 +169,50 @@ impl HashJoinExec {
             column_indices,
             null_equals_null,
             output_order,
+            runtime_filter: None,
+            heavy_hitters: None,
         })
     }
 
+    /// Publishes the bounds of the build side join keys to `runtime_filter`
+    /// once the build side has been collected, so that probe side operators
+    /// it has been pushed down to can skip rows that cannot match.
+    ///
+    /// The filter is only published in [`PartitionMode::CollectLeft`], where
+    /// the build side is shared by all output partitions.
+    pub fn with_runtime_filter(mut self, runtime_filter: Arc<RuntimeJoinFilter>) -> Self {
+        self.runtime_filter = Some(runtime_filter);
+        self
+    }
+
+    /// The filter the bounds of the build side join keys are published to
+    pub fn runtime_filter(&self) -> Option<&Arc<RuntimeJoinFilter>> {
+        self.runtime_filter.as_ref()
+    }
+
+    /// Requires the inputs to be partitioned by [`RepartitionExec`]s that
+    /// broadcast the build side rows and spread the probe side rows holding
+    /// `heavy_hitters` of the join keys, instead of hash partitioned.
+    ///
+    /// The rows holding other values are still hash partitioned on the join
+    /// keys, so each probe side row meets all the matching build side rows.
+    /// Only supported in [`PartitionMode::Partitioned`].
+    ///
+    /// [`RepartitionExec`]: crate::physical_plan::repartition::RepartitionExec
+    pub fn with_heavy_hitters(mut self, heavy_hitters: HeavyHitters) -> Result<Self> {
+        if self.mode != PartitionMode::Partitioned {
+            return plan_err!("Skew handling requires a partitioned hash join");
+        }
+        self.heavy_hitters = Some(heavy_hitters);
+        Ok(self)
+    }
+
+    /// The heavy hitters of the join keys the inputs are partitioned with
+    /// skew handling for
+    pub fn heavy_hitters(&self) -> Option<&HeavyHitters> {
+        self.heavy_hitters.as_ref()
+    }
+
     /// left (build) side which gets hashed
     pub fn left(&self) -> &Arc<dyn ExecutionPlan> {
         &self.left
@@ -236,7 +284,11 @@ impl DisplayAs for HashJoinExec {
                     f,
                     "HashJoinExec: mode={:?}, join_type={:?}, on=[{}]{}",
                     self.mode, self.join_type, on, display_filter
-                )
+                )?;
+                if let Some(heavy_hitters) = &self.heavy_hitters {
+                    write!(f, ", heavy_hitters={heavy_hitters}")?;
+                }
+                Ok(())
             }
         }
     }
@@ -257,6 +309,13 @@ impl ExecutionPlan for HashJoinExec {
                 Distribution::SinglePartition,
                 Distribution::UnspecifiedDistribution,
             ],
+            // The skew handling of the input repartitions sends the rows
+            // holding heavy hitters to other partitions than their hash
+            // partitions, so the inputs are not hash partitioned
+            PartitionMode::Partitioned if self.heavy_hitters.is_some() => vec![
+                Distribution::UnspecifiedDistribution,
+                Distribution::UnspecifiedDistribution,
+            ],
             PartitionMode::Partitioned => {
                 let (left_expr, right_expr) = self
                     .on
@@ -398,15 +457,28 @@ impl ExecutionPlan for HashJoinExec {
         self: Arc<Self>,
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        Ok(Arc::new(HashJoinExec::try_new(
+        // The new join collects its build side again, so it publishes its
+        // bounds to a new filter, which replaces the current one on the probe side
+        let mut probe_side = children[1].clone();
+        let mut runtime_filter = None;
+        if let Some(old) = &self.runtime_filter {
+            let new = Arc::new(RuntimeJoinFilter::new());
+            probe_side = replace_join_filter(probe_side, old, &new)?;
+            runtime_filter = Some(new);
+        }
+
+        let mut join = HashJoinExec::try_new(
             children[0].clone(),
-            children[1].clone(),
+            probe_side,
             self.on.clone(),
             self.filter.clone(),
             &self.join_type,
             self.mode,
             self.null_equals_null,
-        )?))
+        )?;
+        join.runtime_filter = runtime_filter;
+        join.heavy_hitters = self.heavy_hitters.clone();
+        Ok(Arc::new(join))
     }
 
     fn execute(
@@ -436,6 +508,7 @@ impl ExecutionPlan for HashJoinExec {
                     self.random_state.clone(),
                     self.left.clone(),
                     on_left.clone(),
+                    self.runtime_filter.clone(),
                     context.clone(),
                     join_metrics.clone(),
                     reservation,
@@ -451,6 +524,7 @@ impl ExecutionPlan for HashJoinExec {
                     self.random_state.clone(),
                     self.left.clone(),
                     on_left.clone(),
+                    None,
                     context.clone(),
                     join_metrics.clone(),
                     reservation,
@@ -511,6 +585,7 @@ async fn collect_left_input(
     random_state: RandomState,
     left: Arc<dyn ExecutionPlan>,
     on_left: Vec<Column>,
+    runtime_filter: Option<Arc<RuntimeJoinFilter>>,
     context: Arc<TaskContext>,
     metrics: BuildProbeJoinMetrics,
     reservation: MemoryReservation,
@@ -538,20 +613,25 @@ async fn collect_left_input(
     // 1. creates a [JoinHashMap] of all batches from the stream
     // 2. stores the batches in a vector.
     let initial = (Vec::new(), 0, metrics, reservation);
+    let cancellation_token = context.cancellation_token();
     let (batches, num_rows, metrics, mut reservation) = stream
-        .try_fold(initial, |mut acc, batch| async {
-            let batch_size = batch.get_array_memory_size();
-            // Reserve memory for incoming batch
-            acc.3.try_grow(batch_size)?;
-            // Update metrics
-            acc.2.build_mem_used.add(batch_size);
-            acc.2.build_input_batches.add(1);
-            acc.2.build_input_rows.add(batch.num_rows());
-            // Update rowcount
-            acc.1 += batch.num_rows();
-            // Push batch to output
-            acc.0.push(batch);
-            Ok(acc)
+        .try_fold(initial, |mut acc, batch| {
+            let cancelled = cancellation_token.check();
+            async move {
+                cancelled?;
+                let batch_size = batch.get_array_memory_size();
+                // Reserve memory for incoming batch
+                acc.3.try_grow(batch_size)?;
+                // Update metrics
+                acc.2.build_mem_used.add(batch_size);
+                acc.2.build_input_batches.add(1);
+                acc.2.build_input_rows.add(batch.num_rows());
+                // Update rowcount
+                acc.1 += batch.num_rows();
+                // Push batch to output
+                acc.0.push(batch);
+                Ok(acc)
+            }
         })
         .await?;
 
@@ -579,6 +659,7 @@ async fn collect_left_input(
     let mut hashes_buffer = Vec::new();
     let mut offset = 0;
     for batch in batches.iter() {
+        cancellation_token.check()?;
         hashes_buffer.clear();
         hashes_buffer.resize(batch.num_rows(), 0);
         update_hash(
@@ -596,6 +677,17 @@ async fn collect_left_input(
     // can directly index into the arrays
     let single_batch = concat_batches(&schema, &batches, num_rows)?;
 
+    if let Some(runtime_filter) = runtime_filter {
+        let bounds = on_left
+            .iter()
+            .map(|column| {
+                let values = column.evaluate(&single_batch)?.into_array(num_rows);
+                KeyBounds::try_new(&values)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        runtime_filter.publish(bounds);
+    }
+
     Ok((hashmap, single_batch, reservation))
 }
 
@@ -1075,7 +1167,9 @@ mod tests {
         physical_plan::{
             common,
             expressions::Column,
+            filter::FilterExec,
             hash_utils::create_hashes,
+            joins::runtime_filter::RuntimeFilter,
             joins::{hash_join::build_equal_condition_join_indices, utils::JoinSide},
             memory::MemoryExec,
             repartition::RepartitionExec,
@@ -2906,4 +3000,150 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn join_inner_publishes_runtime_filter() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let left = build_table(
+            ("a1", &vec![1, 2, 3]),
+            ("b1", &vec![4, 5, 5]),
+            ("c1", &vec![7, 8, 9]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30, 40]),
+            ("b1", &vec![3, 4, 5, 6]),
+            ("c2", &vec![70, 80, 90, 100]),
+        );
+
+        // the probe side filters its rows by the bounds of the build side
+        let runtime_filter = Arc::new(RuntimeJoinFilter::new());
+        let probe_filter = RuntimeFilter::new(
+            runtime_filter.clone(),
+            vec![(0, Column::new_with_schema("b1", &right.schema())?)],
+        );
+        let right = Arc::new(
+            FilterExec::try_new(Arc::new(Literal::new(ScalarValue::from(true))), right)?
+                .with_runtime_filters(vec![probe_filter]),
+        );
+        let on = vec![(
+            Column::new_with_schema("b1", &left.schema())?,
+            Column::new_with_schema("b1", &right.schema())?,
+        )];
+
+        let join = join(left, right.clone(), on, &JoinType::Inner, false)?
+            .with_runtime_filter(runtime_filter.clone());
+        let stream = join.execute(0, task_ctx)?;
+        let batches = common::collect(stream).await?;
+
+        let expected = [
+            "+----+----+----+----+----+----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2 |",
+            "+----+----+----+----+----+----+",
+            "| 1  | 4  | 7  | 20 | 4  | 80 |",
+            "| 2  | 5  | 8  | 30 | 5  | 90 |",
+            "| 3  | 5  | 9  | 30 | 5  | 90 |",
+            "+----+----+----+----+----+----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        assert_eq!(
+            runtime_filter.bounds(),
+            Some(
+                [KeyBounds::Range {
+                    min: ScalarValue::Int32(Some(4)),
+                    max: ScalarValue::Int32(Some(5)),
+                    values: Some(vec![
+                        ScalarValue::Int32(Some(4)),
+                        ScalarValue::Int32(Some(5)),
+                    ]),
+                }]
+                .as_slice()
+            )
+        );
+
+        // the probe side rows that cannot match were not passed to the join
+        let output_rows = right.metrics().unwrap().output_rows().unwrap();
+        assert_eq!(output_rows, 2);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn join_with_new_children_republishes_runtime_filter() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let left = build_table(
+            ("a1", &vec![1, 2]),
+            ("b1", &vec![4, 5]),
+            ("c1", &vec![7, 8]),
+        );
+        let right = build_table(
+            ("a2", &vec![10, 20, 30, 40]),
+            ("b1", &vec![3, 4, 5, 6]),
+            ("c2", &vec![70, 80, 90, 100]),
+        );
+
+        let runtime_filter = Arc::new(RuntimeJoinFilter::new());
+        let probe_filter = RuntimeFilter::new(
+            runtime_filter.clone(),
+            vec![(0, Column::new_with_schema("b1", &right.schema())?)],
+        );
+        let right: Arc<dyn ExecutionPlan> = Arc::new(
+            FilterExec::try_new(Arc::new(Literal::new(ScalarValue::from(true))), right)?
+                .with_runtime_filters(vec![probe_filter]),
+        );
+        let on = vec![(
+            Column::new_with_schema("b1", &left.schema())?,
+            Column::new_with_schema("b1", &right.schema())?,
+        )];
+        let join: Arc<dyn ExecutionPlan> = Arc::new(
+            join(left, right.clone(), on, &JoinType::Inner, false)?
+                .with_runtime_filter(runtime_filter.clone()),
+        );
+        let batches = common::collect(join.execute(0, task_ctx.clone())?).await?;
+        assert_eq!(batches.iter().map(|b| b.num_rows()).sum::<usize>(), 2);
+
+        // executing the plan again over a build side with other join keys
+        // publishes their bounds instead of those of the first execution
+        let left = build_table(
+            ("a1", &vec![3, 4]),
+            ("b1", &vec![3, 6]),
+            ("c1", &vec![9, 10]),
+        );
+        let join = join.with_new_children(vec![left, right])?;
+        let batches = common::collect(join.execute(0, task_ctx)?).await?;
+
+        let expected = [
+            "+----+----+----+----+----+-----+",
+            "| a1 | b1 | c1 | a2 | b1 | c2  |",
+            "+----+----+----+----+----+-----+",
+            "| 3  | 3  | 9  | 10 | 3  | 70  |",
+            "| 4  | 6  | 10 | 40 | 6  | 100 |",
+            "+----+----+----+----+----+-----+",
+        ];
+        assert_batches_sorted_eq!(expected, &batches);
+
+        let new_filter = join
+            .as_any()
+            .downcast_ref::<HashJoinExec>()
+            .unwrap()
+            .runtime_filter()
+            .unwrap();
+        assert!(!Arc::ptr_eq(new_filter, &runtime_filter));
+        assert_eq!(
+            new_filter.bounds(),
+            Some(
+                [KeyBounds::Range {
+                    min: ScalarValue::Int32(Some(3)),
+                    max: ScalarValue::Int32(Some(6)),
+                    values: Some(vec![
+                        ScalarValue::Int32(Some(3)),
+                        ScalarValue::Int32(Some(6)),
+                    ]),
+                }]
+                .as_slice()
+            )
+        );
+
+        Ok(())
+    }
 }
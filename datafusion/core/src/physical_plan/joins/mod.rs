@@ -27,6 +27,7 @@ mod cross_join;
 mod hash_join;
 mod hash_join_utils;
 mod nested_loop_join;
+pub mod runtime_filter;
 mod sort_merge_join;
 mod symmetric_hash_join;
 pub mod utils;
@@ -0,0 +1,560 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Runtime filters that a [`HashJoinExec`] publishes once its build side has
+//! been collected, so that probe side operators can skip data that cannot
+//! match any build side row.
+//!
+//! [`HashJoinExec`]: crate::physical_plan::joins::HashJoinExec
+
+use std::collections::HashSet;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::task::{Context, Poll};
+
+use arrow::array::{Array, ArrayRef};
+use arrow::datatypes::{Schema, SchemaRef};
+use arrow::record_batch::RecordBatch;
+use datafusion_common::tree_node::{Transformed, TreeNode};
+use datafusion_common::{Result, ScalarValue};
+use datafusion_expr::{Accumulator, Operator};
+use datafusion_physical_expr::expressions::{
+    in_list, BinaryExpr, Column, Literal, MaxAccumulator, MinAccumulator,
+};
+use datafusion_physical_expr::PhysicalExpr;
+use futures::{ready, Stream, StreamExt};
+
+use crate::datasource::physical_plan::ParquetExec;
+use crate::physical_plan::filter::{batch_filter, FilterExec};
+use crate::physical_plan::{ExecutionPlan, RecordBatchStream, SendableRecordBatchStream};
+
+/// Build sides with at most this many non-null values of a join key
+/// publish the distinct values of the key
+const MAX_IN_LIST_BUILD_ROWS: usize = 1024;
+
+/// The largest number of distinct values of a join key that are published.
+/// Keys with more distinct values only publish their range.
+const MAX_IN_LIST_VALUES: usize = 16;
+
+/// What is known about the build side values of a join key
+#[derive(Debug, Clone, PartialEq)]
+pub enum KeyBounds {
+    /// The build side has no non-null values, so no probe side row can match
+    Empty,
+    /// Every non-null build side value lies between `min` and `max`, and is
+    /// one of `values` if these are known
+    Range {
+        /// The smallest build side value
+        min: ScalarValue,
+        /// The largest build side value
+        max: ScalarValue,
+        /// The distinct build side values, for small build sides
+        values: Option<Vec<ScalarValue>>,
+    },
+    /// Nothing is known about the build side values
+    Unknown,
+}
+
+impl KeyBounds {
+    /// Computes the bounds of the build side `values` of a join key
+    pub fn try_new(values: &ArrayRef) -> Result<Self> {
+        if values.null_count() == values.len() {
+            return Ok(Self::Empty);
+        }
+
+        // keys of types without a min / max implementation are not filtered
+        let Ok((min, max)) = min_max(values) else {
+            return Ok(Self::Unknown);
+        };
+
+        let values = if values.len() - values.null_count() <= MAX_IN_LIST_BUILD_ROWS {
+            distinct_values(values)?
+        } else {
+            None
+        };
+
+        Ok(Self::Range { min, max, values })
+    }
+
+    /// Returns a predicate that is false for the values of `column` that
+    /// cannot match any build side value, if anything is known about them
+    fn predicate(
+        &self,
+        column: &Column,
+        schema: &Schema,
+    ) -> Result<Option<Arc<dyn PhysicalExpr>>> {
+        let (min, max, values) = match self {
+            Self::Empty => return Ok(Some(literal(ScalarValue::Boolean(Some(false))))),
+            Self::Range { min, max, values } => (min, max, values),
+            Self::Unknown => return Ok(None),
+        };
+
+        // the join keys of both sides have the same type, but a column of
+        // another type may have been bound through a cast
+        if schema.field(column.index()).data_type() != &min.get_datatype() {
+            return Ok(None);
+        }
+
+        let column: Arc<dyn PhysicalExpr> = Arc::new(column.clone());
+        let predicate = match values {
+            Some(values) if values.len() == 1 => {
+                binary(column, Operator::Eq, literal(values[0].clone()))
+            }
+            Some(values) => {
+                let range = binary(
+                    binary(column.clone(), Operator::GtEq, literal(min.clone())),
+                    Operator::And,
+                    binary(column.clone(), Operator::LtEq, literal(max.clone())),
+                );
+                let list = values.iter().cloned().map(literal).collect();
+                binary(range, Operator::And, in_list(column, list, &false, schema)?)
+            }
+            None => binary(
+                binary(column.clone(), Operator::GtEq, literal(min.clone())),
+                Operator::And,
+                binary(column, Operator::LtEq, literal(max.clone())),
+            ),
+        };
+        Ok(Some(predicate))
+    }
+}
+
+fn min_max(values: &ArrayRef) -> Result<(ScalarValue, ScalarValue)> {
+    let mut min = MinAccumulator::try_new(values.data_type())?;
+    min.update_batch(&[values.clone()])?;
+    let mut max = MaxAccumulator::try_new(values.data_type())?;
+    max.update_batch(&[values.clone()])?;
+    Ok((min.evaluate()?, max.evaluate()?))
+}
+
+/// Returns the sorted distinct non-null `values`, unless there are more than
+/// [`MAX_IN_LIST_VALUES`] of them
+fn distinct_values(values: &ArrayRef) -> Result<Option<Vec<ScalarValue>>> {
+    let mut distinct = HashSet::new();
+    for index in 0..values.len() {
+        if values.is_null(index) {
+            continue;
+        }
+        distinct.insert(ScalarValue::try_from_array(values, index)?);
+        if distinct.len() > MAX_IN_LIST_VALUES {
+            return Ok(None);
+        }
+    }
+
+    let mut distinct: Vec<_> = distinct.into_iter().collect();
+    distinct.sort_by(|a, b| a.partial_cmp(b).unwrap_or(std::cmp::Ordering::Equal));
+    Ok(Some(distinct))
+}
+
+fn literal(value: ScalarValue) -> Arc<dyn PhysicalExpr> {
+    Arc::new(Literal::new(value))
+}
+
+fn binary(
+    left: Arc<dyn PhysicalExpr>,
+    op: Operator,
+    right: Arc<dyn PhysicalExpr>,
+) -> Arc<dyn PhysicalExpr> {
+    Arc::new(BinaryExpr::new(left, op, right))
+}
+
+/// The bounds of the join keys of a [`HashJoinExec`], which are published
+/// once its build side has been collected.
+///
+/// A filter is shared by the join, which publishes it, and the probe side
+/// operators that it has been pushed down to with [`RuntimeFilter`]s. These
+/// operators skip the data that cannot match any build side row, which is
+/// only correct for joins that do not output unmatched probe side rows.
+///
+/// The bounds describe the build side collected by one join instance. A join
+/// recreated with new children collects its build side again, so it publishes
+/// to a new filter, which replaces this one on its probe side.
+///
+/// [`HashJoinExec`]: crate::physical_plan::joins::HashJoinExec
+#[derive(Debug, Default)]
+pub struct RuntimeJoinFilter {
+    bounds: OnceLock<Vec<KeyBounds>>,
+}
+
+impl RuntimeJoinFilter {
+    /// Creates a filter that has not been published yet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publishes the bounds of each join key, in the order of the join keys.
+    /// Only the first call has an effect.
+    pub fn publish(&self, bounds: Vec<KeyBounds>) {
+        let _ = self.bounds.set(bounds);
+    }
+
+    /// Returns the bounds of each join key, once published
+    pub fn bounds(&self) -> Option<&[KeyBounds]> {
+        self.bounds.get().map(|bounds| bounds.as_slice())
+    }
+}
+
+/// A [`RuntimeJoinFilter`] pushed down to an operator on the probe side of
+/// the join, together with the columns of the operator that hold the values
+/// of the join keys.
+#[derive(Debug, Clone)]
+pub struct RuntimeFilter {
+    join_filter: Arc<RuntimeJoinFilter>,
+    /// The index of a join key and the column holding its values
+    columns: Vec<(usize, Column)>,
+}
+
+impl RuntimeFilter {
+    /// Creates a binding of `join_filter` to `columns`, which pair the index
+    /// of a join key with the column that holds its values
+    pub fn new(
+        join_filter: Arc<RuntimeJoinFilter>,
+        columns: Vec<(usize, Column)>,
+    ) -> Self {
+        Self {
+            join_filter,
+            columns,
+        }
+    }
+
+    /// The filter published by the join
+    pub fn join_filter(&self) -> &Arc<RuntimeJoinFilter> {
+        &self.join_filter
+    }
+
+    /// The join key indices and the columns that hold their values
+    pub fn columns(&self) -> &[(usize, Column)] {
+        &self.columns
+    }
+
+    /// Returns this filter with its columns bound by name to `schema`.
+    /// Columns that are not in `schema` are dropped, and `None` is returned
+    /// if no column remains.
+    pub fn with_schema(&self, schema: &Schema) -> Option<Self> {
+        let columns: Vec<_> = self
+            .columns
+            .iter()
+            .filter_map(|(key, column)| {
+                let index = schema.index_of(column.name()).ok()?;
+                Some((*key, Column::new(column.name(), index)))
+            })
+            .collect();
+
+        (!columns.is_empty()).then(|| Self::new(self.join_filter.clone(), columns))
+    }
+
+    /// Returns the predicate that rows of `schema` that can match a build side
+    /// row satisfy, or `None` if the filter has not been published yet or
+    /// nothing is known about the build side values
+    pub fn predicate(&self, schema: &Schema) -> Result<Option<Arc<dyn PhysicalExpr>>> {
+        let Some(bounds) = self.join_filter.bounds() else {
+            return Ok(None);
+        };
+
+        let mut predicates = vec![];
+        for (key, column) in &self.columns {
+            if let Some(predicate) = bounds[*key].predicate(column, schema)? {
+                predicates.push(predicate);
+            }
+        }
+        Ok(conjunction(predicates))
+    }
+}
+
+/// Returns `plan` with the filters of `old` attached to the operators below it
+/// bound to `new` instead, leaving the filters of other joins unchanged
+pub(crate) fn replace_join_filter(
+    plan: Arc<dyn ExecutionPlan>,
+    old: &Arc<RuntimeJoinFilter>,
+    new: &Arc<RuntimeJoinFilter>,
+) -> Result<Arc<dyn ExecutionPlan>> {
+    plan.transform_down(&|plan| {
+        let plan_any = plan.as_any();
+        if let Some(parquet) = plan_any.downcast_ref::<ParquetExec>() {
+            if let Some(filters) = replace_filters(parquet.runtime_filters(), old, new) {
+                let parquet = parquet.clone().with_runtime_filters(filters);
+                return Ok(Transformed::Yes(Arc::new(parquet)));
+            }
+        } else if let Some(filter) = plan_any.downcast_ref::<FilterExec>() {
+            if let Some(filters) = replace_filters(filter.runtime_filters(), old, new) {
+                let filter = FilterExec::try_new(
+                    filter.predicate().clone(),
+                    filter.input().clone(),
+                )?
+                .with_runtime_filters(filters);
+                return Ok(Transformed::Yes(Arc::new(filter)));
+            }
+        }
+        Ok(Transformed::No(plan))
+    })
+}
+
+/// Returns `filters` with those of `old` bound to `new`, or `None` if none of
+/// them belongs to `old`
+fn replace_filters(
+    filters: &[RuntimeFilter],
+    old: &Arc<RuntimeJoinFilter>,
+    new: &Arc<RuntimeJoinFilter>,
+) -> Option<Vec<RuntimeFilter>> {
+    let belongs_to_old = |filter: &RuntimeFilter| Arc::ptr_eq(&filter.join_filter, old);
+    if !filters.iter().any(belongs_to_old) {
+        return None;
+    }
+
+    let filters = filters
+        .iter()
+        .map(|filter| {
+            if belongs_to_old(filter) {
+                RuntimeFilter::new(new.clone(), filter.columns.clone())
+            } else {
+                filter.clone()
+            }
+        })
+        .collect();
+    Some(filters)
+}
+
+/// Returns the conjunction of the predicates of the published `filters`
+pub(crate) fn runtime_filters_predicate(
+    filters: &[RuntimeFilter],
+    schema: &Schema,
+) -> Result<Option<Arc<dyn PhysicalExpr>>> {
+    let mut predicates = vec![];
+    for filter in filters {
+        if let Some(predicate) = filter.predicate(schema)? {
+            predicates.push(predicate);
+        }
+    }
+    Ok(conjunction(predicates))
+}
+
+fn conjunction(predicates: Vec<Arc<dyn PhysicalExpr>>) -> Option<Arc<dyn PhysicalExpr>> {
+    predicates
+        .into_iter()
+        .reduce(|left, right| binary(left, Operator::And, right))
+}
+
+/// Removes the rows that cannot match any build side row from the batches of
+/// `input`, using the `filters` that have been published when each batch
+/// arrives
+pub(crate) fn filter_stream(
+    input: SendableRecordBatchStream,
+    filters: &[RuntimeFilter],
+) -> SendableRecordBatchStream {
+    if filters.is_empty() {
+        return input;
+    }
+
+    Box::pin(RuntimeFilterStream {
+        schema: input.schema(),
+        input,
+        pending: filters.to_vec(),
+        predicate: None,
+    })
+}
+
+struct RuntimeFilterStream {
+    schema: SchemaRef,
+    input: SendableRecordBatchStream,
+    /// The filters that have not been published yet
+    pending: Vec<RuntimeFilter>,
+    /// The conjunction of the predicates of the published filters
+    predicate: Option<Arc<dyn PhysicalExpr>>,
+}
+
+impl RuntimeFilterStream {
+    /// Adds the predicates of the filters published since the last batch
+    fn update_predicate(&mut self) -> Result<()> {
+        let mut predicates: Vec<_> = self.predicate.take().into_iter().collect();
+        let mut pending = vec![];
+        for filter in std::mem::take(&mut self.pending) {
+            if filter.join_filter.bounds().is_none() {
+                pending.push(filter);
+            } else if let Some(predicate) = filter.predicate(&self.schema)? {
+                predicates.push(predicate);
+            }
+        }
+        self.pending = pending;
+        self.predicate = conjunction(predicates);
+        Ok(())
+    }
+
+    fn filter_batch(&mut self, batch: RecordBatch) -> Result<RecordBatch> {
+        if !self.pending.is_empty() {
+            self.update_predicate()?;
+        }
+        match &self.predicate {
+            Some(predicate) => batch_filter(&batch, predicate),
+            None => Ok(batch),
+        }
+    }
+}
+
+impl Stream for RuntimeFilterStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        loop {
+            return Poll::Ready(match ready!(self.input.poll_next_unpin(cx)) {
+                Some(Ok(batch)) => match self.filter_batch(batch) {
+                    // skip entirely filtered batches
+                    Ok(batch) if batch.num_rows() == 0 => continue,
+                    result => Some(result),
+                },
+                other => other,
+            });
+        }
+    }
+}
+
+impl RecordBatchStream for RuntimeFilterStream {
+    fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::common;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::ExecutionPlan;
+    use crate::test::build_table_i32;
+    use arrow::array::Int32Array;
+    use datafusion_execution::TaskContext;
+
+    #[test]
+    fn key_bounds() -> Result<()> {
+        let values: ArrayRef = Arc::new(Int32Array::from(vec![Some(3), None, Some(1)]));
+        assert_eq!(
+            KeyBounds::try_new(&values)?,
+            KeyBounds::Range {
+                min: ScalarValue::Int32(Some(1)),
+                max: ScalarValue::Int32(Some(3)),
+                values: Some(vec![
+                    ScalarValue::Int32(Some(1)),
+                    ScalarValue::Int32(Some(3)),
+                ]),
+            }
+        );
+
+        let values: ArrayRef = Arc::new(Int32Array::from_iter_values(0..100));
+        assert_eq!(
+            KeyBounds::try_new(&values)?,
+            KeyBounds::Range {
+                min: ScalarValue::Int32(Some(0)),
+                max: ScalarValue::Int32(Some(99)),
+                values: None,
+            }
+        );
+
+        let values: ArrayRef = Arc::new(Int32Array::from(vec![None, None]));
+        assert_eq!(KeyBounds::try_new(&values)?, KeyBounds::Empty);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn filter_published_bounds() -> Result<()> {
+        let batch = build_table_i32(
+            ("a", &vec![1, 2, 3, 4, 5]),
+            ("b", &vec![10, 20, 30, 40, 50]),
+            ("c", &vec![5, 4, 3, 2, 1]),
+        );
+        let schema = batch.schema();
+        let exec = MemoryExec::try_new(&[vec![batch.clone(), batch]], schema, None)?;
+        let task_ctx = Arc::new(TaskContext::default());
+
+        let join_filter = Arc::new(RuntimeJoinFilter::new());
+        let filters = [
+            RuntimeFilter::new(join_filter.clone(), vec![(0, Column::new("a", 0))]),
+            RuntimeFilter::new(join_filter.clone(), vec![(1, Column::new("c", 2))]),
+        ];
+
+        // nothing is filtered before the bounds are published
+        let stream = filter_stream(exec.execute(0, task_ctx.clone())?, &filters);
+        assert_eq!(common::collect(stream).await?[0].num_rows(), 5);
+
+        let values = (1..40).map(|v| ScalarValue::Int32(Some(v))).collect();
+        join_filter.publish(vec![
+            KeyBounds::Range {
+                min: ScalarValue::Int32(Some(2)),
+                max: ScalarValue::Int32(Some(4)),
+                values: None,
+            },
+            KeyBounds::Range {
+                min: ScalarValue::Int32(Some(1)),
+                max: ScalarValue::Int32(Some(3)),
+                values: Some(values),
+            },
+        ]);
+
+        let stream = filter_stream(exec.execute(0, task_ctx.clone())?, &filters);
+        let batches = common::collect(stream).await?;
+        let expected = [
+            "+---+----+---+",
+            "| a | b  | c |",
+            "+---+----+---+",
+            "| 3 | 30 | 3 |",
+            "| 4 | 40 | 2 |",
+            "| 3 | 30 | 3 |",
+            "| 4 | 40 | 2 |",
+            "+---+----+---+",
+        ];
+        crate::assert_batches_eq!(expected, &batches);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn filter_empty_build_side() -> Result<()> {
+        let batch =
+            build_table_i32(("a", &vec![1, 2]), ("b", &vec![3, 4]), ("c", &vec![5, 6]));
+        let schema = batch.schema();
+        let exec = MemoryExec::try_new(&[vec![batch]], schema, None)?;
+
+        let join_filter = Arc::new(RuntimeJoinFilter::new());
+        join_filter.publish(vec![KeyBounds::Empty]);
+        let filters = [RuntimeFilter::new(
+            join_filter,
+            vec![(0, Column::new("b", 1))],
+        )];
+
+        let stream =
+            filter_stream(exec.execute(0, Arc::new(TaskContext::default()))?, &filters);
+        assert!(common::collect(stream).await?.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn filter_with_schema() {
+        let join_filter = Arc::new(RuntimeJoinFilter::new());
+        let filter = RuntimeFilter::new(
+            join_filter,
+            vec![(0, Column::new("b", 0)), (1, Column::new("d", 1))],
+        );
+        let schema =
+            build_table_i32(("a", &vec![]), ("b", &vec![]), ("c", &vec![])).schema();
+
+        let bound = filter.with_schema(&schema).unwrap();
+        assert_eq!(bound.columns(), &[(0, Column::new("b", 1))]);
+
+        let schema = Schema::new(vec![schema.field(0).clone()]);
+        assert!(filter.with_schema(&schema).is_none());
+    }
+}
@@ -41,7 +41,8 @@ use arrow::record_batch::{RecordBatch, RecordBatchOptions};
 use datafusion_common::cast::as_boolean_array;
 use datafusion_common::tree_node::{Transformed, TreeNode};
 use datafusion_common::{
-    exec_err, plan_err, DataFusionError, JoinType, Result, ScalarValue, SharedResult,
+    exec_err, plan_err, DataFusionError, Histogram, HistogramBucket, JoinType, Precision,
+    Result, ScalarValue, SharedResult, ValueFrequency,
 };
 use datafusion_physical_expr::expressions::Column;
 use datafusion_physical_expr::utils::{
@@ -137,22 +138,38 @@ pub fn adjust_right_output_partitioning(
         Partitioning::Hash(exprs, size) => {
             let new_exprs = exprs
                 .into_iter()
-                .map(|expr| {
-                    expr.transform_down(&|e| match e.as_any().downcast_ref::<Column>() {
-                        Some(col) => Ok(Transformed::Yes(Arc::new(Column::new(
-                            col.name(),
-                            left_columns_len + col.index(),
-                        )))),
-                        None => Ok(Transformed::No(e)),
-                    })
-                    .unwrap()
-                })
+                .map(|expr| shift_right_columns(expr, left_columns_len))
                 .collect::<Vec<_>>();
             Partitioning::Hash(new_exprs, size)
         }
+        Partitioning::Range(sort_exprs, size) => {
+            let new_exprs = sort_exprs
+                .into_iter()
+                .map(|sort_expr| PhysicalSortExpr {
+                    expr: shift_right_columns(sort_expr.expr, left_columns_len),
+                    options: sort_expr.options,
+                })
+                .collect::<Vec<_>>();
+            Partitioning::Range(new_exprs, size)
+        }
     }
 }
 
+/// Offsets the indices of the columns in `expr` by `left_columns_len`
+fn shift_right_columns(
+    expr: Arc<dyn PhysicalExpr>,
+    left_columns_len: usize,
+) -> Arc<dyn PhysicalExpr> {
+    expr.transform_down(&|e| match e.as_any().downcast_ref::<Column>() {
+        Some(col) => Ok(Transformed::Yes(Arc::new(Column::new(
+            col.name(),
+            left_columns_len + col.index(),
+        )))),
+        None => Ok(Transformed::No(e)),
+    })
+    .unwrap()
+}
+
 /// Replaces the right column (first index in the `on_column` tuple) with
 /// the left column (zeroth index in the tuple) inside `right_ordering`.
 fn replace_on_columns_of_right_ordering(
@@ -812,14 +829,16 @@ pub(crate) fn estimate_join_statistics(
 
     let join_stats = estimate_join_cardinality(join_type, left_stats, right_stats, &on);
     let (num_rows, column_statistics) = match join_stats {
-        Some(stats) => (Some(stats.num_rows), Some(stats.column_statistics)),
-        None => (None, None),
+        Some(stats) => (
+            Precision::Inexact(stats.num_rows),
+            Some(stats.column_statistics),
+        ),
+        None => (Precision::Absent, None),
     };
     Statistics {
         num_rows,
-        total_byte_size: None,
+        total_byte_size: Precision::Absent,
         column_statistics,
-        is_exact: false,
     }
 }
 
@@ -832,8 +851,8 @@ fn estimate_join_cardinality(
 ) -> Option<PartialJoinStatistics> {
     match join_type {
         JoinType::Inner | JoinType::Left | JoinType::Right | JoinType::Full => {
-            let left_num_rows = left_stats.num_rows?;
-            let right_num_rows = right_stats.num_rows?;
+            let left_num_rows = *left_stats.num_rows.get_value()?;
+            let right_num_rows = *right_stats.num_rows.get_value()?;
 
             // Take the left_col_stats and right_col_stats using the index
             // obtained from index() method of the each element of 'on'.
@@ -854,7 +873,8 @@ fn estimate_join_cardinality(
                 right_num_rows,
                 left_col_stats,
                 right_col_stats,
-                left_stats.is_exact && right_stats.is_exact,
+                left_stats.num_rows.is_exact().unwrap_or(false)
+                    && right_stats.num_rows.is_exact().unwrap_or(false),
             )?;
 
             // The cardinality for inner join can also be used to estimate
@@ -878,10 +898,12 @@ fn estimate_join_cardinality(
                 // We don't do anything specific here, just combine the existing
                 // statistics which might yield subpar results (although it is
                 // true, esp regarding min/max). For a better estimation, we need
-                // filter selectivity analysis first.
+                // filter selectivity analysis first. The join may drop rows, so
+                // none of them remains exact.
                 column_statistics: all_left_col_stats
                     .into_iter()
                     .chain(all_right_col_stats)
+                    .map(ColumnStatistics::into_inexact)
                     .collect(),
             })
         }
@@ -908,14 +930,42 @@ fn estimate_inner_join_cardinality(
     // from Spark's Catalyst optimizer.
 
     let mut join_selectivity = None;
+    let mut histogram_cardinality: Option<usize> = None;
     for (left_stat, right_stat) in left_col_stats.iter().zip(right_col_stats.iter()) {
-        if (left_stat.min_value.clone()? > right_stat.max_value.clone()?)
-            || (left_stat.max_value.clone()? < right_stat.min_value.clone()?)
-        {
+        if let Some(cardinality) = estimate_histogram_join_cardinality(
+            left_num_rows,
+            right_num_rows,
+            left_stat,
+            right_stat,
+        ) {
+            // Similar to the distinct counts below, the most selective join
+            // column bounds the cardinality of the join.
+            histogram_cardinality =
+                Some(histogram_cardinality.map_or(cardinality, |c| c.min(cardinality)));
+            continue;
+        }
+
+        let left_min = left_stat.min_value.get_value()?;
+        let left_max = left_stat.max_value.get_value()?;
+        let right_min = right_stat.min_value.get_value()?;
+        let right_max = right_stat.max_value.get_value()?;
+        if left_min > right_max || left_max < right_min {
             // If there is no overlap in any of the join columns, that means the join
             // itself is disjoint and the cardinality is 0. Though we can only assume
             // this when the statistics are exact (since it is a very strong assumption).
-            return if is_exact { Some(0) } else { None };
+            let bounds_exact = [
+                &left_stat.min_value,
+                &left_stat.max_value,
+                &right_stat.min_value,
+                &right_stat.max_value,
+            ]
+            .iter()
+            .all(|bound| bound.is_exact().unwrap_or(false));
+            return if is_exact && bounds_exact {
+                Some(0)
+            } else {
+                None
+            };
         }
 
         let left_max_distinct = max_distinct_count(left_num_rows, left_stat.clone());
@@ -932,7 +982,7 @@ fn estimate_inner_join_cardinality(
     // With the assumption that the smaller input's domain is generally represented in the bigger
     // input's domain, we can estimate the inner join's cardinality by taking the cartesian product
     // of the two inputs and normalizing it by the selectivity factor.
-    match join_selectivity {
+    let distinct_cardinality = match join_selectivity {
         Some(selectivity) if selectivity > 0 => {
             Some((left_num_rows * right_num_rows) / selectivity)
         }
@@ -941,9 +991,126 @@ fn estimate_inner_join_cardinality(
         // And let other passes handle this (otherwise we would need to produce an
         // overestimation using just the cartesian product).
         _ => None,
+    };
+
+    match (histogram_cardinality, distinct_cardinality) {
+        (Some(histogram), Some(distinct)) => Some(histogram.min(distinct)),
+        (histogram, distinct) => histogram.or(distinct),
     }
 }
 
+/// The value distribution of a join column, with frequencies scaled to the
+/// current number of non-null rows of its input. Scaling keeps the histogram
+/// usable once filters or other joins changed the row count it was built for.
+struct ColumnDistribution<'a> {
+    histogram: &'a Histogram,
+    most_common_values: &'a [ValueFrequency],
+    scale: f64,
+}
+
+impl<'a> ColumnDistribution<'a> {
+    fn try_new(num_rows: usize, stats: &'a ColumnStatistics) -> Option<Self> {
+        let histogram = stats.histogram.as_ref()?;
+        let histogram_rows = histogram.num_rows();
+        if histogram_rows == 0 {
+            return None;
+        }
+        let non_null_rows =
+            num_rows.saturating_sub(*stats.null_count.get_value().unwrap_or(&0));
+        Some(Self {
+            histogram,
+            most_common_values: stats.most_common_values.as_deref().unwrap_or(&[]),
+            scale: non_null_rows as f64 / histogram_rows as f64,
+        })
+    }
+
+    /// Estimated number of rows equal to `value`
+    fn value_rows(&self, value: &ScalarValue) -> f64 {
+        self.histogram.value_rows(value, self.most_common_values) * self.scale
+    }
+
+    /// Estimated number of rows and distinct values of `bucket`, leaving out
+    /// the given `values` which are accounted for separately
+    fn residual(&self, bucket: &HistogramBucket, values: &[&ScalarValue]) -> (f64, f64) {
+        values.iter().filter(|value| bucket.contains(value)).fold(
+            (
+                bucket.num_rows as f64 * self.scale,
+                bucket.distinct_count as f64,
+            ),
+            |(num_rows, distinct_count), value| {
+                (
+                    (num_rows - self.value_rows(value)).max(0.0),
+                    (distinct_count - 1.0).max(0.0),
+                )
+            },
+        )
+    }
+}
+
+/// Estimate the inner join cardinality of a single pair of join columns from
+/// their histograms and most common values. Returns `None` unless both columns
+/// have a histogram.
+///
+/// The most common values of either side are matched value by value, which
+/// captures the skew that dominates the output size. The remaining rows are
+/// matched bucket by bucket, assuming that values are uniformly distributed
+/// within each overlapping range and that the side with fewer distinct values
+/// is contained in the other.
+fn estimate_histogram_join_cardinality(
+    left_num_rows: usize,
+    right_num_rows: usize,
+    left_stat: &ColumnStatistics,
+    right_stat: &ColumnStatistics,
+) -> Option<usize> {
+    let left = ColumnDistribution::try_new(left_num_rows, left_stat)?;
+    let right = ColumnDistribution::try_new(right_num_rows, right_stat)?;
+
+    let mut common_values: Vec<&ScalarValue> = vec![];
+    for frequency in left
+        .most_common_values
+        .iter()
+        .chain(right.most_common_values)
+    {
+        if !common_values.contains(&&frequency.value) {
+            common_values.push(&frequency.value);
+        }
+    }
+    let mut cardinality: f64 = common_values
+        .iter()
+        .map(|value| left.value_rows(value) * right.value_rows(value))
+        .sum();
+
+    for left_bucket in &left.histogram.buckets {
+        let (left_rows, left_distinct) = left.residual(left_bucket, &common_values);
+        for right_bucket in &right.histogram.buckets {
+            let lower = if left_bucket.lower >= right_bucket.lower {
+                &left_bucket.lower
+            } else {
+                &right_bucket.lower
+            };
+            let upper = if left_bucket.upper <= right_bucket.upper {
+                &left_bucket.upper
+            } else {
+                &right_bucket.upper
+            };
+            if lower > upper {
+                continue;
+            }
+            let (right_rows, right_distinct) =
+                right.residual(right_bucket, &common_values);
+            let left_fraction = left_bucket.overlap_fraction(lower, upper);
+            let right_fraction = right_bucket.overlap_fraction(lower, upper);
+            let distinct = (left_distinct * left_fraction)
+                .max(right_distinct * right_fraction)
+                .max(1.0);
+            cardinality +=
+                left_rows * left_fraction * right_rows * right_fraction / distinct;
+        }
+    }
+
+    Some(cardinality.ceil() as usize)
+}
+
 /// Estimate the number of maximum distinct values that can be present in the
 /// given column from its statistics.
 ///
@@ -951,17 +1118,21 @@ fn estimate_inner_join_cardinality(
 /// has min/max values, then they might be used as a fallback option. Otherwise,
 /// returns None.
 fn max_distinct_count(num_rows: usize, stats: ColumnStatistics) -> Option<usize> {
-    match (stats.distinct_count, stats.max_value, stats.min_value) {
-        (Some(_), _, _) => stats.distinct_count,
+    match (
+        stats.distinct_count.get_value(),
+        stats.max_value.get_value(),
+        stats.min_value.get_value(),
+    ) {
+        (Some(distinct_count), _, _) => Some(*distinct_count),
         (_, Some(max), Some(min)) => {
             // Note that float support is intentionally omitted here, since the computation
             // of a range between two float values is not trivial and the result would be
             // highly inaccurate.
-            let numeric_range = get_int_range(min, max)?;
+            let numeric_range = get_int_range(min.clone(), max.clone())?;
 
             // The number can never be greater than the number of rows we have (minus
             // the nulls, since they don't count as distinct values).
-            let ceiling = num_rows - stats.null_count.unwrap_or(0);
+            let ceiling = num_rows - stats.null_count.get_value().unwrap_or(&0);
             Some(numeric_range.min(ceiling))
         }
         _ => None,
@@ -1542,9 +1713,12 @@ mod tests {
         is_exact: bool,
     ) -> Statistics {
         Statistics {
-            num_rows,
+            num_rows: if is_exact {
+                Precision::exact_or_absent(num_rows)
+            } else {
+                num_rows.map(Precision::Inexact).unwrap_or_default()
+            },
             column_statistics: column_stats,
-            is_exact,
             ..Default::default()
         }
     }
@@ -1555,13 +1729,24 @@ mod tests {
         distinct_count: Option<usize>,
     ) -> ColumnStatistics {
         ColumnStatistics {
-            distinct_count,
-            min_value: min.map(|size| ScalarValue::Int64(Some(size))),
-            max_value: max.map(|size| ScalarValue::Int64(Some(size))),
+            distinct_count: Precision::exact_or_absent(distinct_count),
+            min_value: Precision::exact_or_absent(
+                min.map(|size| ScalarValue::Int64(Some(size))),
+            ),
+            max_value: Precision::exact_or_absent(
+                max.map(|size| ScalarValue::Int64(Some(size))),
+            ),
             ..Default::default()
         }
     }
 
+    fn inexact_stats(column_stats: Vec<ColumnStatistics>) -> Vec<ColumnStatistics> {
+        column_stats
+            .into_iter()
+            .map(ColumnStatistics::into_inexact)
+            .collect()
+    }
+
     type PartialStats = (usize, Option<i64>, Option<i64>, Option<usize>);
 
     // This is mainly for validating the all edge cases of the estimation, but
@@ -1733,7 +1918,8 @@ mod tests {
             );
             assert_eq!(
                 partial_join_stats.map(|s| s.column_statistics),
-                expected_cardinality.map(|_| [left_col_stats, right_col_stats].concat())
+                expected_cardinality
+                    .map(|_| inexact_stats([left_col_stats, right_col_stats].concat()))
             );
         }
         Ok(())
@@ -1766,19 +1952,69 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_inner_join_cardinality_histogram() -> Result<()> {
+        let bucket = |lower: i64, upper: i64, num_rows: usize| HistogramBucket {
+            lower: ScalarValue::Int64(Some(lower)),
+            upper: ScalarValue::Int64(Some(upper)),
+            num_rows,
+            distinct_count: (upper - lower + 1) as usize,
+        };
+
+        // The left side holds the value 1 in 90 of its rows, and the values
+        // 2..=11 once each. The right side holds the values 1..=10 once each.
+        let left_stats = vec![ColumnStatistics {
+            histogram: Some(Histogram {
+                buckets: vec![bucket(1, 1, 90), bucket(2, 11, 10)],
+            }),
+            most_common_values: Some(vec![ValueFrequency {
+                value: ScalarValue::Int64(Some(1)),
+                count: 90,
+            }]),
+            ..create_column_stats(Some(1), Some(11), Some(11))
+        }];
+        let right_stats = vec![ColumnStatistics {
+            histogram: Some(Histogram {
+                buckets: vec![bucket(1, 10, 10)],
+            }),
+            ..create_column_stats(Some(1), Some(10), Some(10))
+        }];
+
+        // The distinct counts alone estimate 100 * 10 / 11 = 90 rows, while
+        // the actual join produces 90 + 9 = 99 rows.
+        assert_eq!(
+            estimate_inner_join_cardinality(
+                100,
+                10,
+                left_stats.clone(),
+                right_stats.clone(),
+                false
+            ),
+            Some(99)
+        );
+
+        // Frequencies are scaled to the current number of rows of each side
+        assert_eq!(
+            estimate_inner_join_cardinality(50, 10, left_stats, right_stats, false),
+            Some(50)
+        );
+
+        Ok(())
+    }
+
     #[test]
     fn test_inner_join_cardinality_decimal_range() -> Result<()> {
         let left_col_stats = vec![ColumnStatistics {
-            distinct_count: None,
-            min_value: Some(ScalarValue::Decimal128(Some(32500), 14, 4)),
-            max_value: Some(ScalarValue::Decimal128(Some(35000), 14, 4)),
+            distinct_count: Precision::Absent,
+            min_value: Precision::Inexact(ScalarValue::Decimal128(Some(32500), 14, 4)),
+            max_value: Precision::Inexact(ScalarValue::Decimal128(Some(35000), 14, 4)),
             ..Default::default()
         }];
 
         let right_col_stats = vec![ColumnStatistics {
-            distinct_count: None,
-            min_value: Some(ScalarValue::Decimal128(Some(33500), 14, 4)),
-            max_value: Some(ScalarValue::Decimal128(Some(34000), 14, 4)),
+            distinct_count: Precision::Absent,
+            min_value: Precision::Inexact(ScalarValue::Decimal128(Some(33500), 14, 4)),
+            max_value: Precision::Inexact(ScalarValue::Decimal128(Some(34000), 14, 4)),
             ..Default::default()
         }];
 
@@ -1843,7 +2079,7 @@ mod tests {
             assert_eq!(partial_join_stats.num_rows, expected_num_rows);
             assert_eq!(
                 partial_join_stats.column_statistics,
-                [left_col_stats.clone(), right_col_stats.clone()].concat()
+                inexact_stats([left_col_stats.clone(), right_col_stats.clone()].concat())
             );
         }
 
@@ -1908,7 +2144,7 @@ mod tests {
             assert_eq!(partial_join_stats.num_rows, expected_num_rows);
             assert_eq!(
                 partial_join_stats.column_statistics,
-                [left_col_stats.clone(), right_col_stats.clone()].concat()
+                inexact_stats([left_col_stats.clone(), right_col_stats.clone()].concat())
             );
         }
 
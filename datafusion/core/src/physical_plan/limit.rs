@@ -33,7 +33,7 @@ use super::{DisplayAs, RecordBatchStream, SendableRecordBatchStream, Statistics}
 use arrow::array::ArrayRef;
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::{RecordBatch, RecordBatchOptions};
-use datafusion_common::{internal_err, DataFusionError, Result};
+use datafusion_common::{internal_err, DataFusionError, Precision, Result};
 use datafusion_execution::TaskContext;
 use datafusion_physical_expr::OrderingEquivalenceProperties;
 
@@ -201,15 +201,12 @@ impl ExecutionPlan for GlobalLimitExec {
                 }
             })
             .unwrap_or(usize::MAX);
-        match input_stats {
-            Statistics {
-                num_rows: Some(nr), ..
-            } => {
+        match input_stats.num_rows {
+            Precision::Exact(nr) | Precision::Inexact(nr) => {
                 if nr <= skip {
                     // if all input data will be skipped, return 0
                     Statistics {
-                        num_rows: Some(0),
-                        is_exact: input_stats.is_exact,
+                        num_rows: input_stats.num_rows.map(|_| 0),
                         ..Default::default()
                     }
                 } else if nr <= max_row_num {
@@ -219,16 +216,14 @@ impl ExecutionPlan for GlobalLimitExec {
                     // if the input is greater than the "fetch", the num_row will be the "fetch",
                     // but we won't be able to predict the other statistics
                     Statistics {
-                        num_rows: Some(max_row_num),
-                        is_exact: input_stats.is_exact,
+                        num_rows: input_stats.num_rows.map(|_| max_row_num),
                         ..Default::default()
                     }
                 }
             }
-            _ => Statistics {
+            Precision::Absent => Statistics {
                 // the result output row number will always be no greater than the limit number
-                num_rows: Some(max_row_num),
-                is_exact: false,
+                num_rows: Precision::Inexact(max_row_num),
                 ..Default::default()
             },
         }
@@ -355,27 +350,25 @@ impl ExecutionPlan for LocalLimitExec {
 
     fn statistics(&self) -> Statistics {
         let input_stats = self.input.statistics();
-        match input_stats {
+        match input_stats.num_rows {
             // if the input does not reach the limit globally, return input stats
-            Statistics {
-                num_rows: Some(nr), ..
-            } if nr <= self.fetch => input_stats,
+            Precision::Exact(nr) | Precision::Inexact(nr) if nr <= self.fetch => {
+                input_stats
+            }
             // if the input is greater than the limit, the num_row will be greater
             // than the limit because the partitions will be limited separatly
             // the statistic
-            Statistics {
-                num_rows: Some(nr), ..
-            } if nr > self.fetch => Statistics {
-                num_rows: Some(self.fetch),
+            Precision::Exact(_) | Precision::Inexact(_) => Statistics {
                 // this is not actually exact, but will be when GlobalLimit is applied
                 // TODO stats: find a more explicit way to vehiculate this information
-                is_exact: input_stats.is_exact,
+                num_rows: input_stats.num_rows.map(|_| self.fetch),
                 ..Default::default()
             },
-            _ => Statistics {
+            Precision::Absent => Statistics {
                 // the result output row number will always be no greater than the limit number
-                num_rows: Some(self.fetch * self.output_partitioning().partition_count()),
-                is_exact: false,
+                num_rows: Precision::Inexact(
+                    self.fetch * self.output_partitioning().partition_count(),
+                ),
                 ..Default::default()
             },
         }
@@ -720,10 +713,10 @@ mod tests {
     #[tokio::test]
     async fn test_row_number_statistics_for_global_limit() -> Result<()> {
         let row_count = row_number_statistics_for_global_limit(0, Some(10)).await?;
-        assert_eq!(row_count, Some(10));
+        assert_eq!(row_count, Precision::Inexact(10));
 
         let row_count = row_number_statistics_for_global_limit(5, Some(10)).await?;
-        assert_eq!(row_count, Some(15));
+        assert_eq!(row_count, Precision::Inexact(15));
 
         Ok(())
     }
@@ -731,7 +724,7 @@ mod tests {
     #[tokio::test]
     async fn test_row_number_statistics_for_local_limit() -> Result<()> {
         let row_count = row_number_statistics_for_local_limit(4, 10).await?;
-        assert_eq!(row_count, Some(40));
+        assert_eq!(row_count, Precision::Inexact(40));
 
         Ok(())
     }
@@ -739,7 +732,7 @@ mod tests {
     async fn row_number_statistics_for_global_limit(
         skip: usize,
         fetch: Option<usize>,
-    ) -> Result<Option<usize>> {
+    ) -> Result<Precision<usize>> {
         let num_partitions = 4;
         let tmp_dir = TempDir::new()?;
         let csv = test::scan_partitioned_csv(num_partitions, tmp_dir.path())?;
@@ -755,7 +748,7 @@ mod tests {
     async fn row_number_statistics_for_local_limit(
         num_partitions: usize,
         fetch: usize,
-    ) -> Result<Option<usize>> {
+    ) -> Result<Precision<usize>> {
         let tmp_dir = TempDir::new()?;
         let csv = test::scan_partitioned_csv(num_partitions, tmp_dir.path())?;
 
@@ -198,10 +198,11 @@ pub trait ExecutionPlan: Debug + DisplayAs + Send + Sync {
 
 /// Indicate whether a data exchange is needed for the input of `plan`, which will be very helpful
 /// especially for the distributed engine to judge whether need to deal with shuffling.
-/// Currently there are 3 kinds of execution plan which needs data exchange
+/// Currently there are 4 kinds of execution plan which needs data exchange
 ///     1. RepartitionExec for changing the partition number between two operators
 ///     2. CoalescePartitionsExec for collapsing all of the partitions into one without ordering guarantee
 ///     3. SortPreservingMergeExec for collapsing all of the sorted partitions into one with ordering guarantee
+///     4. OrderedConcatExec for collapsing all of the sorted, range partitioned partitions into one
 pub fn need_data_exchange(plan: Arc<dyn ExecutionPlan>) -> bool {
     if let Some(repart) = plan.as_any().downcast_ref::<RepartitionExec>() {
         !matches!(
@@ -219,6 +220,13 @@ pub fn need_data_exchange(plan: Arc<dyn ExecutionPlan>) -> bool {
             .output_partitioning()
             .partition_count()
             > 1
+    } else if let Some(ordered_concat) = plan.as_any().downcast_ref::<OrderedConcatExec>()
+    {
+        ordered_concat
+            .input()
+            .output_partitioning()
+            .partition_count()
+            > 1
     } else {
         false
     }
@@ -404,6 +412,7 @@ pub mod values;
 pub mod windows;
 
 use crate::physical_plan::repartition::RepartitionExec;
+use crate::physical_plan::sorts::ordered_concat::OrderedConcatExec;
 use crate::physical_plan::sorts::sort_preserving_merge::SortPreservingMergeExec;
 pub use datafusion_common::utils::project_schema;
 use datafusion_execution::TaskContext;
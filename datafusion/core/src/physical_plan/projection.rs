@@ -36,7 +36,7 @@ use crate::physical_plan::{
 
 use arrow::datatypes::{Field, Schema, SchemaRef};
 use arrow::record_batch::{RecordBatch, RecordBatchOptions};
-use datafusion_common::Result;
+use datafusion_common::{Precision, Result};
 use datafusion_execution::TaskContext;
 use datafusion_physical_expr::expressions::{Literal, UnKnownColumn};
 use datafusion_physical_expr::{
@@ -236,6 +236,20 @@ impl ExecutionPlan for ProjectionExec {
 
                 Partitioning::Hash(normalized_exprs, part)
             }
+            Partitioning::Range(sort_exprs, part) => {
+                let normalized_exprs = sort_exprs
+                    .into_iter()
+                    .map(|sort_expr| PhysicalSortExpr {
+                        expr: normalize_out_expr_with_columns_map(
+                            sort_expr.expr,
+                            &self.columns_map,
+                        ),
+                        options: sort_expr.options,
+                    })
+                    .collect::<Vec<_>>();
+
+                Partitioning::Range(normalized_exprs, part)
+            }
             _ => input_partition,
         }
     }
@@ -424,24 +438,22 @@ fn stats_projection(
         .try_fold(0usize, |init, v| v.map(|value| init + value));
 
     match (primitive_row_size, stats.num_rows) {
-        (Some(row_size), Some(row_count)) => {
+        (Some(row_size), num_rows @ (Precision::Exact(_) | Precision::Inexact(_))) => {
             Statistics {
-                is_exact: stats.is_exact,
-                num_rows: stats.num_rows,
+                num_rows,
                 column_statistics,
                 // Use the row_size * row_count as the total byte size
-                total_byte_size: Some(row_size * row_count),
+                total_byte_size: num_rows.multiply(&Precision::Exact(row_size)),
             }
         }
         _ => {
             Statistics {
-                is_exact: stats.is_exact,
                 num_rows: stats.num_rows,
                 column_statistics,
                 // TODO stats: knowing the type of the new columns we can guess the output size
                 // If we can't get the exact statistics for the project
                 // Before we get the exact result, we just use the child status
-                total_byte_size: stats.total_byte_size,
+                total_byte_size: stats.total_byte_size.to_inexact(),
             }
         }
     }
@@ -621,27 +633,36 @@ mod tests {
 
     fn get_stats() -> Statistics {
         Statistics {
-            is_exact: true,
-            num_rows: Some(5),
-            total_byte_size: Some(23),
+            num_rows: Precision::Exact(5),
+            total_byte_size: Precision::Exact(23),
             column_statistics: Some(vec![
                 ColumnStatistics {
-                    distinct_count: Some(5),
-                    max_value: Some(ScalarValue::Int64(Some(21))),
-                    min_value: Some(ScalarValue::Int64(Some(-4))),
-                    null_count: Some(0),
+                    distinct_count: Precision::Exact(5),
+                    max_value: Precision::Exact(ScalarValue::Int64(Some(21))),
+                    min_value: Precision::Exact(ScalarValue::Int64(Some(-4))),
+                    null_count: Precision::Exact(0),
+                    histogram: None,
+                    most_common_values: None,
                 },
                 ColumnStatistics {
-                    distinct_count: Some(1),
-                    max_value: Some(ScalarValue::Utf8(Some(String::from("x")))),
-                    min_value: Some(ScalarValue::Utf8(Some(String::from("a")))),
-                    null_count: Some(3),
+                    distinct_count: Precision::Exact(1),
+                    max_value: Precision::Exact(ScalarValue::Utf8(Some(String::from(
+                        "x",
+                    )))),
+                    min_value: Precision::Exact(ScalarValue::Utf8(Some(String::from(
+                        "a",
+                    )))),
+                    null_count: Precision::Exact(3),
+                    histogram: None,
+                    most_common_values: None,
                 },
                 ColumnStatistics {
-                    distinct_count: None,
-                    max_value: Some(ScalarValue::Float32(Some(1.1))),
-                    min_value: Some(ScalarValue::Float32(Some(0.1))),
-                    null_count: None,
+                    distinct_count: Precision::Absent,
+                    max_value: Precision::Exact(ScalarValue::Float32(Some(1.1))),
+                    min_value: Precision::Exact(ScalarValue::Float32(Some(0.1))),
+                    null_count: Precision::Absent,
+                    histogram: None,
+                    most_common_values: None,
                 },
             ]),
         }
@@ -666,21 +687,28 @@ mod tests {
         let result = stats_projection(source, exprs.into_iter(), Arc::new(schema));
 
         let expected = Statistics {
-            is_exact: true,
-            num_rows: Some(5),
-            total_byte_size: Some(23),
+            num_rows: Precision::Exact(5),
+            total_byte_size: Precision::Inexact(23),
             column_statistics: Some(vec![
                 ColumnStatistics {
-                    distinct_count: Some(1),
-                    max_value: Some(ScalarValue::Utf8(Some(String::from("x")))),
-                    min_value: Some(ScalarValue::Utf8(Some(String::from("a")))),
-                    null_count: Some(3),
+                    distinct_count: Precision::Exact(1),
+                    max_value: Precision::Exact(ScalarValue::Utf8(Some(String::from(
+                        "x",
+                    )))),
+                    min_value: Precision::Exact(ScalarValue::Utf8(Some(String::from(
+                        "a",
+                    )))),
+                    null_count: Precision::Exact(3),
+                    histogram: None,
+                    most_common_values: None,
                 },
                 ColumnStatistics {
-                    distinct_count: Some(5),
-                    max_value: Some(ScalarValue::Int64(Some(21))),
-                    min_value: Some(ScalarValue::Int64(Some(-4))),
-                    null_count: Some(0),
+                    distinct_count: Precision::Exact(5),
+                    max_value: Precision::Exact(ScalarValue::Int64(Some(21))),
+                    min_value: Precision::Exact(ScalarValue::Int64(Some(-4))),
+                    null_count: Precision::Exact(0),
+                    histogram: None,
+                    most_common_values: None,
                 },
             ]),
         };
@@ -701,21 +729,24 @@ mod tests {
         let result = stats_projection(source, exprs.into_iter(), Arc::new(schema));
 
         let expected = Statistics {
-            is_exact: true,
-            num_rows: Some(5),
-            total_byte_size: Some(60),
+            num_rows: Precision::Exact(5),
+            total_byte_size: Precision::Exact(60),
             column_statistics: Some(vec![
                 ColumnStatistics {
-                    distinct_count: None,
-                    max_value: Some(ScalarValue::Float32(Some(1.1))),
-                    min_value: Some(ScalarValue::Float32(Some(0.1))),
-                    null_count: None,
+                    distinct_count: Precision::Absent,
+                    max_value: Precision::Exact(ScalarValue::Float32(Some(1.1))),
+                    min_value: Precision::Exact(ScalarValue::Float32(Some(0.1))),
+                    null_count: Precision::Absent,
+                    histogram: None,
+                    most_common_values: None,
                 },
                 ColumnStatistics {
-                    distinct_count: Some(5),
-                    max_value: Some(ScalarValue::Int64(Some(21))),
-                    min_value: Some(ScalarValue::Int64(Some(-4))),
-                    null_count: Some(0),
+                    distinct_count: Precision::Exact(5),
+                    max_value: Precision::Exact(ScalarValue::Int64(Some(21))),
+                    min_value: Precision::Exact(ScalarValue::Int64(Some(-4))),
+                    null_count: Precision::Exact(0),
+                    histogram: None,
+                    most_common_values: None,
                 },
             ]),
         };
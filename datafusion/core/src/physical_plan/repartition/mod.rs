@@ -19,6 +19,7 @@
 //! partitioning scheme (according to flag `preserve_order` ordering can be preserved during
 //! repartitioning if its input is ordered).
 
+use std::collections::VecDeque;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::task::{Context, Poll};
@@ -36,16 +37,22 @@ use crate::physical_plan::{
 };
 
 use self::distributor_channels::{DistributionReceiver, DistributionSender};
+use self::range::{
+    evaluate_sort_keys, range_partition, sort_key_converter, RangeSampler, Reservoir,
+};
 
 use super::common::{AbortOnDropMany, AbortOnDropSingle, SharedMemoryReservation};
 use super::expressions::PhysicalSortExpr;
-use super::metrics::{self, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet};
+use super::metrics::{
+    self, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet, ScopedTimerGuard,
+};
 use super::{DisplayAs, RecordBatchStream, SendableRecordBatchStream};
 
-use arrow::array::{ArrayRef, UInt64Builder};
+use arrow::array::{Array, ArrayRef, UInt64Builder};
 use arrow::datatypes::SchemaRef;
 use arrow::record_batch::RecordBatch;
-use datafusion_common::{not_impl_err, DataFusionError, Result};
+use arrow::row::{RowConverter, Rows};
+use datafusion_common::{internal_err, not_impl_err, plan_err, DataFusionError, Result};
 use datafusion_execution::memory_pool::MemoryConsumer;
 use datafusion_execution::TaskContext;
 use datafusion_physical_expr::{OrderingEquivalenceProperties, PhysicalExpr};
@@ -58,6 +65,10 @@ use parking_lot::Mutex;
 use tokio::task::JoinHandle;
 
 mod distributor_channels;
+mod range;
+mod skew;
+
+pub use skew::{HeavyHitters, SkewHandling};
 
 type MaybeBatch = Option<Result<RecordBatch>>;
 type InputPartitionsToCurrentPartitionSender = Vec<DistributionSender<MaybeBatch>>;
@@ -93,11 +104,20 @@ enum BatchPartitionerState {
         exprs: Vec<Arc<dyn PhysicalExpr>>,
         num_partitions: usize,
         hash_buffer: Vec<u64>,
+        skew_handling: Option<SkewHandling>,
+        /// The output partition of the next spread heavy hitter row
+        next_idx: usize,
     },
     RoundRobin {
         num_partitions: usize,
         next_idx: usize,
     },
+    Range {
+        sort_exprs: Vec<PhysicalSortExpr>,
+        num_partitions: usize,
+        converter: RowConverter,
+        split_points: Rows,
+    },
 }
 
 impl BatchPartitioner {
@@ -118,13 +138,68 @@ impl BatchPartitioner {
                 // Use fixed random hash
                 random_state: ahash::RandomState::with_seeds(0, 0, 0, 0),
                 hash_buffer: vec![],
+                skew_handling: None,
+                next_idx: 0,
             },
+            Partitioning::Range(_, _) => {
+                return not_impl_err!(
+                    "Range partitioning requires split points, use BatchPartitioner::try_new_range"
+                )
+            }
             other => return not_impl_err!("Unsupported repartitioning scheme {other:?}"),
         };
 
         Ok(Self { state, timer })
     }
 
+    /// Create a new [`BatchPartitioner`] that range partitions batches on
+    /// `sort_exprs` into `num_partitions` partitions.
+    ///
+    /// `split_points` holds one array per sort expression with the sorted and
+    /// distinct keys that separate consecutive partitions: rows that sort before
+    /// the first split point go to the first partition, rows equal to or after
+    /// the last one to partition `split_points.len()`.
+    ///
+    /// The time spent repartitioning will be recorded to `timer`
+    pub fn try_new_range(
+        sort_exprs: Vec<PhysicalSortExpr>,
+        num_partitions: usize,
+        split_points: &[ArrayRef],
+        timer: metrics::Time,
+    ) -> Result<Self> {
+        let num_split_points = split_points.first().map(|a| a.len()).unwrap_or(0);
+        if split_points.len() != sort_exprs.len() || num_split_points >= num_partitions {
+            return internal_err!(
+                "Invalid split points for range partitioning into {num_partitions} partitions"
+            );
+        }
+        let mut converter = sort_key_converter(&sort_exprs, split_points)?;
+        let split_points = converter.convert_columns(split_points)?;
+        let state = BatchPartitionerState::Range {
+            sort_exprs,
+            num_partitions,
+            converter,
+            split_points,
+        };
+
+        Ok(Self { state, timer })
+    }
+
+    /// Routes the rows holding heavy hitters as specified by `skew_handling`
+    /// rather than by their hash. Only valid for [`Partitioning::Hash`].
+    pub fn with_skew_handling(mut self, skew_handling: SkewHandling) -> Result<Self> {
+        match &mut self.state {
+            BatchPartitionerState::Hash {
+                skew_handling: state,
+                ..
+            } => {
+                *state = Some(skew_handling);
+                Ok(self)
+            }
+            _ => internal_err!("Skew handling requires hash partitioning"),
+        }
+    }
+
     /// Partition the provided [`RecordBatch`] into one or more partitioned [`RecordBatch`]
     /// based on the [`Partitioning`] specified on construction
     ///
@@ -168,6 +243,8 @@ impl BatchPartitioner {
                     exprs,
                     num_partitions: partitions,
                     hash_buffer,
+                    skew_handling,
+                    next_idx,
                 } => {
                     let timer = self.timer.timer();
 
@@ -187,39 +264,59 @@ impl BatchPartitioner {
                         .map(|_| UInt64Builder::with_capacity(batch.num_rows()))
                         .collect();
 
-                    for (index, hash) in hash_buffer.iter().enumerate() {
-                        indices[(*hash % *partitions as u64) as usize]
+                    match skew_handling {
+                        None => {
+                            for (index, hash) in hash_buffer.iter().enumerate() {
+                                indices[(*hash % *partitions as u64) as usize]
+                                    .append_value(index as u64);
+                            }
+                        }
+                        Some(skew_handling) => {
+                            let hot = skew_handling.heavy_hitters().hot_rows(&arrays)?;
+                            for (index, hash) in hash_buffer.iter().enumerate() {
+                                let is_hot = hot.is_valid(index) && hot.value(index);
+                                match skew_handling {
+                                    _ if !is_hot => {
+                                        indices[(*hash % *partitions as u64) as usize]
+                                            .append_value(index as u64);
+                                    }
+                                    SkewHandling::Spread(_) => {
+                                        indices[*next_idx].append_value(index as u64);
+                                        *next_idx = (*next_idx + 1) % *partitions;
+                                    }
+                                    SkewHandling::Broadcast(_) => {
+                                        for partition_indices in indices.iter_mut() {
+                                            partition_indices.append_value(index as u64);
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+
+                    Box::new(take_partitions(batch, indices, timer))
+                }
+                BatchPartitionerState::Range {
+                    sort_exprs,
+                    num_partitions: partitions,
+                    converter,
+                    split_points,
+                } => {
+                    let timer = self.timer.timer();
+
+                    let arrays = evaluate_sort_keys(sort_exprs, &batch)?;
+                    let rows = converter.convert_columns(&arrays)?;
+
+                    let mut indices: Vec<_> = (0..*partitions)
+                        .map(|_| UInt64Builder::with_capacity(batch.num_rows()))
+                        .collect();
+
+                    for (index, row) in rows.iter().enumerate() {
+                        indices[range_partition(split_points, row)]
                             .append_value(index as u64);
                     }
 
-                    let it = indices
-                        .into_iter()
-                        .enumerate()
-                        .filter_map(|(partition, mut indices)| {
-                            let indices = indices.finish();
-                            (!indices.is_empty()).then_some((partition, indices))
-                        })
-                        .map(move |(partition, indices)| {
-                            // Produce batches based on indices
-                            let columns = batch
-                                .columns()
-                                .iter()
-                                .map(|c| {
-                                    arrow::compute::take(c.as_ref(), &indices, None)
-                                        .map_err(DataFusionError::ArrowError)
-                                })
-                                .collect::<Result<Vec<ArrayRef>>>()?;
-
-                            let batch =
-                                RecordBatch::try_new(batch.schema(), columns).unwrap();
-
-                            // bind timer so it drops w/ this iterator
-                            let _ = &timer;
-
-                            Ok((partition, batch))
-                        });
-
-                    Box::new(it)
+                    Box::new(take_partitions(batch, indices, timer))
                 }
             };
 
@@ -231,10 +328,45 @@ impl BatchPartitioner {
         match self.state {
             BatchPartitionerState::RoundRobin { num_partitions, .. } => num_partitions,
             BatchPartitionerState::Hash { num_partitions, .. } => num_partitions,
+            BatchPartitionerState::Range { num_partitions, .. } => num_partitions,
         }
     }
 }
 
+/// Produces one [`RecordBatch`] per partition with a non empty set of row
+/// `indices` into `batch`, recording the elapsed time to `timer`
+fn take_partitions(
+    batch: RecordBatch,
+    indices: Vec<UInt64Builder>,
+    timer: ScopedTimerGuard<'_>,
+) -> impl Iterator<Item = Result<(usize, RecordBatch)>> + Send + '_ {
+    indices
+        .into_iter()
+        .enumerate()
+        .filter_map(|(partition, mut indices)| {
+            let indices = indices.finish();
+            (!indices.is_empty()).then_some((partition, indices))
+        })
+        .map(move |(partition, indices)| {
+            // Produce batches based on indices
+            let columns = batch
+                .columns()
+                .iter()
+                .map(|c| {
+                    arrow::compute::take(c.as_ref(), &indices, None)
+                        .map_err(DataFusionError::ArrowError)
+                })
+                .collect::<Result<Vec<ArrayRef>>>()?;
+
+            let batch = RecordBatch::try_new(batch.schema(), columns).unwrap();
+
+            // bind timer so it drops w/ this iterator
+            let _ = &timer;
+
+            Ok((partition, batch))
+        })
+}
+
 /// Maps `N` input partitions to `M` output partitions based on a
 /// [`Partitioning`] scheme.
 ///
@@ -288,6 +420,13 @@ impl BatchPartitioner {
 /// No guarantees are made about the order of the resulting
 /// partitions unless `preserve_order` is set.
 ///
+/// # Range Partitioning
+///
+/// With [`Partitioning::Range`], the split points between the output
+/// partitions are not known upfront. Every input partition first buffers its
+/// leading rows and contributes their sort keys to a shared sample, and no
+/// rows are sent until all input partitions have been sampled.
+///
 /// # Footnote
 ///
 /// The "Exchange Operator" was first described in the 1989 paper
@@ -312,6 +451,10 @@ pub struct RepartitionExec {
 
     /// Boolean flag to decide whether to preserve ordering
     preserve_order: bool,
+
+    /// How the rows holding heavy hitters of the hash partitioning
+    /// expressions are routed, if they are not hash partitioned
+    skew_handling: Option<SkewHandling>,
 }
 
 #[derive(Debug, Clone)]
@@ -372,6 +515,12 @@ impl RepartitionExec {
         self.preserve_order
     }
 
+    /// How the rows holding heavy hitters are routed, if they are not hash
+    /// partitioned
+    pub fn skew_handling(&self) -> Option<&SkewHandling> {
+        self.skew_handling.as_ref()
+    }
+
     /// Get name of the Executor
     pub fn name(&self) -> &str {
         if self.preserve_order {
@@ -396,7 +545,11 @@ impl DisplayAs for RepartitionExec {
                     self.name(),
                     self.partitioning,
                     self.input.output_partitioning().partition_count()
-                )
+                )?;
+                if let Some(skew_handling) = &self.skew_handling {
+                    write!(f, ", skew_handling={skew_handling}")?;
+                }
+                Ok(())
             }
         }
     }
@@ -421,9 +574,12 @@ impl ExecutionPlan for RepartitionExec {
         self: Arc<Self>,
         children: Vec<Arc<dyn ExecutionPlan>>,
     ) -> Result<Arc<dyn ExecutionPlan>> {
-        let repartition =
+        let mut repartition =
             RepartitionExec::try_new(children[0].clone(), self.partitioning.clone())?
                 .with_preserve_order(self.preserve_order);
+        if let Some(skew_handling) = &self.skew_handling {
+            repartition = repartition.with_skew_handling(skew_handling.clone())?;
+        }
         Ok(Arc::new(repartition))
     }
 
@@ -435,11 +591,20 @@ impl ExecutionPlan for RepartitionExec {
     }
 
     fn benefits_from_input_partitioning(&self) -> Vec<bool> {
-        vec![matches!(self.partitioning, Partitioning::Hash(_, _))]
+        vec![matches!(
+            self.partitioning,
+            Partitioning::Hash(_, _) | Partitioning::Range(_, _)
+        )]
     }
 
     fn output_partitioning(&self) -> Partitioning {
-        self.partitioning.clone()
+        match self.skew_handling {
+            // rows holding heavy hitters may be sent to any output partition
+            Some(_) => {
+                Partitioning::UnknownPartitioning(self.partitioning.partition_count())
+            }
+            None => self.partitioning.clone(),
+        }
     }
 
     fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
@@ -513,6 +678,20 @@ impl ExecutionPlan for RepartitionExec {
                 state.channels.insert(partition, (tx, rx, reservation));
             }
 
+            // range partitioning chooses its split points from samples of all
+            // input partitions
+            let sampler = match &self.partitioning {
+                Partitioning::Range(sort_exprs, num_partitions) => {
+                    Some(Arc::new(RangeSampler::new(
+                        sort_exprs.clone(),
+                        self.input.schema(),
+                        *num_partitions,
+                        num_input_partitions,
+                    )))
+                }
+                _ => None,
+            };
+
             // launch one async task per *input* partition
             let mut join_handles = Vec::with_capacity(num_input_partitions);
             for i in 0..num_input_partitions {
@@ -532,6 +711,8 @@ impl ExecutionPlan for RepartitionExec {
                         i,
                         txs.clone(),
                         self.partitioning.clone(),
+                        self.skew_handling.clone(),
+                        sampler.clone(),
                         r_metrics,
                         context.clone(),
                     ));
@@ -613,7 +794,11 @@ impl ExecutionPlan for RepartitionExec {
     }
 
     fn statistics(&self) -> Statistics {
-        self.input.statistics()
+        match self.skew_handling {
+            // rows holding heavy hitters are duplicated
+            Some(SkewHandling::Broadcast(_)) => self.input.statistics().into_inexact(),
+            _ => self.input.statistics(),
+        }
     }
 }
 
@@ -632,9 +817,29 @@ impl RepartitionExec {
             })),
             metrics: ExecutionPlanMetricsSet::new(),
             preserve_order: false,
+            skew_handling: None,
         })
     }
 
+    /// Routes the rows holding heavy hitters of the hash partitioning
+    /// expressions as specified by `skew_handling`, see [`SkewHandling`].
+    /// Only valid for [`Partitioning::Hash`].
+    pub fn with_skew_handling(mut self, skew_handling: SkewHandling) -> Result<Self> {
+        let Partitioning::Hash(exprs, _) = &self.partitioning else {
+            return plan_err!("Skew handling requires hash partitioning");
+        };
+        if let Some((index, _)) = skew_handling
+            .heavy_hitters()
+            .values()
+            .iter()
+            .find(|(index, _)| *index >= exprs.len())
+        {
+            return plan_err!("Invalid hash partitioning expression index {index}");
+        }
+        self.skew_handling = Some(skew_handling);
+        Ok(self)
+    }
+
     /// Set Order preserving flag
     pub fn with_preserve_order(mut self, preserve_order: bool) -> Self {
         self.preserve_order = preserve_order;
@@ -653,29 +858,101 @@ impl RepartitionExec {
             (DistributionSender<MaybeBatch>, SharedMemoryReservation),
         >,
         partitioning: Partitioning,
+        skew_handling: Option<SkewHandling>,
+        sampler: Option<Arc<RangeSampler>>,
         metrics: RepartitionMetrics,
         context: Arc<TaskContext>,
     ) -> Result<()> {
-        let mut partitioner =
-            BatchPartitioner::try_new(partitioning, metrics.repartition_time.clone())?;
-
         // execute the child operator
         let timer = metrics.fetch_time.timer();
-        let mut stream = input.execute(partition, context)?;
+        let mut stream = input.execute(partition, Arc::clone(&context))?;
         timer.done();
 
+        // Range partitioning can only start once the split points have been
+        // sampled from all input partitions, so the input is buffered while
+        // it is sampled. Only the rows buffered before reaching the sample
+        // size limit, or exhausting the memory pool, are sampled.
+        let mut sampled_batches = VecDeque::new();
+        let mut sample_reservation =
+            MemoryConsumer::new(format!("RepartitionExec[Sample {partition}]"))
+                .register(context.memory_pool());
+        let mut partitioner = match (partitioning, sampler) {
+            (Partitioning::Range(sort_exprs, num_partitions), Some(sampler)) => {
+                let max_sample_bytes = context
+                    .session_config()
+                    .options()
+                    .execution
+                    .range_partition_sample_bytes;
+                let mut reservoir = Reservoir::new(partition as u64);
+                loop {
+                    let timer = metrics.fetch_time.timer();
+                    let result = stream.next().await;
+                    timer.done();
+
+                    let batch = match result {
+                        Some(result) => result?,
+                        None => break,
+                    };
+
+                    reservoir.offer(sampled_batches.len(), batch.num_rows());
+                    let size = batch.get_array_memory_size();
+                    sampled_batches.push_back(batch);
+                    if sample_reservation.try_grow(size).is_err()
+                        || sample_reservation.size() >= max_sample_bytes
+                    {
+                        break;
+                    }
+                }
+
+                let timer = metrics.repartition_time.timer();
+                let sample = reservoir
+                    .sort_keys(&sort_exprs, sampled_batches.make_contiguous())?;
+                timer.done();
+
+                let split_points = sampler.split_points(sample).await?;
+                BatchPartitioner::try_new_range(
+                    sort_exprs,
+                    num_partitions,
+                    &split_points,
+                    metrics.repartition_time.clone(),
+                )?
+            }
+            (partitioning, _) => {
+                let partitioner = BatchPartitioner::try_new(
+                    partitioning,
+                    metrics.repartition_time.clone(),
+                )?;
+                match skew_handling {
+                    Some(skew_handling) => {
+                        partitioner.with_skew_handling(skew_handling)?
+                    }
+                    None => partitioner,
+                }
+            }
+        };
+
         // While there are still outputs to send to, keep pulling inputs
         let mut batches_until_yield = partitioner.num_partitions();
         while !output_channels.is_empty() {
-            // fetch the next batch
-            let timer = metrics.fetch_time.timer();
-            let result = stream.next().await;
-            timer.done();
-
-            // Input is done
-            let batch = match result {
-                Some(result) => result?,
-                None => break,
+            let batch = match sampled_batches.pop_front() {
+                Some(batch) => {
+                    sample_reservation.shrink(
+                        batch.get_array_memory_size().min(sample_reservation.size()),
+                    );
+                    batch
+                }
+                None => {
+                    // fetch the next batch
+                    let timer = metrics.fetch_time.timer();
+                    let result = stream.next().await;
+                    timer.done();
+
+                    // Input is done
+                    match result {
+                        Some(result) => result?,
+                        None => break,
+                    }
+                }
             };
 
             for res in partitioner.partition_iter(batch)? {
@@ -892,7 +1169,7 @@ mod tests {
     use crate::test::create_vec_batches;
     use crate::{
         assert_batches_sorted_eq,
-        physical_plan::{collect, expressions::col, memory::MemoryExec},
+        physical_plan::{collect, displayable, expressions::col, memory::MemoryExec},
         test::{
             assert_is_pending,
             exec::{
@@ -901,11 +1178,13 @@ mod tests {
             },
         },
     };
-    use arrow::array::{ArrayRef, StringArray};
+    use arrow::array::{ArrayRef, StringArray, UInt32Array};
+    use arrow::compute::SortOptions;
     use arrow::datatypes::{DataType, Field, Schema};
     use arrow::record_batch::RecordBatch;
-    use datafusion_common::cast::as_string_array;
-    use datafusion_common::exec_err;
+    use datafusion_common::cast::{as_string_array, as_uint32_array};
+    use datafusion_common::{exec_err, ScalarValue};
+    use datafusion_execution::config::SessionConfig;
     use datafusion_execution::runtime_env::{RuntimeConfig, RuntimeEnv};
     use futures::FutureExt;
     use std::collections::HashSet;
@@ -994,6 +1273,299 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn many_to_many_range_partition() -> Result<()> {
+        // define 3 input partitions of 4 batches each, holding the values
+        // 0..300 in a scrambled order
+        let schema = test_schema();
+        let partitions = (0..3u32)
+            .map(|p| {
+                (0..4u32)
+                    .map(|b| {
+                        let values = (0..25u32)
+                            .map(|i| ((p * 100 + b * 25 + i) * 7919) % 300)
+                            .collect::<Vec<_>>();
+                        RecordBatch::try_new(
+                            schema.clone(),
+                            vec![Arc::new(UInt32Array::from(values))],
+                        )
+                        .unwrap()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+
+        let sort_exprs = vec![PhysicalSortExpr {
+            expr: col("c0", &schema)?,
+            options: SortOptions::default(),
+        }];
+        let output_partitions =
+            repartition(&schema, partitions, Partitioning::Range(sort_exprs, 4)).await?;
+        assert_eq!(4, output_partitions.len());
+
+        let values = output_partitions
+            .iter()
+            .map(|batches| {
+                let mut values = batches
+                    .iter()
+                    .flat_map(|batch| {
+                        as_uint32_array(batch.column(0)).unwrap().values().to_vec()
+                    })
+                    .collect::<Vec<_>>();
+                values.sort_unstable();
+                values
+            })
+            .collect::<Vec<_>>();
+
+        // all values are sampled, so the ranges are even
+        assert_eq!(
+            values.iter().map(|v| v.len()).collect::<Vec<_>>(),
+            vec![75, 75, 75, 75]
+        );
+        // concatenating the partitions in order produces the sorted input
+        assert_eq!(values.concat(), (0..300).collect::<Vec<_>>());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn range_partition_bounded_sample() -> Result<()> {
+        // the first batch holds the values 0..100, the following ones 100..400
+        let schema = test_schema();
+        let partition = (0..4u32)
+            .map(|b| {
+                let values = (b * 100..(b + 1) * 100).collect::<Vec<_>>();
+                RecordBatch::try_new(
+                    schema.clone(),
+                    vec![Arc::new(UInt32Array::from(values))],
+                )
+                .unwrap()
+            })
+            .collect::<Vec<_>>();
+
+        // only the first batch is buffered and sampled
+        let config = SessionConfig::new()
+            .set_usize("datafusion.execution.range_partition_sample_bytes", 1);
+        let task_ctx = Arc::new(TaskContext::default().with_session_config(config));
+        let sort_exprs = vec![PhysicalSortExpr {
+            expr: col("c0", &schema)?,
+            options: SortOptions::default(),
+        }];
+        let output_partitions = repartition_with_context(
+            &schema,
+            vec![partition],
+            Partitioning::Range(sort_exprs, 4),
+            task_ctx,
+        )
+        .await?;
+
+        let values = output_partitions
+            .iter()
+            .map(|batches| {
+                let mut values = batches
+                    .iter()
+                    .flat_map(|batch| {
+                        as_uint32_array(batch.column(0)).unwrap().values().to_vec()
+                    })
+                    .collect::<Vec<_>>();
+                values.sort_unstable();
+                values
+            })
+            .collect::<Vec<_>>();
+
+        // the split points are taken from the first batch, so the last
+        // partition receives all the rows of the following batches
+        assert_eq!(
+            values.iter().map(|v| v.len()).collect::<Vec<_>>(),
+            vec![25, 25, 25, 325]
+        );
+        assert_eq!(values.concat(), (0..400).collect::<Vec<_>>());
+
+        Ok(())
+    }
+
+    #[test]
+    fn range_batch_partitioner() -> Result<()> {
+        let schema = test_schema();
+        let sort_exprs = vec![PhysicalSortExpr {
+            expr: col("c0", &schema)?,
+            options: SortOptions {
+                descending: true,
+                nulls_first: false,
+            },
+        }];
+        let split_points: Vec<ArrayRef> = vec![Arc::new(UInt32Array::from(vec![5, 2]))];
+        let mut partitioner = BatchPartitioner::try_new_range(
+            sort_exprs.clone(),
+            4,
+            &split_points,
+            metrics::Time::new(),
+        )?;
+
+        let batch = RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(UInt32Array::from(vec![0, 6, 2, 5, 3, 7]))],
+        )?;
+        let mut output = vec![];
+        partitioner.partition(batch, |partition, batch| {
+            let values = as_uint32_array(batch.column(0))?.values().to_vec();
+            output.push((partition, values));
+            Ok(())
+        })?;
+        // the last partition stays empty as there are only two split points
+        assert_eq!(
+            output,
+            vec![(0, vec![6, 7]), (1, vec![5, 3]), (2, vec![0, 2])]
+        );
+
+        // there must be fewer split points than partitions
+        let err = BatchPartitioner::try_new_range(
+            sort_exprs,
+            2,
+            &split_points,
+            metrics::Time::new(),
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("Invalid split points"), "{err}");
+
+        // the split points can not be known upfront
+        let err = BatchPartitioner::try_new(
+            Partitioning::Range(vec![], 2),
+            metrics::Time::new(),
+        )
+        .err()
+        .unwrap();
+        assert!(err.to_string().contains("requires split points"), "{err}");
+
+        Ok(())
+    }
+
+    #[test]
+    fn skew_batch_partitioner() -> Result<()> {
+        let schema = test_schema();
+        let heavy_hitters =
+            HeavyHitters::new(vec![(0, vec![ScalarValue::UInt32(Some(1))])]);
+        let partition = |skew_handling: SkewHandling| -> Result<Vec<Vec<u32>>> {
+            let partitioning = Partitioning::Hash(vec![col("c0", &schema)?], 3);
+            let mut partitioner =
+                BatchPartitioner::try_new(partitioning, metrics::Time::new())?
+                    .with_skew_handling(skew_handling)?;
+            let batch = RecordBatch::try_new(
+                schema.clone(),
+                vec![Arc::new(UInt32Array::from(vec![1, 2, 1, 1, 3, 1]))],
+            )?;
+            let mut output = vec![vec![]; 3];
+            partitioner.partition(batch, |partition, batch| {
+                output[partition].extend(as_uint32_array(batch.column(0))?.values());
+                Ok(())
+            })?;
+            Ok(output)
+        };
+        let count = |values: &[u32], value: u32| -> usize {
+            values.iter().filter(|v| **v == value).count()
+        };
+
+        // the heavy hitter rows are spread round robin
+        let output = partition(SkewHandling::Spread(heavy_hitters.clone()))?;
+        let hot_counts: Vec<_> = output.iter().map(|values| count(values, 1)).collect();
+        assert_eq!(hot_counts, vec![2, 1, 1]);
+        assert_eq!(output.iter().map(|values| values.len()).sum::<usize>(), 6);
+
+        // the heavy hitter rows are sent to every partition, the other rows once
+        let output = partition(SkewHandling::Broadcast(heavy_hitters))?;
+        let hot_counts: Vec<_> = output.iter().map(|values| count(values, 1)).collect();
+        assert_eq!(hot_counts, vec![4, 4, 4]);
+        assert_eq!(output.iter().map(|values| values.len()).sum::<usize>(), 14);
+
+        // skew handling requires hash partitioning
+        let err = BatchPartitioner::try_new(
+            Partitioning::RoundRobinBatch(3),
+            metrics::Time::new(),
+        )?
+        .with_skew_handling(partition_skew_handling())
+        .err()
+        .unwrap();
+        assert!(
+            err.to_string().contains("requires hash partitioning"),
+            "{err}"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn skew_repartition() -> Result<()> {
+        let schema = test_schema();
+        let input = vec![RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(UInt32Array::from(vec![1, 2, 1, 1, 3, 1, 4, 1]))],
+        )?];
+        let input = Arc::new(MemoryExec::try_new(&[input], schema.clone(), None)?);
+        let partitioning = Partitioning::Hash(vec![col("c0", &schema)?], 4);
+        let exec = RepartitionExec::try_new(input.clone(), partitioning.clone())?
+            .with_skew_handling(partition_skew_handling())?;
+
+        // the heavy hitters are not hash partitioned
+        assert!(matches!(
+            exec.output_partitioning(),
+            Partitioning::UnknownPartitioning(4)
+        ));
+        assert_eq!(
+            displayable(&exec).one_line().to_string(),
+            "RepartitionExec: partitioning=Hash([c0@0], 4), input_partitions=1, \
+             skew_handling=Spread({0: [1]})\n"
+        );
+
+        let task_ctx = Arc::new(TaskContext::default());
+        let mut values = vec![];
+        for partition in 0..4 {
+            let batches = crate::physical_plan::common::collect(
+                exec.execute(partition, task_ctx.clone())?,
+            )
+            .await?;
+            let hot = batches
+                .iter()
+                .flat_map(|batch| as_uint32_array(batch.column(0)).unwrap().values())
+                .filter(|value| **value == 1)
+                .count();
+            // five heavy hitter rows over four partitions
+            assert!((1..=2).contains(&hot), "{hot}");
+            values.extend(batches);
+        }
+        assert_eq!(values.iter().map(|b| b.num_rows()).sum::<usize>(), 8);
+
+        // skew handling requires hash partitioning and valid expression indices
+        let err =
+            RepartitionExec::try_new(input.clone(), Partitioning::RoundRobinBatch(4))?
+                .with_skew_handling(partition_skew_handling())
+                .err()
+                .unwrap();
+        assert!(
+            err.to_string().contains("requires hash partitioning"),
+            "{err}"
+        );
+        let heavy_hitters =
+            HeavyHitters::new(vec![(1, vec![ScalarValue::UInt32(Some(1))])]);
+        let err = RepartitionExec::try_new(input, partitioning)?
+            .with_skew_handling(SkewHandling::Broadcast(heavy_hitters))
+            .err()
+            .unwrap();
+        assert!(
+            err.to_string().contains("Invalid hash partitioning"),
+            "{err}"
+        );
+
+        Ok(())
+    }
+
+    fn partition_skew_handling() -> SkewHandling {
+        SkewHandling::Spread(HeavyHitters::new(vec![(
+            0,
+            vec![ScalarValue::UInt32(Some(1))],
+        )]))
+    }
+
     fn test_schema() -> Arc<Schema> {
         Arc::new(Schema::new(vec![Field::new("c0", DataType::UInt32, false)]))
     }
@@ -1004,6 +1576,15 @@ mod tests {
         partitioning: Partitioning,
     ) -> Result<Vec<Vec<RecordBatch>>> {
         let task_ctx = Arc::new(TaskContext::default());
+        repartition_with_context(schema, input_partitions, partitioning, task_ctx).await
+    }
+
+    async fn repartition_with_context(
+        schema: &SchemaRef,
+        input_partitions: Vec<Vec<RecordBatch>>,
+        partitioning: Partitioning,
+        task_ctx: Arc<TaskContext>,
+    ) -> Result<Vec<Vec<RecordBatch>>> {
         // create physical plan
         let exec = MemoryExec::try_new(&input_partitions, schema.clone(), None)?;
         let exec = RepartitionExec::try_new(Arc::new(exec), partitioning)?;
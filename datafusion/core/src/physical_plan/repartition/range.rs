@@ -0,0 +1,405 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Helpers to range partition [`RecordBatch`]es using split points sampled
+//! from the input of a [`RepartitionExec`](super::RepartitionExec)
+
+use std::sync::Arc;
+
+use arrow::array::{new_empty_array, ArrayRef, UInt32Array};
+use arrow::compute::{concat, take};
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use arrow::row::{Row, RowConverter, Rows, SortField};
+use datafusion_common::Result;
+use datafusion_physical_expr::PhysicalSortExpr;
+use parking_lot::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use tokio::sync::Barrier;
+
+/// The number of rows of each input partition whose sort keys are sampled
+/// to choose the split points
+pub(super) const SAMPLE_ROWS_PER_INPUT: usize = 8192;
+
+/// Uniformly samples up to [`SAMPLE_ROWS_PER_INPUT`] rows of a sequence of
+/// batches, with reservoir sampling, so that clustered or sorted inputs are
+/// sampled evenly rather than from their leading rows only
+#[derive(Debug)]
+pub(super) struct Reservoir {
+    /// The batch index and row index of the sampled rows
+    rows: Vec<(usize, usize)>,
+    /// The number of rows offered so far
+    num_seen: usize,
+    rng: StdRng,
+}
+
+impl Reservoir {
+    /// Create a new, empty [`Reservoir`]. The sample only depends on `seed`
+    /// and the sizes of the batches
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rows: Vec::with_capacity(SAMPLE_ROWS_PER_INPUT),
+            num_seen: 0,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    /// Offers the `num_rows` rows of the batch at `batch_index`
+    pub fn offer(&mut self, batch_index: usize, num_rows: usize) {
+        for row in 0..num_rows {
+            if self.rows.len() < SAMPLE_ROWS_PER_INPUT {
+                self.rows.push((batch_index, row));
+            } else {
+                let slot = self.rng.gen_range(0..=self.num_seen);
+                if slot < SAMPLE_ROWS_PER_INPUT {
+                    self.rows[slot] = (batch_index, row);
+                }
+            }
+            self.num_seen += 1;
+        }
+    }
+
+    /// Returns the sort keys of the sampled rows of `batches`, which are the
+    /// batches offered to this reservoir, one entry per sampled batch
+    pub fn sort_keys(
+        mut self,
+        sort_exprs: &[PhysicalSortExpr],
+        batches: &[RecordBatch],
+    ) -> Result<Vec<Vec<ArrayRef>>> {
+        self.rows.sort_unstable();
+        let mut sort_keys = vec![];
+        let mut start = 0;
+        while start < self.rows.len() {
+            let batch_index = self.rows[start].0;
+            let len = self.rows[start..]
+                .iter()
+                .take_while(|(b, _)| *b == batch_index)
+                .count();
+            let indices = UInt32Array::from_iter_values(
+                self.rows[start..start + len]
+                    .iter()
+                    .map(|(_, row)| *row as u32),
+            );
+            let keys = evaluate_sort_keys(sort_exprs, &batches[batch_index])?
+                .iter()
+                .map(|keys| Ok(take(keys.as_ref(), &indices, None)?))
+                .collect::<Result<Vec<_>>>()?;
+            sort_keys.push(keys);
+            start += len;
+        }
+        Ok(sort_keys)
+    }
+}
+
+/// Evaluates the sort keys `sort_exprs` against `batch`
+pub(super) fn evaluate_sort_keys(
+    sort_exprs: &[PhysicalSortExpr],
+    batch: &RecordBatch,
+) -> Result<Vec<ArrayRef>> {
+    sort_exprs
+        .iter()
+        .map(|e| Ok(e.expr.evaluate(batch)?.into_array(batch.num_rows())))
+        .collect()
+}
+
+/// Creates a [`RowConverter`] for sort keys with the same types as
+/// `split_points`, ordered according to `sort_exprs`
+pub(super) fn sort_key_converter(
+    sort_exprs: &[PhysicalSortExpr],
+    split_points: &[ArrayRef],
+) -> Result<RowConverter> {
+    let sort_fields = sort_exprs
+        .iter()
+        .zip(split_points)
+        .map(|(e, array)| {
+            SortField::new_with_options(array.data_type().clone(), e.options)
+        })
+        .collect();
+    Ok(RowConverter::new(sort_fields)?)
+}
+
+/// Returns the partition of `row`, which is the number of split points that
+/// sort before or equal to it. Rows with equal sort keys therefore always end
+/// up in the same partition.
+pub(super) fn range_partition(split_points: &Rows, row: Row<'_>) -> usize {
+    let (mut low, mut high) = (0, split_points.num_rows());
+    while low < high {
+        let mid = low + (high - low) / 2;
+        if split_points.row(mid) <= row {
+            low = mid + 1;
+        } else {
+            high = mid;
+        }
+    }
+    low
+}
+
+/// Collects the sampled sort keys of every input partition of a range
+/// [`RepartitionExec`](super::RepartitionExec) and chooses the split points
+/// between the output partitions once all of them have been sampled.
+#[derive(Debug)]
+pub(super) struct RangeSampler {
+    /// The sort keys to partition by
+    sort_exprs: Vec<PhysicalSortExpr>,
+    /// Schema of the input
+    schema: SchemaRef,
+    /// Number of output partitions
+    num_partitions: usize,
+    /// Waits for every input partition to contribute its sample
+    barrier: Barrier,
+    /// Sort keys sampled so far, one entry per sampled batch
+    samples: Mutex<Vec<Vec<ArrayRef>>>,
+    /// The split points, once computed
+    split_points: Mutex<Option<Arc<[ArrayRef]>>>,
+}
+
+impl RangeSampler {
+    /// Create a new [`RangeSampler`] for `num_inputs` input partitions
+    pub fn new(
+        sort_exprs: Vec<PhysicalSortExpr>,
+        schema: SchemaRef,
+        num_partitions: usize,
+        num_inputs: usize,
+    ) -> Self {
+        Self {
+            sort_exprs,
+            schema,
+            num_partitions,
+            barrier: Barrier::new(num_inputs),
+            samples: Mutex::new(vec![]),
+            split_points: Mutex::new(None),
+        }
+    }
+
+    /// Contributes the sort keys sampled from one input partition, and returns
+    /// the split points once every other input partition has done the same.
+    ///
+    /// The returned split points are sorted and distinct, and there are at most
+    /// `num_partitions - 1` of them.
+    pub async fn split_points(
+        &self,
+        sample: Vec<Vec<ArrayRef>>,
+    ) -> Result<Arc<[ArrayRef]>> {
+        self.samples.lock().extend(sample);
+        self.barrier.wait().await;
+
+        let mut split_points = self.split_points.lock();
+        if let Some(split_points) = split_points.as_ref() {
+            return Ok(Arc::clone(split_points));
+        }
+        let computed: Arc<[ArrayRef]> =
+            self.compute_split_points(&self.samples.lock())?.into();
+        *split_points = Some(Arc::clone(&computed));
+        Ok(computed)
+    }
+
+    /// Picks `num_partitions - 1` evenly spaced quantiles of the sampled sort
+    /// keys, skipping duplicates
+    fn compute_split_points(&self, samples: &[Vec<ArrayRef>]) -> Result<Vec<ArrayRef>> {
+        let columns = self
+            .sort_exprs
+            .iter()
+            .enumerate()
+            .map(|(i, e)| {
+                if samples.is_empty() {
+                    return Ok(new_empty_array(&e.expr.data_type(&self.schema)?));
+                }
+                let arrays = samples.iter().map(|s| s[i].as_ref()).collect::<Vec<_>>();
+                Ok(concat(&arrays)?)
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut converter = sort_key_converter(&self.sort_exprs, &columns)?;
+        let rows = converter.convert_columns(&columns)?;
+        let mut sorted = (0..rows.num_rows()).collect::<Vec<_>>();
+        sorted.sort_unstable_by(|a, b| rows.row(*a).cmp(&rows.row(*b)));
+
+        let mut indices: Vec<u32> = vec![];
+        if !sorted.is_empty() {
+            for i in 1..self.num_partitions {
+                let idx = sorted[i * sorted.len() / self.num_partitions];
+                let is_duplicate = indices
+                    .last()
+                    .map(|last| rows.row(*last as usize) == rows.row(idx))
+                    .unwrap_or(false);
+                if !is_duplicate {
+                    indices.push(idx as u32);
+                }
+            }
+        }
+
+        let indices = UInt32Array::from(indices);
+        columns
+            .iter()
+            .map(|c| Ok(take(c.as_ref(), &indices, None)?))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow::array::{Array, Int32Array, StringArray};
+    use arrow::compute::SortOptions;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_physical_expr::expressions::col;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, true),
+        ]))
+    }
+
+    fn sort_exprs(descending: bool) -> Result<Vec<PhysicalSortExpr>> {
+        Ok(vec![PhysicalSortExpr {
+            expr: col("a", &schema())?,
+            options: SortOptions {
+                descending,
+                nulls_first: false,
+            },
+        }])
+    }
+
+    fn sample(values: Vec<Option<i32>>) -> Vec<ArrayRef> {
+        vec![Arc::new(Int32Array::from(values))]
+    }
+
+    #[tokio::test]
+    async fn split_points_from_all_inputs() -> Result<()> {
+        let sampler = Arc::new(RangeSampler::new(sort_exprs(false)?, schema(), 4, 2));
+
+        let task = {
+            let sampler = Arc::clone(&sampler);
+            tokio::spawn(async move {
+                sampler
+                    .split_points(vec![sample((0..50).map(Some).collect())])
+                    .await
+            })
+        };
+        let split_points = sampler
+            .split_points(vec![
+                sample((50..80).map(Some).collect()),
+                sample((80..100).map(Some).collect()),
+            ])
+            .await?;
+        let other = task.await.unwrap()?;
+
+        assert!(Arc::ptr_eq(&split_points, &other));
+        let expected: ArrayRef = Arc::new(Int32Array::from(vec![25, 50, 75]));
+        assert_eq!(split_points.len(), 1);
+        assert_eq!(&split_points[0], &expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn split_points_skip_duplicates() -> Result<()> {
+        let sampler = RangeSampler::new(sort_exprs(true)?, schema(), 4, 1);
+        let split_points = sampler
+            .split_points(vec![sample(vec![
+                Some(1),
+                Some(1),
+                Some(1),
+                Some(1),
+                Some(1),
+                Some(1),
+                Some(2),
+                None,
+            ])])
+            .await?;
+
+        // descending with nulls last: 2, 1, 1, 1, 1, 1, 1, NULL
+        let expected: ArrayRef = Arc::new(Int32Array::from(vec![1]));
+        assert_eq!(&split_points[0], &expected);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn split_points_without_samples() -> Result<()> {
+        let sampler = RangeSampler::new(sort_exprs(false)?, schema(), 4, 1);
+        let split_points = sampler.split_points(vec![]).await?;
+        assert_eq!(split_points.len(), 1);
+        assert!(split_points[0].is_empty());
+        assert_eq!(split_points[0].data_type(), &DataType::Int32);
+        Ok(())
+    }
+
+    #[test]
+    fn partition_of_rows() -> Result<()> {
+        let sort_exprs = vec![PhysicalSortExpr {
+            expr: col("b", &schema())?,
+            options: SortOptions::default(),
+        }];
+        let split_points: Vec<ArrayRef> =
+            vec![Arc::new(StringArray::from(vec!["b", "d"]))];
+        let mut converter = sort_key_converter(&sort_exprs, &split_points)?;
+        let split_points = converter.convert_columns(&split_points)?;
+
+        let values: Vec<ArrayRef> = vec![Arc::new(StringArray::from(vec![
+            Some("a"),
+            Some("b"),
+            Some("c"),
+            Some("d"),
+            Some("e"),
+            None,
+        ]))];
+        let rows = converter.convert_columns(&values)?;
+        let partitions = rows
+            .iter()
+            .map(|row| range_partition(&split_points, row))
+            .collect::<Vec<_>>();
+        // nulls sort first by default
+        assert_eq!(partitions, vec![0, 1, 1, 2, 2, 0]);
+        Ok(())
+    }
+
+    #[test]
+    fn reservoir_samples_whole_input() -> Result<()> {
+        // a sorted input larger than the reservoir
+        let batch_size = SAMPLE_ROWS_PER_INPUT / 2;
+        let batches = (0..4)
+            .map(|i| {
+                let start = (i * batch_size) as i32;
+                RecordBatch::try_new(
+                    schema(),
+                    vec![
+                        Arc::new(Int32Array::from_iter_values(
+                            start..start + batch_size as i32,
+                        )),
+                        Arc::new(StringArray::from(vec![None::<&str>; batch_size])),
+                    ],
+                )
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let mut reservoir = Reservoir::new(0);
+        for (i, batch) in batches.iter().enumerate() {
+            reservoir.offer(i, batch.num_rows());
+        }
+        let sample = reservoir.sort_keys(&sort_exprs(false)?, &batches)?;
+
+        // every batch is sampled, not only the leading ones
+        assert_eq!(sample.len(), 4);
+        let num_rows: usize = sample.iter().map(|keys| keys[0].len()).sum();
+        assert_eq!(num_rows, SAMPLE_ROWS_PER_INPUT);
+        let last = sample[3][0].as_any().downcast_ref::<Int32Array>().unwrap();
+        assert!(last.values().iter().all(|v| *v >= 3 * batch_size as i32));
+        Ok(())
+    }
+}
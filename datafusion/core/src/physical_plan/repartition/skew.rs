@@ -0,0 +1,141 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Routing of the rows holding heavy hitter values of the hash partitioning
+//! expressions of a [`RepartitionExec`](super::RepartitionExec)
+
+use std::fmt::{self, Display};
+
+use arrow::array::{Array, ArrayRef, BooleanArray};
+use arrow::compute::kernels::boolean::or_kleene;
+use arrow::compute::kernels::cmp::eq;
+use datafusion_common::{Result, ScalarValue};
+
+/// Values of the hash partitioning expressions of a
+/// [`RepartitionExec`](super::RepartitionExec) that are held by so many rows
+/// that sending all of them to the same output partition would overload it
+#[derive(Debug, Clone, PartialEq)]
+pub struct HeavyHitters {
+    /// The index of a hash partitioning expression and its heavy hitters
+    values: Vec<(usize, Vec<ScalarValue>)>,
+}
+
+impl HeavyHitters {
+    /// Creates heavy hitters from pairs of the index of a hash partitioning
+    /// expression and the heavy hitter values of the expression
+    pub fn new(values: Vec<(usize, Vec<ScalarValue>)>) -> Self {
+        Self { values }
+    }
+
+    /// The index of each hash partitioning expression and its heavy hitters
+    pub fn values(&self) -> &[(usize, Vec<ScalarValue>)] {
+        &self.values
+    }
+
+    /// Returns which rows hold a heavy hitter of any expression, given the
+    /// values `keys` of the hash partitioning expressions. A null means the
+    /// row is not known to hold a heavy hitter.
+    pub(super) fn hot_rows(&self, keys: &[ArrayRef]) -> Result<BooleanArray> {
+        let num_rows = keys.first().map(|keys| keys.len()).unwrap_or(0);
+        let mut hot = BooleanArray::from(vec![false; num_rows]);
+        for (index, values) in &self.values {
+            for value in values {
+                hot = or_kleene(&hot, &eq(&keys[*index], &value.to_scalar())?)?;
+            }
+        }
+        Ok(hot)
+    }
+}
+
+impl Display for HeavyHitters {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let values = self
+            .values
+            .iter()
+            .map(|(index, values)| {
+                let values = values
+                    .iter()
+                    .map(|value| value.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                format!("{index}: [{values}]")
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+        write!(f, "{{{values}}}")
+    }
+}
+
+/// How a hash [`RepartitionExec`](super::RepartitionExec) routes the rows
+/// that hold [`HeavyHitters`]. All other rows are hash partitioned.
+///
+/// Rows holding the same values are no longer guaranteed to end up in the
+/// same output partition, so the output is not hash partitioned. Joining the
+/// probe side rows spread with [`Self::Spread`] to the build side rows sent
+/// everywhere with [`Self::Broadcast`], using the same heavy hitters, still
+/// finds every match exactly once.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SkewHandling {
+    /// The rows holding heavy hitters are spread round robin over all the
+    /// output partitions
+    Spread(HeavyHitters),
+    /// The rows holding heavy hitters are sent to every output partition
+    Broadcast(HeavyHitters),
+}
+
+impl SkewHandling {
+    /// The heavy hitters whose rows are not hash partitioned
+    pub fn heavy_hitters(&self) -> &HeavyHitters {
+        match self {
+            Self::Spread(heavy_hitters) | Self::Broadcast(heavy_hitters) => heavy_hitters,
+        }
+    }
+}
+
+impl Display for SkewHandling {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Spread(heavy_hitters) => write!(f, "Spread({heavy_hitters})"),
+            Self::Broadcast(heavy_hitters) => write!(f, "Broadcast({heavy_hitters})"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int32Array, StringArray};
+    use std::sync::Arc;
+
+    #[test]
+    fn hot_rows() -> Result<()> {
+        let keys: Vec<ArrayRef> = vec![
+            Arc::new(Int32Array::from(vec![Some(1), Some(2), None, Some(3)])),
+            Arc::new(StringArray::from(vec!["a", "b", "c", "d"])),
+        ];
+        let heavy_hitters = HeavyHitters::new(vec![
+            (0, vec![ScalarValue::Int32(Some(1))]),
+            (1, vec![ScalarValue::from("c"), ScalarValue::from("x")]),
+        ]);
+
+        let hot = heavy_hitters.hot_rows(&keys)?;
+        let hot: Vec<_> = hot.iter().map(|hot| hot == Some(true)).collect();
+        assert_eq!(hot, vec![true, false, true, false]);
+        assert_eq!(heavy_hitters.to_string(), "{0: [1], 1: [c, x]}");
+        Ok(())
+    }
+}
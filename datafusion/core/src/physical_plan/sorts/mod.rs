@@ -21,9 +21,12 @@ mod builder;
 mod cursor;
 mod index;
 pub mod merge;
+pub mod ordered_concat;
+pub mod partial_sort;
 pub mod sort;
 pub mod sort_preserving_merge;
 mod stream;
+pub mod topk;
 
 pub use index::RowIndex;
 pub(crate) use merge::streaming_merge;
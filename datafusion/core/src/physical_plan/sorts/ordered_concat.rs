@@ -0,0 +1,323 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the ordered concatenation plan, which combines the sorted partitions
+//! of a range partitioned input into a single sorted partition
+
+use std::any::Any;
+use std::sync::Arc;
+
+use crate::physical_plan::common::spawn_buffered;
+use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::metrics::{
+    BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet,
+};
+use crate::physical_plan::stream::{ObservedStream, RecordBatchStreamAdapter};
+use crate::physical_plan::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, Partitioning,
+    SendableRecordBatchStream, Statistics,
+};
+
+use arrow::datatypes::SchemaRef;
+use datafusion_common::{internal_err, DataFusionError, Result};
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::{
+    EquivalenceProperties, OrderingEquivalenceProperties, PhysicalSortRequirement,
+};
+use futures::StreamExt;
+
+/// Ordered concatenation execution plan
+///
+/// This takes an input execution plan whose partitions are range partitioned
+/// on a list of sort expressions (see [`Partitioning::Range`]) and sorted with
+/// respect to them, and yields a single sorted partition by emitting the input
+/// partitions one after the other, in partition order.
+///
+/// Unlike a [`SortPreservingMergeExec`], no rows have to be compared: all input
+/// partitions are executed in parallel, and each one only has to buffer a
+/// single batch while the partitions before it are emitted.
+///
+/// ```text
+///  Partition 0 (a < 10)   ──▶ ┌───────────────────┐
+///  Partition 1 (a < 20)   ──▶ │ OrderedConcatExec │ ──▶ partition 0, then 1, then 2
+///  Partition 2 (a >= 20)  ──▶ └───────────────────┘
+/// ```
+///
+/// [`SortPreservingMergeExec`]: super::sort_preserving_merge::SortPreservingMergeExec
+#[derive(Debug)]
+pub struct OrderedConcatExec {
+    /// Input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// Sort expressions
+    expr: Vec<PhysicalSortExpr>,
+    /// Execution metrics
+    metrics: ExecutionPlanMetricsSet,
+}
+
+impl OrderedConcatExec {
+    /// Create a new ordered concatenation plan
+    pub fn new(expr: Vec<PhysicalSortExpr>, input: Arc<dyn ExecutionPlan>) -> Self {
+        Self {
+            input,
+            expr,
+            metrics: ExecutionPlanMetricsSet::new(),
+        }
+    }
+
+    /// Input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Sort expressions
+    pub fn expr(&self) -> &[PhysicalSortExpr] {
+        &self.expr
+    }
+}
+
+impl DisplayAs for OrderedConcatExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let expr: Vec<String> = self.expr.iter().map(|e| e.to_string()).collect();
+                write!(f, "OrderedConcatExec: [{}]", expr.join(","))
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for OrderedConcatExec {
+    /// Return a reference to Any that can be used for downcasting
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        Partitioning::UnknownPartitioning(1)
+    }
+
+    /// Specifies whether this plan generates an infinite stream of records.
+    /// If the plan does not support pipelining, but its input(s) are
+    /// infinite, returns an error to indicate this.
+    fn unbounded_output(&self, children: &[bool]) -> Result<bool> {
+        Ok(children[0])
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        vec![Distribution::RangePartitioned(self.expr.clone())]
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<Vec<PhysicalSortRequirement>>> {
+        vec![Some(PhysicalSortRequirement::from_sort_exprs(&self.expr))]
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        self.input.output_ordering()
+    }
+
+    fn maintains_input_order(&self) -> Vec<bool> {
+        vec![true]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false]
+    }
+
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        self.input.equivalence_properties()
+    }
+
+    fn ordering_equivalence_properties(&self) -> OrderingEquivalenceProperties {
+        self.input.ordering_equivalence_properties()
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        Ok(Arc::new(OrderedConcatExec::new(
+            self.expr.clone(),
+            children[0].clone(),
+        )))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        if 0 != partition {
+            return internal_err!("OrderedConcatExec invalid partition {partition}");
+        }
+
+        let input_partitions = self.input.output_partitioning().partition_count();
+        match input_partitions {
+            0 => internal_err!("OrderedConcatExec requires at least one input partition"),
+            1 => {
+                // bypass if there is only one partition to concatenate
+                self.input.execute(0, context)
+            }
+            _ => {
+                // start all input partitions, so that they are sorted in
+                // parallel while the earlier partitions are emitted
+                let streams = (0..input_partitions)
+                    .map(|partition| {
+                        let stream = self.input.execute(partition, context.clone())?;
+                        Ok(spawn_buffered(stream, 1))
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+
+                let stream = RecordBatchStreamAdapter::new(
+                    self.schema(),
+                    futures::stream::iter(streams).flatten(),
+                );
+                let baseline_metrics = BaselineMetrics::new(&self.metrics, partition);
+                Ok(Box::pin(ObservedStream::new(
+                    Box::pin(stream),
+                    baseline_metrics,
+                )))
+            }
+        }
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        self.input.statistics()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::physical_plan::collect;
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::physical_plan::repartition::RepartitionExec;
+    use crate::physical_plan::sorts::sort::SortExec;
+    use crate::test::assert_is_pending;
+    use crate::test::exec::{assert_strong_count_converges_to_zero, BlockingExec};
+
+    use arrow::array::Int32Array;
+    use arrow::compute::SortOptions;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use arrow::record_batch::RecordBatch;
+    use datafusion_common::cast::as_int32_array;
+    use futures::FutureExt;
+
+    fn sort_exprs(schema: &SchemaRef) -> Vec<PhysicalSortExpr> {
+        vec![PhysicalSortExpr {
+            expr: col("a", schema).unwrap(),
+            options: SortOptions {
+                descending: true,
+                nulls_first: true,
+            },
+        }]
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_range_partitioned_sort() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+
+        // 3 partitions with 10 batches of 100 rows each, in a scrambled order
+        let partitions = (0..3)
+            .map(|p| {
+                (0..10)
+                    .map(|b| {
+                        let values = (0..100).map(|i| {
+                            let n = p * 1000 + b * 100 + i;
+                            (n % 7 != 0).then_some((n * 7919) % 3000)
+                        });
+                        RecordBatch::try_new(
+                            schema.clone(),
+                            vec![Arc::new(Int32Array::from_iter(values))],
+                        )
+                        .unwrap()
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect::<Vec<_>>();
+        let input = Arc::new(MemoryExec::try_new(&partitions, schema.clone(), None)?);
+
+        let repartition = Arc::new(RepartitionExec::try_new(
+            input,
+            Partitioning::Range(sort_exprs(&schema), 4),
+        )?);
+        let sort = Arc::new(
+            SortExec::new(sort_exprs(&schema), repartition)
+                .with_preserve_partitioning(true),
+        );
+        let concat = Arc::new(OrderedConcatExec::new(sort_exprs(&schema), sort));
+        assert_eq!(
+            concat.output_ordering(),
+            Some(sort_exprs(&schema).as_slice())
+        );
+
+        let batches = collect(concat, task_ctx).await?;
+        let values = batches
+            .iter()
+            .flat_map(|batch| as_int32_array(batch.column(0)).unwrap().iter())
+            .collect::<Vec<_>>();
+
+        let mut expected = (0..3000)
+            .map(|n| (n % 7 != 0).then_some((n * 7919) % 3000))
+            .collect::<Vec<_>>();
+        // nulls first, then descending
+        expected.sort_unstable_by(|a, b| b.cmp(a));
+        expected.rotate_right(expected.iter().filter(|v| v.is_none()).count());
+        assert_eq!(values, expected);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_drop_cancel() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let schema = Arc::new(Schema::new(vec![Field::new("a", DataType::Int32, true)]));
+
+        let blocking_exec = Arc::new(BlockingExec::new(Arc::clone(&schema), 2));
+        let refs = blocking_exec.refs();
+        let concat_exec =
+            Arc::new(OrderedConcatExec::new(sort_exprs(&schema), blocking_exec));
+
+        let fut = collect(concat_exec, task_ctx);
+        let mut fut = fut.boxed();
+
+        assert_is_pending(&mut fut);
+        drop(fut);
+        assert_strong_count_converges_to_zero(refs).await;
+
+        Ok(())
+    }
+}
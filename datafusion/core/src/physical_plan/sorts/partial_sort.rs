@@ -0,0 +1,659 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines the partial sort plan, which sorts an input that is already sorted
+//! on a prefix of the sort expressions
+
+use std::any::Any;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::metrics::{
+    BaselineMetrics, ExecutionPlanMetricsSet, MetricsSet,
+};
+use crate::physical_plan::sorts::sort::{fetch_statistics, sort_batch};
+use crate::physical_plan::{
+    DisplayAs, DisplayFormatType, Distribution, ExecutionPlan, Partitioning,
+    RecordBatchStream, SendableRecordBatchStream, Statistics,
+};
+
+use arrow::compute::kernels::cmp::not_distinct;
+use arrow::compute::{and, concat_batches};
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use arrow_array::{Array, ArrayRef, BooleanArray, Scalar};
+use datafusion_common::{internal_err, Result};
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_execution::TaskContext;
+use datafusion_physical_expr::{EquivalenceProperties, PhysicalSortRequirement};
+use futures::{ready, Stream, StreamExt};
+use log::trace;
+
+/// Partial sort execution plan
+///
+/// Sorts an input that is already sorted on the first `common_prefix_length`
+/// sort expressions. Rows sharing the same values of the prefix expressions
+/// form a run, and each run is sorted on its own as soon as the first row of
+/// the next run arrives. Only the current run is buffered, so unlike a
+/// [`SortExec`] this plan can be used on unbounded inputs. The buffered run
+/// is accounted to the memory pool, and the plan fails if a single run does
+/// not fit in memory.
+///
+/// ```text
+///  Input sorted on [a]      Output sorted on [a, b]
+///  a=1 b=3 ┐                a=1 b=1
+///  a=1 b=1 ├ run 1  ──▶     a=1 b=3
+///  a=2 b=5 ┐                a=2 b=2
+///  a=2 b=2 ┘ run 2          a=2 b=5
+/// ```
+///
+/// [`SortExec`]: super::sort::SortExec
+#[derive(Debug)]
+pub struct PartialSortExec {
+    /// Input plan
+    input: Arc<dyn ExecutionPlan>,
+    /// Sort expressions
+    expr: Vec<PhysicalSortExpr>,
+    /// Length of the prefix of `expr` on which the input is already sorted
+    common_prefix_length: usize,
+    /// Containing all metrics set created during sort
+    metrics_set: ExecutionPlanMetricsSet,
+    /// Preserve partitions of input plan. If false, the input partitions
+    /// will be sorted and merged into a single output partition.
+    preserve_partitioning: bool,
+    /// Fetch highest/lowest n results
+    fetch: Option<usize>,
+}
+
+impl PartialSortExec {
+    /// Create a new partial sort execution plan that produces a single,
+    /// sorted output partition.
+    pub fn new(
+        expr: Vec<PhysicalSortExpr>,
+        input: Arc<dyn ExecutionPlan>,
+        common_prefix_length: usize,
+    ) -> Self {
+        assert!(common_prefix_length > 0);
+        Self {
+            input,
+            expr,
+            common_prefix_length,
+            metrics_set: ExecutionPlanMetricsSet::new(),
+            preserve_partitioning: false,
+            fetch: None,
+        }
+    }
+
+    /// Whether this `PartialSortExec` preserves partitioning of the children
+    pub fn preserve_partitioning(&self) -> bool {
+        self.preserve_partitioning
+    }
+
+    /// Specify the partitioning behavior of this partial sort exec
+    ///
+    /// If `preserve_partitioning` is true, sorts each partition
+    /// individually, producing one sorted stream for each input partition.
+    ///
+    /// If `preserve_partitioning` is false, requires a single input partition.
+    pub fn with_preserve_partitioning(mut self, preserve_partitioning: bool) -> Self {
+        self.preserve_partitioning = preserve_partitioning;
+        self
+    }
+
+    /// Limits the output to the first `fetch` rows
+    pub fn with_fetch(mut self, fetch: Option<usize>) -> Self {
+        self.fetch = fetch;
+        self
+    }
+
+    /// Input plan
+    pub fn input(&self) -> &Arc<dyn ExecutionPlan> {
+        &self.input
+    }
+
+    /// Sort expressions
+    pub fn expr(&self) -> &[PhysicalSortExpr] {
+        &self.expr
+    }
+
+    /// Length of the prefix of the sort expressions on which the input is
+    /// already sorted
+    pub fn common_prefix_length(&self) -> usize {
+        self.common_prefix_length
+    }
+
+    /// If `Some(fetch)`, limits output to only the first "fetch" items
+    pub fn fetch(&self) -> Option<usize> {
+        self.fetch
+    }
+}
+
+impl DisplayAs for PartialSortExec {
+    fn fmt_as(
+        &self,
+        t: DisplayFormatType,
+        f: &mut std::fmt::Formatter,
+    ) -> std::fmt::Result {
+        match t {
+            DisplayFormatType::Default | DisplayFormatType::Verbose => {
+                let expr: Vec<String> = self.expr.iter().map(|e| e.to_string()).collect();
+                let common_prefix_length = self.common_prefix_length;
+                match self.fetch {
+                    Some(fetch) => write!(
+                        f,
+                        "PartialSortExec: fetch={fetch}, expr=[{}], common_prefix_length={common_prefix_length}",
+                        expr.join(",")
+                    ),
+                    None => write!(
+                        f,
+                        "PartialSortExec: expr=[{}], common_prefix_length={common_prefix_length}",
+                        expr.join(",")
+                    ),
+                }
+            }
+        }
+    }
+}
+
+impl ExecutionPlan for PartialSortExec {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+
+    /// Get the output partitioning of this plan
+    fn output_partitioning(&self) -> Partitioning {
+        if self.preserve_partitioning {
+            self.input.output_partitioning()
+        } else {
+            Partitioning::UnknownPartitioning(1)
+        }
+    }
+
+    /// Only the current run is buffered, so unbounded inputs can be sorted
+    fn unbounded_output(&self, children: &[bool]) -> Result<bool> {
+        Ok(children[0])
+    }
+
+    fn required_input_distribution(&self) -> Vec<Distribution> {
+        if self.preserve_partitioning {
+            vec![Distribution::UnspecifiedDistribution]
+        } else {
+            vec![Distribution::SinglePartition]
+        }
+    }
+
+    fn required_input_ordering(&self) -> Vec<Option<Vec<PhysicalSortRequirement>>> {
+        vec![Some(PhysicalSortRequirement::from_sort_exprs(
+            &self.expr[..self.common_prefix_length],
+        ))]
+    }
+
+    fn children(&self) -> Vec<Arc<dyn ExecutionPlan>> {
+        vec![self.input.clone()]
+    }
+
+    fn benefits_from_input_partitioning(&self) -> Vec<bool> {
+        vec![false]
+    }
+
+    fn output_ordering(&self) -> Option<&[PhysicalSortExpr]> {
+        Some(&self.expr)
+    }
+
+    fn equivalence_properties(&self) -> EquivalenceProperties {
+        self.input.equivalence_properties()
+    }
+
+    fn with_new_children(
+        self: Arc<Self>,
+        children: Vec<Arc<dyn ExecutionPlan>>,
+    ) -> Result<Arc<dyn ExecutionPlan>> {
+        let new_partial_sort = PartialSortExec::new(
+            self.expr.clone(),
+            children[0].clone(),
+            self.common_prefix_length,
+        )
+        .with_fetch(self.fetch)
+        .with_preserve_partitioning(self.preserve_partitioning);
+
+        Ok(Arc::new(new_partial_sort))
+    }
+
+    fn execute(
+        &self,
+        partition: usize,
+        context: Arc<TaskContext>,
+    ) -> Result<SendableRecordBatchStream> {
+        trace!("Start PartialSortExec::execute for partition {} of context session_id {} and task_id {:?}", partition, context.session_id(), context.task_id());
+
+        let input = self.input.execute(partition, context.clone())?;
+        let reservation = MemoryConsumer::new(format!("PartialSortExec[{partition}]"))
+            .register(context.memory_pool());
+
+        Ok(Box::pin(PartialSortStream {
+            input,
+            expr: self.expr.clone(),
+            common_prefix_length: self.common_prefix_length,
+            in_mem_batches: vec![],
+            reservation,
+            fetch: self.fetch,
+            is_closed: false,
+            baseline_metrics: BaselineMetrics::new(&self.metrics_set, partition),
+        }))
+    }
+
+    fn metrics(&self) -> Option<MetricsSet> {
+        Some(self.metrics_set.clone_inner())
+    }
+
+    fn statistics(&self) -> Statistics {
+        fetch_statistics(
+            self.input.statistics(),
+            self.fetch,
+            self.output_partitioning().partition_count(),
+        )
+    }
+}
+
+/// Stream of [`PartialSortExec`], which emits each run of equal prefix
+/// values sorted once it is complete
+struct PartialSortStream {
+    /// The input plan
+    input: SendableRecordBatchStream,
+    /// Sort expressions
+    expr: Vec<PhysicalSortExpr>,
+    /// Length of the prefix of `expr` on which the input is already sorted
+    common_prefix_length: usize,
+    /// The buffered rows of the current run, which all have the same values
+    /// of the prefix expressions
+    in_mem_batches: Vec<RecordBatch>,
+    /// Reservation for the memory used by `in_mem_batches`
+    reservation: MemoryReservation,
+    /// The number of rows left to emit, if limited
+    fetch: Option<usize>,
+    /// Whether the stream has finished
+    is_closed: bool,
+    /// Execution metrics
+    baseline_metrics: BaselineMetrics,
+}
+
+impl PartialSortStream {
+    fn poll_next_inner(
+        &mut self,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<RecordBatch>>> {
+        if self.is_closed {
+            return Poll::Ready(None);
+        }
+        loop {
+            let sorted = match ready!(self.input.poll_next_unpin(cx)) {
+                Some(Ok(batch)) => {
+                    if batch.num_rows() == 0 {
+                        continue;
+                    }
+                    let slice_point = self.get_slice_point(&batch)?;
+
+                    // The slices of `batch` share its buffers, so its memory
+                    // is reserved once, for as long as a slice is buffered
+                    let size = batch.get_array_memory_size();
+                    self.reservation.try_grow(size)?;
+                    match slice_point {
+                        Some(slice_point) => {
+                            // The rows before the slice point complete the
+                            // buffered run, and possibly some more runs
+                            self.in_mem_batches.push(batch.slice(0, slice_point));
+                            let sorted = self.sort_in_mem_batches(size)?;
+                            self.in_mem_batches.push(
+                                batch.slice(slice_point, batch.num_rows() - slice_point),
+                            );
+                            sorted
+                        }
+                        None => {
+                            self.in_mem_batches.push(batch);
+                            continue;
+                        }
+                    }
+                }
+                Some(Err(e)) => return Poll::Ready(Some(Err(e))),
+                None => {
+                    self.is_closed = true;
+                    if self.in_mem_batches.is_empty() {
+                        return Poll::Ready(None);
+                    }
+                    self.sort_in_mem_batches(0)?
+                }
+            };
+
+            if let Some(fetch) = self.fetch.as_mut() {
+                *fetch -= sorted.num_rows();
+                if *fetch == 0 {
+                    self.is_closed = true;
+                }
+            }
+            return Poll::Ready(Some(Ok(sorted)));
+        }
+    }
+
+    /// Returns the index of the first row of `batch` which belongs to the run
+    /// of its last row, or `None` if all the rows of `batch` belong to the
+    /// buffered run
+    fn get_slice_point(&self, batch: &RecordBatch) -> Result<Option<usize>> {
+        let last_row = batch.num_rows() - 1;
+        let prefix = self.evaluate_prefix(batch)?;
+        let last_values = prefix
+            .iter()
+            .map(|values| values.slice(last_row, 1))
+            .collect::<Vec<_>>();
+
+        let same_run = Self::equal_to(&prefix, &last_values)?;
+        if let Some(position) = (0..last_row).rev().find(|row| !same_run.value(*row)) {
+            return Ok(Some(position + 1));
+        }
+
+        // All rows belong to the same run, which is a new run if it differs
+        // from the buffered one
+        match self.in_mem_batches.first() {
+            Some(buffered) => {
+                let buffered = self.evaluate_prefix(&buffered.slice(0, 1))?;
+                let same_run = Self::equal_to(&buffered, &last_values)?;
+                Ok((!same_run.value(0)).then_some(0))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// Evaluates the prefix expressions against `batch`
+    fn evaluate_prefix(&self, batch: &RecordBatch) -> Result<Vec<ArrayRef>> {
+        self.expr[..self.common_prefix_length]
+            .iter()
+            .map(|expr| Ok(expr.expr.evaluate(batch)?.into_array(batch.num_rows())))
+            .collect()
+    }
+
+    /// Compares each row of `columns` with the single row of `values`,
+    /// treating nulls as equal
+    fn equal_to(columns: &[ArrayRef], values: &[ArrayRef]) -> Result<BooleanArray> {
+        let mut result: Option<BooleanArray> = None;
+        for (column, value) in columns.iter().zip(values) {
+            let equal = not_distinct(column, &Scalar::new(value.clone()))?;
+            result = Some(match result {
+                Some(result) => and(&result, &equal)?,
+                None => equal,
+            });
+        }
+        match result {
+            Some(result) => Ok(result),
+            None => internal_err!("PartialSortExec requires a non empty sort prefix"),
+        }
+    }
+
+    /// Sorts and takes the buffered rows, keeping `retained` bytes reserved
+    /// for the buffers shared with the rows buffered next
+    fn sort_in_mem_batches(&mut self, retained: usize) -> Result<RecordBatch> {
+        let batches = std::mem::take(&mut self.in_mem_batches);
+        let batch = concat_batches(&self.input.schema(), &batches)?;
+        let sorted = sort_batch(&batch, &self.expr, self.fetch)?;
+        self.reservation.resize(retained);
+        Ok(sorted)
+    }
+}
+
+impl Stream for PartialSortStream {
+    type Item = Result<RecordBatch>;
+
+    fn poll_next(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Self::Item>> {
+        let poll = self.poll_next_inner(cx);
+        self.baseline_metrics.record_poll(poll)
+    }
+}
+
+impl RecordBatchStream for PartialSortStream {
+    fn schema(&self) -> SchemaRef {
+        self.input.schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use crate::execution::runtime_env::{RuntimeConfig, RuntimeEnv};
+    use crate::physical_plan::collect;
+    use crate::physical_plan::expressions::col;
+    use crate::physical_plan::memory::MemoryExec;
+    use crate::test::assert_is_pending;
+    use crate::test::exec::{assert_strong_count_converges_to_zero, BlockingExec};
+
+    use arrow::array::Int32Array;
+    use arrow::compute::SortOptions;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_common::cast::as_int32_array;
+    use futures::FutureExt;
+
+    fn test_schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Int32, true),
+        ]))
+    }
+
+    fn sort_exprs(schema: &SchemaRef) -> Vec<PhysicalSortExpr> {
+        let options = SortOptions {
+            descending: false,
+            nulls_first: true,
+        };
+        vec![
+            PhysicalSortExpr {
+                expr: col("a", schema).unwrap(),
+                options,
+            },
+            PhysicalSortExpr {
+                expr: col("b", schema).unwrap(),
+                options,
+            },
+        ]
+    }
+
+    fn batch(
+        schema: &SchemaRef,
+        a: Vec<Option<i32>>,
+        b: Vec<Option<i32>>,
+    ) -> RecordBatch {
+        RecordBatch::try_new(
+            schema.clone(),
+            vec![Arc::new(Int32Array::from(a)), Arc::new(Int32Array::from(b))],
+        )
+        .unwrap()
+    }
+
+    /// Input sorted on `a` only, with runs spanning several batches
+    fn test_input(schema: &SchemaRef) -> Vec<RecordBatch> {
+        vec![
+            batch(
+                schema,
+                vec![None, None, Some(1), Some(1)],
+                vec![Some(2), None, Some(9), Some(4)],
+            ),
+            batch(schema, vec![Some(1), Some(1)], vec![Some(1), None]),
+            batch(schema, vec![], vec![]),
+            batch(schema, vec![Some(2)], vec![Some(7)]),
+            batch(
+                schema,
+                vec![Some(3), Some(3), Some(4), Some(4)],
+                vec![Some(5), Some(3), Some(8), Some(6)],
+            ),
+        ]
+    }
+
+    fn values(batches: &[RecordBatch], column: usize) -> Vec<Option<i32>> {
+        batches
+            .iter()
+            .flat_map(|batch| as_int32_array(batch.column(column)).unwrap().iter())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_partial_sort() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let schema = test_schema();
+        let input = Arc::new(MemoryExec::try_new(
+            &[test_input(&schema)],
+            schema.clone(),
+            None,
+        )?);
+        let partial_sort = Arc::new(PartialSortExec::new(sort_exprs(&schema), input, 1));
+        assert_eq!(
+            partial_sort.required_input_ordering(),
+            vec![Some(PhysicalSortRequirement::from_sort_exprs(
+                &sort_exprs(&schema)[..1]
+            ))]
+        );
+
+        let batches = collect(partial_sort, task_ctx).await?;
+        // each run is emitted once the next one starts
+        assert_eq!(
+            batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+            vec![2, 4, 3, 2]
+        );
+        assert_eq!(
+            values(&batches, 0),
+            vec![None, None, Some(1), Some(1), Some(1), Some(1)]
+                .into_iter()
+                .chain(vec![Some(2), Some(3), Some(3), Some(4), Some(4)])
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            values(&batches, 1),
+            vec![
+                None,
+                Some(2),
+                None,
+                Some(1),
+                Some(4),
+                Some(9),
+                Some(7),
+                Some(3),
+                Some(5),
+                Some(6),
+                Some(8)
+            ]
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_partial_sort_with_fetch() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let schema = test_schema();
+        let input = Arc::new(MemoryExec::try_new(
+            &[test_input(&schema)],
+            schema.clone(),
+            None,
+        )?);
+        let partial_sort = Arc::new(
+            PartialSortExec::new(sort_exprs(&schema), input, 1).with_fetch(Some(4)),
+        );
+
+        let batches = collect(partial_sort, task_ctx).await?;
+        assert_eq!(values(&batches, 0), vec![None, None, Some(1), Some(1)]);
+        assert_eq!(values(&batches, 1), vec![None, Some(2), None, Some(1)]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_partial_sort_memory_limit() -> Result<()> {
+        let schema = test_schema();
+        // a single run that does not fit in the memory pool
+        let input = (0..10)
+            .map(|i| batch(&schema, vec![Some(1); 1000], vec![Some(i); 1000]))
+            .collect::<Vec<_>>();
+        let input = Arc::new(MemoryExec::try_new(&[input], schema.clone(), None)?);
+        let partial_sort = Arc::new(PartialSortExec::new(sort_exprs(&schema), input, 1));
+
+        let rt_config = RuntimeConfig::new().with_memory_limit(10_000, 1.0);
+        let task_ctx = Arc::new(
+            TaskContext::default().with_runtime(Arc::new(RuntimeEnv::new(rt_config)?)),
+        );
+        let err = collect(partial_sort, task_ctx).await.unwrap_err();
+        assert!(
+            err.to_string().contains("Resources exhausted"),
+            "unexpected error: {err}"
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_partial_sort_memory_accounting() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let schema = test_schema();
+        let first = batch(&schema, vec![Some(1), Some(1)], vec![Some(2), Some(1)]);
+        let second = batch(
+            &schema,
+            vec![Some(1), Some(2), Some(2)],
+            vec![Some(3), Some(5), Some(4)],
+        );
+        let second_size = second.get_array_memory_size();
+        let input = Arc::new(MemoryExec::try_new(
+            &[vec![first, second]],
+            schema.clone(),
+            None,
+        )?);
+        let partial_sort = Arc::new(PartialSortExec::new(sort_exprs(&schema), input, 1));
+
+        let mut stream = partial_sort.execute(0, task_ctx.clone())?;
+        let sorted = stream.next().await.unwrap()?;
+        assert_eq!(values(&[sorted], 1), vec![Some(1), Some(2), Some(3)]);
+        // the rows of the second batch after the first run keep its buffers
+        // in memory, which are reserved once
+        assert_eq!(task_ctx.memory_pool().reserved(), second_size);
+
+        let sorted = stream.next().await.unwrap()?;
+        assert_eq!(values(&[sorted], 1), vec![Some(4), Some(5)]);
+        assert!(stream.next().await.is_none());
+        assert_eq!(task_ctx.memory_pool().reserved(), 0);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_drop_cancel() -> Result<()> {
+        let task_ctx = Arc::new(TaskContext::default());
+        let schema = test_schema();
+
+        let blocking_exec = Arc::new(BlockingExec::new(Arc::clone(&schema), 1));
+        let refs = blocking_exec.refs();
+        let partial_sort_exec =
+            Arc::new(PartialSortExec::new(sort_exprs(&schema), blocking_exec, 1));
+
+        let fut = collect(partial_sort_exec, task_ctx);
+        let mut fut = fut.boxed();
+
+        assert_is_pending(&mut fut);
+        drop(fut);
+        assert_strong_count_converges_to_zero(refs).await;
+
+        Ok(())
+    }
+}
@@ -19,12 +19,13 @@
 //! It will do in-memory sorting if it has enough memory budget
 //! but spills to disk if needed.
 
-use crate::physical_plan::common::{spawn_buffered, IPCWriter};
+use crate::physical_plan::common::{spawn_buffered, spill_write_options, IPCWriter};
 use crate::physical_plan::expressions::PhysicalSortExpr;
 use crate::physical_plan::metrics::{
-    BaselineMetrics, Count, ExecutionPlanMetricsSet, MetricBuilder, MetricsSet,
+    BaselineMetrics, Count, ExecutionPlanMetricsSet, Gauge, MetricBuilder, MetricsSet,
 };
 use crate::physical_plan::sorts::merge::streaming_merge;
+use crate::physical_plan::sorts::topk::TopK;
 use crate::physical_plan::stream::{RecordBatchReceiverStream, RecordBatchStreamAdapter};
 use crate::physical_plan::{
     DisplayAs, DisplayFormatType, Distribution, EmptyRecordBatchStream, ExecutionPlan,
@@ -34,13 +35,17 @@ pub use arrow::compute::SortOptions;
 use arrow::compute::{concat_batches, lexsort_to_indices, take};
 use arrow::datatypes::SchemaRef;
 use arrow::ipc::reader::FileReader;
+use arrow::ipc::writer::IpcWriteOptions;
 use arrow::record_batch::RecordBatch;
-use datafusion_common::{exec_err, plan_err, DataFusionError, Result};
+use datafusion_common::{
+    exec_err, plan_err, ColumnStatistics, DataFusionError, Precision, Result,
+};
+use datafusion_execution::disk_manager::{SpillFile, SpillUsage};
 use datafusion_execution::memory_pool::{
     human_readable_size, MemoryConsumer, MemoryReservation,
 };
 use datafusion_execution::runtime_env::RuntimeEnv;
-use datafusion_execution::TaskContext;
+use datafusion_execution::{CancellationToken, TaskContext};
 use datafusion_physical_expr::EquivalenceProperties;
 use futures::{StreamExt, TryStreamExt};
 use log::{debug, error, trace};
@@ -49,9 +54,8 @@ use std::fmt;
 use std::fmt::{Debug, Formatter};
 use std::fs::File;
 use std::io::BufReader;
-use std::path::{Path, PathBuf};
+use std::path::Path;
 use std::sync::Arc;
-use tempfile::NamedTempFile;
 use tokio::sync::mpsc::Sender;
 use tokio::task;
 
@@ -64,6 +68,9 @@ struct ExternalSorterMetrics {
 
     /// total spilled bytes during the execution of the operator
     spilled_bytes: Count,
+
+    /// peak memory reserved by the operator
+    mem_used: Gauge,
 }
 
 impl ExternalSorterMetrics {
@@ -72,6 +79,7 @@ impl ExternalSorterMetrics {
             baseline: BaselineMetrics::new(metrics, partition),
             spill_count: MetricBuilder::new(metrics).spill_count(partition),
             spilled_bytes: MetricBuilder::new(metrics).spilled_bytes(partition),
+            mem_used: MetricBuilder::new(metrics).mem_used(partition),
         }
     }
 }
@@ -202,7 +210,7 @@ struct ExternalSorter {
     in_mem_batches_sorted: bool,
     /// If data has previously been spilled, the locations of the
     /// spill files (in Arrow IPC format)
-    spills: Vec<NamedTempFile>,
+    spills: Vec<SpillFile>,
     /// Sort expressions
     expr: Arc<[PhysicalSortExpr]>,
     /// Runtime metrics
@@ -227,6 +235,12 @@ struct ExternalSorter {
     /// the data will be concated and sorted in place rather than
     /// sort/merged.
     sort_in_place_threshold_bytes: usize,
+    /// Checked for every inserted batch to stop sorting cancelled queries
+    cancellation_token: CancellationToken,
+    /// Options, such as the compression codec, for writing spill files
+    spill_write_options: IpcWriteOptions,
+    /// The spill usage of the query this sort belongs to
+    spill_usage: SpillUsage,
 }
 
 impl ExternalSorter {
@@ -267,13 +281,35 @@ impl ExternalSorter {
             batch_size,
             sort_spill_reservation_bytes,
             sort_in_place_threshold_bytes,
+            cancellation_token: CancellationToken::new(),
+            spill_write_options: IpcWriteOptions::default(),
+            spill_usage: SpillUsage::new(),
         }
     }
 
+    /// Stop with an error once `token` is cancelled
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = token;
+        self
+    }
+
+    /// Write spill files with `options`, e.g. to compress them
+    pub fn with_spill_write_options(mut self, options: IpcWriteOptions) -> Self {
+        self.spill_write_options = options;
+        self
+    }
+
+    /// Account the disk space used by spill files to `usage`
+    pub fn with_spill_usage(mut self, usage: SpillUsage) -> Self {
+        self.spill_usage = usage;
+        self
+    }
+
     /// Appends an unsorted [`RecordBatch`] to `in_mem_batches`
     ///
     /// Updates memory usage metrics, and possibly triggers spilling to disk
     async fn insert_batch(&mut self, input: RecordBatch) -> Result<()> {
+        self.cancellation_token.check()?;
         if input.num_rows() == 0 {
             return Ok(());
         }
@@ -300,6 +336,9 @@ impl ExternalSorter {
             }
         }
 
+        self.metrics
+            .mem_used
+            .set_max(self.reservation.size() + self.merge_reservation.size());
         self.in_mem_batches.push(input);
         self.in_mem_batches_sorted = false;
         Ok(())
@@ -319,6 +358,7 @@ impl ExternalSorter {
     /// 2. A combined streaming merge incorporating both in-memory
     /// batches and data from spill files on disk.
     fn sort(&mut self) -> Result<SendableRecordBatchStream> {
+        self.cancellation_token.check()?;
         if self.spilled_before() {
             let mut streams = vec![];
             if !self.in_mem_batches.is_empty() {
@@ -383,9 +423,18 @@ impl ExternalSorter {
 
         self.in_mem_sort().await?;
 
-        let spillfile = self.runtime.disk_manager.create_tmp_file("Sorting")?;
+        let spillfile = self
+            .runtime
+            .disk_manager
+            .create_spill_file("Sorting", &self.spill_usage)?;
         let batches = std::mem::take(&mut self.in_mem_batches);
-        spill_sorted_batches(batches, spillfile.path(), self.schema.clone()).await?;
+        let spillfile = spill_sorted_batches(
+            batches,
+            spillfile,
+            self.schema.clone(),
+            self.spill_write_options.clone(),
+        )
+        .await?;
         let used = self.reservation.free();
         self.metrics.spill_count.add(1);
         self.metrics.spilled_bytes.add(used);
@@ -574,7 +623,7 @@ impl Debug for ExternalSorter {
     }
 }
 
-fn sort_batch(
+pub(crate) fn sort_batch(
     batch: &RecordBatch,
     expressions: &[PhysicalSortExpr],
     fetch: Option<usize>,
@@ -597,11 +646,14 @@ fn sort_batch(
 
 async fn spill_sorted_batches(
     batches: Vec<RecordBatch>,
-    path: &Path,
+    mut spill_file: SpillFile,
     schema: SchemaRef,
-) -> Result<()> {
-    let path: PathBuf = path.into();
-    let handle = task::spawn_blocking(move || write_sorted(batches, path, schema));
+    write_options: IpcWriteOptions,
+) -> Result<SpillFile> {
+    let handle = task::spawn_blocking(move || {
+        write_sorted(batches, &mut spill_file, schema, write_options)?;
+        Ok(spill_file)
+    });
     match handle.await {
         Ok(r) => r,
         Err(e) => exec_err!("Error occurred while spilling {e}"),
@@ -609,7 +661,7 @@ async fn spill_sorted_batches(
 }
 
 fn read_spill_as_stream(
-    path: NamedTempFile,
+    path: SpillFile,
     schema: SchemaRef,
 ) -> Result<SendableRecordBatchStream> {
     let mut builder = RecordBatchReceiverStream::builder(schema, 2);
@@ -624,16 +676,25 @@ fn read_spill_as_stream(
     Ok(builder.build())
 }
 
+/// Writes `batches` to `spill_file`, reserving space under the spill limit
+/// of the [`DiskManager`] before writing every batch
+///
+/// [`DiskManager`]: datafusion_execution::disk_manager::DiskManager
 fn write_sorted(
     batches: Vec<RecordBatch>,
-    path: PathBuf,
+    spill_file: &mut SpillFile,
     schema: SchemaRef,
+    write_options: IpcWriteOptions,
 ) -> Result<()> {
-    let mut writer = IPCWriter::new(path.as_ref(), schema.as_ref())?;
+    let mut writer =
+        IPCWriter::new_with_options(spill_file.path(), schema.as_ref(), write_options)?;
     for batch in batches {
+        spill_file.try_reserve(batch.get_array_memory_size())?;
         writer.write(&batch)?;
+        spill_file.update_size()?;
     }
     writer.finish()?;
+    spill_file.update_size()?;
     debug!(
         "Spilled {} batches of total {} rows to disk, memory released {}",
         writer.num_batches,
@@ -851,6 +912,37 @@ impl ExecutionPlan for SortExec {
 
         trace!("End SortExec's input.execute for partition: {}", partition);
 
+        // Small limits only need to keep the top rows of the input in memory
+        match self.fetch {
+            Some(fetch)
+                if fetch > 0 && fetch <= execution_options.sort_topk_max_fetch =>
+            {
+                let mut topk = TopK::try_new(
+                    partition,
+                    input.schema(),
+                    self.expr.clone(),
+                    fetch,
+                    context.session_config().batch_size(),
+                    context.runtime_env(),
+                    &self.metrics_set,
+                    context.cancellation_token().clone(),
+                )?;
+
+                return Ok(Box::pin(RecordBatchStreamAdapter::new(
+                    self.schema(),
+                    futures::stream::once(async move {
+                        while let Some(batch) = input.next().await {
+                            let batch = batch?;
+                            topk.insert_batch(batch)?;
+                        }
+                        topk.emit()
+                    })
+                    .try_flatten(),
+                )));
+            }
+            _ => {}
+        }
+
         let mut sorter = ExternalSorter::new(
             partition,
             input.schema(),
@@ -861,7 +953,12 @@ impl ExecutionPlan for SortExec {
             execution_options.sort_in_place_threshold_bytes,
             &self.metrics_set,
             context.runtime_env(),
-        );
+        )
+        .with_cancellation_token(context.cancellation_token().clone())
+        .with_spill_write_options(spill_write_options(
+            context.session_config().options(),
+        )?)
+        .with_spill_usage(context.spill_usage().clone());
 
         Ok(Box::pin(RecordBatchStreamAdapter::new(
             self.schema(),
@@ -881,7 +978,45 @@ impl ExecutionPlan for SortExec {
     }
 
     fn statistics(&self) -> Statistics {
-        self.input.statistics()
+        fetch_statistics(
+            self.input.statistics(),
+            self.fetch,
+            self.output_partitioning().partition_count(),
+        )
+    }
+}
+
+/// Statistics of a sort keeping the first `fetch` rows of each of its
+/// `output_partitions` output partitions, given its input statistics
+pub(crate) fn fetch_statistics(
+    input_stats: Statistics,
+    fetch: Option<usize>,
+    output_partitions: usize,
+) -> Statistics {
+    let Some(fetch) = fetch else {
+        return input_stats;
+    };
+    match input_stats.num_rows {
+        // no row is dropped
+        Precision::Exact(num_rows) if num_rows <= fetch => input_stats,
+        num_rows => Statistics {
+            num_rows: if output_partitions == 1 {
+                num_rows.map(|num_rows| num_rows.min(fetch))
+            } else {
+                // the number of rows of each partition is not known
+                num_rows
+                    .map(|num_rows| num_rows.min(fetch.saturating_mul(output_partitions)))
+                    .to_inexact()
+            },
+            total_byte_size: Precision::Absent,
+            // the dropped rows may hold the minimum, maximum or nulls
+            column_statistics: input_stats.column_statistics.map(|column_statistics| {
+                column_statistics
+                    .into_iter()
+                    .map(ColumnStatistics::into_inexact)
+                    .collect()
+            }),
+        },
     }
 }
 
@@ -894,12 +1029,16 @@ mod tests {
     use crate::physical_plan::memory::MemoryExec;
     use crate::test;
     use crate::test::assert_is_pending;
-    use crate::test::exec::{assert_strong_count_converges_to_zero, BlockingExec};
+    use crate::test::exec::{
+        assert_strong_count_converges_to_zero, BlockingExec, StatisticsExec,
+    };
     use arrow::array::*;
     use arrow::compute::SortOptions;
     use arrow::datatypes::*;
     use datafusion_common::cast::{as_primitive_array, as_string_array};
+    use datafusion_common::ScalarValue;
     use datafusion_execution::config::SessionConfig;
+    use datafusion_execution::disk_manager::DiskManagerConfig;
     use datafusion_execution::runtime_env::RuntimeConfig;
     use futures::FutureExt;
     use std::collections::HashMap;
@@ -934,7 +1073,7 @@ mod tests {
             Arc::new(CoalescePartitionsExec::new(csv)),
         ));
 
-        let result = collect(sort_exec, task_ctx.clone()).await?;
+        let result = collect(sort_exec.clone(), task_ctx.clone()).await?;
 
         assert_eq!(result.len(), 1);
 
@@ -952,6 +1091,44 @@ mod tests {
         assert_eq!(c7.value(0), 15);
         assert_eq!(c7.value(c7.len() - 1), 254,);
 
+        let mem_used = sort_exec
+            .metrics()
+            .unwrap()
+            .sum_by_name("mem_used")
+            .unwrap();
+        assert!(mem_used.as_usize() > 0);
+
+        assert_eq!(
+            task_ctx.runtime_env().memory_pool.reserved(),
+            0,
+            "The sort should have returned all memory used back to the memory manager"
+        );
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sort_cancelled() -> Result<()> {
+        let token = CancellationToken::new();
+        let task_ctx =
+            Arc::new(TaskContext::default().with_cancellation_token(token.clone()));
+        let partitions = 4;
+        let tmp_dir = TempDir::new()?;
+        let csv = test::scan_partitioned_csv(partitions, tmp_dir.path())?;
+        let schema = csv.schema();
+
+        let sort_exec = Arc::new(SortExec::new(
+            vec![PhysicalSortExpr {
+                expr: col("c1", &schema)?,
+                options: SortOptions::default(),
+            }],
+            Arc::new(CoalescePartitionsExec::new(csv)),
+        ));
+
+        token.cancel_with_reason("test");
+        let err = collect(sort_exec, task_ctx.clone()).await.unwrap_err();
+        assert_eq!(err.to_string(), "Query cancelled: test");
+
         assert_eq!(
             task_ctx.runtime_env().memory_pool.reserved(),
             0,
@@ -1039,6 +1216,81 @@ mod tests {
         Ok(())
     }
 
+    /// Sorts the partitioned CSV test data by `c1` with `rt_config`, which
+    /// should cause the sort to spill
+    async fn sort_spilling(
+        session_config: SessionConfig,
+        rt_config: RuntimeConfig,
+    ) -> Result<(Vec<RecordBatch>, SpillUsage)> {
+        let sort_spill_reservation_bytes = session_config
+            .options()
+            .execution
+            .sort_spill_reservation_bytes;
+        let rt_config =
+            rt_config.with_memory_limit(sort_spill_reservation_bytes + 12288, 1.0);
+        let spill_usage = SpillUsage::new();
+        let task_ctx = Arc::new(
+            TaskContext::default()
+                .with_session_config(session_config)
+                .with_runtime(Arc::new(RuntimeEnv::new(rt_config)?))
+                .with_spill_usage(spill_usage.clone()),
+        );
+
+        let tmp_dir = TempDir::new()?;
+        let csv = test::scan_partitioned_csv(4, tmp_dir.path())?;
+        let schema = csv.schema();
+        let sort_exec = Arc::new(SortExec::new(
+            vec![PhysicalSortExpr {
+                expr: col("c1", &schema)?,
+                options: SortOptions::default(),
+            }],
+            Arc::new(CoalescePartitionsExec::new(csv)),
+        ));
+
+        let result = collect(sort_exec, task_ctx).await?;
+        Ok((result, spill_usage))
+    }
+
+    #[tokio::test]
+    async fn test_sort_spill_compressed() -> Result<()> {
+        for codec in ["lz4_frame", "zstd"] {
+            let session_config = SessionConfig::new()
+                .set_str("datafusion.execution.spill_compression", codec);
+            let (result, spill_usage) =
+                sort_spilling(session_config, RuntimeConfig::new()).await?;
+
+            assert_eq!(result.iter().map(|b| b.num_rows()).sum::<usize>(), 100);
+            let c1 = as_string_array(result[0].column(0))?;
+            assert_eq!(c1.value(0), "a");
+            assert_eq!(c1.value(c1.len() - 1), "e");
+
+            assert!(spill_usage.num_files() > 0);
+            assert!(spill_usage.total_bytes() > 0);
+            assert!(spill_usage.peak_bytes() <= spill_usage.total_bytes());
+        }
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sort_spill_limit() -> Result<()> {
+        let rt_config = RuntimeConfig::new()
+            .with_disk_manager(DiskManagerConfig::new().with_max_spill_size(1));
+        let err = sort_spilling(SessionConfig::new(), rt_config)
+            .await
+            .unwrap_err();
+
+        assert!(
+            matches!(err, DataFusionError::ResourcesExhausted(_)),
+            "{err}"
+        );
+        assert!(
+            err.to_string()
+                .contains("Spill limit exceeded while Sorting"),
+            "{err}"
+        );
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_sort_fetch_memory_calculation() -> Result<()> {
         // This test mirrors down the size from the example above.
@@ -1056,7 +1308,9 @@ mod tests {
         ];
 
         for (fetch, expect_spillage) in test_options {
-            let session_config = SessionConfig::new();
+            let mut session_config = SessionConfig::new();
+            // use the external sorter rather than the TopK
+            session_config.options_mut().execution.sort_topk_max_fetch = 0;
             let sort_spill_reservation_bytes = session_config
                 .options()
                 .execution
@@ -1111,6 +1365,45 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_sort_fetch_top_k() -> Result<()> {
+        let partitions = 4;
+        let tmp_dir = TempDir::new()?;
+        let csv = test::scan_partitioned_csv(partitions, tmp_dir.path())?;
+        let schema = csv.schema();
+
+        let task_ctx = Arc::new(TaskContext::default());
+
+        let sort_exec = Arc::new(
+            SortExec::new(
+                vec![PhysicalSortExpr {
+                    expr: col("c12", &schema)?,
+                    options: SortOptions {
+                        descending: true,
+                        nulls_first: false,
+                    },
+                }],
+                Arc::new(CoalescePartitionsExec::new(csv)),
+            )
+            .with_fetch(Some(3)),
+        );
+
+        let result = collect(sort_exec.clone(), task_ctx).await?;
+        assert_eq!(result.iter().map(|b| b.num_rows()).sum::<usize>(), 3);
+
+        // the rows are in descending order of c12
+        let c12 = concat_batches(&schema, &result)?;
+        let c12 = as_primitive_array::<Float64Type>(c12.column(11))?;
+        assert!(c12.values().windows(2).all(|w| w[0] >= w[1]));
+
+        // the TopK was used: it reports replaced rows and has no spill metrics
+        let metrics = sort_exec.metrics().unwrap();
+        assert!(metrics.sum_by_name("row_replacements").unwrap().as_usize() >= 3);
+        assert_eq!(metrics.spill_count(), None);
+
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_sort_metadata() -> Result<()> {
         let task_ctx = Arc::new(TaskContext::default());
@@ -1296,4 +1589,48 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_sort_fetch_statistics() -> Result<()> {
+        let schema = Schema::new(vec![Field::new("a", DataType::Int32, true)]);
+        let input_stats = Statistics {
+            num_rows: Precision::Exact(100),
+            total_byte_size: Precision::Exact(400),
+            column_statistics: Some(vec![ColumnStatistics {
+                null_count: Precision::Exact(0),
+                max_value: Precision::Exact(ScalarValue::Int32(Some(99))),
+                min_value: Precision::Exact(ScalarValue::Int32(Some(0))),
+                ..Default::default()
+            }]),
+        };
+        let input = Arc::new(StatisticsExec::new(input_stats.clone(), schema));
+        let sort_expr = vec![PhysicalSortExpr {
+            expr: col("a", &input.schema())?,
+            options: SortOptions::default(),
+        }];
+
+        // without a fetch or with a fetch keeping all the rows, the statistics
+        // are those of the input
+        let sort = SortExec::new(sort_expr.clone(), input.clone());
+        assert_eq!(sort.statistics(), input_stats);
+        let sort = SortExec::new(sort_expr.clone(), input.clone()).with_fetch(Some(100));
+        assert_eq!(sort.statistics(), input_stats);
+
+        // the dropped rows may hold the minimum or the maximum
+        let sort = SortExec::new(sort_expr.clone(), input.clone()).with_fetch(Some(10));
+        let stats = sort.statistics();
+        assert_eq!(stats.num_rows, Precision::Exact(10));
+        assert_eq!(
+            stats.column_statistics.unwrap()[0].max_value,
+            Precision::Inexact(ScalarValue::Int32(Some(99)))
+        );
+
+        // each partition keeps up to `fetch` rows
+        let sort = SortExec::new(sort_expr, input)
+            .with_preserve_partitioning(true)
+            .with_fetch(Some(10));
+        assert_eq!(sort.statistics().num_rows, Precision::Inexact(20));
+
+        Ok(())
+    }
 }
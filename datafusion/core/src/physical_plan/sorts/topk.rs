@@ -0,0 +1,620 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! TopK: a specialized sort that only keeps the first `k` rows of its
+//! input in memory
+
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::Arc;
+
+use arrow::compute::interleave;
+use arrow::datatypes::SchemaRef;
+use arrow::record_batch::RecordBatch;
+use arrow::row::{RowConverter, SortField};
+use arrow_array::ArrayRef;
+use datafusion_common::Result;
+use datafusion_execution::memory_pool::{MemoryConsumer, MemoryReservation};
+use datafusion_execution::runtime_env::RuntimeEnv;
+use datafusion_execution::CancellationToken;
+
+use crate::physical_plan::expressions::PhysicalSortExpr;
+use crate::physical_plan::metrics::{
+    BaselineMetrics, Count, ExecutionPlanMetricsSet, MetricBuilder,
+};
+use crate::physical_plan::stream::RecordBatchStreamAdapter;
+use crate::physical_plan::SendableRecordBatchStream;
+
+/// Keeps the first `k` rows of its input according to the sort
+/// expressions, for `ORDER BY ... LIMIT k` queries.
+///
+/// Unlike [`ExternalSorter`](super::sort), which buffers the entire input
+/// and may have to spill, the memory used by `TopK` is proportional to `k`
+/// rather than to the size of the input:
+///
+/// 1. The sort keys of each input batch are converted to the
+///    [row format](arrow::row), so that rows can be compared as bytes.
+///
+/// 2. A max heap holds the (at most) `k` smallest rows seen so far. A new
+///    row only enters the heap if it is smaller than the largest row in it,
+///    which it then replaces.
+///
+/// 3. The heap only references the input batches its rows belong to. Batches
+///    with no rows in the heap are released, and once too many rows of the
+///    retained batches are unused, the heap rows are compacted into a single
+///    new batch.
+///
+/// 4. When the input is exhausted, the heap rows are emitted in order.
+pub struct TopK {
+    /// schema of the output (and the input)
+    schema: SchemaRef,
+    /// Runtime metrics
+    metrics: TopKMetrics,
+    /// Reservation for the memory used by the heap and its batches
+    reservation: MemoryReservation,
+    /// The target number of rows of the output batches
+    batch_size: usize,
+    /// sort expressions
+    expr: Arc<[PhysicalSortExpr]>,
+    /// Converts the sort keys to the row format
+    row_converter: RowConverter,
+    /// The top `k` rows seen so far
+    heap: TopKHeap,
+    /// Aborts the operator when the query is cancelled
+    cancellation_token: CancellationToken,
+}
+
+impl TopK {
+    /// Create a new [`TopK`] that stores the top `k` values, as
+    /// defined by the sort expressions in `expr`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn try_new(
+        partition_id: usize,
+        schema: SchemaRef,
+        expr: Vec<PhysicalSortExpr>,
+        k: usize,
+        batch_size: usize,
+        runtime: Arc<RuntimeEnv>,
+        metrics: &ExecutionPlanMetricsSet,
+        cancellation_token: CancellationToken,
+    ) -> Result<Self> {
+        let reservation = MemoryConsumer::new(format!("TopK[{partition_id}]"))
+            .register(&runtime.memory_pool);
+
+        let sort_fields = expr
+            .iter()
+            .map(|e| {
+                Ok(SortField::new_with_options(
+                    e.expr.data_type(&schema)?,
+                    e.options,
+                ))
+            })
+            .collect::<Result<_>>()?;
+        let row_converter = RowConverter::new(sort_fields)?;
+
+        Ok(Self {
+            schema: schema.clone(),
+            metrics: TopKMetrics::new(metrics, partition_id),
+            reservation,
+            batch_size,
+            expr: expr.into(),
+            row_converter,
+            heap: TopKHeap::new(k, batch_size, schema),
+            cancellation_token,
+        })
+    }
+
+    /// Insert `batch`, remembering if any of its rows are among the top
+    /// `k` rows seen so far
+    pub fn insert_batch(&mut self, batch: RecordBatch) -> Result<()> {
+        self.cancellation_token.check()?;
+        // Updates on drop
+        let _timer = self.metrics.baseline.elapsed_compute().timer();
+
+        let sort_keys = self
+            .expr
+            .iter()
+            .map(|expr| {
+                let value = expr.expr.evaluate(&batch)?;
+                Ok(value.into_array(batch.num_rows()))
+            })
+            .collect::<Result<Vec<ArrayRef>>>()?;
+        let rows = self.row_converter.convert_columns(&sort_keys)?;
+
+        let mut batch_entry = self.heap.register_batch(batch);
+        for (index, row) in rows.iter().enumerate() {
+            match self.heap.max() {
+                // the heap is full and the row is not smaller than its
+                // largest row
+                Some(max_row) if row.as_ref() >= max_row.row() => {}
+                _ => {
+                    self.heap.add(&mut batch_entry, row.as_ref(), index);
+                    self.metrics.row_replacements.add(1);
+                }
+            }
+        }
+        self.heap.insert_batch_entry(batch_entry);
+
+        // Compact the retained batches if too many of their rows are unused
+        self.heap.maybe_compact()?;
+
+        // update memory reservation
+        self.reservation.try_resize(self.size())?;
+        Ok(())
+    }
+
+    /// Returns the top `k` rows in order as a stream of batches
+    pub fn emit(self) -> Result<SendableRecordBatchStream> {
+        let Self {
+            schema,
+            metrics,
+            reservation,
+            batch_size,
+            mut heap,
+            ..
+        } = self;
+        let _timer = metrics.baseline.elapsed_compute().timer();
+
+        let mut batch = heap.emit()?;
+        metrics.baseline.output_rows().add(batch.num_rows());
+        // the heap and its batches are no longer needed
+        drop(heap);
+        drop(reservation);
+
+        // break the output into batches of at most `batch_size` rows
+        let empty = RecordBatch::new_empty(schema.clone());
+        let iter = std::iter::from_fn(move || {
+            if batch.num_rows() == 0 {
+                None
+            } else if batch.num_rows() <= batch_size {
+                Some(Ok(std::mem::replace(&mut batch, empty.clone())))
+            } else {
+                let output = batch.slice(0, batch_size);
+                batch = batch.slice(batch_size, batch.num_rows() - batch_size);
+                Some(Ok(output))
+            }
+        });
+        Ok(Box::pin(RecordBatchStreamAdapter::new(
+            schema,
+            futures::stream::iter(iter),
+        )))
+    }
+
+    /// Return the size of memory used by this operator, in bytes
+    fn size(&self) -> usize {
+        std::mem::size_of::<Self>() + self.row_converter.size() + self.heap.size()
+    }
+}
+
+struct TopKMetrics {
+    /// metrics
+    baseline: BaselineMetrics,
+
+    /// count of rows that entered the heap
+    row_replacements: Count,
+}
+
+impl TopKMetrics {
+    fn new(metrics: &ExecutionPlanMetricsSet, partition: usize) -> Self {
+        Self {
+            baseline: BaselineMetrics::new(metrics, partition),
+            row_replacements: MetricBuilder::new(metrics)
+                .counter("row_replacements", partition),
+        }
+    }
+}
+
+/// A max heap of the `k` smallest rows seen so far, along with the batches
+/// holding their values
+struct TopKHeap {
+    /// The maximum number of rows to keep
+    k: usize,
+    /// The target number of rows of the output batches
+    batch_size: usize,
+    /// The rows, the largest on top
+    inner: BinaryHeap<TopKRow>,
+    /// The batches referenced by the rows of the heap
+    store: RecordBatchStore,
+    /// The size of the row keys owned by the heap, in bytes
+    owned_bytes: usize,
+}
+
+impl TopKHeap {
+    fn new(k: usize, batch_size: usize, schema: SchemaRef) -> Self {
+        assert!(k > 0);
+        Self {
+            k,
+            batch_size,
+            inner: BinaryHeap::new(),
+            store: RecordBatchStore::new(schema),
+            owned_bytes: 0,
+        }
+    }
+
+    /// Register a batch whose rows may be added to the heap
+    fn register_batch(&mut self, batch: RecordBatch) -> RecordBatchEntry {
+        self.store.register(batch)
+    }
+
+    /// Store the batch of `entry` if any of its rows were added to the heap
+    fn insert_batch_entry(&mut self, entry: RecordBatchEntry) {
+        self.store.insert(entry)
+    }
+
+    /// Returns the largest row of the heap, if the heap is full. Only rows
+    /// smaller than it can enter the heap.
+    fn max(&self) -> Option<&TopKRow> {
+        if self.inner.len() < self.k {
+            None
+        } else {
+            self.inner.peek()
+        }
+    }
+
+    /// Adds the row at `index` of the batch of `batch_entry`, evicting the
+    /// largest row if the heap is full
+    fn add(&mut self, batch_entry: &mut RecordBatchEntry, row: &[u8], index: usize) {
+        let batch_id = batch_entry.id;
+        batch_entry.uses += 1;
+
+        let new_top_k = if self.inner.len() == self.k {
+            let prev_max = self.inner.pop().unwrap();
+            // the evicted row no longer uses its batch
+            if prev_max.batch_id == batch_id {
+                batch_entry.uses -= 1;
+            } else {
+                self.store.unuse(prev_max.batch_id);
+            }
+            self.owned_bytes -= prev_max.owned_size();
+            // reuse the allocation of the evicted row
+            prev_max.with_new_row(row, batch_id, index)
+        } else {
+            TopKRow::new(row, batch_id, index)
+        };
+
+        self.owned_bytes += new_top_k.owned_size();
+        self.inner.push(new_top_k)
+    }
+
+    /// Returns the rows of the heap in order in a single batch, leaving the
+    /// heap empty
+    fn emit(&mut self) -> Result<RecordBatch> {
+        Ok(self.emit_with_state()?.0)
+    }
+
+    /// Returns the rows of the heap in order in a single batch, along with
+    /// the rows of the heap in the same order
+    fn emit_with_state(&mut self) -> Result<(RecordBatch, Vec<TopKRow>)> {
+        let schema = self.store.schema().clone();
+
+        // sorted from smallest to largest
+        let topk_rows = std::mem::take(&mut self.inner).into_sorted_vec();
+        if self.store.is_empty() {
+            return Ok((RecordBatch::new_empty(schema), topk_rows));
+        }
+
+        // the batches referenced by the rows, and for each row the index of
+        // its batch in that list along with its index in the batch
+        let mut record_batches = Vec::new();
+        let mut record_batch_indices = HashMap::new();
+        let indices: Vec<_> = topk_rows
+            .iter()
+            .map(|row| {
+                let batch_idx =
+                    *record_batch_indices.entry(row.batch_id).or_insert_with(|| {
+                        record_batches.push(&self.store.get(row.batch_id).unwrap().batch);
+                        record_batches.len() - 1
+                    });
+                (batch_idx, row.index)
+            })
+            .collect();
+
+        let output_columns = (0..schema.fields().len())
+            .map(|col| {
+                let input_arrays: Vec<_> = record_batches
+                    .iter()
+                    .map(|batch| batch.column(col).as_ref())
+                    .collect();
+                interleave(&input_arrays, &indices)
+            })
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let batch = RecordBatch::try_new(schema, output_columns)?;
+        Ok((batch, topk_rows))
+    }
+
+    /// Copies the rows of the heap into a single new batch, releasing the
+    /// retained batches, if they hold too many rows that are not in the heap
+    fn maybe_compact(&mut self) -> Result<()> {
+        // the number of unused rows to tolerate before compacting
+        let max_unused_rows = 20 * self.batch_size + self.k;
+        if self.store.len() <= 2 || self.store.unused_rows() < max_unused_rows {
+            return Ok(());
+        }
+
+        let (batch, mut topk_rows) = self.emit_with_state()?;
+        self.store.clear();
+
+        let mut batch_entry = self.register_batch(batch);
+        batch_entry.uses = topk_rows.len();
+        // the rows are in the order of the new batch
+        for (index, row) in topk_rows.iter_mut().enumerate() {
+            row.batch_id = batch_entry.id;
+            row.index = index;
+        }
+        self.insert_batch_entry(batch_entry);
+        self.inner = BinaryHeap::from(topk_rows);
+
+        Ok(())
+    }
+
+    /// Return the size of memory used by the heap and its batches, in bytes
+    fn size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.inner.capacity() * std::mem::size_of::<TopKRow>()
+            + self.store.size()
+            + self.owned_bytes
+    }
+}
+
+/// A row of the heap: the sort key of the row in the row format, and the
+/// location of its values
+#[derive(Debug, PartialEq)]
+struct TopKRow {
+    /// the sort key
+    row: Vec<u8>,
+    /// the id of the batch holding the row
+    batch_id: u32,
+    /// the index of the row in its batch
+    index: usize,
+}
+
+impl TopKRow {
+    fn new(row: &[u8], batch_id: u32, index: usize) -> Self {
+        Self {
+            row: row.to_vec(),
+            batch_id,
+            index,
+        }
+    }
+
+    /// Replaces the row, reusing the allocation of the previous key
+    fn with_new_row(self, new_row: &[u8], batch_id: u32, index: usize) -> Self {
+        let Self { mut row, .. } = self;
+        row.clear();
+        row.extend_from_slice(new_row);
+        Self {
+            row,
+            batch_id,
+            index,
+        }
+    }
+
+    /// The size of the memory owned by the row, in bytes
+    fn owned_size(&self) -> usize {
+        self.row.capacity()
+    }
+
+    fn row(&self) -> &[u8] {
+        self.row.as_slice()
+    }
+}
+
+impl Eq for TopKRow {}
+
+impl PartialOrd for TopKRow {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TopKRow {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.row.cmp(&other.row)
+    }
+}
+
+/// A batch of the input, along with the number of its rows in the heap
+struct RecordBatchEntry {
+    id: u32,
+    batch: RecordBatch,
+    /// the number of rows of the batch in the heap
+    uses: usize,
+}
+
+/// The batches referenced by the rows of the heap
+struct RecordBatchStore {
+    /// the id of the next batch to register
+    next_id: u32,
+    /// the retained batches, by id
+    batches: HashMap<u32, RecordBatchEntry>,
+    /// the size of the retained batches, in bytes
+    batches_size: usize,
+    schema: SchemaRef,
+}
+
+impl RecordBatchStore {
+    fn new(schema: SchemaRef) -> Self {
+        Self {
+            next_id: 0,
+            batches: HashMap::new(),
+            batches_size: 0,
+            schema,
+        }
+    }
+
+    /// Assigns an id to `batch`, without retaining it
+    fn register(&mut self, batch: RecordBatch) -> RecordBatchEntry {
+        let id = self.next_id;
+        self.next_id += 1;
+        RecordBatchEntry { id, batch, uses: 0 }
+    }
+
+    /// Retains the batch of `entry` if any of its rows are used
+    fn insert(&mut self, entry: RecordBatchEntry) {
+        if entry.uses > 0 {
+            self.batches_size += entry.batch.get_array_memory_size();
+            self.batches.insert(entry.id, entry);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.batches.clear();
+        self.batches_size = 0;
+    }
+
+    fn get(&self, id: u32) -> Option<&RecordBatchEntry> {
+        self.batches.get(&id)
+    }
+
+    fn len(&self) -> usize {
+        self.batches.len()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.batches.is_empty()
+    }
+
+    /// The number of rows of the retained batches that are not in the heap
+    fn unused_rows(&self) -> usize {
+        self.batches
+            .values()
+            .map(|entry| entry.batch.num_rows() - entry.uses)
+            .sum()
+    }
+
+    fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// Records that one less row of batch `id` is in the heap, releasing
+    /// the batch once none are
+    fn unuse(&mut self, id: u32) {
+        let entry = self
+            .batches
+            .get_mut(&id)
+            .expect("rows of the heap reference retained batches");
+        entry.uses -= 1;
+        if entry.uses == 0 {
+            let entry = self.batches.remove(&id).unwrap();
+            self.batches_size -= entry.batch.get_array_memory_size();
+        }
+    }
+
+    /// Return the size of the retained batches, in bytes
+    fn size(&self) -> usize {
+        std::mem::size_of::<Self>()
+            + self.batches.capacity()
+                * (std::mem::size_of::<u32>() + std::mem::size_of::<RecordBatchEntry>())
+            + self.batches_size
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::physical_plan::common::collect;
+    use crate::physical_plan::expressions::col;
+    use arrow::array::{Int32Array, StringArray};
+    use arrow::compute::SortOptions;
+    use arrow::datatypes::{DataType, Field, Schema};
+    use datafusion_common::cast::{as_int32_array, as_string_array};
+
+    fn batch(schema: &SchemaRef, values: Vec<i32>) -> RecordBatch {
+        let names = values.iter().map(|v| format!("v{v}")).collect::<Vec<_>>();
+        RecordBatch::try_new(
+            schema.clone(),
+            vec![
+                Arc::new(Int32Array::from(values)),
+                Arc::new(StringArray::from(names)),
+            ],
+        )
+        .unwrap()
+    }
+
+    async fn top_k(
+        k: usize,
+        batch_size: usize,
+        descending: bool,
+        inputs: Vec<Vec<i32>>,
+    ) -> Result<Vec<i32>> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, false),
+            Field::new("b", DataType::Utf8, false),
+        ]));
+        let expr = vec![PhysicalSortExpr {
+            expr: col("a", &schema)?,
+            options: SortOptions {
+                descending,
+                nulls_first: false,
+            },
+        }];
+        let mut topk = TopK::try_new(
+            0,
+            schema.clone(),
+            expr,
+            k,
+            batch_size,
+            Arc::new(RuntimeEnv::default()),
+            &ExecutionPlanMetricsSet::new(),
+            CancellationToken::new(),
+        )?;
+        for input in inputs {
+            topk.insert_batch(batch(&schema, input))?;
+        }
+
+        let batches = collect(topk.emit()?).await?;
+        assert!(batches.iter().all(|b| b.num_rows() <= batch_size));
+        let mut values = vec![];
+        for batch in batches {
+            let a = as_int32_array(batch.column(0))?;
+            let b = as_string_array(batch.column(1))?;
+            for i in 0..batch.num_rows() {
+                // the other columns follow the sort key
+                assert_eq!(b.value(i), format!("v{}", a.value(i)));
+                values.push(a.value(i));
+            }
+        }
+        Ok(values)
+    }
+
+    #[tokio::test]
+    async fn test_top_k() -> Result<()> {
+        let inputs = vec![vec![5, 1, 9], vec![7, 3], vec![8, 2, 6, 4]];
+        assert_eq!(top_k(3, 8, false, inputs.clone()).await?, vec![1, 2, 3]);
+        assert_eq!(top_k(3, 8, true, inputs.clone()).await?, vec![9, 8, 7]);
+        // k larger than the input
+        assert_eq!(
+            top_k(20, 4, false, inputs).await?,
+            vec![1, 2, 3, 4, 5, 6, 7, 8, 9]
+        );
+        // no input
+        assert_eq!(top_k(3, 8, false, vec![]).await?, Vec::<i32>::new());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_top_k_compaction() -> Result<()> {
+        // many small batches, each with a row of the top k, trigger the
+        // compaction of the retained batches
+        let inputs = (0..100)
+            .map(|i| (0..10).map(|j| 1000 - i * 10 - j).collect())
+            .collect();
+        assert_eq!(top_k(5, 2, false, inputs).await?, vec![1, 2, 3, 4, 5]);
+        Ok(())
+    }
+}
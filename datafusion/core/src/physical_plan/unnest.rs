@@ -17,14 +17,9 @@
 
 //! Defines the unnest column plan for unnesting values in a column that contains a list
 //! type, conceptually is like joining each row with all the values in the list column.
-use arrow::array::{
-    Array, ArrayRef, ArrowPrimitiveType, FixedSizeListArray, LargeListArray, ListArray,
-    PrimitiveArray,
-};
+use arrow::array::{Array, ArrayRef, AsArray, UInt64Array};
 use arrow::compute::kernels;
-use arrow::datatypes::{
-    ArrowNativeType, DataType, Int32Type, Int64Type, Schema, SchemaRef,
-};
+use arrow::datatypes::{ArrowNativeType, DataType, Schema, SchemaRef};
 use arrow::record_batch::RecordBatch;
 use arrow_array::{GenericListArray, OffsetSizeTrait};
 use async_trait::async_trait;
@@ -34,6 +29,7 @@ use datafusion_execution::TaskContext;
 use futures::Stream;
 use futures::StreamExt;
 use log::trace;
+use std::ops::Range;
 use std::time::Instant;
 use std::{any::Any, sync::Arc};
 
@@ -45,8 +41,8 @@ use crate::physical_plan::{
 
 use super::DisplayAs;
 
-/// Unnest the given column by joining the row with each value in the
-/// nested type.
+/// Unnest the given columns by joining the row with each value in the
+/// nested types, zipping the values of the columns together.
 ///
 /// See [`UnnestOptions`] for more details and an example.
 #[derive(Debug)]
@@ -55,8 +51,8 @@ pub struct UnnestExec {
     input: Arc<dyn ExecutionPlan>,
     /// The schema once the unnest is applied
     schema: SchemaRef,
-    /// The unnest column
-    column: Column,
+    /// The unnest columns
+    columns: Vec<Column>,
     /// Options
     options: UnnestOptions,
 }
@@ -65,14 +61,14 @@ impl UnnestExec {
     /// Create a new [UnnestExec].
     pub fn new(
         input: Arc<dyn ExecutionPlan>,
-        column: Column,
+        columns: Vec<Column>,
         schema: SchemaRef,
         options: UnnestOptions,
     ) -> Self {
         UnnestExec {
             input,
             schema,
-            column,
+            columns,
             options,
         }
     }
@@ -118,7 +114,7 @@ impl ExecutionPlan for UnnestExec {
     ) -> Result<Arc<dyn ExecutionPlan>> {
         Ok(Arc::new(UnnestExec::new(
             children[0].clone(),
-            self.column.clone(),
+            self.columns.clone(),
             self.schema.clone(),
             self.options.clone(),
         )))
@@ -150,7 +146,7 @@ impl ExecutionPlan for UnnestExec {
         Ok(Box::pin(UnnestStream {
             input,
             schema: self.schema.clone(),
-            column: self.column.clone(),
+            columns: self.columns.clone(),
             options: self.options.clone(),
             num_input_batches: 0,
             num_input_rows: 0,
@@ -171,8 +167,8 @@ struct UnnestStream {
     input: SendableRecordBatchStream,
     /// Unnested schema
     schema: Arc<Schema>,
-    /// The unnest column
-    column: Column,
+    /// The unnest columns
+    columns: Vec<Column>,
     /// Options
     options: UnnestOptions,
     /// number of input batches
@@ -218,7 +214,7 @@ impl UnnestStream {
                 Some(Ok(batch)) => {
                     let start = Instant::now();
                     let result =
-                        build_batch(&batch, &self.schema, &self.column, &self.options);
+                        build_batch(&batch, &self.schema, &self.columns, &self.options);
                     self.num_input_batches += 1;
                     self.num_input_rows += batch.num_rows();
                     if let Ok(ref batch) = result {
@@ -245,323 +241,285 @@ impl UnnestStream {
     }
 }
 
-fn build_batch(
-    batch: &RecordBatch,
-    schema: &SchemaRef,
-    column: &Column,
-    options: &UnnestOptions,
-) -> Result<RecordBatch> {
-    let list_array = column.evaluate(batch)?.into_array(batch.num_rows());
-    match list_array.data_type() {
-        DataType::List(_) => {
-            let list_array = list_array.as_any().downcast_ref::<ListArray>().unwrap();
-            build_batch_generic_list::<i32, Int32Type>(
-                batch,
-                schema,
-                column.index(),
-                list_array,
-                options,
-            )
-        }
-        DataType::LargeList(_) => {
-            let list_array = list_array
-                .as_any()
-                .downcast_ref::<LargeListArray>()
-                .unwrap();
-            build_batch_generic_list::<i64, Int64Type>(
-                batch,
-                schema,
-                column.index(),
-                list_array,
-                options,
-            )
-        }
-        DataType::FixedSizeList(_, _) => {
-            let list_array = list_array
-                .as_any()
-                .downcast_ref::<FixedSizeListArray>()
-                .unwrap();
-            build_batch_fixedsize_list(batch, schema, column.index(), list_array, options)
-        }
-        _ => exec_err!("Invalid unnest column {column}"),
-    }
-}
-
-fn build_batch_generic_list<T: OffsetSizeTrait, P: ArrowPrimitiveType<Native = T>>(
-    batch: &RecordBatch,
-    schema: &SchemaRef,
-    unnest_column_idx: usize,
-    list_array: &GenericListArray<T>,
-    options: &UnnestOptions,
-) -> Result<RecordBatch> {
-    let unnested_array = unnest_generic_list::<T, P>(list_array, options)?;
-
-    let take_indicies =
-        create_take_indicies_generic::<T, P>(list_array, unnested_array.len(), options);
-
-    batch_from_indices(
-        batch,
-        schema,
-        unnest_column_idx,
-        &unnested_array,
-        &take_indicies,
-    )
-}
-
-/// Given this `GenericList` list_array:
-///   
-/// ```ignore
-/// [1], null, [2, 3, 4], null, [5, 6]
-/// ```
-/// Its values array is represented like this:
+/// Unnests the list `columns` of `batch`, zipping their values
+///
+/// For example if we have the following `RecordBatch`, unnesting `c1` and
+/// `c2` with [`UnnestOptions::preserve_nulls`] set:
 ///
 /// ```ignore
-/// [1, 2, 3, 4, 5, 6]
+/// c1: [1, 2], null, [],   [3]
+/// c2: [a],    null, null, [b, c, d]
+/// c3: 'x',    'y',  'z',  'w'
 /// ```
 ///
-/// So if there are no null values or `UnnestOptions.preserve_nulls` is false
-/// we can return the values array without any copying.
-///
-/// Otherwise we'll transfrom the values array using the take kernel and the following take indicies:
+/// The values of each input row are repeated as many times as the longest of
+/// its lists, and the shorter lists are padded with nulls:
 ///
 /// ```ignore
-/// 0, null, 1, 2, 3, null, 4, 5
+/// c1: 1, 2,    null, 3, null, null
+/// c2: a, null, null, b, c,    d
+/// c3: x, x,    y,    w, w,    w
 /// ```
 ///
-fn unnest_generic_list<T: OffsetSizeTrait, P: ArrowPrimitiveType<Native = T>>(
-    list_array: &GenericListArray<T>,
-    options: &UnnestOptions,
-) -> Result<Arc<dyn Array + 'static>> {
-    let values = list_array.values();
-    if list_array.null_count() == 0 || !options.preserve_nulls {
-        Ok(values.clone())
-    } else {
-        let mut take_indicies_builder =
-            PrimitiveArray::<P>::builder(values.len() + list_array.null_count());
-        let mut take_offset = 0;
-
-        list_array.iter().for_each(|elem| match elem {
-            Some(array) => {
-                for i in 0..array.len() {
-                    // take_offset + i is always positive
-                    let take_index = P::Native::from_usize(take_offset + i).unwrap();
-                    take_indicies_builder.append_value(take_index);
-                }
-                take_offset += array.len();
-            }
-            None => {
-                take_indicies_builder.append_null();
-            }
-        });
-        Ok(kernels::take::take(
-            &values,
-            &take_indicies_builder.finish(),
-            None,
-        )?)
-    }
-}
-
-fn build_batch_fixedsize_list(
+/// Rows whose lists are all empty are dropped, and so are rows whose lists
+/// are all null or empty unless nulls are preserved, in which case they
+/// produce a single row of nulls.
+fn build_batch(
     batch: &RecordBatch,
     schema: &SchemaRef,
-    unnest_column_idx: usize,
-    list_array: &FixedSizeListArray,
+    columns: &[Column],
     options: &UnnestOptions,
 ) -> Result<RecordBatch> {
-    let unnested_array = unnest_fixed_list(list_array, options)?;
-
-    let take_indicies =
-        create_take_indicies_fixed(list_array, unnested_array.len(), options);
-
-    batch_from_indices(
-        batch,
-        schema,
-        unnest_column_idx,
-        &unnested_array,
-        &take_indicies,
-    )
-}
+    let lists = columns
+        .iter()
+        .map(|column| {
+            let list_array = column.evaluate(batch)?.into_array(batch.num_rows());
+            list_ranges(&list_array, column)
+        })
+        .collect::<Result<Vec<_>>>()?;
 
-/// Given this `FixedSizeListArray` list_array:
-///   
-/// ```ignore
-/// [1, 2], null, [3, 4], null, [5, 6]
-/// ```
-/// Its values array is represented like this:
-///
-/// ```ignore
-/// [1, 2, null, null 3, 4, null, null, 5, 6]
-/// ```
-///
-/// So if there are no null values
-/// we can return the values array without any copying.
-///
-/// Otherwise we'll transfrom the values array using the take kernel.
-///
-/// If `UnnestOptions.preserve_nulls` is true the take indicies will look like this:
-///
-/// ```ignore
-/// 0, 1, null, 4, 5, null, 8, 9
-/// ```
-/// Otherwise we drop the nulls and take indicies will look like this:
-///
-/// ```ignore
-/// 0, 1, 4, 5, 8, 9
-/// ```
-///
-fn unnest_fixed_list(
-    list_array: &FixedSizeListArray,
-    options: &UnnestOptions,
-) -> Result<Arc<dyn Array + 'static>> {
-    let values = list_array.values();
-
-    if list_array.null_count() == 0 {
-        Ok(values.clone())
-    } else {
-        let len_without_nulls =
-            values.len() - list_array.null_count() * list_array.value_length() as usize;
-        let null_count = if options.preserve_nulls {
-            list_array.null_count()
-        } else {
-            0
-        };
-        let mut builder =
-            PrimitiveArray::<Int32Type>::builder(len_without_nulls + null_count);
-        let mut take_offset = 0;
-        let fixed_value_length = list_array.value_length() as usize;
-        list_array.iter().for_each(|elem| match elem {
-            Some(_) => {
-                for i in 0..fixed_value_length {
-                    //take_offset + i is always positive
-                    let take_index = take_offset + i;
-                    builder.append_value(take_index as i32);
-                }
-                take_offset += fixed_value_length;
-            }
-            None => {
-                if options.preserve_nulls {
-                    builder.append_null();
-                }
-                take_offset += fixed_value_length;
+    // The input row of each output row
+    let mut row_indices: Vec<u64> = vec![];
+    // The index in the values of each list column of each output row
+    let mut value_indices: Vec<Vec<Option<u64>>> = vec![vec![]; lists.len()];
+    // The 1 based position in the lists of each output row
+    let mut ordinality: Vec<Option<u64>> = vec![];
+
+    for row in 0..batch.num_rows() {
+        let length = lists
+            .iter()
+            .map(|(_, ranges)| ranges[row].as_ref().map(|r| r.len()).unwrap_or(0))
+            .max()
+            .unwrap_or(0);
+
+        if length == 0 {
+            let has_null = lists.iter().any(|(_, ranges)| ranges[row].is_none());
+            if has_null && options.preserve_nulls {
+                row_indices.push(row as u64);
+                value_indices
+                    .iter_mut()
+                    .for_each(|indices| indices.push(None));
+                ordinality.push(None);
             }
-        });
-        Ok(kernels::take::take(&values, &builder.finish(), None)?)
-    }
-}
+            continue;
+        }
 
-/// Creates take indicies to be used to expand all other column's data.
-/// Every column value needs to be repeated as many times as many elements there is in each corresponding array value.
-///
-/// If the column being unnested looks like this:
-///    
-/// ```ignore
-/// [1], null, [2, 3, 4], null, [5, 6]
-/// ```
-/// Then `create_take_indicies_generic` will return an array like this
-///
-/// ```ignore
-/// [1, null, 2, 2, 2, null, 4, 4]
-/// ```
-///
-fn create_take_indicies_generic<T: OffsetSizeTrait, P: ArrowPrimitiveType<Native = T>>(
-    list_array: &GenericListArray<T>,
-    capacity: usize,
-    options: &UnnestOptions,
-) -> PrimitiveArray<P> {
-    let mut builder = PrimitiveArray::<P>::builder(capacity);
-    let null_repeat: usize = if options.preserve_nulls { 1 } else { 0 };
-
-    for row in 0..list_array.len() {
-        let repeat = if list_array.is_null(row) {
-            null_repeat
-        } else {
-            list_array.value(row).len()
-        };
-
-        // `index` is a positive interger.
-        let index = P::Native::from_usize(row).unwrap();
-        (0..repeat).for_each(|_| builder.append_value(index));
+        for position in 0..length {
+            row_indices.push(row as u64);
+            for ((_, ranges), indices) in lists.iter().zip(value_indices.iter_mut()) {
+                indices.push(
+                    ranges[row]
+                        .as_ref()
+                        .filter(|range| position < range.len())
+                        .map(|range| (range.start + position) as u64),
+                );
+            }
+            ordinality.push(Some(position as u64 + 1));
+        }
     }
 
-    builder.finish()
-}
+    let row_indices = UInt64Array::from(row_indices);
+    let mut arrays = batch
+        .columns()
+        .iter()
+        .enumerate()
+        .map(|(col_idx, array)| {
+            match columns.iter().position(|column| column.index() == col_idx) {
+                Some(list_idx) => {
+                    let indices =
+                        UInt64Array::from(std::mem::take(&mut value_indices[list_idx]));
+                    Ok(kernels::take::take(&lists[list_idx].0, &indices, None)?)
+                }
+                None => Ok(kernels::take::take(array, &row_indices, None)?),
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
 
-fn create_take_indicies_fixed(
-    list_array: &FixedSizeListArray,
-    capacity: usize,
-    options: &UnnestOptions,
-) -> PrimitiveArray<Int32Type> {
-    let mut builder = PrimitiveArray::<Int32Type>::builder(capacity);
-    let null_repeat: usize = if options.preserve_nulls { 1 } else { 0 };
-
-    for row in 0..list_array.len() {
-        let repeat = if list_array.is_null(row) {
-            null_repeat
-        } else {
-            list_array.value_length() as usize
-        };
-
-        // `index` is a positive interger.
-        let index = <Int32Type as ArrowPrimitiveType>::Native::from_usize(row).unwrap();
-        (0..repeat).for_each(|_| builder.append_value(index));
+    if options.ordinality.is_some() {
+        arrays.push(Arc::new(UInt64Array::from(ordinality)));
     }
 
-    builder.finish()
+    Ok(RecordBatch::try_new(schema.clone(), arrays)?)
 }
 
-/// Create the final batch given the unnested column array and a `indices` array
-/// that is used by the take kernel to copy values.
-///
-/// For example if we have the following `RecordBatch`:
-///
-/// ```ignore
-/// c1: [1], null, [2, 3, 4], null, [5, 6]
-/// c2: 'a', 'b',  'c', null, 'd'
-/// ```
-///
-/// then the `unnested_array` contains the unnest column that will replace `c1` in
-/// the final batch:
-///
-/// ```ignore
-/// c1: 1, null, 2, 3, 4, null, 5, 6
-/// ```
+/// Returns the values of `list_array`, and the range in the values of the
+/// list of each row, which is `None` for null lists
 ///
-/// And the `indices` array contains the indices that are used by `take` kernel to
-/// repeat the values in `c2`:
+/// For example this `ListArray`:
 ///
 /// ```ignore
-/// 0, 1, 2, 2, 2, 3, 4, 4
+/// [1], null, [2, 3, 4], [], [5, 6]
 /// ```
 ///
-/// so that the final batch will look like:
+/// is represented by the values `[1, 2, 3, 4, 5, 6]` and the ranges:
 ///
 /// ```ignore
-/// c1: 1, null, 2, 3, 4, null, 5, 6
-/// c2: 'a', 'b', 'c', 'c', 'c', null, 'd', 'd'
+/// 0..1, null, 1..4, 4..4, 4..6
 /// ```
-///
-fn batch_from_indices<T>(
-    batch: &RecordBatch,
-    schema: &SchemaRef,
-    unnest_column_idx: usize,
-    unnested_array: &ArrayRef,
-    indices: &PrimitiveArray<T>,
-) -> Result<RecordBatch>
-where
-    T: ArrowPrimitiveType,
-{
-    let arrays = batch
-        .columns()
-        .iter()
+fn list_ranges(
+    list_array: &ArrayRef,
+    column: &Column,
+) -> Result<(ArrayRef, Vec<Option<Range<usize>>>)> {
+    match list_array.data_type() {
+        DataType::List(_) => Ok(generic_list_ranges(list_array.as_list::<i32>())),
+        DataType::LargeList(_) => Ok(generic_list_ranges(list_array.as_list::<i64>())),
+        DataType::FixedSizeList(_, _) => {
+            let list_array = list_array.as_fixed_size_list();
+            let ranges = (0..list_array.len())
+                .map(|row| {
+                    let start = list_array.value_offset(row) as usize;
+                    let end = start + list_array.value_length() as usize;
+                    list_array.is_valid(row).then_some(start..end)
+                })
+                .collect();
+            Ok((list_array.values().clone(), ranges))
+        }
+        _ => exec_err!("Invalid unnest column {column}"),
+    }
+}
+
+fn generic_list_ranges<T: OffsetSizeTrait>(
+    list_array: &GenericListArray<T>,
+) -> (ArrayRef, Vec<Option<Range<usize>>>) {
+    let ranges = list_array
+        .value_offsets()
+        .windows(2)
         .enumerate()
-        .map(|(col_idx, arr)| {
-            if col_idx == unnest_column_idx {
-                Ok(unnested_array.clone())
-            } else {
-                Ok(kernels::take::take(&arr, indices, None)?)
-            }
+        .map(|(row, offsets)| {
+            list_array
+                .is_valid(row)
+                .then_some(offsets[0].as_usize()..offsets[1].as_usize())
         })
-        .collect::<Result<Vec<_>>>()?;
+        .collect();
+    (list_array.values().clone(), ranges)
+}
 
-    Ok(RecordBatch::try_new(schema.clone(), arrays.to_vec())?)
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow::array::{Int32Array, ListArray, StringArray};
+    use arrow::datatypes::{Field, Int32Type};
+    use arrow_array::FixedSizeListArray;
+
+    fn unnest(
+        batch: &RecordBatch,
+        columns: &[&str],
+        options: &UnnestOptions,
+    ) -> Result<RecordBatch> {
+        let input_schema = batch.schema();
+        let columns = columns
+            .iter()
+            .map(|name| Column::new_with_schema(name, &input_schema))
+            .collect::<Result<Vec<_>>>()?;
+        let mut fields = input_schema
+            .fields()
+            .iter()
+            .enumerate()
+            .map(|(index, field)| {
+                match (
+                    columns.iter().any(|c| c.index() == index),
+                    field.data_type(),
+                ) {
+                    (true, DataType::List(item) | DataType::FixedSizeList(item, _)) => {
+                        Field::new(field.name(), item.data_type().clone(), true)
+                    }
+                    _ => field.as_ref().clone(),
+                }
+            })
+            .collect::<Vec<_>>();
+        if let Some(ordinality) = &options.ordinality {
+            fields.push(Field::new(ordinality, DataType::UInt64, true));
+        }
+        build_batch(batch, &Arc::new(Schema::new(fields)), &columns, options)
+    }
+
+    fn test_batch() -> RecordBatch {
+        let c1 = ListArray::from_iter_primitive::<Int32Type, _, _>(vec![
+            Some(vec![Some(1), Some(2)]),
+            None,
+            Some(vec![]),
+            Some(vec![Some(3)]),
+            Some(vec![]),
+        ]);
+        let c2 = FixedSizeListArray::from_iter_primitive::<Int32Type, _, _>(
+            vec![
+                Some(vec![Some(10), Some(20)]),
+                None,
+                None,
+                Some(vec![Some(30), Some(40)]),
+                None,
+            ],
+            2,
+        );
+        let c3 = StringArray::from(vec!["a", "b", "c", "d", "e"]);
+        RecordBatch::try_from_iter(vec![
+            ("c1", Arc::new(c1) as ArrayRef),
+            ("c2", Arc::new(c2) as ArrayRef),
+            ("c3", Arc::new(c3) as ArrayRef),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn unnest_multiple_columns() -> Result<()> {
+        let batch = test_batch();
+        let options = UnnestOptions::new().with_ordinality("ordinality");
+        let result = unnest(&batch, &["c1", "c2"], &options)?;
+
+        let expected = RecordBatch::try_new(
+            result.schema(),
+            vec![
+                Arc::new(Int32Array::from(vec![
+                    Some(1),
+                    Some(2),
+                    None,
+                    None,
+                    Some(3),
+                    None,
+                    None,
+                ])),
+                Arc::new(Int32Array::from(vec![
+                    Some(10),
+                    Some(20),
+                    None,
+                    None,
+                    Some(30),
+                    Some(40),
+                    None,
+                ])),
+                Arc::new(StringArray::from(vec!["a", "a", "b", "c", "d", "d", "e"])),
+                Arc::new(UInt64Array::from(vec![
+                    Some(1),
+                    Some(2),
+                    None,
+                    None,
+                    Some(1),
+                    Some(2),
+                    None,
+                ])),
+            ],
+        )?;
+        assert_eq!(result, expected);
+
+        // Without preserving nulls, the rows with only empty or null lists
+        // are dropped
+        let options = UnnestOptions::new().with_preserve_nulls(false);
+        let result = unnest(&batch, &["c1", "c2"], &options)?;
+        assert_eq!(result.num_rows(), 4);
+        assert_eq!(
+            result.column(2).as_ref(),
+            &StringArray::from(vec!["a", "a", "d", "d"])
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn unnest_sliced_list() -> Result<()> {
+        let batch = test_batch().slice(3, 2);
+        let result = unnest(&batch, &["c1"], &UnnestOptions::new())?;
+        assert_eq!(result.num_rows(), 1);
+        assert_eq!(result.column(0).as_ref(), &Int32Array::from(vec![3]));
+        assert_eq!(result.column(2).as_ref(), &StringArray::from(vec!["d"]));
+        Ok(())
+    }
 }
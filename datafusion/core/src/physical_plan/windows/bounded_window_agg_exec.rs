@@ -31,7 +31,7 @@ use crate::physical_plan::{
     ColumnStatistics, DisplayAs, DisplayFormatType, Distribution, ExecutionPlan,
     Partitioning, RecordBatchStream, SendableRecordBatchStream, Statistics, WindowExpr,
 };
-use datafusion_common::{exec_err, plan_err, Result};
+use datafusion_common::{exec_err, plan_err, Precision, Result};
 use datafusion_execution::TaskContext;
 
 use ahash::RandomState;
@@ -345,10 +345,9 @@ impl ExecutionPlan for BoundedWindowAggExec {
         }
         column_statistics.extend(vec![ColumnStatistics::default(); win_cols]);
         Statistics {
-            is_exact: input_stat.is_exact,
             num_rows: input_stat.num_rows,
             column_statistics: Some(column_statistics),
-            total_byte_size: None,
+            total_byte_size: Precision::Absent,
         }
     }
 }
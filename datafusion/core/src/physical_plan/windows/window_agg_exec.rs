@@ -40,7 +40,7 @@ use arrow::{
 };
 use datafusion_common::utils::{evaluate_partition_ranges, get_at_indices};
 use datafusion_common::Result;
-use datafusion_common::{internal_err, plan_err, DataFusionError};
+use datafusion_common::{internal_err, plan_err, DataFusionError, Precision};
 use datafusion_execution::TaskContext;
 use datafusion_physical_expr::{OrderingEquivalenceProperties, PhysicalSortRequirement};
 use futures::stream::Stream;
@@ -269,10 +269,9 @@ impl ExecutionPlan for WindowAggExec {
         }
         column_statistics.extend(vec![ColumnStatistics::default(); win_cols]);
         Statistics {
-            is_exact: input_stat.is_exact,
             num_rows: input_stat.num_rows,
             column_statistics: Some(column_statistics),
-            total_byte_size: None,
+            total_byte_size: Precision::Absent,
         }
     }
 }
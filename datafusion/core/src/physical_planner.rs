@@ -609,10 +609,13 @@ impl DefaultPhysicalPlanner {
                     ..
                 }) => {
                     let name = table_name.table();
-                    let schema = session_state.schema_for_ref(table_name)?;
+                    let schema = session_state.resolve_schema_for_ref(table_name).await?;
                     if let Some(provider) = schema.table(name).await {
                         let input_exec = self.create_initial_plan(input, session_state).await?;
-                        provider.insert_into(session_state, input_exec, false).await
+                        let plan = provider.insert_into(session_state, input_exec, false).await?;
+                        // the statistics of `ANALYZE TABLE` no longer describe the table
+                        schema.deregister_table_statistics(name)?;
+                        Ok(plan)
                     } else {
                         return exec_err!(
                             "Table '{table_name}' does not exist"
@@ -626,10 +629,13 @@ impl DefaultPhysicalPlanner {
                     ..
                 }) => {
                     let name = table_name.table();
-                    let schema = session_state.schema_for_ref(table_name)?;
+                    let schema = session_state.resolve_schema_for_ref(table_name).await?;
                     if let Some(provider) = schema.table(name).await {
                         let input_exec = self.create_initial_plan(input, session_state).await?;
-                        provider.insert_into(session_state, input_exec, true).await
+                        let plan = provider.insert_into(session_state, input_exec, true).await?;
+                        // the statistics of `ANALYZE TABLE` no longer describe the table
+                        schema.deregister_table_statistics(name)?;
+                        Ok(plan)
                     } else {
                         return exec_err!(
                             "Table '{table_name}' does not exist"
@@ -1222,12 +1228,23 @@ impl DefaultPhysicalPlanner {
 
                     Ok(Arc::new(GlobalLimitExec::new(input, *skip, *fetch)))
                 }
-                LogicalPlan::Unnest(Unnest { input, column, schema, options }) => {
+                LogicalPlan::Unnest(Unnest { input, columns, schema, options }) => {
                     let input = self.create_initial_plan(input, session_state).await?;
-                    let column_exec = schema.index_of_column(column)
-                        .map(|idx| Column::new(&column.name, idx))?;
+                    let column_execs = columns
+                        .iter()
+                        .map(|column| {
+                            schema
+                                .index_of_column(column)
+                                .map(|idx| Column::new(&column.name, idx))
+                        })
+                        .collect::<Result<Vec<_>>>()?;
                     let schema = SchemaRef::new(schema.as_ref().to_owned().into());
-                    Ok(Arc::new(UnnestExec::new(input, column_exec, schema, options.clone())))
+                    Ok(Arc::new(UnnestExec::new(
+                        input,
+                        column_execs,
+                        schema,
+                        options.clone(),
+                    )))
                 }
                 LogicalPlan::Ddl(ddl) => {
                     // There is no default plan for DDl statements --
@@ -201,6 +201,8 @@ pub fn partitioned_csv_config(
         table_partition_cols: vec![],
         output_ordering: vec![],
         infinite_source: false,
+        column_renames: vec![],
+        nested_projection: vec![],
     })
 }
 
@@ -350,6 +352,8 @@ pub fn csv_exec_sorted(
             table_partition_cols: vec![],
             output_ordering: vec![sort_exprs],
             infinite_source,
+            column_renames: vec![],
+            nested_projection: vec![],
         },
         false,
         0,
@@ -153,6 +153,8 @@ impl TestParquetFile {
             table_partition_cols: vec![],
             output_ordering: vec![],
             infinite_source: false,
+            column_renames: vec![],
+            nested_projection: vec![],
         };
 
         let df_schema = self.schema.clone().to_dfschema_ref()?;
@@ -37,7 +37,7 @@ use datafusion::{
 use datafusion::{error::Result, physical_plan::DisplayFormatType};
 
 use datafusion_common::cast::as_primitive_array;
-use datafusion_common::project_schema;
+use datafusion_common::{project_schema, Precision};
 use futures::stream::Stream;
 use std::any::Any;
 use std::pin::Pin;
@@ -156,20 +156,19 @@ impl ExecutionPlan for CustomExecutionPlan {
     fn statistics(&self) -> Statistics {
         let batch = TEST_CUSTOM_RECORD_BATCH!().unwrap();
         Statistics {
-            is_exact: true,
-            num_rows: Some(batch.num_rows()),
-            total_byte_size: None,
+            num_rows: Precision::Exact(batch.num_rows()),
+            total_byte_size: Precision::Absent,
             column_statistics: Some(
                 self.projection
                     .clone()
                     .unwrap_or_else(|| (0..batch.columns().len()).collect())
                     .iter()
                     .map(|i| ColumnStatistics {
-                        null_count: Some(batch.column(*i).null_count()),
-                        min_value: Some(ScalarValue::Int32(aggregate::min(
+                        null_count: Precision::Exact(batch.column(*i).null_count()),
+                        min_value: Precision::Exact(ScalarValue::Int32(aggregate::min(
                             as_primitive_array::<Int32Type>(batch.column(*i)).unwrap(),
                         ))),
-                        max_value: Some(ScalarValue::Int32(aggregate::max(
+                        max_value: Precision::Exact(ScalarValue::Int32(aggregate::max(
                             as_primitive_array::<Int32Type>(batch.column(*i)).unwrap(),
                         ))),
                         ..Default::default()
@@ -1086,6 +1086,88 @@ async fn unnest_column_nulls() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test]
+async fn unnest_multiple_columns() -> Result<()> {
+    // Unnest points and tags together, with the position of the values
+    let df = table_with_nested_types(4).await?;
+    let options = UnnestOptions::new().with_ordinality("ordinality");
+    let results = df
+        .unnest_columns_with_options(&["points", "tags"], options)?
+        .collect()
+        .await?;
+    let expected = [
+        "+----------+-----------------+------+------------+",
+        "| shape_id | points          | tags | ordinality |",
+        "+----------+-----------------+------+------------+",
+        "| 1        | {x: -3, y: -4}  | tag1 | 1          |",
+        "| 1        | {x: -3, y: 6}   |      | 2          |",
+        "| 1        | {x: 2, y: -2}   |      | 3          |",
+        "| 2        |                 | tag1 | 1          |",
+        "| 2        |                 | tag2 | 2          |",
+        "| 3        | {x: -10, y: -4} |      | 2          |",
+        "| 3        | {x: -9, y: 2}   |      | 1          |",
+        "| 4        |                 | tag3 | 3          |",
+        "| 4        | {x: -3, y: 5}   | tag1 | 1          |",
+        "| 4        | {x: 2, y: -1}   | tag2 | 2          |",
+        "+----------+-----------------+------+------------+",
+    ];
+    assert_batches_sorted_eq!(expected, &results);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unnest_struct_column() -> Result<()> {
+    let df = table_with_nested_types(4).await?;
+    let results = df
+        .unnest_column("points")?
+        .unnest_columns(&["points"])?
+        .collect()
+        .await?;
+    let expected = [
+        "+----------+-----+----+--------------------+",
+        "| shape_id | x   | y  | tags               |",
+        "+----------+-----+----+--------------------+",
+        "| 1        | -3  | -4 | [tag1]             |",
+        "| 1        | -3  | 6  | [tag1]             |",
+        "| 1        | 2   | -2 | [tag1]             |",
+        "| 2        |     |    | [tag1, tag2]       |",
+        "| 3        | -10 | -4 |                    |",
+        "| 3        | -9  | 2  |                    |",
+        "| 4        | -3  | 5  | [tag1, tag2, tag3] |",
+        "| 4        | 2   | -1 | [tag1, tag2, tag3] |",
+        "+----------+-----+----+--------------------+",
+    ];
+    assert_batches_sorted_eq!(expected, &results);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn unnest_recursive() -> Result<()> {
+    let ctx = SessionContext::new();
+    let df = ctx
+        .sql("SELECT make_array(make_array(1, 2), make_array(3)) AS l, 'a' AS id")
+        .await?;
+    let options = UnnestOptions::new().with_recursive(true);
+    let results = df
+        .unnest_columns_with_options(&["l"], options)?
+        .collect()
+        .await?;
+    let expected = [
+        "+---+----+",
+        "| l | id |",
+        "+---+----+",
+        "| 1 | a  |",
+        "| 2 | a  |",
+        "| 3 | a  |",
+        "+---+----+",
+    ];
+    assert_batches_eq!(expected, &results);
+
+    Ok(())
+}
+
 #[tokio::test]
 async fn unnest_fixed_list() -> Result<()> {
     let batch = get_fixed_list_batch()?;
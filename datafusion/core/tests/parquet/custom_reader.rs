@@ -84,6 +84,8 @@ async fn route_data_access_ops_to_parquet_file_reader_factory() {
             table_partition_cols: vec![],
             output_ordering: vec![],
             infinite_source: false,
+            column_renames: vec![],
+            nested_projection: vec![],
         },
         None,
         None,
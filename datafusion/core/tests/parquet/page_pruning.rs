@@ -79,6 +79,8 @@ async fn get_parquet_exec(state: &SessionState, filter: Expr) -> ParquetExec {
             table_partition_cols: vec![],
             output_ordering: vec![],
             infinite_source: false,
+            column_renames: vec![],
+            nested_projection: vec![],
         },
         Some(predicate),
         None,
@@ -18,9 +18,16 @@
 use arrow::datatypes::{Field, Schema};
 use arrow::record_batch::RecordBatch;
 use arrow_array::types::Int32Type;
-use arrow_array::{ArrayRef, DictionaryArray, Float32Array, Int64Array, StringArray};
+use arrow_array::{
+    ArrayRef, DictionaryArray, Float32Array, Int32Array, Int64Array, StringArray,
+    StructArray,
+};
 use arrow_schema::DataType;
 use datafusion::assert_batches_sorted_eq;
+use datafusion::datasource::file_format::parquet::ParquetFormat;
+use datafusion::datasource::listing::{
+    ListingOptions, ListingTable, ListingTableConfig, ListingTableUrl,
+};
 use datafusion::datasource::physical_plan::{FileScanConfig, ParquetExec};
 use datafusion::physical_plan::collect;
 use datafusion::prelude::SessionContext;
@@ -32,7 +39,7 @@ use object_store::ObjectMeta;
 use parquet::arrow::ArrowWriter;
 use parquet::file::properties::WriterProperties;
 use std::sync::Arc;
-use tempfile::NamedTempFile;
+use tempfile::{NamedTempFile, TempDir};
 
 /// Test for reading data from multiple parquet files with different schemas and coercing them into a single schema.
 #[tokio::test]
@@ -69,6 +76,8 @@ async fn multi_parquet_coercion() {
             table_partition_cols: vec![],
             output_ordering: vec![],
             infinite_source: false,
+            column_renames: vec![],
+            nested_projection: vec![],
         },
         None,
         None,
@@ -133,6 +142,8 @@ async fn multi_parquet_coercion_projection() {
             table_partition_cols: vec![],
             output_ordering: vec![],
             infinite_source: false,
+            column_renames: vec![],
+            nested_projection: vec![],
         },
         None,
         None,
@@ -157,6 +168,101 @@ async fn multi_parquet_coercion_projection() {
     assert_batches_sorted_eq!(expected, &read);
 }
 
+/// Test for reading files written at different points of a table's history
+#[tokio::test]
+async fn multi_parquet_schema_evolution() -> Result<()> {
+    let tmp_dir = TempDir::new()?;
+
+    // the first file stores `user_id` under its previous name `uid`
+    let s1 = StructArray::from(vec![(
+        Arc::new(Field::new("x", DataType::Int32, true)),
+        Arc::new(Int32Array::from(vec![1, 2])) as ArrayRef,
+    )]);
+    let batch1 = RecordBatch::try_from_iter(vec![
+        ("id", Arc::new(Int32Array::from(vec![1, 2])) as ArrayRef),
+        (
+            "uid",
+            Arc::new(StringArray::from(vec!["a", "b"])) as ArrayRef,
+        ),
+        ("s", Arc::new(s1) as ArrayRef),
+    ])?;
+    // the second file has wider types and a new struct field
+    let s2 = StructArray::from(vec![
+        (
+            Arc::new(Field::new("x", DataType::Int64, true)),
+            Arc::new(Int64Array::from(vec![3])) as ArrayRef,
+        ),
+        (
+            Arc::new(Field::new("y", DataType::Utf8, true)),
+            Arc::new(StringArray::from(vec!["c"])) as ArrayRef,
+        ),
+    ]);
+    let batch2 = RecordBatch::try_from_iter(vec![
+        ("id", Arc::new(Int64Array::from(vec![3])) as ArrayRef),
+        (
+            "user_id",
+            Arc::new(StringArray::from(vec!["c"])) as ArrayRef,
+        ),
+        ("s", Arc::new(s2) as ArrayRef),
+    ])?;
+
+    for (i, batch) in [batch1, batch2].iter().enumerate() {
+        let file = std::fs::File::create(tmp_dir.path().join(format!("{i}.parquet")))?;
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), None)?;
+        writer.write(batch)?;
+        writer.close()?;
+    }
+
+    let ctx = SessionContext::new();
+    let table_path = ListingTableUrl::parse(tmp_dir.path().to_str().unwrap())?;
+    let options = ListingOptions::new(Arc::new(ParquetFormat::default()))
+        .with_file_extension(".parquet")
+        .with_schema_evolution(true)
+        .with_column_renames(vec![("user_id".to_string(), "uid".to_string())]);
+
+    let inferred = options.infer_schema(&ctx.state(), &table_path).await?;
+    let struct_type = DataType::Struct(
+        vec![
+            Field::new("x", DataType::Int64, true),
+            Field::new("y", DataType::Utf8, true),
+        ]
+        .into(),
+    );
+    assert_eq!(
+        inferred.field_with_name("id")?.data_type(),
+        &DataType::Int64
+    );
+    assert_eq!(inferred.field_with_name("s")?.data_type(), &struct_type);
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Int64, true),
+        Field::new("user_id", DataType::Utf8, true),
+        Field::new("s", struct_type, true),
+    ]));
+    let config = ListingTableConfig::new(table_path)
+        .with_listing_options(options)
+        .with_schema(schema);
+    ctx.register_table("t", Arc::new(ListingTable::try_new(config)?))?;
+
+    let read = ctx
+        .sql("SELECT id, user_id, s['x'] AS x, s['y'] AS y FROM t")
+        .await?
+        .collect()
+        .await?;
+
+    let expected = [
+        "+----+---------+---+---+",
+        "| id | user_id | x | y |",
+        "+----+---------+---+---+",
+        "| 1  | a       | 1 |   |",
+        "| 2  | b       | 2 |   |",
+        "| 3  | c       | 3 | c |",
+        "+----+---------+---+---+",
+    ];
+    assert_batches_sorted_eq!(expected, &read);
+    Ok(())
+}
+
 /// Writes `batches` to a temporary parquet file
 pub async fn store_parquet(
     batches: Vec<RecordBatch>,
@@ -39,7 +39,7 @@ use datafusion::{
     prelude::SessionContext,
     test_util::{self, arrow_test_data, parquet_test_data},
 };
-use datafusion_common::ScalarValue;
+use datafusion_common::{Precision, ScalarValue};
 use futures::stream;
 use futures::stream::BoxStream;
 use object_store::{
@@ -466,7 +466,7 @@ async fn parquet_statistics() -> Result<()> {
         .expect("col stats should be defined");
     assert_eq!(stat_cols.len(), 4);
     // stats for the first col are read from the parquet file
-    assert_eq!(stat_cols[0].null_count, Some(3));
+    assert_eq!(stat_cols[0].null_count, Precision::Exact(3));
     // TODO assert partition column (1,2,3) stats once implemented (#1186)
     assert_eq!(stat_cols[1], ColumnStatistics::default());
     assert_eq!(stat_cols[2], ColumnStatistics::default());
@@ -483,7 +483,7 @@ async fn parquet_statistics() -> Result<()> {
         .expect("col stats should be defined");
     assert_eq!(stat_cols.len(), 2);
     // stats for the first col are read from the parquet file
-    assert_eq!(stat_cols[0].null_count, Some(1));
+    assert_eq!(stat_cols[0].null_count, Precision::Exact(1));
     // TODO assert partition column stats once implemented (#1186)
     assert_eq!(stat_cols[1], ColumnStatistics::default());
 
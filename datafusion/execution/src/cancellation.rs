@@ -0,0 +1,215 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`CancellationToken`] for cooperatively cancelling running queries
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::task::{Context, Poll, Waker};
+
+use datafusion_common::{DataFusionError, Result};
+use parking_lot::Mutex;
+
+/// A cheaply cloneable flag shared by all the tasks executing a query.
+///
+/// Long running operators (e.g. sorts, hash join builds and file scans)
+/// periodically call [`CancellationToken::check`] and stop with a
+/// [`DataFusionError::Cancelled`] once the query has been cancelled.
+/// Operators that wait on other tasks can instead poll the future returned
+/// by [`CancellationToken::cancelled`] to be woken up on cancellation.
+///
+/// ```
+/// # use datafusion_execution::CancellationToken;
+/// let token = CancellationToken::new();
+/// let clone = token.clone();
+/// assert!(token.check().is_ok());
+///
+/// clone.cancel_with_reason("deadline exceeded");
+/// assert!(token.is_cancelled());
+/// assert_eq!(
+///     token.check().unwrap_err().to_string(),
+///     "Query cancelled: deadline exceeded"
+/// );
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken {
+    inner: Arc<CancellationInner>,
+}
+
+#[derive(Debug, Default)]
+struct CancellationInner {
+    /// Set once the query is cancelled, never reset
+    cancelled: AtomicBool,
+    /// Why the query was cancelled
+    reason: Mutex<Option<String>>,
+    /// The waker of each pending [`Cancelled`] future, by key
+    wakers: Mutex<HashMap<usize, Waker>>,
+    /// The key of the next [`Cancelled`] future
+    next_key: AtomicUsize,
+}
+
+impl CancellationToken {
+    /// Create a new token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the query this token belongs to
+    pub fn cancel(&self) {
+        self.cancel_with_reason("cancelled by user")
+    }
+
+    /// Cancel the query this token belongs to, reporting `reason` in the
+    /// error returned by [`Self::check`]. Only the first reason is kept.
+    pub fn cancel_with_reason(&self, reason: impl Into<String>) {
+        {
+            let mut current = self.inner.reason.lock();
+            if current.is_none() {
+                *current = Some(reason.into());
+            }
+        }
+        self.inner.cancelled.store(true, Ordering::Release);
+
+        let wakers = std::mem::take(&mut *self.inner.wakers.lock());
+        wakers.into_values().for_each(Waker::wake);
+    }
+
+    /// Returns true if the query has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.inner.cancelled.load(Ordering::Acquire)
+    }
+
+    /// Returns [`DataFusionError::Cancelled`] if the query has been cancelled
+    pub fn check(&self) -> Result<()> {
+        if !self.is_cancelled() {
+            return Ok(());
+        }
+        let reason = self
+            .inner
+            .reason
+            .lock()
+            .clone()
+            .unwrap_or_else(|| "cancelled by user".to_string());
+        Err(DataFusionError::Cancelled(reason))
+    }
+
+    /// Returns a future that completes once the query is cancelled
+    pub fn cancelled(&self) -> Cancelled {
+        Cancelled {
+            token: self.clone(),
+            key: self.inner.next_key.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+}
+
+/// Future returned by [`CancellationToken::cancelled`].
+///
+/// The future registers a single waker with the token, which is replaced
+/// when the future is polled again and removed when it is dropped.
+#[derive(Debug)]
+pub struct Cancelled {
+    token: CancellationToken,
+    key: usize,
+}
+
+impl Future for Cancelled {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let inner = &self.token.inner;
+        if inner.cancelled.load(Ordering::Acquire) {
+            return Poll::Ready(());
+        }
+
+        {
+            let mut wakers = inner.wakers.lock();
+            match wakers.get_mut(&self.key) {
+                Some(waker) if waker.will_wake(cx.waker()) => {}
+                Some(waker) => *waker = cx.waker().clone(),
+                None => {
+                    wakers.insert(self.key, cx.waker().clone());
+                }
+            }
+        }
+
+        // check again in case the token was cancelled while registering
+        match inner.cancelled.load(Ordering::Acquire) {
+            true => Poll::Ready(()),
+            false => Poll::Pending,
+        }
+    }
+}
+
+impl Drop for Cancelled {
+    fn drop(&mut self) {
+        self.token.inner.wakers.lock().remove(&self.key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::task::noop_waker_ref;
+    use futures::FutureExt;
+
+    #[test]
+    fn cancel() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+        assert!(clone.check().is_ok());
+
+        token.cancel_with_reason("first");
+        token.cancel_with_reason("second");
+        assert!(clone.is_cancelled());
+
+        let err = clone.check().unwrap_err();
+        assert!(matches!(err, DataFusionError::Cancelled(_)));
+        assert_eq!(err.to_string(), "Query cancelled: first");
+    }
+
+    #[test]
+    fn cancelled_future() {
+        let token = CancellationToken::new();
+        let mut fut = token.cancelled();
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        assert!(fut.poll_unpin(&mut cx).is_pending());
+        assert!(fut.poll_unpin(&mut cx).is_pending());
+        assert_eq!(token.inner.wakers.lock().len(), 1);
+
+        token.cancel();
+        assert!(fut.poll_unpin(&mut cx).is_ready());
+        assert!(token.inner.wakers.lock().is_empty());
+    }
+
+    #[test]
+    fn dropped_future_removes_waker() {
+        let token = CancellationToken::new();
+        let mut cx = Context::from_waker(noop_waker_ref());
+
+        for _ in 0..10 {
+            let mut fut = token.cancelled();
+            assert!(fut.poll_unpin(&mut cx).is_pending());
+            assert_eq!(token.inner.wakers.lock().len(), 1);
+        }
+        assert!(token.inner.wakers.lock().is_empty());
+    }
+}
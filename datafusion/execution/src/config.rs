@@ -145,6 +145,12 @@ impl SessionConfig {
         self.options.optimizer.repartition_sorts
     }
 
+    /// Do we range partition the input of sorts that produce a single
+    /// partition, instead of merging the per-partition sorts afterwards?
+    pub fn repartition_sorts_by_range(&self) -> bool {
+        self.options.optimizer.repartition_sorts_by_range
+    }
+
     /// Remove sorts by replacing with order-preserving variants of operators,
     /// even when query is bounded?
     pub fn bounded_order_preserving_variants(&self) -> bool {
@@ -221,6 +227,13 @@ impl SessionConfig {
         self
     }
 
+    /// Enables or disables the use of range partitioning to sort in parallel
+    /// without merging the sorted partitions afterwards
+    pub fn with_repartition_sorts_by_range(mut self, enabled: bool) -> Self {
+        self.options.optimizer.repartition_sorts_by_range = enabled;
+        self
+    }
+
     /// Enables or disables the use of order-preserving variants of `CoalescePartitions`
     /// and `RepartitionExec` operators, even when the query is bounded
     pub fn with_bounded_order_preserving_variants(mut self, enabled: bool) -> Self {
@@ -18,11 +18,13 @@
 //! Manages files generated during query execution, files are
 //! hashed among the directories listed in RuntimeConfig::local_dirs.
 
+use crate::memory_pool::human_readable_size;
 use datafusion_common::{DataFusionError, Result};
 use log::debug;
 use parking_lot::Mutex;
 use rand::{thread_rng, Rng};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::Arc;
 use tempfile::{Builder, NamedTempFile, TempDir};
 
@@ -42,6 +44,15 @@ pub enum DiskManagerConfig {
 
     /// Disable disk manager, attempts to create temporary files will error
     Disabled,
+
+    /// Create a [DiskManager] as configured by `config`, limiting the total
+    /// size of its spill files to `max_spill_size` bytes
+    MaxSpillSize {
+        /// The configuration of the [DiskManager]
+        config: Box<DiskManagerConfig>,
+        /// The maximum total size of the spill files, in bytes
+        max_spill_size: usize,
+    },
 }
 
 impl Default for DiskManagerConfig {
@@ -65,6 +76,18 @@ impl DiskManagerConfig {
     pub fn new_specified(paths: Vec<PathBuf>) -> Self {
         Self::NewSpecified(paths)
     }
+
+    /// Limit the total size of the spill files to `max_spill_size` bytes.
+    ///
+    /// Spilling operators fail with a "Resources exhausted" error once the
+    /// limit is reached. If the disk manager is [`Self::Existing`], the limit
+    /// applies to all the users of that manager.
+    pub fn with_max_spill_size(self, max_spill_size: usize) -> Self {
+        Self::MaxSpillSize {
+            config: Box::new(self),
+            max_spill_size,
+        }
+    }
 }
 
 /// Manages files generated during query execution, e.g. spill files generated
@@ -76,6 +99,13 @@ pub struct DiskManager {
     /// If `Some(vec![])` a new OS specified temporary directory will be created
     /// If `None` an error will be returned (configured not to spill)
     local_dirs: Mutex<Option<Vec<TempDir>>>,
+
+    /// The maximum total size of the spill files of this manager, in
+    /// bytes. `usize::MAX` if unlimited
+    max_spill_size: AtomicUsize,
+
+    /// The disk space used by all the spill files of this manager
+    spill_usage: SpillUsage,
 }
 
 impl DiskManager {
@@ -83,25 +113,59 @@ impl DiskManager {
     pub fn try_new(config: DiskManagerConfig) -> Result<Arc<Self>> {
         match config {
             DiskManagerConfig::Existing(manager) => Ok(manager),
-            DiskManagerConfig::NewOs => Ok(Arc::new(Self {
-                local_dirs: Mutex::new(Some(vec![])),
-            })),
+            DiskManagerConfig::NewOs => Ok(Arc::new(Self::new(Some(vec![])))),
             DiskManagerConfig::NewSpecified(conf_dirs) => {
                 let local_dirs = create_local_dirs(conf_dirs)?;
                 debug!(
                     "Created local dirs {:?} as DataFusion working directory",
                     local_dirs
                 );
-                Ok(Arc::new(Self {
-                    local_dirs: Mutex::new(Some(local_dirs)),
-                }))
+                Ok(Arc::new(Self::new(Some(local_dirs))))
             }
-            DiskManagerConfig::Disabled => Ok(Arc::new(Self {
-                local_dirs: Mutex::new(None),
-            })),
+            DiskManagerConfig::Disabled => Ok(Arc::new(Self::new(None))),
+            DiskManagerConfig::MaxSpillSize {
+                config,
+                max_spill_size,
+            } => {
+                let manager = Self::try_new(*config)?;
+                manager.set_max_spill_size(Some(max_spill_size));
+                Ok(manager)
+            }
+        }
+    }
+
+    fn new(local_dirs: Option<Vec<TempDir>>) -> Self {
+        Self {
+            local_dirs: Mutex::new(local_dirs),
+            max_spill_size: AtomicUsize::new(usize::MAX),
+            spill_usage: SpillUsage::new(),
         }
     }
 
+    /// Limit the total size of the spill files created by
+    /// [`Self::create_spill_file`] to `max_spill_size` bytes, or remove
+    /// the limit if `None`.
+    ///
+    /// Reserving space for a spill file that would exceed the limit returns
+    /// a [`DataFusionError::ResourcesExhausted`] error.
+    pub fn set_max_spill_size(&self, max_spill_size: Option<usize>) {
+        self.max_spill_size
+            .store(max_spill_size.unwrap_or(usize::MAX), Ordering::Relaxed);
+    }
+
+    /// Returns the maximum total size of the spill files, if limited
+    pub fn max_spill_size(&self) -> Option<usize> {
+        match self.max_spill_size.load(Ordering::Relaxed) {
+            usize::MAX => None,
+            max => Some(max),
+        }
+    }
+
+    /// Returns the disk space used by the spill files of all queries
+    pub fn spill_usage(&self) -> &SpillUsage {
+        &self.spill_usage
+    }
+
     /// Return true if this disk manager supports creating temporary
     /// files. If this returns false, any call to `create_tmp_file`
     /// will error.
@@ -139,6 +203,186 @@ impl DiskManager {
             .tempfile_in(&local_dirs[dir_index])
             .map_err(DataFusionError::IoError)
     }
+
+    /// Return a new [`SpillFile`] in a randomized choice of the configured
+    /// locations, whose size is accounted to this manager and to
+    /// `query_usage`
+    pub fn create_spill_file(
+        &self,
+        request_description: &str,
+        query_usage: &SpillUsage,
+    ) -> Result<SpillFile> {
+        let file = self.create_tmp_file(request_description)?;
+        self.spill_usage.add_file();
+        query_usage.add_file();
+        Ok(SpillFile {
+            file,
+            size: 0,
+            accounted: 0,
+            request_description: request_description.to_string(),
+            max_spill_size: self.max_spill_size(),
+            manager_usage: self.spill_usage.clone(),
+            query_usage: query_usage.clone(),
+        })
+    }
+}
+
+/// The disk space used by spill files, either of all the queries of a
+/// [`DiskManager`] or of a single query
+#[derive(Debug, Clone, Default)]
+pub struct SpillUsage {
+    inner: Arc<SpillUsageInner>,
+}
+
+#[derive(Debug, Default)]
+struct SpillUsageInner {
+    /// Size of the spill files currently on disk
+    current: AtomicUsize,
+    /// Largest value of `current`
+    peak: AtomicUsize,
+    /// Total size of all the spill files ever written
+    total: AtomicUsize,
+    /// Number of spill files ever created
+    files: AtomicUsize,
+}
+
+impl SpillUsage {
+    /// Create a new [`SpillUsage`] with no spill files
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the size of the spill files currently on disk, in bytes
+    pub fn current_bytes(&self) -> usize {
+        self.inner.current.load(Ordering::Relaxed)
+    }
+
+    /// Returns the largest size of the spill files on disk at any point in
+    /// time, in bytes
+    pub fn peak_bytes(&self) -> usize {
+        self.inner.peak.load(Ordering::Relaxed)
+    }
+
+    /// Returns the total size of all the spill files ever written, in bytes
+    pub fn total_bytes(&self) -> usize {
+        self.inner.total.load(Ordering::Relaxed)
+    }
+
+    /// Returns the number of spill files ever created
+    pub fn num_files(&self) -> usize {
+        self.inner.files.load(Ordering::Relaxed)
+    }
+
+    fn add_file(&self) {
+        self.inner.files.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Atomically grows the size of the spill files on disk by `additional`
+    /// bytes, unless that would exceed `max` bytes. Returns the size of the
+    /// spill files on failure.
+    fn try_grow(&self, additional: usize, max: usize) -> Result<(), usize> {
+        let current = self.inner.current.fetch_update(
+            Ordering::Relaxed,
+            Ordering::Relaxed,
+            |current| current.checked_add(additional).filter(|new| *new <= max),
+        )?;
+        self.inner
+            .peak
+            .fetch_max(current + additional, Ordering::Relaxed);
+        Ok(())
+    }
+
+    fn shrink(&self, shrink: usize) {
+        self.inner.current.fetch_sub(shrink, Ordering::Relaxed);
+    }
+
+    fn add_written(&self, written: usize) {
+        self.inner.total.fetch_add(written, Ordering::Relaxed);
+    }
+}
+
+/// A temporary file created by [`DiskManager::create_spill_file`] that is
+/// deleted on drop.
+///
+/// The size of the file is accounted to the [`SpillUsage`] of its
+/// [`DiskManager`] and of its query. Space must be reserved with
+/// [`Self::try_reserve`] before writing to the file, so that concurrent
+/// spills cannot exceed the maximum spill size, and the size must then be
+/// updated with [`Self::update_size`].
+#[derive(Debug)]
+pub struct SpillFile {
+    file: NamedTempFile,
+    /// The size of the file as of the last call to `update_size`
+    size: usize,
+    /// The number of bytes accounted to the spill usages, at least `size`
+    accounted: usize,
+    request_description: String,
+    max_spill_size: Option<usize>,
+    manager_usage: SpillUsage,
+    query_usage: SpillUsage,
+}
+
+impl SpillFile {
+    /// Returns the path of this file
+    pub fn path(&self) -> &Path {
+        self.file.path()
+    }
+
+    /// Returns the size of this file, as of the last call to
+    /// [`Self::update_size`]
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Reserves `additional` bytes of disk space for data about to be
+    /// written to this file, returning an error if the spill files of the
+    /// [`DiskManager`] would exceed its maximum spill size
+    pub fn try_reserve(&mut self, additional: usize) -> Result<()> {
+        let max = self.max_spill_size.unwrap_or(usize::MAX);
+        if let Err(used) = self.manager_usage.try_grow(additional, max) {
+            return Err(DataFusionError::ResourcesExhausted(format!(
+                "Spill limit exceeded while {}: writing {} more would use {} of disk, \
+                above the maximum spill size of {}",
+                self.request_description,
+                human_readable_size(additional),
+                human_readable_size(used.saturating_add(additional)),
+                human_readable_size(max),
+            )));
+        }
+        // the limit only applies to the spill files of all queries
+        let _ = self.query_usage.try_grow(additional, usize::MAX);
+        self.accounted += additional;
+        Ok(())
+    }
+
+    /// Accounts for the current size of this file on disk, releasing the
+    /// reserved space that was not written. Returns an error if the file
+    /// outgrew its reservation and the spill files of the [`DiskManager`]
+    /// now exceed its maximum spill size.
+    pub fn update_size(&mut self) -> Result<()> {
+        let size = std::fs::metadata(self.path())?.len() as usize;
+        if size > self.size {
+            self.manager_usage.add_written(size - self.size);
+            self.query_usage.add_written(size - self.size);
+        }
+        self.size = size;
+
+        if size > self.accounted {
+            self.try_reserve(size - self.accounted)?;
+        } else {
+            self.manager_usage.shrink(self.accounted - size);
+            self.query_usage.shrink(self.accounted - size);
+            self.accounted = size;
+        }
+        Ok(())
+    }
+}
+
+impl Drop for SpillFile {
+    fn drop(&mut self) {
+        self.manager_usage.shrink(self.accounted);
+        self.query_usage.shrink(self.accounted);
+    }
 }
 
 /// Setup local dirs by creating one new dir in each of the given dirs
@@ -225,6 +469,56 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_spill_file_limit() -> Result<()> {
+        let config = DiskManagerConfig::NewOs.with_max_spill_size(100);
+        let manager = DiskManager::try_new(config)?;
+        assert_eq!(manager.max_spill_size(), Some(100));
+
+        let query1 = SpillUsage::new();
+        let query2 = SpillUsage::new();
+        let mut f1 = manager.create_spill_file("Testing", &query1)?;
+        let mut f2 = manager.create_spill_file("Testing", &query2)?;
+
+        f1.try_reserve(60)?;
+        std::fs::write(f1.path(), [0; 60])?;
+        f1.update_size()?;
+        assert_eq!(f1.size(), 60);
+        assert_eq!(query1.current_bytes(), 60);
+        assert_eq!(manager.spill_usage().current_bytes(), 60);
+
+        // the reservation fails before anything is written
+        let err = f2.try_reserve(50).unwrap_err().strip_backtrace();
+        assert_eq!(err, "Resources exhausted: Spill limit exceeded while Testing: writing 50.0 B more would use 110.0 B of disk, above the maximum spill size of 100.0 B");
+        assert_eq!(query2.current_bytes(), 0);
+
+        // the unwritten part of a reservation is released
+        f2.try_reserve(40)?;
+        assert_eq!(manager.spill_usage().current_bytes(), 100);
+        std::fs::write(f2.path(), [0; 30])?;
+        f2.update_size()?;
+        assert_eq!(query2.current_bytes(), 30);
+        assert_eq!(manager.spill_usage().current_bytes(), 90);
+
+        // writing past the reservation is checked against the limit
+        std::fs::write(f2.path(), [0; 50])?;
+        let err = f2.update_size().unwrap_err().strip_backtrace();
+        assert_eq!(err, "Resources exhausted: Spill limit exceeded while Testing: writing 20.0 B more would use 110.0 B of disk, above the maximum spill size of 100.0 B");
+
+        drop(f1);
+        assert_eq!(query1.current_bytes(), 0);
+        assert_eq!(query1.peak_bytes(), 60);
+        assert_eq!(query1.total_bytes(), 60);
+        assert_eq!(query1.num_files(), 1);
+        assert_eq!(manager.spill_usage().current_bytes(), 30);
+        assert_eq!(manager.spill_usage().peak_bytes(), 100);
+        assert_eq!(manager.spill_usage().num_files(), 2);
+
+        drop(f2);
+        assert_eq!(manager.spill_usage().current_bytes(), 0);
+        Ok(())
+    }
+
     #[test]
     fn test_disk_manager_create_spill_folder() {
         let config = DiskManagerConfig::new_specified(vec!["DOESNT_EXIST".into()]);
@@ -0,0 +1,401 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::memory_pool::{
+    describe_consumers, MemoryConsumer, MemoryConsumerInfo, MemoryPool,
+    MemoryReservation, TrackedConsumers, TOP_CONSUMERS,
+};
+use datafusion_common::{DataFusionError, Result};
+use log::debug;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// A [`MemoryPool`] with its own limit that can be nested within another
+/// [`MemoryPool`], forming a tree of pools.
+///
+/// A process wide pool can be the root of the tree, with a child pool per
+/// session or query:
+///
+/// ```text
+///                 ┌───────────────────────────────┐
+///                 │ global (HierarchicalMemoryPool│
+///                 │  or any other MemoryPool)     │
+///                 └───────────────────────────────┘
+///                        ▲                 ▲
+///          ┌─────────────┴───┐     ┌───────┴─────────┐
+///          │ tenant A (10GB) │     │ tenant B (2GB)  │
+///          └─────────────────┘     └─────────────────┘
+///              ▲          ▲
+///     ┌────────┴──┐   ┌───┴───────┐
+///     │ query 1   │   │ query 2   │
+///     └───────────┘   └───────────┘
+/// ```
+///
+/// A reservation in a child pool must fit within the limit of the child
+/// and of all its ancestors. Each child is accounted as a single
+/// unspillable consumer in its parent.
+///
+/// Within a pool:
+///
+/// * Unspillable memory is allocated in a first-come, first-serve fashion
+/// * Spillable consumers may use no more than an even fraction of the memory
+///   not used by unspillable consumers, like [`FairSpillPool`]
+/// * Consumers of a given operator can be capped with
+///   [`Self::with_operator_limit`]
+///
+/// When a reservation cannot be satisfied, the returned
+/// [`DataFusionError::ResourcesExhausted`] lists the largest consumers of
+/// the pool.
+///
+/// # Example: A pool per query
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use datafusion_execution::memory_pool::{HierarchicalMemoryPool, MemoryConsumer, MemoryPool};
+/// # use datafusion_execution::runtime_env::RuntimeEnv;
+/// let global: Arc<dyn MemoryPool> =
+///     Arc::new(HierarchicalMemoryPool::new("global", 1024 * 1024));
+///
+/// // Each query may use up to 64KB, and sorts no more than 16KB each
+/// let query: Arc<dyn MemoryPool> = Arc::new(
+///     HierarchicalMemoryPool::new_child(&global, "query-1", 64 * 1024)
+///         .with_operator_limit("ExternalSorter", 16 * 1024),
+/// );
+///
+/// // run the query with its own pool
+/// let mut runtime = RuntimeEnv::default();
+/// runtime.memory_pool = Arc::clone(&query);
+///
+/// let mut reservation = MemoryConsumer::new("ExternalSorter[0]").register(&query);
+/// reservation.try_grow(1024).unwrap();
+/// assert!(reservation.try_grow(16 * 1024).is_err());
+/// assert_eq!(global.reserved(), 1024);
+/// ```
+///
+/// [`FairSpillPool`]: crate::memory_pool::FairSpillPool
+#[derive(Debug)]
+pub struct HierarchicalMemoryPool {
+    /// The name of this pool, used in error messages and as the
+    /// name of its consumer in the parent pool
+    name: String,
+
+    /// The memory limit of this pool
+    pool_size: usize,
+
+    /// The reservation of this pool in its parent pool, if any
+    parent: Option<Mutex<MemoryReservation>>,
+
+    /// Maximum memory of each consumer of an operator, keyed by operator
+    /// name (the consumer name up to the first `[`)
+    operator_limits: HashMap<String, usize>,
+
+    state: Mutex<HierarchicalPoolState>,
+
+    consumers: TrackedConsumers,
+}
+
+#[derive(Debug, Default)]
+struct HierarchicalPoolState {
+    /// The number of consumers that can spill
+    num_spill: usize,
+
+    /// The total amount of memory reserved that can be spilled
+    spillable: usize,
+
+    /// The total amount of memory reserved by consumers that cannot spill
+    unspillable: usize,
+}
+
+impl HierarchicalMemoryPool {
+    /// Create a new root pool that allows up to `pool_size` bytes
+    pub fn new(name: impl Into<String>, pool_size: usize) -> Self {
+        let name = name.into();
+        debug!("Created new HierarchicalMemoryPool(name={name}, pool_size={pool_size})");
+        Self {
+            name,
+            pool_size,
+            parent: None,
+            operator_limits: HashMap::new(),
+            state: Mutex::new(HierarchicalPoolState::default()),
+            consumers: TrackedConsumers::default(),
+        }
+    }
+
+    /// Create a new pool that allows up to `pool_size` bytes, allocating
+    /// its memory from `parent`
+    pub fn new_child(
+        parent: &Arc<dyn MemoryPool>,
+        name: impl Into<String>,
+        pool_size: usize,
+    ) -> Self {
+        let name = name.into();
+        let reservation = MemoryConsumer::new(name.clone()).register(parent);
+        Self {
+            parent: Some(Mutex::new(reservation)),
+            ..Self::new(name, pool_size)
+        }
+    }
+
+    /// Limit every consumer of `operator` to at most `limit` bytes.
+    ///
+    /// The operator of a consumer is its name up to the first `[`, for
+    /// example `ExternalSorter` for `ExternalSorter[2]`.
+    pub fn with_operator_limit(
+        mut self,
+        operator: impl Into<String>,
+        limit: usize,
+    ) -> Self {
+        self.operator_limits.insert(operator.into(), limit);
+        self
+    }
+
+    /// Returns the name of this pool
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the memory limit of this pool
+    pub fn pool_size(&self) -> usize {
+        self.pool_size
+    }
+
+    /// Returns the maximum size of a consumer named `name`, if its
+    /// operator is limited
+    fn operator_limit(&self, name: &str) -> Option<usize> {
+        let operator = name.split('[').next().unwrap_or(name).trim();
+        self.operator_limits.get(operator).copied()
+    }
+
+    /// Returns an error for failing to grow `reservation` by `additional`
+    /// bytes, listing the largest consumers of this pool
+    fn exhausted_err(
+        &self,
+        reservation: &MemoryReservation,
+        additional: usize,
+        available: usize,
+    ) -> DataFusionError {
+        DataFusionError::ResourcesExhausted(format!(
+            "Failed to allocate additional {} bytes for {} with {} bytes already allocated - maximum available is {}. {}",
+            additional,
+            reservation.consumer().name(),
+            reservation.size(),
+            available,
+            self.top_consumers()
+        ))
+    }
+
+    /// Describes the largest consumers of this pool
+    fn top_consumers(&self) -> String {
+        describe_consumers(&self.consumers.top(TOP_CONSUMERS), Some(&self.name))
+    }
+
+    fn update(
+        state: &mut HierarchicalPoolState,
+        reservation: &MemoryReservation,
+        additional: usize,
+    ) {
+        match reservation.consumer().can_spill() {
+            true => state.spillable += additional,
+            false => state.unspillable += additional,
+        }
+    }
+}
+
+impl MemoryPool for HierarchicalMemoryPool {
+    fn register(&self, consumer: &MemoryConsumer) {
+        if consumer.can_spill() {
+            self.state.lock().num_spill += 1;
+        }
+        self.consumers.register(consumer)
+    }
+
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        if consumer.can_spill() {
+            let mut state = self.state.lock();
+            state.num_spill = state.num_spill.checked_sub(1).unwrap();
+        }
+        self.consumers.unregister(consumer)
+    }
+
+    fn grow(&self, reservation: &MemoryReservation, additional: usize) {
+        let mut state = self.state.lock();
+        if let Some(parent) = &self.parent {
+            parent.lock().grow(additional);
+        }
+        Self::update(&mut state, reservation, additional);
+    }
+
+    fn shrink(&self, reservation: &MemoryReservation, shrink: usize) {
+        let mut state = self.state.lock();
+        if let Some(parent) = &self.parent {
+            parent.lock().shrink(shrink);
+        }
+        match reservation.consumer().can_spill() {
+            true => state.spillable -= shrink,
+            false => state.unspillable -= shrink,
+        }
+    }
+
+    fn try_grow(&self, reservation: &MemoryReservation, additional: usize) -> Result<()> {
+        let mut state = self.state.lock();
+        let consumer = reservation.consumer();
+
+        // Memory already reserved by all reservations of this consumer
+        let consumer_reserved = consumer.reserved();
+
+        let mut available = match consumer.can_spill() {
+            true => {
+                // No spiller may use more than their fraction of the memory
+                // not used by unspillable consumers
+                let spill_available = self.pool_size.saturating_sub(state.unspillable);
+                spill_available
+                    .checked_div(state.num_spill)
+                    .unwrap_or(spill_available)
+                    .saturating_sub(consumer_reserved)
+            }
+            false => self
+                .pool_size
+                .saturating_sub(state.unspillable + state.spillable),
+        };
+        if let Some(limit) = self.operator_limit(consumer.name()) {
+            available = available.min(limit.saturating_sub(consumer_reserved));
+        }
+
+        if available < additional {
+            return Err(self.exhausted_err(reservation, additional, available));
+        }
+
+        if let Some(parent) = &self.parent {
+            parent.lock().try_grow(additional).map_err(|e| match e {
+                DataFusionError::ResourcesExhausted(msg) => {
+                    DataFusionError::ResourcesExhausted(format!(
+                        "{msg}. {}",
+                        self.top_consumers()
+                    ))
+                }
+                e => e,
+            })?;
+        }
+        Self::update(&mut state, reservation, additional);
+        Ok(())
+    }
+
+    fn reserved(&self) -> usize {
+        let state = self.state.lock();
+        state.spillable + state.unspillable
+    }
+
+    fn consumers(&self) -> Vec<MemoryConsumerInfo> {
+        self.consumers.consumers()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hierarchical_limits() {
+        let global = Arc::new(HierarchicalMemoryPool::new("global", 100)) as _;
+        let q1 = Arc::new(HierarchicalMemoryPool::new_child(&global, "q1", 60)) as _;
+        let q2 = Arc::new(HierarchicalMemoryPool::new_child(&global, "q2", 60)) as _;
+
+        let mut r1 = MemoryConsumer::new("r1").register(&q1);
+        r1.try_grow(50).unwrap();
+        assert_eq!(q1.reserved(), 50);
+        assert_eq!(global.reserved(), 50);
+
+        // limited by q1
+        let err = r1.try_grow(20).unwrap_err().strip_backtrace();
+        assert_eq!(err, "Resources exhausted: Failed to allocate additional 20 bytes for r1 with 50 bytes already allocated - maximum available is 10. Top memory consumers of pool q1: r1: 50.0 B (peak 50.0 B)");
+
+        // limited by global
+        let mut r2 = MemoryConsumer::new("r2").register(&q2);
+        let err = r2.try_grow(55).unwrap_err().strip_backtrace();
+        assert_eq!(err, "Resources exhausted: Failed to allocate additional 55 bytes for q2 with 0 bytes already allocated - maximum available is 50. Top memory consumers of pool global: q1: 50.0 B (peak 50.0 B). No memory reserved by consumers of pool q2");
+        assert_eq!(q2.reserved(), 0);
+
+        r2.try_grow(50).unwrap();
+        assert_eq!(global.reserved(), 100);
+
+        // infallible growth is accounted in the parent
+        r2.grow(10);
+        assert_eq!(q2.reserved(), 60);
+        assert_eq!(global.reserved(), 110);
+
+        drop(r1);
+        assert_eq!(q1.reserved(), 0);
+        assert_eq!(global.reserved(), 60);
+
+        r2.free();
+        assert_eq!(global.reserved(), 0);
+    }
+
+    #[test]
+    fn test_hierarchical_fair_spill() {
+        let pool = Arc::new(HierarchicalMemoryPool::new("query", 100)) as _;
+
+        let mut r1 = MemoryConsumer::new("unspillable").register(&pool);
+        r1.try_grow(20).unwrap();
+
+        let mut s1 = MemoryConsumer::new("s1")
+            .with_can_spill(true)
+            .register(&pool);
+        let mut s2 = MemoryConsumer::new("s2")
+            .with_can_spill(true)
+            .register(&pool);
+
+        // each spiller may use (100 - 20) / 2
+        s1.try_grow(40).unwrap();
+        let err = s1.try_grow(1).unwrap_err().strip_backtrace();
+        assert_eq!(err, "Resources exhausted: Failed to allocate additional 1 bytes for s1 with 40 bytes already allocated - maximum available is 0. Top memory consumers of pool query: s1: 40.0 B (peak 40.0 B), unspillable: 20.0 B (peak 20.0 B)");
+
+        // the share of a consumer includes all its reservations
+        let mut s1_split = s1.new_empty();
+        s1_split.try_grow(1).unwrap_err();
+
+        s2.try_grow(40).unwrap();
+        assert_eq!(pool.reserved(), 100);
+
+        // once s2 is dropped, s1 may use all the spillable memory
+        drop(s2);
+        s1_split.try_grow(40).unwrap();
+        assert_eq!(pool.reserved(), 100);
+    }
+
+    #[test]
+    fn test_hierarchical_operator_limit() {
+        let pool = Arc::new(
+            HierarchicalMemoryPool::new("query", 100)
+                .with_operator_limit("ExternalSorter", 30),
+        ) as _;
+
+        let mut sort0 = MemoryConsumer::new("ExternalSorter[0]").register(&pool);
+        let mut sort1 = MemoryConsumer::new("ExternalSorter[1]").register(&pool);
+        let mut merge = MemoryConsumer::new("ExternalSorterMerge[0]").register(&pool);
+
+        sort0.try_grow(30).unwrap();
+        let err = sort0.try_grow(1).unwrap_err().strip_backtrace();
+        assert_eq!(err, "Resources exhausted: Failed to allocate additional 1 bytes for ExternalSorter[0] with 30 bytes already allocated - maximum available is 0. Top memory consumers of pool query: ExternalSorter[0]: 30.0 B (peak 30.0 B)");
+
+        // the limit applies to each consumer separately
+        sort1.try_grow(30).unwrap();
+        merge.try_grow(40).unwrap();
+        assert_eq!(pool.reserved(), 100);
+    }
+}
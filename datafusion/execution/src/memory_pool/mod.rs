@@ -18,12 +18,22 @@
 //! Manages all available memory during query execution
 
 use datafusion_common::Result;
-use std::{cmp::Ordering, sync::Arc};
-
+use std::{
+    cmp::Ordering,
+    sync::{
+        atomic::{self, AtomicUsize},
+        Arc,
+    },
+};
+
+mod hierarchical;
 mod pool;
 pub mod proxy;
+mod tracked;
 
+pub use hierarchical::*;
 pub use pool::*;
+pub use tracked::*;
 
 /// The pool of memory on which [`MemoryReservation`]s record their
 /// memory reservations.
@@ -49,6 +59,7 @@ pub use pool::*;
 /// * [`UnboundedMemoryPool`](pool::UnboundedMemoryPool)
 /// * [`GreedyMemoryPool`](pool::GreedyMemoryPool)
 /// * [`FairSpillPool`](pool::FairSpillPool)
+/// * [`HierarchicalMemoryPool`](hierarchical::HierarchicalMemoryPool)
 pub trait MemoryPool: Send + Sync + std::fmt::Debug {
     /// Registers a new [`MemoryConsumer`]
     ///
@@ -75,6 +86,14 @@ pub trait MemoryPool: Send + Sync + std::fmt::Debug {
 
     /// Return the total amount of memory reserved
     fn reserved(&self) -> usize;
+
+    /// Return the consumers currently registered with this pool, with their
+    /// current and peak reservations, largest first
+    ///
+    /// Pools that do not track their consumers return an empty list
+    fn consumers(&self) -> Vec<MemoryConsumerInfo> {
+        vec![]
+    }
 }
 
 /// A memory consumer that can be tracked by [`MemoryReservation`] in
@@ -82,19 +101,61 @@ pub trait MemoryPool: Send + Sync + std::fmt::Debug {
 /// `MemoryConsumer`;
 #[derive(Debug)]
 pub struct MemoryConsumer {
+    id: usize,
     name: String,
     can_spill: bool,
+    /// Memory reserved by all the reservations of this consumer
+    usage: Arc<ConsumerUsage>,
 }
 
+/// The current and peak memory reserved by a [`MemoryConsumer`]
+#[derive(Debug, Default)]
+struct ConsumerUsage {
+    reserved: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+impl ConsumerUsage {
+    fn grow(&self, additional: usize) {
+        let reserved = self
+            .reserved
+            .fetch_add(additional, atomic::Ordering::Relaxed);
+        self.peak
+            .fetch_max(reserved + additional, atomic::Ordering::Relaxed);
+    }
+
+    fn shrink(&self, shrink: usize) {
+        self.reserved.fetch_sub(shrink, atomic::Ordering::Relaxed);
+    }
+
+    fn reserved(&self) -> usize {
+        self.reserved.load(atomic::Ordering::Relaxed)
+    }
+
+    fn peak(&self) -> usize {
+        self.peak.load(atomic::Ordering::Relaxed)
+    }
+}
+
+/// The id of the next [`MemoryConsumer`]
+static NEXT_CONSUMER_ID: AtomicUsize = AtomicUsize::new(0);
+
 impl MemoryConsumer {
     /// Create a new empty [`MemoryConsumer`] that can be grown using [`MemoryReservation`]
     pub fn new(name: impl Into<String>) -> Self {
         Self {
+            id: NEXT_CONSUMER_ID.fetch_add(1, atomic::Ordering::Relaxed),
             name: name.into(),
             can_spill: false,
+            usage: Default::default(),
         }
     }
 
+    /// Returns an id that uniquely identifies this consumer within the process
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
     /// Set whether this allocation can be spilled to disk
     pub fn with_can_spill(self, can_spill: bool) -> Self {
         Self { can_spill, ..self }
@@ -110,6 +171,29 @@ impl MemoryConsumer {
         &self.name
     }
 
+    /// Returns the memory currently reserved by all the reservations of
+    /// this consumer
+    pub fn reserved(&self) -> usize {
+        self.usage.reserved()
+    }
+
+    /// Returns the largest amount of memory reserved by this consumer
+    /// at any point in time
+    pub fn peak(&self) -> usize {
+        self.usage.peak()
+    }
+
+    /// Returns a snapshot of this consumer and its memory usage
+    pub fn info(&self) -> MemoryConsumerInfo {
+        MemoryConsumerInfo {
+            id: self.id,
+            name: self.name.clone(),
+            can_spill: self.can_spill,
+            reserved: self.usage.reserved(),
+            peak: self.usage.peak(),
+        }
+    }
+
     /// Registers this [`MemoryConsumer`] with the provided [`MemoryPool`] returning
     /// a [`MemoryReservation`] that can be used to grow or shrink the memory reservation
     pub fn register(self, pool: &Arc<dyn MemoryPool>) -> MemoryReservation {
@@ -157,6 +241,11 @@ impl MemoryReservation {
         self.size
     }
 
+    /// Returns the [`MemoryConsumer`] of this reservation
+    pub fn consumer(&self) -> &MemoryConsumer {
+        &self.registration.consumer
+    }
+
     /// Frees all bytes from this reservation back to the underlying
     /// pool, returning the number of bytes freed.
     pub fn free(&mut self) -> usize {
@@ -175,6 +264,7 @@ impl MemoryReservation {
     pub fn shrink(&mut self, capacity: usize) {
         let new_size = self.size.checked_sub(capacity).unwrap();
         self.registration.pool.shrink(self, capacity);
+        self.registration.consumer.usage.shrink(capacity);
         self.size = new_size
     }
 
@@ -200,6 +290,7 @@ impl MemoryReservation {
     /// Increase the size of this reservation by `capacity` bytes
     pub fn grow(&mut self, capacity: usize) {
         self.registration.pool.grow(self, capacity);
+        self.registration.consumer.usage.grow(capacity);
         self.size += capacity;
     }
 
@@ -208,6 +299,7 @@ impl MemoryReservation {
     /// in the pool.
     pub fn try_grow(&mut self, capacity: usize) -> Result<()> {
         self.registration.pool.try_grow(self, capacity)?;
+        self.registration.consumer.usage.grow(capacity);
         self.size += capacity;
         Ok(())
     }
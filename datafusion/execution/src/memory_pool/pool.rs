@@ -15,7 +15,10 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use crate::memory_pool::{MemoryConsumer, MemoryPool, MemoryReservation};
+use crate::memory_pool::{
+    describe_consumers, MemoryConsumer, MemoryConsumerInfo, MemoryPool,
+    MemoryReservation, TrackedConsumers, TOP_CONSUMERS,
+};
 use datafusion_common::{DataFusionError, Result};
 use log::debug;
 use parking_lot::Mutex;
@@ -25,9 +28,18 @@ use std::sync::atomic::{AtomicUsize, Ordering};
 #[derive(Debug, Default)]
 pub struct UnboundedMemoryPool {
     used: AtomicUsize,
+    consumers: TrackedConsumers,
 }
 
 impl MemoryPool for UnboundedMemoryPool {
+    fn register(&self, consumer: &MemoryConsumer) {
+        self.consumers.register(consumer)
+    }
+
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        self.consumers.unregister(consumer)
+    }
+
     fn grow(&self, _reservation: &MemoryReservation, additional: usize) {
         self.used.fetch_add(additional, Ordering::Relaxed);
     }
@@ -44,6 +56,10 @@ impl MemoryPool for UnboundedMemoryPool {
     fn reserved(&self) -> usize {
         self.used.load(Ordering::Relaxed)
     }
+
+    fn consumers(&self) -> Vec<MemoryConsumerInfo> {
+        self.consumers.consumers()
+    }
 }
 
 /// A [`MemoryPool`] that implements a greedy first-come first-serve limit.
@@ -55,6 +71,7 @@ impl MemoryPool for UnboundedMemoryPool {
 pub struct GreedyMemoryPool {
     pool_size: usize,
     used: AtomicUsize,
+    consumers: TrackedConsumers,
 }
 
 impl GreedyMemoryPool {
@@ -64,11 +81,20 @@ impl GreedyMemoryPool {
         Self {
             pool_size,
             used: AtomicUsize::new(0),
+            consumers: TrackedConsumers::default(),
         }
     }
 }
 
 impl MemoryPool for GreedyMemoryPool {
+    fn register(&self, consumer: &MemoryConsumer) {
+        self.consumers.register(consumer)
+    }
+
+    fn unregister(&self, consumer: &MemoryConsumer) {
+        self.consumers.unregister(consumer)
+    }
+
     fn grow(&self, _reservation: &MemoryReservation, additional: usize) {
         self.used.fetch_add(additional, Ordering::Relaxed);
     }
@@ -88,6 +114,7 @@ impl MemoryPool for GreedyMemoryPool {
                     reservation,
                     additional,
                     self.pool_size.saturating_sub(used),
+                    &self.consumers,
                 )
             })?;
         Ok(())
@@ -96,6 +123,10 @@ impl MemoryPool for GreedyMemoryPool {
     fn reserved(&self) -> usize {
         self.used.load(Ordering::Relaxed)
     }
+
+    fn consumers(&self) -> Vec<MemoryConsumerInfo> {
+        self.consumers.consumers()
+    }
 }
 
 /// A [`MemoryPool`] that prevents spillable reservations from using more than
@@ -126,6 +157,8 @@ pub struct FairSpillPool {
     pool_size: usize,
 
     state: Mutex<FairSpillPoolState>,
+
+    consumers: TrackedConsumers,
 }
 
 #[derive(Debug)]
@@ -151,6 +184,7 @@ impl FairSpillPool {
                 spillable: 0,
                 unspillable: 0,
             }),
+            consumers: TrackedConsumers::default(),
         }
     }
 }
@@ -160,6 +194,7 @@ impl MemoryPool for FairSpillPool {
         if consumer.can_spill {
             self.state.lock().num_spill += 1;
         }
+        self.consumers.register(consumer)
     }
 
     fn unregister(&self, consumer: &MemoryConsumer) {
@@ -167,6 +202,7 @@ impl MemoryPool for FairSpillPool {
             let mut state = self.state.lock();
             state.num_spill = state.num_spill.checked_sub(1).unwrap();
         }
+        self.consumers.unregister(consumer)
     }
 
     fn grow(&self, reservation: &MemoryReservation, additional: usize) {
@@ -203,6 +239,7 @@ impl MemoryPool for FairSpillPool {
                         reservation,
                         additional,
                         available,
+                        &self.consumers,
                     ));
                 }
                 state.spillable += additional;
@@ -217,6 +254,7 @@ impl MemoryPool for FairSpillPool {
                         reservation,
                         additional,
                         available,
+                        &self.consumers,
                     ));
                 }
                 state.unspillable += additional;
@@ -229,14 +267,22 @@ impl MemoryPool for FairSpillPool {
         let state = self.state.lock();
         state.spillable + state.unspillable
     }
+
+    fn consumers(&self) -> Vec<MemoryConsumerInfo> {
+        self.consumers.consumers()
+    }
 }
 
+/// Returns an error for failing to grow `reservation` by `additional` bytes,
+/// listing the largest of `consumers`
 fn insufficient_capacity_err(
     reservation: &MemoryReservation,
     additional: usize,
     available: usize,
+    consumers: &TrackedConsumers,
 ) -> DataFusionError {
-    DataFusionError::ResourcesExhausted(format!("Failed to allocate additional {} bytes for {} with {} bytes already allocated - maximum available is {}", additional, reservation.registration.consumer.name, reservation.size, available))
+    let consumers = describe_consumers(&consumers.top(TOP_CONSUMERS), None);
+    DataFusionError::ResourcesExhausted(format!("Failed to allocate additional {} bytes for {} with {} bytes already allocated - maximum available is {}. {}", additional, reservation.registration.consumer.name, reservation.size, available, consumers))
 }
 
 #[cfg(test)]
@@ -262,10 +308,10 @@ mod tests {
         assert_eq!(pool.reserved(), 4000);
 
         let err = r2.try_grow(1).unwrap_err().strip_backtrace();
-        assert_eq!(err, "Resources exhausted: Failed to allocate additional 1 bytes for r2 with 2000 bytes already allocated - maximum available is 0");
+        assert_eq!(err, "Resources exhausted: Failed to allocate additional 1 bytes for r2 with 2000 bytes already allocated - maximum available is 0. Top memory consumers: r2: 2000.0 B (peak 2000.0 B), unspillable: 2000.0 B (peak 2000.0 B)");
 
         let err = r2.try_grow(1).unwrap_err().strip_backtrace();
-        assert_eq!(err, "Resources exhausted: Failed to allocate additional 1 bytes for r2 with 2000 bytes already allocated - maximum available is 0");
+        assert_eq!(err, "Resources exhausted: Failed to allocate additional 1 bytes for r2 with 2000 bytes already allocated - maximum available is 0. Top memory consumers: r2: 2000.0 B (peak 2000.0 B), unspillable: 2000.0 B (peak 2000.0 B)");
 
         r1.shrink(1990);
         r2.shrink(2000);
@@ -290,12 +336,12 @@ mod tests {
             .register(&pool);
 
         let err = r3.try_grow(70).unwrap_err().strip_backtrace();
-        assert_eq!(err, "Resources exhausted: Failed to allocate additional 70 bytes for r3 with 0 bytes already allocated - maximum available is 40");
+        assert_eq!(err, "Resources exhausted: Failed to allocate additional 70 bytes for r3 with 0 bytes already allocated - maximum available is 40. Top memory consumers: unspillable: 20.0 B (peak 2000.0 B), r2: 10.0 B (peak 2000.0 B)");
 
         //Shrinking r2 to zero doesn't allow a3 to allocate more than 45
         r2.free();
         let err = r3.try_grow(70).unwrap_err().strip_backtrace();
-        assert_eq!(err, "Resources exhausted: Failed to allocate additional 70 bytes for r3 with 0 bytes already allocated - maximum available is 40");
+        assert_eq!(err, "Resources exhausted: Failed to allocate additional 70 bytes for r3 with 0 bytes already allocated - maximum available is 40. Top memory consumers: unspillable: 20.0 B (peak 2000.0 B)");
 
         // But dropping r2 does
         drop(r2);
@@ -308,6 +354,6 @@ mod tests {
 
         let mut r4 = MemoryConsumer::new("s4").register(&pool);
         let err = r4.try_grow(30).unwrap_err().strip_backtrace();
-        assert_eq!(err, "Resources exhausted: Failed to allocate additional 30 bytes for s4 with 0 bytes already allocated - maximum available is 20");
+        assert_eq!(err, "Resources exhausted: Failed to allocate additional 30 bytes for s4 with 0 bytes already allocated - maximum available is 20. Top memory consumers: r3: 80.0 B (peak 80.0 B)");
     }
 }
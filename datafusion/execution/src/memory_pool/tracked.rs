@@ -0,0 +1,180 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::memory_pool::{human_readable_size, ConsumerUsage, MemoryConsumer};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+
+/// The number of consumers listed in "Resources exhausted" errors
+pub(crate) const TOP_CONSUMERS: usize = 3;
+
+/// A snapshot of the memory usage of a [`MemoryConsumer`], as returned by
+/// [`MemoryPool::consumers`]
+///
+/// [`MemoryPool::consumers`]: crate::memory_pool::MemoryPool::consumers
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MemoryConsumerInfo {
+    /// The id of the consumer, see [`MemoryConsumer::id`]
+    pub id: usize,
+    /// The name of the consumer
+    pub name: String,
+    /// Whether the consumer can spill to disk
+    pub can_spill: bool,
+    /// The memory currently reserved by the consumer, in bytes
+    pub reserved: usize,
+    /// The largest amount of memory reserved by the consumer, in bytes
+    pub peak: usize,
+}
+
+impl Display for MemoryConsumerInfo {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "{}: {} (peak {})",
+            self.name,
+            human_readable_size(self.reserved),
+            human_readable_size(self.peak)
+        )
+    }
+}
+
+/// Keeps track of the [`MemoryConsumer`]s registered with a [`MemoryPool`],
+/// for implementing [`MemoryPool::consumers`].
+///
+/// The memory usage of each consumer is updated by its reservations, so
+/// only registering and unregistering consumers requires a lock.
+///
+/// [`MemoryPool`]: crate::memory_pool::MemoryPool
+/// [`MemoryPool::consumers`]: crate::memory_pool::MemoryPool::consumers
+#[derive(Debug, Default)]
+pub struct TrackedConsumers {
+    consumers: Mutex<HashMap<usize, TrackedConsumer>>,
+}
+
+#[derive(Debug)]
+struct TrackedConsumer {
+    name: String,
+    can_spill: bool,
+    usage: Arc<ConsumerUsage>,
+}
+
+impl TrackedConsumers {
+    /// Start tracking `consumer`
+    pub fn register(&self, consumer: &MemoryConsumer) {
+        self.consumers.lock().insert(
+            consumer.id(),
+            TrackedConsumer {
+                name: consumer.name().to_string(),
+                can_spill: consumer.can_spill(),
+                usage: Arc::clone(&consumer.usage),
+            },
+        );
+    }
+
+    /// Stop tracking `consumer`
+    pub fn unregister(&self, consumer: &MemoryConsumer) {
+        self.consumers.lock().remove(&consumer.id());
+    }
+
+    /// Returns the tracked consumers, largest current reservation first
+    pub fn consumers(&self) -> Vec<MemoryConsumerInfo> {
+        let mut consumers: Vec<_> = self
+            .consumers
+            .lock()
+            .iter()
+            .map(|(id, c)| MemoryConsumerInfo {
+                id: *id,
+                name: c.name.clone(),
+                can_spill: c.can_spill,
+                reserved: c.usage.reserved(),
+                peak: c.usage.peak(),
+            })
+            .collect();
+        consumers.sort_by(|a, b| {
+            b.reserved
+                .cmp(&a.reserved)
+                .then_with(|| a.name.cmp(&b.name))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        consumers
+    }
+
+    /// Returns the `n` largest consumers currently reserving memory
+    pub fn top(&self, n: usize) -> Vec<MemoryConsumerInfo> {
+        self.consumers()
+            .into_iter()
+            .filter(|c| c.reserved > 0)
+            .take(n)
+            .collect()
+    }
+}
+
+/// Describes `consumers` of the pool named `pool`, if any, for inclusion in
+/// error messages
+pub(crate) fn describe_consumers(
+    consumers: &[MemoryConsumerInfo],
+    pool: Option<&str>,
+) -> String {
+    let of_pool = pool.map(|p| format!(" of pool {p}")).unwrap_or_default();
+    if consumers.is_empty() {
+        return format!("No memory reserved by consumers{of_pool}");
+    }
+
+    let consumers = consumers
+        .iter()
+        .map(|c| c.to_string())
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("Top memory consumers{of_pool}: {consumers}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory_pool::{GreedyMemoryPool, MemoryPool};
+
+    #[test]
+    fn test_consumers() {
+        let pool = Arc::new(GreedyMemoryPool::new(100)) as Arc<dyn MemoryPool>;
+
+        let mut r1 = MemoryConsumer::new("r1").register(&pool);
+        let mut r2 = MemoryConsumer::new("r2")
+            .with_can_spill(true)
+            .register(&pool);
+        r1.try_grow(30).unwrap();
+        r2.try_grow(50).unwrap();
+        r2.shrink(40);
+
+        // splits are accounted to the same consumer
+        let mut r1_split = r1.split(10);
+        r1_split.grow(5);
+
+        let consumers = pool.consumers();
+        let summary = consumers
+            .iter()
+            .map(|c| (c.name.as_str(), c.can_spill, c.reserved, c.peak))
+            .collect::<Vec<_>>();
+        assert_eq!(summary, [("r1", false, 35, 35), ("r2", true, 10, 50)]);
+
+        drop(r2);
+        let consumers = pool.consumers();
+        assert_eq!(consumers.len(), 1);
+        assert_eq!(consumers[0].to_string(), "r1: 35.0 B (peak 35.0 B)");
+    }
+}
@@ -27,7 +27,9 @@ use datafusion_common::{
 use datafusion_expr::{AggregateUDF, ScalarUDF, WindowUDF};
 
 use crate::{
+    cancellation::CancellationToken,
     config::SessionConfig,
+    disk_manager::SpillUsage,
     memory_pool::MemoryPool,
     registry::FunctionRegistry,
     runtime_env::{RuntimeConfig, RuntimeEnv},
@@ -56,6 +58,10 @@ pub struct TaskContext {
     window_functions: HashMap<String, Arc<WindowUDF>>,
     /// Runtime environment associated with this task context
     runtime: Arc<RuntimeEnv>,
+    /// Token used to cancel the query this task belongs to
+    cancellation_token: CancellationToken,
+    /// Disk space used by the spill files of the query this task belongs to
+    spill_usage: SpillUsage,
 }
 
 impl Default for TaskContext {
@@ -72,6 +78,8 @@ impl Default for TaskContext {
             aggregate_functions: HashMap::new(),
             window_functions: HashMap::new(),
             runtime: Arc::new(runtime),
+            cancellation_token: CancellationToken::new(),
+            spill_usage: SpillUsage::new(),
         }
     }
 }
@@ -99,6 +107,8 @@ impl TaskContext {
             aggregate_functions,
             window_functions,
             runtime,
+            cancellation_token: CancellationToken::new(),
+            spill_usage: SpillUsage::new(),
         }
     }
 
@@ -160,6 +170,31 @@ impl TaskContext {
         self.runtime.clone()
     }
 
+    /// Return the [`CancellationToken`] of the query this task belongs to
+    pub fn cancellation_token(&self) -> &CancellationToken {
+        &self.cancellation_token
+    }
+
+    /// Return the [`SpillUsage`] of the query this task belongs to
+    pub fn spill_usage(&self) -> &SpillUsage {
+        &self.spill_usage
+    }
+
+    /// Return the user defined scalar functions registered with this [TaskContext]
+    pub fn scalar_functions(&self) -> &HashMap<String, Arc<ScalarUDF>> {
+        &self.scalar_functions
+    }
+
+    /// Return the user defined aggregate functions registered with this [TaskContext]
+    pub fn aggregate_functions(&self) -> &HashMap<String, Arc<AggregateUDF>> {
+        &self.aggregate_functions
+    }
+
+    /// Return the user defined window functions registered with this [TaskContext]
+    pub fn window_functions(&self) -> &HashMap<String, Arc<WindowUDF>> {
+        &self.window_functions
+    }
+
     /// Update the [`ConfigOptions`]
     pub fn with_session_config(mut self, session_config: SessionConfig) -> Self {
         self.session_config = session_config;
@@ -171,6 +206,18 @@ impl TaskContext {
         self.runtime = runtime;
         self
     }
+
+    /// Update the [`CancellationToken`]
+    pub fn with_cancellation_token(mut self, token: CancellationToken) -> Self {
+        self.cancellation_token = token;
+        self
+    }
+
+    /// Update the [`SpillUsage`]
+    pub fn with_spill_usage(mut self, spill_usage: SpillUsage) -> Self {
+        self.spill_usage = spill_usage;
+        self
+    }
 }
 
 impl FunctionRegistry for TaskContext {
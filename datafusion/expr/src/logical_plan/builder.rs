@@ -1061,6 +1061,19 @@ impl LogicalPlanBuilder {
             options,
         )?))
     }
+
+    /// Unnest the given columns given [`UnnestOptions`]
+    ///
+    /// See [`unnest_columns_with_options`] for how the columns are unnested
+    pub fn unnest_columns_with_options(
+        self,
+        columns: Vec<Column>,
+        options: UnnestOptions,
+    ) -> Result<Self> {
+        Ok(Self::from(unnest_columns_with_options(
+            self.plan, columns, options,
+        )?))
+    }
 }
 
 /// Creates a schema for a join operation.
@@ -1415,37 +1428,123 @@ pub fn unnest_with_options(
     column: Column,
     options: UnnestOptions,
 ) -> Result<LogicalPlan> {
-    let unnest_field = input.schema().field_from_column(&column)?;
-
-    // Extract the type of the nested field in the list.
-    let unnested_field = match unnest_field.data_type() {
-        DataType::List(field)
-        | DataType::FixedSizeList(field, _)
-        | DataType::LargeList(field) => DFField::new(
-            unnest_field.qualifier().cloned(),
-            unnest_field.name(),
-            field.data_type().clone(),
-            unnest_field.is_nullable(),
-        ),
-        _ => {
-            // If the unnest field is not a list type return the input plan.
-            return Ok(input);
+    unnest_columns_with_options(input, vec![column], options)
+}
+
+/// Create a plan unnesting several columns with options
+///
+/// The list columns are unnested together by a single [`LogicalPlan::Unnest`],
+/// zipping their values, followed by more [`LogicalPlan::Unnest`]s for the
+/// lists of lists if [`UnnestOptions::recursive`] is set. The struct columns
+/// are replaced by one column per field. Columns of other types are ignored.
+pub fn unnest_columns_with_options(
+    input: LogicalPlan,
+    columns: Vec<Column>,
+    options: UnnestOptions,
+) -> Result<LogicalPlan> {
+    let mut list_columns = vec![];
+    let mut struct_columns = vec![];
+    for column in columns {
+        let field = input.schema().field_from_column(&column)?;
+        match field.data_type() {
+            DataType::List(_)
+            | DataType::FixedSizeList(_, _)
+            | DataType::LargeList(_) => list_columns.push(field.qualified_column()),
+            DataType::Struct(_) => struct_columns.push(field.qualified_column()),
+            // Other columns are not nested and left as they are
+            _ => {}
         }
-    };
+    }
+
+    let mut plan = input;
+    if !list_columns.is_empty() {
+        plan = build_unnest(plan, list_columns.clone(), options.clone())?;
+        if options.recursive {
+            // The ordinality only refers to the outermost lists
+            let nested_options = UnnestOptions {
+                ordinality: None,
+                ..options
+            };
+            loop {
+                let nested_columns = list_columns
+                    .iter()
+                    .filter(|column| {
+                        plan.schema()
+                            .field_from_column(column)
+                            .map(|field| {
+                                matches!(
+                                    field.data_type(),
+                                    DataType::List(_)
+                                        | DataType::FixedSizeList(_, _)
+                                        | DataType::LargeList(_)
+                                )
+                            })
+                            .unwrap_or(false)
+                    })
+                    .cloned()
+                    .collect::<Vec<_>>();
+                if nested_columns.is_empty() {
+                    break;
+                }
+                plan = build_unnest(plan, nested_columns, nested_options.clone())?;
+            }
+        }
+    }
+
+    if struct_columns.is_empty() {
+        return Ok(plan);
+    }
+
+    // Replace each struct column with its fields
+    let mut exprs = vec![];
+    for field in plan.schema().fields() {
+        let column = field.qualified_column();
+        match field.data_type() {
+            DataType::Struct(fields) if struct_columns.contains(&column) => {
+                exprs.extend(fields.iter().map(|f| {
+                    Expr::Column(column.clone()).field(f.name()).alias(f.name())
+                }));
+            }
+            _ => exprs.push(Expr::Column(column)),
+        }
+    }
+    LogicalPlanBuilder::from(plan).project(exprs)?.build()
+}
 
-    // Update the schema with the unnest column type changed to contain the nested type.
+/// Create a single [`LogicalPlan::Unnest`] zipping the list `columns`
+pub(crate) fn build_unnest(
+    input: LogicalPlan,
+    columns: Vec<Column>,
+    options: UnnestOptions,
+) -> Result<LogicalPlan> {
     let input_schema = input.schema();
-    let fields = input_schema
-        .fields()
-        .iter()
-        .map(|f| {
-            if f == unnest_field {
-                unnested_field.clone()
-            } else {
-                f.clone()
+    let mut fields = input_schema.fields().clone();
+    for column in &columns {
+        let index = input_schema.index_of_column(column)?;
+        let unnest_field = &input_schema.fields()[index];
+
+        // Extract the type of the nested field in the list.
+        let unnested_field = match unnest_field.data_type() {
+            DataType::List(field)
+            | DataType::FixedSizeList(field, _)
+            | DataType::LargeList(field) => DFField::new(
+                unnest_field.qualifier().cloned(),
+                unnest_field.name(),
+                field.data_type().clone(),
+                // shorter lists are padded with nulls
+                unnest_field.is_nullable() || columns.len() > 1,
+            ),
+            data_type => {
+                return plan_err!("Can not unnest column {column} of type {data_type}")
             }
-        })
-        .collect::<Vec<_>>();
+        };
+        // Update the schema with the unnest column type changed to contain
+        // the nested type.
+        fields[index] = unnested_field;
+    }
+    if let Some(ordinality) = &options.ordinality {
+        fields.push(DFField::new_unqualified(ordinality, DataType::UInt64, true));
+    }
 
     let schema = Arc::new(
         DFSchema::new_with_metadata(fields, input_schema.metadata().clone())?
@@ -1455,7 +1554,7 @@ pub fn unnest_with_options(
 
     Ok(LogicalPlan::Unnest(Unnest {
         input: Arc::new(input),
-        column: unnested_field.qualified_column(),
+        columns,
         schema,
         options,
     }))
@@ -1933,6 +2032,70 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn plan_builder_unnest_columns() -> Result<()> {
+        // Unnesting two lists together, with ordinality
+        let options = UnnestOptions::new().with_ordinality("ordinality");
+        let plan = nested_table_scan("test_table")?
+            .unnest_columns_with_options(
+                vec!["strings".into(), "structs".into(), "scalar".into()],
+                options,
+            )?
+            .build()?;
+
+        let expected = "\
+        Unnest: test_table.strings, test_table.structs with ordinality as ordinality\
+        \n  TableScan: test_table";
+        assert_eq!(expected, format!("{plan:?}"));
+
+        let field = plan.schema().field_with_unqualified_name("ordinality")?;
+        assert_eq!(&DataType::UInt64, field.data_type());
+        // The shorter lists are padded with nulls
+        let field = plan
+            .schema()
+            .field_with_name(Some(&TableReference::bare("test_table")), "strings")?;
+        assert!(field.is_nullable());
+
+        // Unnesting the list of structs, then the structs into their fields
+        let plan = nested_table_scan("test_table")?
+            .unnest_column("structs")?
+            .unnest_columns_with_options(vec!["structs".into()], UnnestOptions::new())?
+            .build()?;
+
+        let expected = "\
+        Projection: test_table.scalar, test_table.strings, (test_table.structs)[a] AS a, (test_table.structs)[b] AS b\
+        \n  Unnest: test_table.structs\
+        \n    TableScan: test_table";
+        assert_eq!(expected, format!("{plan:?}"));
+        assert_eq!(
+            &DataType::UInt32,
+            plan.schema().field_with_unqualified_name("b")?.data_type()
+        );
+
+        // Unnesting a list of lists recursively
+        let matrix_field = Field::new_list(
+            "matrix",
+            Field::new_list("item", Field::new("item", DataType::UInt32, true), true),
+            true,
+        );
+        let schema = Schema::new(vec![matrix_field]);
+        let plan = table_scan(Some("test_table"), &schema, None)?
+            .unnest_columns_with_options(
+                vec!["matrix".into()],
+                UnnestOptions::new().with_recursive(true),
+            )?
+            .build()?;
+
+        let expected = "\
+        Unnest: test_table.matrix\
+        \n  Unnest: test_table.matrix\
+        \n    TableScan: test_table";
+        assert_eq!(expected, format!("{plan:?}"));
+        assert_eq!(&DataType::UInt32, plan.schema().field(0).data_type());
+
+        Ok(())
+    }
+
     fn nested_table_scan(table_name: &str) -> Result<LogicalPlanBuilder> {
         // Create a schema with a scalar field, a list of strings, and a list of structs.
         let struct_field = Field::new_struct(
@@ -48,6 +48,8 @@ pub enum DdlStatement {
     DropView(DropView),
     /// Drops a catalog schema
     DropCatalogSchema(DropCatalogSchema),
+    /// Collects the statistics of a table.
+    AnalyzeTable(AnalyzeTable),
 }
 
 impl DdlStatement {
@@ -66,6 +68,7 @@ impl DdlStatement {
             DdlStatement::DropTable(DropTable { schema, .. }) => schema,
             DdlStatement::DropView(DropView { schema, .. }) => schema,
             DdlStatement::DropCatalogSchema(DropCatalogSchema { schema, .. }) => schema,
+            DdlStatement::AnalyzeTable(AnalyzeTable { schema, .. }) => schema,
         }
     }
 
@@ -81,6 +84,7 @@ impl DdlStatement {
             DdlStatement::DropTable(_) => "DropTable",
             DdlStatement::DropView(_) => "DropView",
             DdlStatement::DropCatalogSchema(_) => "DropCatalogSchema",
+            DdlStatement::AnalyzeTable(_) => "AnalyzeTable",
         }
     }
 
@@ -97,6 +101,7 @@ impl DdlStatement {
             DdlStatement::DropTable(_) => vec![],
             DdlStatement::DropView(_) => vec![],
             DdlStatement::DropCatalogSchema(_) => vec![],
+            DdlStatement::AnalyzeTable(_) => vec![],
         }
     }
 
@@ -155,6 +160,16 @@ impl DdlStatement {
                     }) => {
                         write!(f, "DropCatalogSchema: {name:?} if not exist:={if_exists} cascade:={cascade}")
                     }
+                    DdlStatement::AnalyzeTable(AnalyzeTable {
+                        name, columns, ..
+                    }) => match columns {
+                        Some(columns) => write!(
+                            f,
+                            "AnalyzeTable: {name:?} columns:=[{}]",
+                            columns.join(", ")
+                        ),
+                        None => write!(f, "AnalyzeTable: {name:?}"),
+                    },
                 }
             }
         }
@@ -296,3 +311,14 @@ pub struct DropCatalogSchema {
     /// Dummy schema
     pub schema: DFSchemaRef,
 }
+
+/// Collects the statistics of a table and stores them in its schema.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct AnalyzeTable {
+    /// The table name
+    pub name: OwnedTableReference,
+    /// The columns to collect statistics for, or `None` for all columns
+    pub columns: Option<Vec<String>>,
+    /// Dummy schema
+    pub schema: DFSchemaRef,
+}
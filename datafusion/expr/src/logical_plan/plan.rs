@@ -20,6 +20,7 @@
 use crate::dml::CopyOptions;
 use crate::expr::{Alias, Exists, InSubquery, Placeholder};
 use crate::expr_rewriter::create_col_from_scalar_expr;
+use crate::logical_plan::builder::build_unnest;
 use crate::logical_plan::display::{GraphvizVisitor, IndentVisitor};
 use crate::logical_plan::extension::UserDefinedLogicalNode;
 use crate::logical_plan::{DmlStatement, Statement};
@@ -149,7 +150,7 @@ pub enum LogicalPlan {
     /// Describe the schema of the table. This is used to implement the
     /// SQL `DESCRIBE` command from MySQL.
     DescribeTable(DescribeTable),
-    /// Unnest a column that contains a nested list type such as an
+    /// Unnest columns that contain a nested list type such as an
     /// ARRAY. This is used to implement SQL `UNNEST`
     Unnest(Unnest),
 }
@@ -357,9 +358,9 @@ impl LogicalPlan {
             LogicalPlan::TableScan(TableScan { filters, .. }) => {
                 filters.iter().try_for_each(f)
             }
-            LogicalPlan::Unnest(Unnest { column, .. }) => {
-                f(&Expr::Column(column.clone()))
-            }
+            LogicalPlan::Unnest(Unnest { columns, .. }) => columns
+                .iter()
+                .try_for_each(|column| f(&Expr::Column(column.clone()))),
             // plans without expressions
             LogicalPlan::EmptyRelation(_)
             | LogicalPlan::Subquery(_)
@@ -877,45 +878,10 @@ impl LogicalPlan {
             }
             LogicalPlan::DescribeTable(_) => Ok(self.clone()),
             LogicalPlan::Unnest(Unnest {
-                column,
-                schema,
-                options,
-                ..
+                columns, options, ..
             }) => {
-                // Update schema with unnested column type.
-                let input = Arc::new(inputs[0].clone());
-                let nested_field = input.schema().field_from_column(column)?;
-                let unnested_field = schema.field_from_column(column)?;
-                let fields = input
-                    .schema()
-                    .fields()
-                    .iter()
-                    .map(|f| {
-                        if f == nested_field {
-                            unnested_field.clone()
-                        } else {
-                            f.clone()
-                        }
-                    })
-                    .collect::<Vec<_>>();
-
-                let schema = Arc::new(
-                    DFSchema::new_with_metadata(
-                        fields,
-                        input.schema().metadata().clone(),
-                    )?
-                    // We can use the existing functional dependencies as is:
-                    .with_functional_dependencies(
-                        input.schema().functional_dependencies().clone(),
-                    ),
-                );
-
-                Ok(LogicalPlan::Unnest(Unnest {
-                    input,
-                    column: column.clone(),
-                    schema,
-                    options: options.clone(),
-                }))
+                // Update schema with unnested column types.
+                build_unnest(inputs[0].clone(), columns.clone(), options.clone())
             }
         }
     }
@@ -1645,8 +1611,18 @@ impl LogicalPlan {
                     LogicalPlan::DescribeTable(DescribeTable { .. }) => {
                         write!(f, "DescribeTable")
                     }
-                    LogicalPlan::Unnest(Unnest { column, .. }) => {
-                        write!(f, "Unnest: {column}")
+                    LogicalPlan::Unnest(Unnest {
+                        columns, options, ..
+                    }) => {
+                        let columns = columns
+                            .iter()
+                            .map(|column| column.to_string())
+                            .collect::<Vec<_>>();
+                        write!(f, "Unnest: {}", columns.join(", "))?;
+                        if let Some(ordinality) = &options.ordinality {
+                            write!(f, " with ordinality as {ordinality}")?;
+                        }
+                        Ok(())
                     }
                 }
             }
@@ -2273,15 +2249,16 @@ pub enum Partitioning {
     DistributeBy(Vec<Expr>),
 }
 
-/// Unnest a column that contains a nested list type. See
-/// [`UnnestOptions`] for more details.
+/// Unnest columns that contain a nested list type, zipping their values.
+/// See [`UnnestOptions`] for more details.
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Unnest {
     /// The incoming logical plan
     pub input: Arc<LogicalPlan>,
-    /// The column to unnest
-    pub column: Column,
-    /// The output schema, containing the unnested field column.
+    /// The columns to unnest
+    pub columns: Vec<Column>,
+    /// The output schema, containing the unnested field columns and the
+    /// ordinality column, if any.
     pub schema: DFSchemaRef,
     /// Options
     pub options: UnnestOptions,
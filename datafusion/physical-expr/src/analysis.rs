@@ -25,7 +25,8 @@ use crate::PhysicalExpr;
 
 use arrow::datatypes::Schema;
 use datafusion_common::{
-    internal_err, ColumnStatistics, DataFusionError, Result, ScalarValue,
+    internal_err, ColumnStatistics, DataFusionError, Histogram, Result, ScalarValue,
+    ValueFrequency,
 };
 
 use std::fmt::Debug;
@@ -83,6 +84,10 @@ pub struct ExprBoundaries {
     pub interval: Interval,
     /// Maximum number of distinct values this expression can produce, if known.
     pub distinct_count: Option<usize>,
+    /// Equi-depth histogram of the values of this expression, if known.
+    pub histogram: Option<Histogram>,
+    /// Most common values of this expression and their frequencies, if known.
+    pub most_common_values: Option<Vec<ValueFrequency>>,
 }
 
 impl ExprBoundaries {
@@ -92,15 +97,25 @@ impl ExprBoundaries {
             column: Column::new(&col, index),
             interval: Interval::new(
                 IntervalBound::new(
-                    stats.min_value.clone().unwrap_or(ScalarValue::Null),
+                    stats
+                        .min_value
+                        .get_value()
+                        .cloned()
+                        .unwrap_or(ScalarValue::Null),
                     false,
                 ),
                 IntervalBound::new(
-                    stats.max_value.clone().unwrap_or(ScalarValue::Null),
+                    stats
+                        .max_value
+                        .get_value()
+                        .cloned()
+                        .unwrap_or(ScalarValue::Null),
                     false,
                 ),
             ),
-            distinct_count: stats.distinct_count,
+            distinct_count: stats.distinct_count.get_value().copied(),
+            histogram: stats.histogram.clone(),
+            most_common_values: stats.most_common_values.clone(),
         }
     }
 }
@@ -110,7 +125,9 @@ impl ExprBoundaries {
 /// The function accepts boundaries of the input columns in the `context` parameter.
 /// It then tries to tighten these boundaries based on the provided `expr`.
 /// The resulting selectivity value is calculated by comparing the initial and final boundaries.
-/// The computation assumes that the data within the column is uniformly distributed and not sorted.
+/// Columns with a histogram are estimated from their value distribution; for
+/// the others, the computation assumes that the data within the column is
+/// uniformly distributed and not sorted.
 ///
 /// # Arguments
 ///
@@ -224,17 +241,40 @@ fn calculate_selectivity(
         (ScalarValue::Boolean(Some(true)), ScalarValue::Boolean(Some(true))) => Ok(1.0),
         (ScalarValue::Boolean(Some(false)), ScalarValue::Boolean(Some(false))) => Ok(0.0),
         _ => {
-            // Since the intervals are assumed uniform and the values
-            // are not correlated, we need to multiply the selectivities
-            // of multiple columns to get the overall selectivity.
+            // Since the values of different columns are assumed not to be
+            // correlated, we need to multiply the selectivities of multiple
+            // columns to get the overall selectivity.
             target_boundaries.iter().enumerate().try_fold(
                 1.0,
                 |acc, (i, ExprBoundaries { interval, .. })| {
-                    let temp =
-                        cardinality_ratio(&initial_boundaries[i].interval, interval)?;
+                    let initial = &initial_boundaries[i];
+                    let temp = match histogram_ratio(initial, interval) {
+                        Some(ratio) => ratio,
+                        None => cardinality_ratio(&initial.interval, interval)?,
+                    };
                     Ok(acc * temp)
                 },
             )
         }
     }
 }
+
+/// Estimates the ratio of the values of a column that fall within
+/// `final_interval` from the histogram and the most common values of its
+/// initial boundaries. Returns `None` if the column has no histogram.
+fn histogram_ratio(initial: &ExprBoundaries, final_interval: &Interval) -> Option<f64> {
+    let histogram = initial.histogram.as_ref()?;
+    let num_rows = histogram.num_rows();
+    if num_rows == 0 {
+        return None;
+    }
+    let interval = final_interval.clone().close_bounds();
+    let (lower, upper) = (&interval.lower.value, &interval.upper.value);
+    let rows = if !lower.is_null() && lower == upper {
+        let most_common_values = initial.most_common_values.as_deref().unwrap_or(&[]);
+        histogram.value_rows(lower, most_common_values)
+    } else {
+        histogram.range_rows(lower, upper)
+    };
+    Some((rows / num_rows as f64).clamp(0.0, 1.0))
+}
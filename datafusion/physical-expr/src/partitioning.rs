@@ -22,7 +22,7 @@ use std::sync::Arc;
 
 use crate::{
     expr_list_eq_strict_order, normalize_expr_with_equivalence_properties,
-    EquivalenceProperties, PhysicalExpr,
+    EquivalenceProperties, PhysicalExpr, PhysicalSortExpr,
 };
 
 /// Partitioning schemes supported by operators.
@@ -33,6 +33,12 @@ pub enum Partitioning {
     /// Allocate rows based on a hash of one of more expressions and the specified number of
     /// partitions
     Hash(Vec<Arc<dyn PhysicalExpr>>, usize),
+    /// Allocate rows to the specified number of partitions based on ranges of the
+    /// sort keys, so that every row of partition `i` sorts before every row of
+    /// partition `i + 1`. The split points between the ranges are sampled from the
+    /// input when the partitioning is executed, and rows with equal sort keys are
+    /// always allocated to the same partition
+    Range(Vec<PhysicalSortExpr>, usize),
     /// Unknown partitioning scheme with a known number of partitions
     UnknownPartitioning(usize),
 }
@@ -49,6 +55,14 @@ impl fmt::Display for Partitioning {
                     .join(", ");
                 write!(f, "Hash([{phy_exprs_str}], {size})")
             }
+            Partitioning::Range(sort_exprs, size) => {
+                let sort_exprs_str = sort_exprs
+                    .iter()
+                    .map(|e| format!("{e}"))
+                    .collect::<Vec<String>>()
+                    .join(", ");
+                write!(f, "Range([{sort_exprs_str}], {size})")
+            }
             Partitioning::UnknownPartitioning(size) => {
                 write!(f, "UnknownPartitioning({size})")
             }
@@ -60,7 +74,7 @@ impl Partitioning {
     pub fn partition_count(&self) -> usize {
         use Partitioning::*;
         match self {
-            RoundRobinBatch(n) | Hash(_, n) | UnknownPartitioning(n) => *n,
+            RoundRobinBatch(n) | Hash(_, n) | Range(_, n) | UnknownPartitioning(n) => *n,
         }
     }
 
@@ -80,51 +94,60 @@ impl Partitioning {
                     // and hash functions in the system are the same. In future if we plan to support storage partition-wise joins,
                     // then we need to have the partition count and hash functions validation.
                     Partitioning::Hash(partition_exprs, _) => {
-                        let fast_match =
-                            expr_list_eq_strict_order(&required_exprs, partition_exprs);
-                        // If the required exprs do not match, need to leverage the eq_properties provided by the child
-                        // and normalize both exprs based on the eq_properties
-                        if !fast_match {
-                            let eq_properties = equal_properties();
-                            let eq_classes = eq_properties.classes();
-                            if !eq_classes.is_empty() {
-                                let normalized_required_exprs = required_exprs
-                                    .iter()
-                                    .map(|e| {
-                                        normalize_expr_with_equivalence_properties(
-                                            e.clone(),
-                                            eq_classes,
-                                        )
-                                    })
-                                    .collect::<Vec<_>>();
-                                let normalized_partition_exprs = partition_exprs
-                                    .iter()
-                                    .map(|e| {
-                                        normalize_expr_with_equivalence_properties(
-                                            e.clone(),
-                                            eq_classes,
-                                        )
-                                    })
-                                    .collect::<Vec<_>>();
-                                expr_list_eq_strict_order(
-                                    &normalized_required_exprs,
-                                    &normalized_partition_exprs,
-                                )
-                            } else {
-                                fast_match
-                            }
-                        } else {
-                            fast_match
-                        }
+                        exprs_satisfy(&required_exprs, partition_exprs, equal_properties)
                     }
                     _ => false,
                 }
             }
+            Distribution::RangePartitioned(_) if self.partition_count() == 1 => true,
+            Distribution::RangePartitioned(required_exprs) => match self {
+                Partitioning::Range(sort_exprs, _) => required_exprs == *sort_exprs,
+                _ => false,
+            },
             _ => false,
         }
     }
 }
 
+/// Returns true if `partition_exprs` are the same as `required_exprs`, either
+/// directly or after normalizing both with the equivalence properties
+/// provided by `equal_properties`
+fn exprs_satisfy<F: FnOnce() -> EquivalenceProperties>(
+    required_exprs: &[Arc<dyn PhysicalExpr>],
+    partition_exprs: &[Arc<dyn PhysicalExpr>],
+    equal_properties: F,
+) -> bool {
+    let fast_match = expr_list_eq_strict_order(required_exprs, partition_exprs);
+    // If the required exprs do not match, need to leverage the eq_properties provided by the child
+    // and normalize both exprs based on the eq_properties
+    if !fast_match {
+        let eq_properties = equal_properties();
+        let eq_classes = eq_properties.classes();
+        if !eq_classes.is_empty() {
+            let normalized_required_exprs = required_exprs
+                .iter()
+                .map(|e| {
+                    normalize_expr_with_equivalence_properties(e.clone(), eq_classes)
+                })
+                .collect::<Vec<_>>();
+            let normalized_partition_exprs = partition_exprs
+                .iter()
+                .map(|e| {
+                    normalize_expr_with_equivalence_properties(e.clone(), eq_classes)
+                })
+                .collect::<Vec<_>>();
+            expr_list_eq_strict_order(
+                &normalized_required_exprs,
+                &normalized_partition_exprs,
+            )
+        } else {
+            fast_match
+        }
+    } else {
+        fast_match
+    }
+}
+
 impl PartialEq for Partitioning {
     fn eq(&self, other: &Partitioning) -> bool {
         match (self, other) {
@@ -137,6 +160,10 @@ impl PartialEq for Partitioning {
             {
                 true
             }
+            (
+                Partitioning::Range(exprs1, count1),
+                Partitioning::Range(exprs2, count2),
+            ) if exprs1 == exprs2 && count1 == count2 => true,
             _ => false,
         }
     }
@@ -152,6 +179,9 @@ pub enum Distribution {
     /// Requires children to be distributed in such a way that the same
     /// values of the keys end up in the same partition
     HashPartitioned(Vec<Arc<dyn PhysicalExpr>>),
+    /// Requires children to be distributed in such a way that the partitions
+    /// cover consecutive ranges of the sort keys, in partition order
+    RangePartitioned(Vec<PhysicalSortExpr>),
 }
 
 impl Distribution {
@@ -165,6 +195,9 @@ impl Distribution {
             Distribution::HashPartitioned(expr) => {
                 Partitioning::Hash(expr.clone(), partition_count)
             }
+            Distribution::RangePartitioned(sort_exprs) => {
+                Partitioning::Range(sort_exprs.clone(), partition_count)
+            }
         }
     }
 }
@@ -174,6 +207,7 @@ mod tests {
     use crate::expressions::Column;
 
     use super::*;
+    use arrow::compute::SortOptions;
     use arrow::datatypes::DataType;
     use arrow::datatypes::Field;
     use arrow::datatypes::Schema;
@@ -202,6 +236,10 @@ mod tests {
             Distribution::UnspecifiedDistribution,
             Distribution::SinglePartition,
             Distribution::HashPartitioned(partition_exprs1.clone()),
+            Distribution::RangePartitioned(vec![PhysicalSortExpr {
+                expr: partition_exprs1[0].clone(),
+                options: SortOptions::default(),
+            }]),
         ];
 
         let single_partition = Partitioning::UnknownPartitioning(1);
@@ -239,9 +277,84 @@ mod tests {
                 Distribution::HashPartitioned(_) => {
                     assert_eq!(result, (false, false, false, true, false))
                 }
+                Distribution::RangePartitioned(_) => {
+                    assert_eq!(result, (true, false, false, false, false))
+                }
             }
         }
 
         Ok(())
     }
+
+    #[test]
+    fn range_partitioning_satisfy_distribution() -> Result<()> {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("column_1", DataType::Int64, false),
+            Field::new("column_2", DataType::Utf8, false),
+        ]));
+        let col_1: Arc<dyn PhysicalExpr> =
+            Arc::new(Column::new_with_schema("column_1", &schema)?);
+        let col_2: Arc<dyn PhysicalExpr> =
+            Arc::new(Column::new_with_schema("column_2", &schema)?);
+        let asc = |expr: &Arc<dyn PhysicalExpr>| PhysicalSortExpr {
+            expr: expr.clone(),
+            options: SortOptions::default(),
+        };
+        let desc = |expr: &Arc<dyn PhysicalExpr>| PhysicalSortExpr {
+            expr: expr.clone(),
+            options: SortOptions {
+                descending: true,
+                nulls_first: true,
+            },
+        };
+
+        let range_partition = Partitioning::Range(vec![asc(&col_1), asc(&col_2)], 10);
+        let eq_properties = || EquivalenceProperties::new(schema.clone());
+
+        let cases = vec![
+            (Distribution::UnspecifiedDistribution, true),
+            (Distribution::SinglePartition, false),
+            // each range repartition samples its own split points, so two
+            // range partitioned inputs are not co-partitioned
+            (
+                Distribution::HashPartitioned(vec![col_1.clone(), col_2.clone()]),
+                false,
+            ),
+            (
+                Distribution::HashPartitioned(vec![col_2.clone(), col_1.clone()]),
+                false,
+            ),
+            (
+                Distribution::RangePartitioned(vec![asc(&col_1), asc(&col_2)]),
+                true,
+            ),
+            (
+                Distribution::RangePartitioned(vec![asc(&col_1), desc(&col_2)]),
+                false,
+            ),
+            (Distribution::RangePartitioned(vec![asc(&col_1)]), false),
+        ];
+        for (distribution, expected) in cases {
+            assert_eq!(
+                range_partition.satisfy(distribution.clone(), eq_properties),
+                expected,
+                "{distribution:?}"
+            );
+        }
+
+        assert_eq!(
+            range_partition.to_string(),
+            "Range([column_1@0 ASC, column_2@1 ASC], 10)"
+        );
+        assert_eq!(
+            Distribution::RangePartitioned(vec![asc(&col_1)]).create_partitioning(4),
+            Partitioning::Range(vec![asc(&col_1)], 4)
+        );
+        assert_ne!(
+            range_partition,
+            Partitioning::Range(vec![asc(&col_1), asc(&col_2)], 4)
+        );
+
+        Ok(())
+    }
 }
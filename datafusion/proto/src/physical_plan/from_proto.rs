@@ -39,7 +39,7 @@ use datafusion::physical_plan::{
     functions, Partitioning,
 };
 use datafusion::physical_plan::{ColumnStatistics, PhysicalExpr, Statistics};
-use datafusion_common::{not_impl_err, DataFusionError, Result};
+use datafusion_common::{not_impl_err, DataFusionError, Precision, Result};
 use object_store::path::Path;
 use object_store::ObjectMeta;
 use std::convert::{TryFrom, TryInto};
@@ -475,6 +475,8 @@ pub fn parse_protobuf_file_scan_config(
         table_partition_cols,
         output_ordering,
         infinite_source: false,
+        column_renames: vec![],
+        nested_projection: vec![],
     })
 }
 
@@ -525,10 +527,16 @@ impl TryFrom<&protobuf::FileGroup> for Vec<PartitionedFile> {
 impl From<&protobuf::ColumnStats> for ColumnStatistics {
     fn from(cs: &protobuf::ColumnStats) -> ColumnStatistics {
         ColumnStatistics {
-            null_count: Some(cs.null_count as usize),
-            max_value: cs.max_value.as_ref().map(|m| m.try_into().unwrap()),
-            min_value: cs.min_value.as_ref().map(|m| m.try_into().unwrap()),
-            distinct_count: Some(cs.distinct_count as usize),
+            null_count: Precision::Exact(cs.null_count as usize),
+            max_value: Precision::exact_or_absent(
+                cs.max_value.as_ref().map(|m| m.try_into().unwrap()),
+            ),
+            min_value: Precision::exact_or_absent(
+                cs.min_value.as_ref().map(|m| m.try_into().unwrap()),
+            ),
+            distinct_count: Precision::Exact(cs.distinct_count as usize),
+            histogram: None,
+            most_common_values: None,
         }
     }
 }
@@ -550,16 +558,16 @@ impl TryFrom<&protobuf::Statistics> for Statistics {
         let none_value = -1_i64;
         let column_statistics =
             s.column_stats.iter().map(|s| s.into()).collect::<Vec<_>>();
-        Ok(Statistics {
+        let statistics = Statistics {
             num_rows: if s.num_rows == none_value {
-                None
+                Precision::Absent
             } else {
-                Some(s.num_rows as usize)
+                Precision::Exact(s.num_rows as usize)
             },
             total_byte_size: if s.total_byte_size == none_value {
-                None
+                Precision::Absent
             } else {
-                Some(s.total_byte_size as usize)
+                Precision::Exact(s.total_byte_size as usize)
             },
             // No column statistic (None) is encoded with empty array
             column_statistics: if column_statistics.is_empty() {
@@ -567,7 +575,11 @@ impl TryFrom<&protobuf::Statistics> for Statistics {
             } else {
                 Some(column_statistics)
             },
-            is_exact: s.is_exact,
+        };
+        Ok(if s.is_exact {
+            statistics
+        } else {
+            statistics.into_inexact()
         })
     }
 }
@@ -1006,6 +1006,11 @@ impl AsExecutionPlan for PhysicalPlanNode {
                 AggregateMode::SinglePartitioned => {
                     protobuf::AggregateMode::SinglePartitioned
                 }
+                AggregateMode::PartialReduce => {
+                    return not_impl_err!(
+                        "Serializing AggregateExec in PartialReduce mode is not supported"
+                    )
+                }
             };
             let input_schema = exec.input_schema();
             let input = protobuf::PhysicalPlanNode::try_from_physical_plan(
@@ -1120,6 +1125,11 @@ impl AsExecutionPlan for PhysicalPlanNode {
                 ))),
             })
         } else if let Some(exec) = plan.downcast_ref::<RepartitionExec>() {
+            if exec.skew_handling().is_some() {
+                return not_impl_err!(
+                    "Serializing RepartitionExec with skew handling is not supported"
+                );
+            }
             let input = protobuf::PhysicalPlanNode::try_from_physical_plan(
                 exec.input().to_owned(),
                 extension_codec,
@@ -1141,6 +1151,11 @@ impl AsExecutionPlan for PhysicalPlanNode {
                 Partitioning::UnknownPartitioning(partition_count) => {
                     PartitionMethod::Unknown(*partition_count as u64)
                 }
+                Partitioning::Range(_, _) => {
+                    return not_impl_err!(
+                        "Serializing range partitioning is not supported yet"
+                    );
+                }
             };
 
             Ok(protobuf::PhysicalPlanNode {
@@ -1419,7 +1434,7 @@ mod roundtrip_tests {
         prelude::SessionContext,
         scalar::ScalarValue,
     };
-    use datafusion_common::Result;
+    use datafusion_common::{Precision, Result};
     use datafusion_expr::{
         Accumulator, AccumulatorFactoryFunction, AggregateUDF, ReturnTypeFunction,
         Signature, StateTypeFunction,
@@ -1769,16 +1784,17 @@ mod roundtrip_tests {
                 1024,
             )]],
             statistics: Statistics {
-                num_rows: Some(100),
-                total_byte_size: Some(1024),
+                num_rows: Precision::Inexact(100),
+                total_byte_size: Precision::Inexact(1024),
                 column_statistics: None,
-                is_exact: false,
             },
             projection: None,
             limit: None,
             table_partition_cols: vec![],
             output_ordering: vec![],
             infinite_source: false,
+            column_renames: vec![],
+            nested_projection: vec![],
         };
 
         let predicate = Arc::new(BinaryExpr::new(
@@ -463,10 +463,14 @@ impl TryFrom<&[PartitionedFile]> for protobuf::FileGroup {
 impl From<&ColumnStatistics> for protobuf::ColumnStats {
     fn from(cs: &ColumnStatistics) -> protobuf::ColumnStats {
         protobuf::ColumnStats {
-            min_value: cs.min_value.as_ref().map(|m| m.try_into().unwrap()),
-            max_value: cs.max_value.as_ref().map(|m| m.try_into().unwrap()),
-            null_count: cs.null_count.map(|n| n as u32).unwrap_or(0),
-            distinct_count: cs.distinct_count.map(|n| n as u32).unwrap_or(0),
+            min_value: cs.min_value.get_value().map(|m| m.try_into().unwrap()),
+            max_value: cs.max_value.get_value().map(|m| m.try_into().unwrap()),
+            null_count: cs.null_count.get_value().map(|n| *n as u32).unwrap_or(0),
+            distinct_count: cs
+                .distinct_count
+                .get_value()
+                .map(|n| *n as u32)
+                .unwrap_or(0),
         }
     }
 }
@@ -479,10 +483,20 @@ impl From<&Statistics> for protobuf::Statistics {
             Some(column_stats) => column_stats.iter().map(|s| s.into()).collect(),
         };
         protobuf::Statistics {
-            num_rows: s.num_rows.map(|n| n as i64).unwrap_or(none_value),
-            total_byte_size: s.total_byte_size.map(|n| n as i64).unwrap_or(none_value),
+            num_rows: s
+                .num_rows
+                .get_value()
+                .map(|n| *n as i64)
+                .unwrap_or(none_value),
+            total_byte_size: s
+                .total_byte_size
+                .get_value()
+                .map(|n| *n as i64)
+                .unwrap_or(none_value),
             column_stats,
-            is_exact: s.is_exact,
+            // The precision is not encoded per value, the whole statistics are
+            // considered exact if the row count is
+            is_exact: s.num_rows.is_exact().unwrap_or(false),
         }
     }
 }
@@ -25,9 +25,12 @@ use sqlparser::{
         ColumnDef, ColumnOptionDef, ObjectName, Statement as SQLStatement,
         TableConstraint,
     },
-    dialect::{keywords::Keyword, Dialect, GenericDialect},
+    dialect::{
+        keywords::{Keyword, RESERVED_FOR_TABLE_ALIAS},
+        Dialect, GenericDialect,
+    },
     parser::{Parser, ParserError},
-    tokenizer::{Token, TokenWithLocation, Tokenizer},
+    tokenizer::{Token, TokenWithLocation, Tokenizer, Whitespace},
 };
 use std::collections::VecDeque;
 use std::fmt;
@@ -199,15 +202,69 @@ pub struct CreateExternalTable {
     pub options: HashMap<String, String>,
 }
 
+/// Formats the statement such that it can be parsed back into an equal
+/// [`CreateExternalTable`], which allows persisting table definitions
 impl fmt::Display for CreateExternalTable {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "CREATE EXTERNAL TABLE ")?;
+        fn join<T: fmt::Display>(items: &[T]) -> String {
+            items
+                .iter()
+                .map(|i| i.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        }
+
+        write!(f, "CREATE ")?;
+        if self.unbounded {
+            write!(f, "UNBOUNDED ")?;
+        }
+        write!(f, "EXTERNAL TABLE ")?;
         if self.if_not_exists {
             write!(f, "IF NOT EXISTS ")?;
         }
-        write!(f, "{} ", self.name)?;
-        write!(f, "STORED AS {} ", self.file_type)?;
-        write!(f, "LOCATION {} ", self.location)
+        write!(f, "{}", self.name)?;
+        if !self.columns.is_empty() {
+            write!(f, " ({})", join(&self.columns))?;
+        }
+        write!(f, " STORED AS {}", self.file_type)?;
+        if self.has_header {
+            write!(f, " WITH HEADER ROW")?;
+        }
+        if self.delimiter != ',' {
+            let delimiter = Value::SingleQuotedString(self.delimiter.to_string());
+            write!(f, " DELIMITER {delimiter}")?;
+        }
+        if self.file_compression_type.is_compressed() {
+            write!(
+                f,
+                " COMPRESSION TYPE {}",
+                self.file_compression_type.to_string()
+            )?;
+        }
+        if !self.table_partition_cols.is_empty() {
+            write!(f, " PARTITIONED BY ({})", join(&self.table_partition_cols))?;
+        }
+        for order in &self.order_exprs {
+            write!(f, " WITH ORDER ({})", join(order))?;
+        }
+        if !self.options.is_empty() {
+            // print them in sorted order
+            let mut options: Vec<_> = self.options.iter().collect();
+            options.sort();
+            let options: Vec<_> = options
+                .into_iter()
+                .map(|(k, v)| {
+                    format!(
+                        "{} {}",
+                        Value::SingleQuotedString(k.clone()),
+                        Value::SingleQuotedString(v.clone())
+                    )
+                })
+                .collect();
+            write!(f, " OPTIONS ({})", options.join(", "))?;
+        }
+        let location = Value::SingleQuotedString(self.location.clone());
+        write!(f, " LOCATION {location}")
     }
 }
 
@@ -253,6 +310,198 @@ impl fmt::Display for Statement {
 
 /// Datafusion SQL Parser based on [`sqlparser`]
 ///
+/// The name of the `WITH OFFSET` alias that [`DFParser`] rewrites
+/// `UNNEST(...) WITH ORDINALITY` to
+pub(crate) const UNNEST_ORDINALITY: &str = "__unnest_ordinality";
+
+/// The argument that [`DFParser`] rewrites the `recursive := true` argument
+/// of `UNNEST` to
+pub(crate) const UNNEST_RECURSIVE: &str = "__unnest_recursive";
+
+/// Rewrites the options of `UNNEST` that `sqlparser` can't parse to a syntax
+/// it can, which [`SqlToRel`] plans accordingly:
+///
+/// * `UNNEST(...) WITH ORDINALITY [[AS] alias]` to
+///   `UNNEST(...) [[AS] alias] WITH OFFSET AS __unnest_ordinality`
+/// * `UNNEST(..., recursive := true)` (or `=> true`) to
+///   `UNNEST(..., __unnest_recursive)`, and `recursive := false` is removed
+///
+/// [`SqlToRel`]: crate::planner::SqlToRel
+fn rewrite_unnest_options(mut tokens: Vec<Token>) -> Vec<Token> {
+    let mut index = 0;
+    while index < tokens.len() {
+        let is_unnest = matches!(
+            &tokens[index],
+            Token::Word(Word {
+                keyword: Keyword::UNNEST,
+                ..
+            })
+        );
+        index += 1;
+        if !is_unnest {
+            continue;
+        }
+        let Some(left_paren) =
+            next_token_index(&tokens, index).filter(|i| tokens[*i] == Token::LParen)
+        else {
+            continue;
+        };
+        let Some(right_paren) = closing_paren_index(&tokens, left_paren) else {
+            continue;
+        };
+        let right_paren = rewrite_unnest_recursive(&mut tokens, left_paren, right_paren);
+        rewrite_unnest_ordinality(&mut tokens, right_paren + 1);
+    }
+    tokens
+}
+
+/// Rewrites a last `recursive := <bool>` argument of the `UNNEST` arguments
+/// between `left_paren` and `right_paren`, returning the new index of the
+/// right parenthesis
+fn rewrite_unnest_recursive(
+    tokens: &mut Vec<Token>,
+    left_paren: usize,
+    right_paren: usize,
+) -> usize {
+    // the non whitespace tokens of the last argument and its preceding comma
+    let mut last = vec![];
+    let mut index = right_paren;
+    while last.len() < 4 && index > left_paren + 1 {
+        index -= 1;
+        if !matches!(tokens[index], Token::Whitespace(_)) {
+            last.push(index);
+        }
+    }
+    let &[value, assignment, name, comma] = last.as_slice() else {
+        return right_paren;
+    };
+    let recursive = match &tokens[value] {
+        Token::Word(Word {
+            keyword: Keyword::TRUE,
+            ..
+        }) => true,
+        Token::Word(Word {
+            keyword: Keyword::FALSE,
+            ..
+        }) => false,
+        _ => return right_paren,
+    };
+    if !matches!(tokens[assignment], Token::DuckAssignment | Token::RArrow)
+        || !matches!(
+            &tokens[name],
+            Token::Word(Word {
+                keyword: Keyword::RECURSIVE,
+                quote_style: None,
+                ..
+            })
+        )
+        || tokens[comma] != Token::Comma
+    {
+        return right_paren;
+    }
+
+    if recursive {
+        let argument = Token::make_word(UNNEST_RECURSIVE, None);
+        tokens.splice(name..=value, [argument]);
+        right_paren - (value - name)
+    } else {
+        tokens.drain(comma..=value);
+        right_paren - (value - comma + 1)
+    }
+}
+
+/// Rewrites `WITH ORDINALITY [[AS] alias]` starting at `index`, following
+/// the arguments of `UNNEST`
+fn rewrite_unnest_ordinality(tokens: &mut Vec<Token>, index: usize) {
+    let Some(with) = next_token_index(tokens, index).filter(|i| {
+        matches!(
+            &tokens[*i],
+            Token::Word(Word {
+                keyword: Keyword::WITH,
+                ..
+            })
+        )
+    }) else {
+        return;
+    };
+    let Some(ordinality) = next_token_index(tokens, with + 1).filter(|i| {
+        matches!(&tokens[*i], Token::Word(w) if w.quote_style.is_none()
+            && w.value.eq_ignore_ascii_case("ordinality"))
+    }) else {
+        return;
+    };
+    tokens.drain(with..=ordinality);
+
+    // the end of the optional table alias and its column names
+    let mut alias_end = with;
+    if let Some(mut next) = next_token_index(tokens, with) {
+        let has_as = matches!(
+            &tokens[next],
+            Token::Word(Word {
+                keyword: Keyword::AS,
+                ..
+            })
+        );
+        if has_as {
+            next = next_token_index(tokens, next + 1).unwrap_or(next);
+        }
+        let is_alias = match &tokens[next] {
+            Token::Word(w) => {
+                has_as
+                    || w.quote_style.is_some()
+                    || !RESERVED_FOR_TABLE_ALIAS.contains(&w.keyword)
+            }
+            _ => false,
+        };
+        if is_alias {
+            alias_end = next + 1;
+            if let Some(columns) = next_token_index(tokens, alias_end)
+                .filter(|i| tokens[*i] == Token::LParen)
+            {
+                if let Some(right_paren) = closing_paren_index(tokens, columns) {
+                    alias_end = right_paren + 1;
+                }
+            }
+        }
+    }
+
+    let space = || Token::Whitespace(Whitespace::Space);
+    let offset = [
+        space(),
+        Token::make_keyword("WITH"),
+        space(),
+        Token::make_keyword("OFFSET"),
+        space(),
+        Token::make_keyword("AS"),
+        space(),
+        Token::make_word(UNNEST_ORDINALITY, None),
+    ];
+    tokens.splice(alias_end..alias_end, offset);
+}
+
+/// Returns the index of the first token from `index` that is not whitespace
+fn next_token_index(tokens: &[Token], index: usize) -> Option<usize> {
+    (index..tokens.len()).find(|i| !matches!(tokens[*i], Token::Whitespace(_)))
+}
+
+/// Returns the index of the parenthesis closing the one at `left_paren`
+fn closing_paren_index(tokens: &[Token], left_paren: usize) -> Option<usize> {
+    let mut depth = 0;
+    for (index, token) in tokens.iter().enumerate().skip(left_paren) {
+        match token {
+            Token::LParen => depth += 1,
+            Token::RParen => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(index);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
 /// Parses DataFusion's SQL dialect, often delegating to [`sqlparser`]'s
 /// [`Parser`](sqlparser::parser::Parser).
 ///
@@ -279,7 +528,7 @@ impl<'a> DFParser<'a> {
         dialect: &'a dyn Dialect,
     ) -> Result<Self, ParserError> {
         let mut tokenizer = Tokenizer::new(dialect, sql);
-        let tokens = tokenizer.tokenize()?;
+        let tokens = rewrite_unnest_options(tokenizer.tokenize()?);
 
         Ok(DFParser {
             parser: Parser::new(dialect).with_tokens(tokens),
@@ -773,14 +1022,24 @@ impl<'a> DFParser<'a> {
         }
     }
 
-    /// Parses (key value) style options where the values are literal strings.
+    /// Parses (key value) style options where the values are converted to strings.
+    ///
+    /// Keys and values may be quoted or unquoted words, and values may also be
+    /// numbers, e.g. `('bloom_filter_enabled::col1' true, max_row_group_size 1000)`
     fn parse_string_options(&mut self) -> Result<HashMap<String, String>, ParserError> {
         let mut options = HashMap::new();
         self.parser.expect_token(&Token::LParen)?;
 
         loop {
-            let key = self.parser.parse_literal_string()?;
-            let value = self.parser.parse_literal_string()?;
+            let key = self.parse_option_key()?;
+            let value = match self.parse_option_value()? {
+                Value::SingleQuotedString(s)
+                | Value::DoubleQuotedString(s)
+                | Value::EscapedStringLiteral(s)
+                | Value::UnQuotedString(s) => s,
+                Value::Number(n, _) => n.to_string(),
+                value => value.to_string(),
+            };
             options.insert(key, value);
             let comma = self.parser.consume_token(&Token::Comma);
             if self.parser.consume_token(&Token::RParen) {
@@ -1147,6 +1406,28 @@ mod tests {
         });
         expect_parse_ok(sql, expected)?;
 
+        // positive case: option values may be unquoted keywords or numbers
+        let sql =
+            "CREATE EXTERNAL TABLE t STORED AS x OPTIONS ('bloom_filter_enabled::c1' true, max_row_group_size 1000) LOCATION 'blahblah'";
+        let expected = Statement::CreateExternalTable(CreateExternalTable {
+            name: "t".into(),
+            columns: vec![],
+            file_type: "X".to_string(),
+            has_header: false,
+            delimiter: ',',
+            location: "blahblah".into(),
+            table_partition_cols: vec![],
+            order_exprs: vec![],
+            if_not_exists: false,
+            file_compression_type: UNCOMPRESSED,
+            unbounded: false,
+            options: HashMap::from([
+                ("bloom_filter_enabled::c1".into(), "true".into()),
+                ("max_row_group_size".into(), "1000".into()),
+            ]),
+        });
+        expect_parse_ok(sql, expected)?;
+
         // Ordered Col
         let sqls = ["CREATE EXTERNAL TABLE t(c1 int) STORED AS CSV WITH ORDER (c1) LOCATION 'foo.csv'",
                         "CREATE EXTERNAL TABLE t(c1 int) STORED AS CSV WITH ORDER (c1 NULLS FIRST) LOCATION 'foo.csv'",
@@ -1321,6 +1602,17 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn create_external_table_round_trip() {
+        verified_stmt("CREATE EXTERNAL TABLE t STORED AS CSV LOCATION 'foo.csv'");
+        verified_stmt(
+            "CREATE UNBOUNDED EXTERNAL TABLE IF NOT EXISTS t (c1 INT NOT NULL, c2 VARCHAR) \
+             STORED AS CSV WITH HEADER ROW DELIMITER '|' COMPRESSION TYPE GZIP \
+             PARTITIONED BY (p1, p2) WITH ORDER (c1 ASC NULLS LAST) \
+             OPTIONS ('k1' 'v1', 'k2' 'it''s') LOCATION 'foo.csv.gz'",
+        );
+    }
+
     #[test]
     fn copy_to_table_to_table() -> Result<(), ParserError> {
         // positive case
@@ -1403,6 +1695,31 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn unnest_options() {
+        one_statement_parses_to(
+            "SELECT * FROM UNNEST(a) WITH ORDINALITY",
+            "SELECT * FROM UNNEST(a) WITH OFFSET AS __unnest_ordinality",
+        );
+        one_statement_parses_to(
+            "SELECT * FROM t, UNNEST(t.a, t.b) WITH ORDINALITY AS u (x, y, n) WHERE n > 1",
+            "SELECT * FROM t, UNNEST(t.a, t.b) AS u (x, y, n) WITH OFFSET AS __unnest_ordinality WHERE n > 1",
+        );
+        one_statement_parses_to(
+            "SELECT * FROM UNNEST([[1], [2, 3]], recursive := true) WITH ORDINALITY u",
+            "SELECT * FROM UNNEST([[1], [2, 3]], __unnest_recursive) AS u WITH OFFSET AS __unnest_ordinality",
+        );
+        one_statement_parses_to(
+            "SELECT * FROM UNNEST(a, recursive => false)",
+            "SELECT * FROM UNNEST(a)",
+        );
+        // a column named recursive is an argument
+        one_statement_parses_to(
+            "SELECT * FROM UNNEST(a, recursive)",
+            "SELECT * FROM UNNEST(a, recursive)",
+        );
+    }
+
     #[test]
     fn copy_to_multi_options() -> Result<(), ParserError> {
         // order of options is preserved
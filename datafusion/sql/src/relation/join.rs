@@ -18,7 +18,7 @@
 use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
 use datafusion_common::{not_impl_err, Column, DataFusionError, Result};
 use datafusion_expr::{JoinType, LogicalPlan, LogicalPlanBuilder};
-use sqlparser::ast::{Join, JoinConstraint, JoinOperator, TableWithJoins};
+use sqlparser::ast::{Join, JoinConstraint, JoinOperator, TableFactor, TableWithJoins};
 use std::collections::HashSet;
 
 impl<'a, S: ContextProvider> SqlToRel<'a, S> {
@@ -57,12 +57,35 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         }
     }
 
+    /// Plans `t` after `left` in a FROM list, `t.relation` being joined
+    /// laterally to `left` and `t.joins` applied to the result
+    pub(crate) fn plan_lateral_table_with_joins(
+        &self,
+        left: LogicalPlan,
+        t: TableWithJoins,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let origin_planner_context = planner_context.clone();
+        let mut left = self.create_lateral_relation(left, t.relation, planner_context)?;
+        for join in t.joins {
+            *planner_context = origin_planner_context.clone();
+            left = self.parse_relation_join(left, join, planner_context)?;
+        }
+        *planner_context = origin_planner_context;
+        Ok(left)
+    }
+
     fn parse_relation_join(
         &self,
         left: LogicalPlan,
         join: Join,
         planner_context: &mut PlannerContext,
     ) -> Result<LogicalPlan> {
+        if let (TableFactor::UNNEST { .. }, JoinOperator::CrossJoin) =
+            (&join.relation, &join.join_operator)
+        {
+            return self.create_lateral_relation(left, join.relation, planner_context);
+        }
         let right = self.create_relation(join.relation, planner_context)?;
         match join.join_operator {
             JoinOperator::LeftOuter(constraint) => {
@@ -15,10 +15,14 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use crate::parser::{UNNEST_ORDINALITY, UNNEST_RECURSIVE};
 use crate::planner::{ContextProvider, PlannerContext, SqlToRel};
-use datafusion_common::{not_impl_err, DataFusionError, Result};
-use datafusion_expr::{LogicalPlan, LogicalPlanBuilder};
-use sqlparser::ast::TableFactor;
+use datafusion_common::{
+    not_impl_err, plan_err, Column, DFSchema, DataFusionError, OwnedTableReference,
+    Result, UnnestOptions,
+};
+use datafusion_expr::{col, lit, Expr, LogicalPlan, LogicalPlanBuilder};
+use sqlparser::ast::{Expr as SQLExpr, Ident, TableAlias, TableFactor};
 
 mod join;
 
@@ -62,6 +66,26 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 self.plan_table_with_joins(*table_with_joins, planner_context)?,
                 alias,
             ),
+            TableFactor::UNNEST {
+                alias,
+                array_exprs,
+                with_offset,
+                with_offset_alias,
+            } => {
+                let (exprs, recursive) =
+                    self.unnest_exprs(array_exprs, &DFSchema::empty(), planner_context)?;
+                let input = LogicalPlanBuilder::empty(true).build()?;
+                (
+                    self.plan_unnest(
+                        input,
+                        exprs,
+                        recursive,
+                        with_offset,
+                        with_offset_alias,
+                    )?,
+                    alias,
+                )
+            }
             // @todo Support TableFactory::TableFunction?
             _ => {
                 return not_impl_err!(
@@ -75,4 +99,227 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
             Ok(plan)
         }
     }
+
+    /// Create a `LogicalPlan` for `relation` placed after `left` in a FROM
+    /// list or a CROSS JOIN
+    ///
+    /// An `UNNEST` whose arguments reference the columns of `left` is planned
+    /// laterally: each row of `left` is repeated for every unnested value.
+    /// Any other relation is cross joined with `left`. The columns of an
+    /// `UNNEST` without alias are qualified by `unnest`, or `unnest_2`,
+    /// `unnest_3`, ... if `left` already has columns qualified by it, so that
+    /// several of them can follow each other.
+    pub(crate) fn create_lateral_relation(
+        &self,
+        left: LogicalPlan,
+        relation: TableFactor,
+        planner_context: &mut PlannerContext,
+    ) -> Result<LogicalPlan> {
+        let TableFactor::UNNEST {
+            alias,
+            array_exprs,
+            with_offset,
+            with_offset_alias,
+        } = relation
+        else {
+            let right = self.create_relation(relation, planner_context)?;
+            return LogicalPlanBuilder::from(left).cross_join(right)?.build();
+        };
+
+        let (exprs, recursive) =
+            self.unnest_exprs(array_exprs, left.schema(), planner_context)?;
+        let mut is_lateral = false;
+        for expr in &exprs {
+            is_lateral |= !expr.to_columns()?.is_empty();
+        }
+        let alias = alias.unwrap_or_else(|| TableAlias {
+            name: Ident::with_quote('"', unnest_qualifier(left.schema())),
+            columns: vec![],
+        });
+
+        if !is_lateral {
+            let input = LogicalPlanBuilder::empty(true).build()?;
+            let right = self.plan_unnest(
+                input,
+                exprs,
+                recursive,
+                with_offset,
+                with_offset_alias,
+            )?;
+            let right = self.apply_table_alias(right, alias)?;
+            return LogicalPlanBuilder::from(left).cross_join(right)?.build();
+        }
+
+        let num_left_fields = left.schema().fields().len();
+        let plan =
+            self.plan_unnest(left, exprs, recursive, with_offset, with_offset_alias)?;
+        self.apply_lateral_alias(plan, num_left_fields, alias)
+    }
+
+    /// Qualifies the columns added to `plan` after its first `num_left_fields`
+    /// by the name of `alias`, renaming them to its column names if any
+    fn apply_lateral_alias(
+        &self,
+        plan: LogicalPlan,
+        num_left_fields: usize,
+        alias: TableAlias,
+    ) -> Result<LogicalPlan> {
+        let qualifier = OwnedTableReference::bare(self.normalizer.normalize(alias.name));
+        let column_alias = alias.columns;
+        let fields = plan.schema().fields().clone();
+        let (left_fields, unnest_fields) = fields.split_at(num_left_fields);
+        let names = if column_alias.is_empty() {
+            unnest_fields.iter().map(|f| f.name().clone()).collect()
+        } else if column_alias.len() == unnest_fields.len() {
+            column_alias
+                .into_iter()
+                .map(|ident| self.normalizer.normalize(ident))
+                .collect::<Vec<_>>()
+        } else {
+            return plan_err!(
+                "UNNEST produces {} columns but {} names given as column alias",
+                unnest_fields.len(),
+                column_alias.len()
+            );
+        };
+
+        // A projection only qualifies the columns it references, so the
+        // unnested columns are first renamed to their qualified names, which
+        // the qualified columns then resolve to
+        let left_exprs = left_fields
+            .iter()
+            .map(|field| Expr::Column(field.qualified_column()));
+        let qualified = names
+            .into_iter()
+            .map(|name| Column::new(Some(qualifier.clone()), name))
+            .collect::<Vec<_>>();
+        let renamed = unnest_fields.iter().zip(&qualified).map(|(field, column)| {
+            Expr::Column(field.qualified_column()).alias(column.quoted_flat_name())
+        });
+        LogicalPlanBuilder::from(plan)
+            .project(left_exprs.clone().chain(renamed))?
+            .project(left_exprs.chain(qualified.into_iter().map(Expr::Column)))?
+            .build()
+    }
+
+    /// Plans the arguments of `UNNEST` against `schema`, naming them `unnest`
+    /// for a single argument and `unnest_1`, `unnest_2`, ... otherwise.
+    /// Returns the arguments and whether they are unnested recursively, as
+    /// requested by a `recursive := true` argument (see [`DFParser`]).
+    ///
+    /// [`DFParser`]: crate::parser::DFParser
+    fn unnest_exprs(
+        &self,
+        mut array_exprs: Vec<SQLExpr>,
+        schema: &DFSchema,
+        planner_context: &mut PlannerContext,
+    ) -> Result<(Vec<Expr>, bool)> {
+        let recursive = matches!(
+            array_exprs.last(),
+            Some(SQLExpr::Identifier(ident)) if ident.value == UNNEST_RECURSIVE
+        );
+        if recursive {
+            array_exprs.pop();
+        }
+        if array_exprs.is_empty() {
+            return plan_err!("UNNEST must have at least one argument");
+        }
+        let single = array_exprs.len() == 1;
+        let exprs = array_exprs
+            .into_iter()
+            .enumerate()
+            .map(|(i, sql_expr)| {
+                let expr = self.sql_to_expr(sql_expr, schema, planner_context)?;
+                let name = if single {
+                    "unnest".to_string()
+                } else {
+                    format!("unnest_{}", i + 1)
+                };
+                Ok(expr.alias(name))
+            })
+            .collect::<Result<_>>()?;
+        Ok((exprs, recursive))
+    }
+
+    /// Create a `LogicalPlan` for `UNNEST(exprs) [WITH OFFSET [AS alias]]`
+    /// evaluated against every row of `input`
+    ///
+    /// The output holds the columns of `input` followed by the unnested
+    /// ones. The arrays are unnested together, the shorter ones being padded
+    /// with nulls, and structs are replaced by their fields. If `recursive`
+    /// is set, the lists of lists are unnested down to their values.
+    /// `WITH OFFSET` adds a column holding the 0 based position of the values
+    /// in their arrays, and `WITH ORDINALITY` (see [`DFParser`]) a column
+    /// named `ordinality` holding their 1 based position.
+    ///
+    /// [`DFParser`]: crate::parser::DFParser
+    fn plan_unnest(
+        &self,
+        input: LogicalPlan,
+        exprs: Vec<Expr>,
+        recursive: bool,
+        with_offset: bool,
+        with_offset_alias: Option<Ident>,
+    ) -> Result<LogicalPlan> {
+        let columns = exprs
+            .iter()
+            .map(|expr| Column::from_name(expr.display_name()?))
+            .collect::<Result<Vec<_>>>()?;
+        let input_columns = input
+            .schema()
+            .fields()
+            .iter()
+            .map(|field| Expr::Column(field.qualified_column()))
+            .collect::<Vec<_>>();
+
+        // Like in PostgreSQL, null and empty arrays produce no rows
+        let mut options = UnnestOptions::new()
+            .with_preserve_nulls(false)
+            .with_recursive(recursive);
+        let ordinality = "__unnest_position";
+        if with_offset {
+            options = options.with_ordinality(ordinality);
+        }
+        let plan = LogicalPlanBuilder::from(input)
+            .project(input_columns.into_iter().chain(exprs))?
+            .unnest_columns_with_options(columns, options)?;
+        if !with_offset {
+            return plan.build();
+        }
+
+        // Struct columns have been replaced by their fields
+        let output_columns = plan
+            .schema()
+            .fields()
+            .iter()
+            .filter(|field| field.name() != ordinality)
+            .map(|field| Expr::Column(field.qualified_column()))
+            .collect::<Vec<_>>();
+        let offset = match with_offset_alias {
+            Some(ident) if ident.value == UNNEST_ORDINALITY => {
+                col(ordinality).alias("ordinality")
+            }
+            Some(ident) => {
+                (col(ordinality) - lit(1_u64)).alias(self.normalizer.normalize(ident))
+            }
+            None => (col(ordinality) - lit(1_u64)).alias("offset"),
+        };
+        plan.project(output_columns.into_iter().chain([offset]))?
+            .build()
+    }
+}
+
+/// Returns `unnest`, or else the first of `unnest_2`, `unnest_3`, ... that
+/// no column of `schema` is qualified by
+fn unnest_qualifier(schema: &DFSchema) -> String {
+    let is_used = |qualifier: &str| {
+        schema
+            .fields()
+            .iter()
+            .any(|field| field.qualifier().map_or(false, |q| q.table() == qualifier))
+    };
+    std::iter::once("unnest".to_string())
+        .chain((2..).map(|i| format!("unnest_{i}")))
+        .find(|qualifier| !is_used(qualifier))
+        .unwrap()
 }
@@ -44,7 +44,9 @@ use datafusion_expr::{
 use sqlparser::ast::{
     Distinct, Expr as SQLExpr, ReplaceSelectItem, WildcardAdditionalOptions, WindowType,
 };
-use sqlparser::ast::{NamedWindowDefinition, Select, SelectItem, TableWithJoins};
+use sqlparser::ast::{
+    NamedWindowDefinition, Select, SelectItem, TableFactor, TableWithJoins,
+};
 
 impl<'a, S: ContextProvider> SqlToRel<'a, S> {
     /// Generate a logic plan from an SQL select
@@ -292,16 +294,23 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 self.plan_table_with_joins(from, planner_context)
             }
             _ => {
-                let mut plans = from
-                    .into_iter()
-                    .map(|t| self.plan_table_with_joins(t, planner_context));
-
-                let mut left = LogicalPlanBuilder::from(plans.next().unwrap()?);
-
-                for right in plans {
-                    left = left.cross_join(right?)?;
+                let mut from = from.into_iter();
+                let mut left =
+                    self.plan_table_with_joins(from.next().unwrap(), planner_context)?;
+
+                for t in from {
+                    left = match t.relation {
+                        // UNNEST may reference the columns of the relations before it
+                        TableFactor::UNNEST { .. } => {
+                            self.plan_lateral_table_with_joins(left, t, planner_context)?
+                        }
+                        _ => {
+                            let right = self.plan_table_with_joins(t, planner_context)?;
+                            LogicalPlanBuilder::from(left).cross_join(right)?.build()?
+                        }
+                    };
                 }
-                Ok(left.build()?)
+                Ok(left)
             }
         }
     }
@@ -39,7 +39,7 @@ use datafusion_expr::logical_plan::builder::project;
 use datafusion_expr::logical_plan::DdlStatement;
 use datafusion_expr::utils::expr_to_columns;
 use datafusion_expr::{
-    cast, col, Analyze, CreateCatalog, CreateCatalogSchema,
+    cast, col, Analyze, AnalyzeTable, CreateCatalog, CreateCatalogSchema,
     CreateExternalTable as PlanCreateExternalTable, CreateMemoryTable, CreateView,
     DescribeTable, DmlStatement, DropCatalogSchema, DropTable, DropView, EmptyRelation,
     Explain, ExprSchemable, Filter, LogicalPlan, LogicalPlanBuilder, PlanType, Prepare,
@@ -516,6 +516,27 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
                 });
                 Ok(LogicalPlan::Statement(statement))
             }
+            Statement::Analyze {
+                table_name,
+                partitions,
+                for_columns: _,
+                columns,
+                cache_metadata,
+                noscan,
+                compute_statistics: _,
+            } => {
+                if partitions.is_some() {
+                    return not_impl_err!(
+                        "ANALYZE TABLE with PARTITION is not supported"
+                    );
+                }
+                if noscan || cache_metadata {
+                    return not_impl_err!(
+                        "ANALYZE TABLE with NOSCAN or CACHE METADATA is not supported"
+                    );
+                }
+                self.analyze_table_to_plan(table_name, columns)
+            }
 
             _ => not_impl_err!("Unsupported SQL statement: {sql:?}"),
         }
@@ -582,6 +603,39 @@ impl<'a, S: ContextProvider> SqlToRel<'a, S> {
         }))
     }
 
+    fn analyze_table_to_plan(
+        &self,
+        table_name: ObjectName,
+        columns: Vec<Ident>,
+    ) -> Result<LogicalPlan> {
+        let table_ref = self.object_name_to_table_reference(table_name)?;
+        let table_source = self.schema_provider.get_table_provider(table_ref.clone())?;
+        let table_schema = table_source.schema();
+
+        // No columns means collecting statistics for all columns of the table
+        let columns = if columns.is_empty() {
+            None
+        } else {
+            let columns = columns
+                .into_iter()
+                .map(|c| self.normalizer.normalize(c))
+                .collect::<Vec<_>>();
+            if let Some(column) = columns
+                .iter()
+                .find(|c| table_schema.field_with_name(c).is_err())
+            {
+                return plan_err!("Column '{column}' not found in table '{table_ref}'");
+            }
+            Some(columns)
+        };
+
+        Ok(LogicalPlan::Ddl(DdlStatement::AnalyzeTable(AnalyzeTable {
+            name: table_ref,
+            columns,
+            schema: DFSchemaRef::new(DFSchema::empty()),
+        })))
+    }
+
     fn copy_to_plan(&self, statement: CopyToStatement) -> Result<LogicalPlan> {
         // determine if source is table or query and handle accordingly
         let copy_source = statement.source;
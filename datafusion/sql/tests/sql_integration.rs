@@ -408,6 +408,25 @@ CopyTo: format=csv output_url=output.csv single_file_output=true options: ()
     quick_test(sql, plan);
 }
 
+#[test]
+fn plan_analyze_table() {
+    quick_test("ANALYZE TABLE person", r#"AnalyzeTable: Bare { table: "person" }"#);
+    quick_test(
+        "ANALYZE TABLE person COMPUTE STATISTICS FOR COLUMNS id, Age",
+        r#"AnalyzeTable: Bare { table: "person" } columns:=[id, age]"#,
+    );
+}
+
+#[test]
+fn plan_analyze_table_unknown_column() {
+    let sql = "ANALYZE TABLE person COMPUTE STATISTICS FOR COLUMNS id, nope";
+    let err = logical_plan(sql).expect_err("query should have failed");
+    assert_eq!(
+        "Error during planning: Column 'nope' not found in table 'person'",
+        err.strip_backtrace()
+    );
+}
+
 #[test]
 fn plan_insert() {
     let sql =
@@ -110,6 +110,8 @@ pub async fn from_substrait_rel(
                         table_partition_cols: vec![],
                         output_ordering: vec![],
                         infinite_source: false,
+                        column_renames: vec![],
+                        nested_projection: vec![],
                     };
 
                     if let Some(MaskExpression { select, .. }) = &read.projection {
@@ -48,6 +48,8 @@ async fn parquet_exec() -> Result<()> {
         table_partition_cols: vec![],
         output_ordering: vec![],
         infinite_source: false,
+        column_renames: vec![],
+        nested_projection: vec![],
     };
     let parquet_exec: Arc<dyn ExecutionPlan> =
         Arc::new(ParquetExec::new(scan_config, None, None));